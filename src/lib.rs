@@ -1,3 +1,11 @@
+#![allow(clippy::bool_assert_comparison)]
 
-mod soft_float;
+pub mod soft_float;
+pub mod soft_f16;
+pub mod soft_bf16;
 pub mod soft_f32;
+pub mod soft_f64;
+pub mod soft_f80;
+pub mod soft_f128;
+
+pub use soft_float::{ Env, RoundingMode, DetectTininess, RoundingPrecision, IntOverflow, MinMax, NanPolicy, FlushToZero, ExceptionFlags };
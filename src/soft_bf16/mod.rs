@@ -0,0 +1,260 @@
+mod soft_bf16_add;
+mod soft_bf16_sub;
+mod soft_bf16_mul;
+mod soft_bf16_div;
+mod soft_bf16_comp;
+mod soft_bf16_sqrt;
+mod soft_bf16_conv;
+pub(crate) mod util;
+
+// The procedural API works on raw u16 bit patterns of bfloat16, which is the
+// high half of a binary32. Each function uses the environment of the current
+// thread, and has a `_with_env` variant which takes an explicit environment.
+
+// Operations
+pub use soft_bf16_add::{ bf16_add, bf16_add_with_env };
+pub use soft_bf16_sub::{ bf16_sub, bf16_sub_with_env };
+pub use soft_bf16_mul::{ bf16_mul, bf16_mul_with_env };
+pub use soft_bf16_div::{ bf16_div, bf16_div_with_env };
+
+// Comparisons
+pub use soft_bf16_comp::{ bf16_eq, bf16_eq_with_env };
+pub use soft_bf16_comp::{ bf16_ne, bf16_ne_with_env };
+pub use soft_bf16_comp::{ bf16_lt, bf16_lt_with_env };
+pub use soft_bf16_comp::{ bf16_gt, bf16_gt_with_env };
+pub use soft_bf16_comp::{ bf16_le, bf16_le_with_env };
+pub use soft_bf16_comp::{ bf16_ge, bf16_ge_with_env };
+
+// Others
+pub use soft_bf16_sqrt::{ bf16_sqrt, bf16_sqrt_with_env };
+
+// Conversions
+pub use soft_bf16_conv::{ bf16_to_f32, bf16_to_f32_with_env };
+pub use soft_bf16_conv::{ f32_to_bf16, f32_to_bf16_with_env };
+
+// Utilities
+pub use util::{
+    bf16_is_nan,
+    bf16_is_signaling_nan,
+    bf16_pack,
+};
+
+// BF16 struct
+#[derive(Clone, Copy, Debug)]
+pub struct BF16 {
+    value: u16,
+}
+
+impl BF16 {
+    pub const fn from_bits(bits: u16) -> BF16 {
+        BF16 {
+            value: bits
+        }
+    }
+
+    pub fn to_bits(self) -> u16 {
+        self.value
+    }
+
+    #[deprecated(note = "use from_bits, as F32::from_bits")]
+    pub const fn from_u16(value: u16) -> BF16 {
+        BF16::from_bits(value)
+    }
+
+    // The bit pattern, as to_bits
+    pub fn value(self) -> u16 {
+        self.value
+    }
+
+    pub fn sqrt(self) -> Self {
+        BF16 {
+            value: bf16_sqrt(self.value)
+        }
+    }
+
+    pub fn from_f32(value: F32) -> BF16 {
+        BF16 {
+            value: f32_to_bf16(value.value())
+        }
+    }
+
+    pub fn to_f32(self) -> F32 {
+        F32::from_bits(bf16_to_f32(self.value))
+    }
+}
+
+use crate::soft_f32::F32;
+use std::ops;
+
+impl ops::Add<BF16> for BF16 {
+    type Output = BF16;
+
+    fn add(self, other: BF16) -> BF16 {
+        BF16 {
+            value: bf16_add(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Sub<BF16> for BF16 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        BF16 {
+            value: bf16_sub(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Mul<BF16> for BF16 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        BF16 {
+            value: bf16_mul(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Div<BF16> for BF16 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        BF16 {
+            value: bf16_div(self.value, other.value)
+        }
+    }
+}
+
+use std::cmp;
+
+impl cmp::PartialEq for BF16 {
+    // Implement equal with only symmetric and transitive for BF16.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
+    // For example, in floating point numbers NaN != NaN,
+    // so floating point types implement PartialEq but not Eq.
+    fn eq(&self, other: &Self) -> bool {
+        bf16_eq(self.value, other.value)
+    }
+
+    #[allow(clippy::partialeq_ne_impl)]
+    fn ne(&self, other: &Self) -> bool {
+        bf16_ne(self.value, other.value)
+    }
+}
+
+impl cmp::PartialOrd for BF16 {
+    // Implement compare with only symmetric and transitive for BF16.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if bf16_eq(self.value, other.value) { return Some(cmp::Ordering::Equal); }
+        if bf16_lt(self.value, other.value) { return Some(cmp::Ordering::Less); }
+        if bf16_gt(self.value, other.value) { return Some(cmp::Ordering::Greater); }
+
+        None
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        bf16_gt(self.value, other.value)
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        bf16_lt(self.value, other.value)
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        bf16_ge(self.value, other.value)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        bf16_le(self.value, other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_bf16_add_with_struct() {
+        let v0_1 = crate::soft_bf16::BF16::from_bits(0x3DCD);
+        let v0_2 = crate::soft_bf16::BF16::from_bits(0x3E4D);
+
+        let v0_3 = v0_1 + v0_2;
+
+        assert_eq!(v0_3.value(), 0x3E9A);
+    }
+
+    #[test]
+    fn test_bf16_sub_with_struct() {
+        let v0_1 = crate::soft_bf16::BF16::from_bits(0x3DCD);
+        let v0_2 = crate::soft_bf16::BF16::from_bits(0x3E4D);
+
+        let v0_1_result = v0_2 - v0_1;
+
+        assert_eq!(v0_1_result.value(), 0x3DCD);
+    }
+
+    #[test]
+    fn test_bf16_mul_with_struct() {
+        let v0_1 = crate::soft_bf16::BF16::from_bits(0x3DCD);
+        let v0_2 = crate::soft_bf16::BF16::from_bits(0x3E4D);
+
+        let v0_02 = v0_2 * v0_1;
+
+        assert_eq!(v0_02.value(), 0x3CA4);
+    }
+
+    #[test]
+    fn test_bf16_div_with_struct() {
+        let v0_1 = crate::soft_bf16::BF16::from_bits(0x3DCD);
+        let v0_2 = crate::soft_bf16::BF16::from_bits(0x3E4D);
+
+        let v0_5 = v0_1 / v0_2;
+
+        assert_eq!(v0_5.value(), 0x3F00);
+    }
+
+    #[test]
+    fn test_bf16_compare_with_struct() {
+        let v0_1 = crate::soft_bf16::BF16::from_bits(0x3DCD);
+        let v0_2 = crate::soft_bf16::BF16::from_bits(0x3E4D);
+
+        assert_eq!(v0_1 == v0_2, false);
+        assert_eq!(v0_1 != v0_2, true);
+
+        assert_eq!(v0_1 < v0_2, true);
+        assert_eq!(v0_1 <= v0_1, true);
+        assert_eq!(v0_1 > v0_2, false);
+        assert_eq!(v0_1 >= v0_1, true);
+    }
+
+    #[test]
+    fn test_bf16_sqrt_with_struct() {
+        // sqrt(4) = 2
+        let v4 = crate::soft_bf16::BF16::from_bits(0x4080);
+
+        let v2 = v4.sqrt();
+
+        assert_eq!(v2.value(), 0x4000);
+    }
+
+    #[test]
+    fn test_bf16_f32_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+
+        let v0_1_bf16 = crate::soft_bf16::BF16::from_f32(v0_1);
+        let v0_1_f32 = crate::soft_bf16::BF16::from_bits(0x3DCD).to_f32();
+
+        assert_eq!(v0_1_bf16.value(), 0x3DCD);
+        assert_eq!(v0_1_f32.value(), 0x3DCD0000);
+    }
+
+    #[test]
+    fn test_bf16_bits_with_struct() {
+        assert_eq!(crate::soft_bf16::BF16::from_bits(0x3E4D).to_bits(), 0x3E4D);
+        #[allow(deprecated)]
+        let v = crate::soft_bf16::BF16::from_u16(0x3E4D);
+        assert_eq!(v.to_bits(), 0x3E4D);
+    }
+}
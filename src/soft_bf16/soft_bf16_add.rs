@@ -0,0 +1,68 @@
+use super::util::bf16_widen;
+use super::soft_bf16_conv::f32_to_bf16_with_env;
+
+use crate::soft_f32::f32_add_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn bf16_add(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| bf16_add_with_env(a, b, env))
+}
+
+// Add as Float32, the result is rounded only once
+pub fn bf16_add_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = bf16_widen(a);
+    let b32 = bf16_widen(b);
+
+    f32_round_to_narrow(|env| f32_add_with_env(a32, b32, env), f32_to_bf16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_bf16_add() {
+        // 0.1 + 0.2 = 0.3
+        assert_eq!(crate::soft_bf16::bf16_add(0x3DCD, 0x3E4D), 0x3E9A);
+        // 1 + 2 = 3
+        assert_eq!(crate::soft_bf16::bf16_add(0x3F80, 0x4000), 0x4040);
+        // Subnormals
+        assert_eq!(crate::soft_bf16::bf16_add(0x0001, 0x0001), 0x0002);
+    }
+
+    #[test]
+    fn test_bf16_add_inf_nan() {
+        // Inf + 1 = Inf
+        assert_eq!(crate::soft_bf16::bf16_add(0x7F80, 0x3F80), 0x7F80);
+        // -Inf + Inf = NaN
+        assert_eq!(crate::soft_bf16::bf16_add(0x7F80 | 0x8000, 0x7F80), 0xFFC0);
+
+        // A signaling NaN is quiet and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x7F81, 0x3F80, &mut env), 0x7FC1);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_bf16_add_rounding_mode() {
+        // 1 + 2^-8, a tie rounded once
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x3F80, 0x3B80, &mut Env::new(RoundingMode::NearEven)), 0x3F80);
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x3F80, 0x3B80, &mut Env::new(RoundingMode::MinMag)), 0x3F80);
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x3F80, 0x3B80, &mut Env::new(RoundingMode::Min)), 0x3F80);
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x3F80, 0x3B80, &mut Env::new(RoundingMode::Max)), 0x3F81);
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x3F80, 0x3B80, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F81);
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x3F80, 0x3B80, &mut Env::new(RoundingMode::Odd)), 0x3F81);
+
+        // MAX + MAX, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x7F7F, 0x7F7F, &mut env), 0x7F80);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        let mut env = Env::new(RoundingMode::MinMag);
+        assert_eq!(crate::soft_bf16::bf16_add_with_env(0x7F7F, 0x7F7F, &mut env), 0x7F7F);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+    }
+}
@@ -0,0 +1,109 @@
+use super::util::bf16_widen;
+
+use crate::soft_f32::{
+    f32_eq_with_env,
+    f32_ne_with_env,
+    f32_lt_with_env,
+    f32_gt_with_env,
+    f32_le_with_env,
+    f32_ge_with_env,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+// Widening to Float32 is exact and keeps signaling NaNs, so comparing there is the same
+
+pub fn bf16_eq(a: u16, b: u16) -> bool {
+    with_thread_env(|env| bf16_eq_with_env(a, b, env))
+}
+
+pub fn bf16_eq_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_eq_with_env(bf16_widen(a), bf16_widen(b), env)
+}
+
+pub fn bf16_ne(a: u16, b: u16) -> bool {
+    with_thread_env(|env| bf16_ne_with_env(a, b, env))
+}
+
+pub fn bf16_ne_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_ne_with_env(bf16_widen(a), bf16_widen(b), env)
+}
+
+pub fn bf16_lt(a: u16, b: u16) -> bool {
+    with_thread_env(|env| bf16_lt_with_env(a, b, env))
+}
+
+pub fn bf16_lt_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_lt_with_env(bf16_widen(a), bf16_widen(b), env)
+}
+
+pub fn bf16_gt(a: u16, b: u16) -> bool {
+    with_thread_env(|env| bf16_gt_with_env(a, b, env))
+}
+
+pub fn bf16_gt_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_gt_with_env(bf16_widen(a), bf16_widen(b), env)
+}
+
+pub fn bf16_le(a: u16, b: u16) -> bool {
+    with_thread_env(|env| bf16_le_with_env(a, b, env))
+}
+
+pub fn bf16_le_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_le_with_env(bf16_widen(a), bf16_widen(b), env)
+}
+
+pub fn bf16_ge(a: u16, b: u16) -> bool {
+    with_thread_env(|env| bf16_ge_with_env(a, b, env))
+}
+
+pub fn bf16_ge_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_ge_with_env(bf16_widen(a), bf16_widen(b), env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_bf16_compare() {
+        // 0.1 and 0.2
+        assert_eq!(crate::soft_bf16::bf16_eq(0x3DCD, 0x3E4D), false);
+        assert_eq!(crate::soft_bf16::bf16_ne(0x3DCD, 0x3E4D), true);
+        assert_eq!(crate::soft_bf16::bf16_lt(0x3DCD, 0x3E4D), true);
+        assert_eq!(crate::soft_bf16::bf16_le(0x3DCD, 0x3E4D), true);
+        assert_eq!(crate::soft_bf16::bf16_gt(0x3DCD, 0x3E4D), false);
+        assert_eq!(crate::soft_bf16::bf16_ge(0x3DCD, 0x3E4D), false);
+
+        // 0 and -0
+        assert_eq!(crate::soft_bf16::bf16_eq(0x0000, 0x8000), true);
+        assert_eq!(crate::soft_bf16::bf16_lt(0x8000, 0x0000), false);
+
+        // Subnormals
+        assert_eq!(crate::soft_bf16::bf16_lt(0x0001, 0x0002), true);
+
+        // NaN is unordered
+        assert_eq!(crate::soft_bf16::bf16_eq(0x7FC1, 0x7FC1), false);
+        assert_eq!(crate::soft_bf16::bf16_ne(0x7FC1, 0x7FC1), true);
+        assert_eq!(crate::soft_bf16::bf16_ge(0x7FC1, 0x3F80), false);
+    }
+
+    #[test]
+    fn test_bf16_compare_exception_flags() {
+        // Equality with a quiet NaN is quiet
+        let mut env = Env::default();
+        crate::soft_bf16::bf16_eq_with_env(0x7FC1, 0x3F80, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Equality with a signaling NaN is invalid
+        let mut env = Env::default();
+        crate::soft_bf16::bf16_eq_with_env(0x7F81, 0x3F80, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Ordering with any NaN is invalid
+        let mut env = Env::default();
+        crate::soft_bf16::bf16_lt_with_env(0x7FC1, 0x3F80, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,146 @@
+use super::util::{
+    bf16_round_and_pack,
+    bf16_pack_raw,
+    bf16_is_nan,
+    bf16_widen,
+};
+
+use crate::soft_f32::util::{
+    f32_shift_right_jam,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_convert_nan,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn bf16_to_f32(a: u16) -> u32 {
+    with_thread_env(|env| bf16_to_f32_with_env(a, env))
+}
+
+// Every BFloat16 is exactly representable as a Float32
+pub fn bf16_to_f32_with_env(a: u16, env: &mut Env) -> u32 {
+    if bf16_is_nan(a) {
+        // NaN, as chosen by the NaN policy
+        let a32 = bf16_widen(a);
+        return f32_convert_nan(f32_sign(a32), (a32 & 0x00400000) != 0, a32 & 0x003FFFFF, env);
+    }
+
+    bf16_widen(a)
+}
+
+pub fn f32_to_bf16(a: u32) -> u16 {
+    with_thread_env(|env| f32_to_bf16_with_env(a, env))
+}
+
+pub fn f32_to_bf16_with_env(a: u32, env: &mut Env) -> u16 {
+    let sign = f32_sign(a);
+    let mut exp = f32_exp(a);
+    let mut frac = f32_frac(a);
+
+    if exp == 0xFF {
+        if frac != 0 {
+            // NaN, as chosen by the NaN policy, with the high bits of its payload
+            let nan = f32_convert_nan(sign, (frac & 0x00400000) != 0, (frac & 0x003FFFFF) as u32, env);
+            return match f32_frac(nan) >> 16 {
+                // A MIPS legacy quiet NaN whose payload is too low for BFloat16
+                0 => bf16_pack_raw(f32_sign(nan), 0xFF, 0x003F),
+                nan_frac => bf16_pack_raw(f32_sign(nan), 0xFF, nan_frac),
+            };
+        }
+        // Infinity
+        return bf16_pack_raw(sign, 0xFF, 0);
+    }
+
+    if exp == 0 {
+        if frac == 0 {
+            // Zero
+            return bf16_pack_raw(sign, 0, 0);
+        }
+        // Both formats share the exponent range, so a subnormal stays subnormal
+        let (norm_exp, norm_frac) = f32_norm_subnormal_frac(frac);
+        exp = norm_exp;
+        frac = norm_frac;
+    }
+
+    // Keep 14 bits of the significand, the rest is sticky
+    let frac16 = f32_shift_right_jam(frac | 0x00800000, 9);
+    bf16_round_and_pack(sign, exp - 1, frac16, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_bf16_to_f32() {
+        // 1
+        assert_eq!(crate::soft_bf16::bf16_to_f32(0x3F80), 0x3F800000);
+        // 0.1
+        assert_eq!(crate::soft_bf16::bf16_to_f32(0x3DCD), 0x3DCD0000);
+        // Subnormal
+        assert_eq!(crate::soft_bf16::bf16_to_f32(0x0001), 0x00010000);
+        // -Inf
+        assert_eq!(crate::soft_bf16::bf16_to_f32(0xFF80), 0xFF800000);
+
+        // A signaling NaN is quiet and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_to_f32_with_env(0x7F81, &mut env), 0x7FC10000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_to_bf16() {
+        // 1
+        assert_eq!(crate::soft_bf16::f32_to_bf16(0x3F800000), 0x3F80);
+        // 0.1
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3DCD);
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3DCC);
+        // 1 + 2^-8, a tie
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x3F808000, &mut Env::new(RoundingMode::NearEven)), 0x3F80);
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x3F808000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F81);
+        // NaN
+        assert_eq!(crate::soft_bf16::f32_to_bf16(0xFFC00000), 0xFFC0);
+
+        // The largest Float32 rounds to infinity
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x7F7FFFFF, &mut env), 0x7F80);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // Subnormals are rounded as well
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x00018000, &mut env), 0x0002);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_bf16_conv_nan_policy() {
+        // The default NaN replaces every NaN, and a signaling NaN is invalid
+        for &nan_policy in [NanPolicy::RiscV, NanPolicy::Armv8DefaultNan].iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            assert_eq!(crate::soft_bf16::bf16_to_f32_with_env(0x7FC1, &mut env), 0x7FC00000);
+            assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0xFFC10000, &mut env), 0x7FC0);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+            assert_eq!(crate::soft_bf16::bf16_to_f32_with_env(0xFF81, &mut env), 0x7FC00000);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+            env.clear_flags();
+            assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x7F800001, &mut env), 0x7FC0);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+
+        // The quiet bit is inverted in MIPS legacy
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_bf16::bf16_to_f32_with_env(0x7F81, &mut env), 0x7F810000);
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0xFF810000, &mut env), 0xFF81);
+        // A payload too low for BFloat16 gives the default NaN
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x7F800001, &mut env), 0x7FBF);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_bf16::bf16_to_f32_with_env(0x7FC0, &mut env), 0x7FBFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        env.clear_flags();
+        assert_eq!(crate::soft_bf16::f32_to_bf16_with_env(0x7FC00000, &mut env), 0x7FBF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,48 @@
+use super::util::bf16_widen;
+use super::soft_bf16_conv::f32_to_bf16_with_env;
+
+use crate::soft_f32::f32_div_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn bf16_div(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| bf16_div_with_env(a, b, env))
+}
+
+// Divide as Float32, the result is rounded only once
+pub fn bf16_div_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = bf16_widen(a);
+    let b32 = bf16_widen(b);
+
+    f32_round_to_narrow(|env| f32_div_with_env(a32, b32, env), f32_to_bf16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_bf16_div() {
+        // 0.1 / 0.2 = 0.5
+        assert_eq!(crate::soft_bf16::bf16_div(0x3DCD, 0x3E4D), 0x3F00);
+        // 3 / -1 = -3
+        assert_eq!(crate::soft_bf16::bf16_div(0x4040, 0xBF80), 0x4040 | 0x8000);
+        // 0 / 0 = NaN
+        assert_eq!(crate::soft_bf16::bf16_div(0x0000, 0x0000), 0xFFC0);
+    }
+
+    #[test]
+    fn test_bf16_div_rounding_mode() {
+        // 1 / 3
+        assert_eq!(crate::soft_bf16::bf16_div_with_env(0x3F80, 0x4040, &mut Env::new(RoundingMode::NearEven)), 0x3EAB);
+        assert_eq!(crate::soft_bf16::bf16_div_with_env(0x3F80, 0x4040, &mut Env::new(RoundingMode::MinMag)), 0x3EAA);
+        assert_eq!(crate::soft_bf16::bf16_div_with_env(0x3F80, 0x4040, &mut Env::new(RoundingMode::Max)), 0x3EAB);
+
+        // 1 / 0 = Inf
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_div_with_env(0x3F80, 0x0000, &mut env), 0x7F80);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+    }
+}
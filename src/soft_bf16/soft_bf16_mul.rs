@@ -0,0 +1,48 @@
+use super::util::bf16_widen;
+use super::soft_bf16_conv::f32_to_bf16_with_env;
+
+use crate::soft_f32::f32_mul_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn bf16_mul(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| bf16_mul_with_env(a, b, env))
+}
+
+// Multiply as Float32, the result is rounded only once
+pub fn bf16_mul_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = bf16_widen(a);
+    let b32 = bf16_widen(b);
+
+    f32_round_to_narrow(|env| f32_mul_with_env(a32, b32, env), f32_to_bf16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_bf16_mul() {
+        // 0.1 * 0.2 = 0.02
+        assert_eq!(crate::soft_bf16::bf16_mul(0x3DCD, 0x3E4D), 0x3CA4);
+        // -1 * 3 = -3
+        assert_eq!(crate::soft_bf16::bf16_mul(0xBF80, 0x4040), 0x4040 | 0x8000);
+        // 0 * Inf = NaN
+        assert_eq!(crate::soft_bf16::bf16_mul(0x0000, 0x7F80), 0xFFC0);
+    }
+
+    #[test]
+    fn test_bf16_mul_underflow() {
+        // Half of the smallest subnormal is a tie, rounded to zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_mul_with_env(0x0001, 0x3F00, &mut env), 0x0000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // 1.5 times the smallest subnormal is a tie, rounded to even
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_mul_with_env(0x0003, 0x3F00, &mut env), 0x0002);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+}
@@ -0,0 +1,48 @@
+use super::util::bf16_widen;
+use super::soft_bf16_conv::f32_to_bf16_with_env;
+
+use crate::soft_f32::f32_sqrt_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn bf16_sqrt(a: u16) -> u16 {
+    with_thread_env(|env| bf16_sqrt_with_env(a, env))
+}
+
+// Square root as Float32, the result is rounded only once
+pub fn bf16_sqrt_with_env(a: u16, env: &mut Env) -> u16 {
+    let a32 = bf16_widen(a);
+
+    f32_round_to_narrow(|env| f32_sqrt_with_env(a32, env), f32_to_bf16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_bf16_sqrt() {
+        // sqrt(4) = 2
+        assert_eq!(crate::soft_bf16::bf16_sqrt(0x4080), 0x4000);
+        // sqrt(-0) = -0
+        assert_eq!(crate::soft_bf16::bf16_sqrt(0x8000), 0x8000);
+        // sqrt(Inf) = Inf
+        assert_eq!(crate::soft_bf16::bf16_sqrt(0x7F80), 0x7F80);
+    }
+
+    #[test]
+    fn test_bf16_sqrt_rounding_mode() {
+        // sqrt(2)
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_sqrt_with_env(0x4000, &mut env), 0x3FB5);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_bf16::bf16_sqrt_with_env(0x4000, &mut Env::new(RoundingMode::Max)), 0x3FB6);
+
+        // sqrt(-1) = NaN
+        let mut env = Env::default();
+        assert_eq!(crate::soft_bf16::bf16_sqrt_with_env(0xBF80, &mut env), 0xFFC0);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,45 @@
+use super::util::bf16_widen;
+use super::soft_bf16_conv::f32_to_bf16_with_env;
+
+use crate::soft_f32::f32_sub_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn bf16_sub(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| bf16_sub_with_env(a, b, env))
+}
+
+// Subtract as Float32, the result is rounded only once
+pub fn bf16_sub_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = bf16_widen(a);
+    let b32 = bf16_widen(b);
+
+    f32_round_to_narrow(|env| f32_sub_with_env(a32, b32, env), f32_to_bf16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, RoundingMode };
+
+    #[test]
+    fn test_bf16_sub() {
+        // 0.2 - 0.1 = 0.1
+        assert_eq!(crate::soft_bf16::bf16_sub(0x3E4D, 0x3DCD), 0x3DCD);
+        // 2 - 3 = -1
+        assert_eq!(crate::soft_bf16::bf16_sub(0x4000, 0x4040), 0xBF80);
+        // Inf - Inf = NaN
+        assert_eq!(crate::soft_bf16::bf16_sub(0x7F80, 0x7F80), 0xFFC0);
+    }
+
+    #[test]
+    fn test_bf16_sub_zero_sign() {
+        // 1 - 1 is -0 only when rounding toward -Inf
+        assert_eq!(crate::soft_bf16::bf16_sub_with_env(0x3F80, 0x3F80, &mut Env::new(RoundingMode::NearEven)), 0x0000);
+        assert_eq!(crate::soft_bf16::bf16_sub_with_env(0x3F80, 0x3F80, &mut Env::new(RoundingMode::Min)), 0x8000);
+        assert_eq!(crate::soft_bf16::bf16_sub_with_env(0x3F80, 0x3F80, &mut Env::new(RoundingMode::Odd)), 0x0000);
+        // -0 - 0 = -0
+        assert_eq!(crate::soft_bf16::bf16_sub_with_env(0x8000, 0x0000, &mut Env::new(RoundingMode::NearEven)), 0x8000);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::soft_float::{ RoundingMode, DetectTininess, Env, ExceptionFlags };
+use crate::soft_f32::util::f32_shift_right_jam;
+
+pub(crate) fn bf16_pack_raw(in_sign: i32, in_exp: i32, in_frac: i32) -> u16 {
+    // Use + rather than |, so that a carry out of the fraction increments the exponent
+    ((in_sign << 15) | ((in_exp << 7) + in_frac)) as u16
+}
+
+pub fn bf16_is_nan(a: u16) -> bool {
+    let is_exp_nan = (a & 0x7F80) == 0x7F80;
+    let is_frac_nan = (a & 0x007F) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// A signaling NaN has the most significant bit of its fraction cleared
+pub fn bf16_is_signaling_nan(a: u16) -> bool {
+    let is_exp_nan = (a & 0x7FC0) == 0x7F80;
+    let is_frac_nan = (a & 0x003F) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+pub fn bf16_pack(in_sign: i32, in_exp: i32, in_frac: i32) -> u16 {
+    (((in_sign & 0x01) << 15) | ((in_exp & 0xFF) << 7) | (in_frac & 0x007F)) as u16
+}
+
+// Round the significand, where the hidden bit is bit 14.
+// The exponent is one less than the biased exponent of the result, as the hidden bit is added to it.
+pub(crate) fn bf16_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i32, env: &mut Env) -> u16 {
+    let rounding_mode = env.rounding_mode();
+    let detect_tininess = env.detect_tininess();
+
+    let round_near_even = rounding_mode == RoundingMode::NearEven;
+    let mut round_increment = 0x40;
+
+    let sign = in_sign;
+    let mut exp = in_exp;
+    let mut frac = in_frac;
+
+    match rounding_mode {
+        RoundingMode::NearEven | RoundingMode::NearMaxMag => { /* Do nothing */ }
+        _ => {
+            // Round away from zero only when rounding toward the infinity of the same sign
+            let round_away = match rounding_mode {
+                RoundingMode::Min => sign == 1,
+                RoundingMode::Max => sign == 0,
+                _ => false,
+            };
+            round_increment = if round_away { 0x7F } else { 0 };
+        }
+    }
+    let mut round_bits = frac & 0x7F;
+
+    // A negative exponent is seen as a large unsigned one, so that tiny results are caught here as well
+    if (exp as u32) >= 0xFD {
+        if exp < 0 {
+            // Tiny result, denormalize it before rounding
+            let is_tiny = detect_tininess == DetectTininess::Before
+                || exp < -1
+                || frac + round_increment < 0x8000;
+            frac = f32_shift_right_jam(frac, -exp);
+            exp = 0;
+            round_bits = frac & 0x7F;
+            if is_tiny && round_bits != 0 {
+                // Underflow
+                env.raise(ExceptionFlags::UNDERFLOW);
+            }
+        } else if exp > 0xFD || frac + round_increment >= 0x8000 {
+            // Overflow and inexact, round to infinity or to the largest finite value
+            env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+            return bf16_pack_raw(sign, 0xFF, 0) - (round_increment == 0) as u16;
+        }
+    }
+
+    let mut r_frac = (frac + round_increment) >> 7;
+
+    if round_bits != 0 {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    if round_bits != 0 && rounding_mode == RoundingMode::Odd {
+        // Inexact, jam the lost bits into the least significant bit
+        r_frac |= 1;
+    } else if round_near_even && round_bits == 0x40 {
+        // Tie, round to even
+        r_frac &= !1;
+    }
+    if r_frac == 0 { exp = 0; }
+
+    bf16_pack_raw(sign, exp, r_frac)
+}
+
+// Widen to Float32 exactly, a signaling NaN is kept signaling.
+// A BFloat16 is the high half of a Float32.
+pub(crate) fn bf16_widen(a: u16) -> u32 {
+    (a as u32) << 16
+}
@@ -0,0 +1,287 @@
+mod soft_f128_add;
+mod soft_f128_sub;
+mod soft_f128_mul;
+mod soft_f128_div;
+mod soft_f128_mul_add;
+mod soft_f128_comp;
+mod soft_f128_sqrt;
+mod soft_f128_round;
+mod soft_f128_conv;
+pub(crate) mod util;
+
+// The procedural API works on raw u128 bit patterns of IEEE 754 binary128. Each
+// function uses the environment of the current thread, and has a `_with_env`
+// variant which takes an explicit environment.
+
+// Operations
+pub use soft_f128_add::{ f128_add, f128_add_with_env };
+pub use soft_f128_sub::{ f128_sub, f128_sub_with_env };
+pub use soft_f128_mul::{ f128_mul, f128_mul_with_env };
+pub use soft_f128_div::{ f128_div, f128_div_with_env };
+pub use soft_f128_mul_add::{ f128_mul_add, f128_mul_add_with_env };
+
+// Comparisons
+pub use soft_f128_comp::{ f128_eq, f128_eq_with_env };
+pub use soft_f128_comp::{ f128_ne, f128_ne_with_env };
+pub use soft_f128_comp::{ f128_lt, f128_lt_with_env };
+pub use soft_f128_comp::{ f128_gt, f128_gt_with_env };
+pub use soft_f128_comp::{ f128_le, f128_le_with_env };
+pub use soft_f128_comp::{ f128_ge, f128_ge_with_env };
+
+// Others
+pub use soft_f128_sqrt::{ f128_sqrt, f128_sqrt_with_env };
+pub use soft_f128_round::{ f128_round, f128_round_with_env };
+
+// Conversions
+pub use soft_f128_round::{ from_int32, from_int32_with_env };
+pub use soft_f128_round::{ from_int64, from_int64_with_env };
+pub use soft_f128_round::{ to_int32, to_int32_with_env };
+pub use soft_f128_round::{ to_int64, to_int64_with_env };
+pub use soft_f128_conv::{ f32_to_f128, f32_to_f128_with_env };
+pub use soft_f128_conv::{ f128_to_f32, f128_to_f32_with_env };
+
+// Utilities
+pub use util::{
+    f128_is_nan,
+    f128_is_signaling_nan,
+    f128_pack,
+};
+
+// F128 struct
+#[derive(Clone, Copy, Debug)]
+pub struct F128 {
+    value: u128,
+}
+
+impl F128 {
+    pub const fn from_bits(bits: u128) -> F128 {
+        F128 {
+            value: bits
+        }
+    }
+
+    pub fn to_bits(self) -> u128 {
+        self.value
+    }
+
+    #[deprecated(note = "use from_bits, as F32::from_bits")]
+    pub const fn from_u128(value: u128) -> F128 {
+        F128::from_bits(value)
+    }
+
+    // The bit pattern, as to_bits
+    pub fn value(self) -> u128 {
+        self.value
+    }
+
+    pub fn sqrt(self) -> Self {
+        F128 {
+            value: f128_sqrt(self.value)
+        }
+    }
+
+    // Compute self x a + b with a single rounding
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        F128 {
+            value: f128_mul_add(self.value, a.value, b.value)
+        }
+    }
+
+    pub fn from_f32(value: F32) -> F128 {
+        F128 {
+            value: f32_to_f128(value.value())
+        }
+    }
+
+    pub fn to_f32(self) -> F32 {
+        F32::from_bits(f128_to_f32(self.value))
+    }
+}
+
+use crate::soft_f32::F32;
+use std::ops;
+
+impl ops::Add<F128> for F128 {
+    type Output = F128;
+
+    fn add(self, other: F128) -> F128 {
+        F128 {
+            value: f128_add(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Sub<F128> for F128 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        F128 {
+            value: f128_sub(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Mul<F128> for F128 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        F128 {
+            value: f128_mul(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Div<F128> for F128 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        F128 {
+            value: f128_div(self.value, other.value)
+        }
+    }
+}
+
+use std::cmp;
+
+impl cmp::PartialEq for F128 {
+    // Implement equal with only symmetric and transitive for F128.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
+    // For example, in floating point numbers NaN != NaN,
+    // so floating point types implement PartialEq but not Eq.
+    fn eq(&self, other: &Self) -> bool {
+        f128_eq(self.value, other.value)
+    }
+
+    #[allow(clippy::partialeq_ne_impl)]
+    fn ne(&self, other: &Self) -> bool {
+        f128_ne(self.value, other.value)
+    }
+}
+
+impl cmp::PartialOrd for F128 {
+    // Implement compare with only symmetric and transitive for F128.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if f128_eq(self.value, other.value) { return Some(cmp::Ordering::Equal); }
+        if f128_lt(self.value, other.value) { return Some(cmp::Ordering::Less); }
+        if f128_gt(self.value, other.value) { return Some(cmp::Ordering::Greater); }
+
+        None
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        f128_gt(self.value, other.value)
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        f128_lt(self.value, other.value)
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        f128_ge(self.value, other.value)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        f128_le(self.value, other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_f128_add_with_struct() {
+        let v0_1 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A);
+        let v0_2 = crate::soft_f128::F128::from_bits(0x3FFC999999999999999999999999999A);
+
+        let v0_3 = v0_1 + v0_2;
+
+        assert_eq!(v0_3.value(), 0x3FFD3333333333333333333333333334);
+    }
+
+    #[test]
+    fn test_f128_sub_with_struct() {
+        let v0_1 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A);
+        let v0_2 = crate::soft_f128::F128::from_bits(0x3FFC999999999999999999999999999A);
+
+        let v0_1_result = v0_2 - v0_1;
+
+        assert_eq!(v0_1_result.value(), 0x3FFB999999999999999999999999999A);
+    }
+
+    #[test]
+    fn test_f128_mul_with_struct() {
+        let v0_1 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A);
+        let v0_2 = crate::soft_f128::F128::from_bits(0x3FFC999999999999999999999999999A);
+
+        let v0_02 = v0_2 * v0_1;
+
+        assert_eq!(v0_02.value(), 0x3FF947AE147AE147AE147AE147AE147C);
+    }
+
+    #[test]
+    fn test_f128_div_with_struct() {
+        let v0_1 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A);
+        let v0_2 = crate::soft_f128::F128::from_bits(0x3FFC999999999999999999999999999A);
+
+        let v0_5 = v0_1 / v0_2;
+
+        assert_eq!(v0_5.value(), 0x3FFE0000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_compare_with_struct() {
+        let v0_1 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A);
+        let v0_2 = crate::soft_f128::F128::from_bits(0x3FFC999999999999999999999999999A);
+
+        assert_eq!(v0_1 == v0_2, false);
+        assert_eq!(v0_1 != v0_2, true);
+
+        assert_eq!(v0_1 < v0_2, true);
+        assert_eq!(v0_1 <= v0_1, true);
+        assert_eq!(v0_1 > v0_2, false);
+        assert_eq!(v0_1 >= v0_1, true);
+    }
+
+    #[test]
+    fn test_f128_sqrt_with_struct() {
+        // sqrt(4) = 2
+        let v4 = crate::soft_f128::F128::from_bits(0x40010000000000000000000000000000);
+
+        let v2 = v4.sqrt();
+
+        assert_eq!(v2.value(), 0x40000000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_mul_add_with_struct() {
+        let v0_1 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A);
+        let v10 = crate::soft_f128::F128::from_bits(0x40024000000000000000000000000000);
+        let v_1 = crate::soft_f128::F128::from_bits(0xBFFF0000000000000000000000000000);
+
+        // 0.1 * 10 - 1, rounded once
+        let r = v0_1.mul_add(v10, v_1);
+
+        assert_eq!(r.value(), 0x3F8D0000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_f32_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+
+        let v0_1_f128 = crate::soft_f128::F128::from_f32(v0_1);
+        let v0_1_f32 = crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A).to_f32();
+
+        assert_eq!(v0_1_f128.value(), 0x3FFB99999A0000000000000000000000);
+        assert_eq!(v0_1_f32.value(), 0x3DCCCCCD);
+    }
+
+    #[test]
+    fn test_f128_bits_with_struct() {
+        assert_eq!(crate::soft_f128::F128::from_bits(0x3FFB999999999999999999999999999A).to_bits(), 0x3FFB999999999999999999999999999A);
+        #[allow(deprecated)]
+        let v = crate::soft_f128::F128::from_u128(0x3FFB999999999999999999999999999A);
+        assert_eq!(v.to_bits(), 0x3FFB999999999999999999999999999A);
+    }
+}
@@ -0,0 +1,232 @@
+use super::util::{
+    f128_shift_right_jam_extra,
+    f128_round_and_pack,
+    f128_pack_raw,
+    f128_propagate_nan,
+    f128_sign, f128_exp, f128_frac,
+    F128_HIDDEN_BIT,
+};
+
+use super::soft_f128_sub::f128_sub_mags;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f128_add(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f128_add_with_env(a, b, env))
+}
+
+pub fn f128_add_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    if f128_sign(a) != f128_sign(b) {
+        // Consider as substraction
+        return f128_sub_mags(a, b, env);
+    }
+
+    f128_add_mags(a, b, env)
+}
+
+// Add the magnitudes of A and B, which have the same sign
+pub(crate) fn f128_add_mags(a: u128, b: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f128_sign(a);
+    let r_sign = a_sign;
+
+    // Exp
+    let a_exp = f128_exp(a);
+    let b_exp = f128_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f128_frac(a);
+    let mut b_frac = f128_frac(b);
+    let mut r_frac;
+    let mut r_frac_extra = 0;
+
+    let mut diff_exp = a_exp - b_exp;
+
+    if diff_exp == 0 {
+        if a_exp == 0x7FFF {
+            if (a_frac | b_frac) != 0 {
+                // Propagate NaN
+                return f128_propagate_nan(a, b, env);
+            } else {
+                // Infinity
+                return a;
+            }
+        }
+
+        r_frac = a_frac + b_frac;
+        if a_exp == 0 {
+            // Subnormal, a carry makes a normal number
+            return f128_pack_raw(r_sign, 0, r_frac);
+        }
+
+        // Both hidden bits, the sum needs one more bit
+        r_exp = a_exp;
+        r_frac |= F128_HIDDEN_BIT << 1;
+    } else {
+        if diff_exp < 0 {
+            // a_exp < b_exp
+            if b_exp == 0x7FFF {
+                if b_frac != 0 {
+                    // Propagate NaN
+                    return f128_propagate_nan(a, b, env);
+                } else {
+                    // Infinity
+                    return f128_pack_raw(r_sign, 0x7FFF, 0);
+                }
+            }
+
+            r_exp = b_exp;
+
+            // Prepare the Fraction of A to do an addition with B
+            if a_exp != 0 {
+                a_frac |= F128_HIDDEN_BIT;
+            } else {
+                diff_exp += 1;
+            }
+            let (frac, frac_extra) = f128_shift_right_jam_extra(a_frac, 0, -diff_exp);
+            a_frac = frac;
+            r_frac_extra = frac_extra;
+        } else {
+            // a_exp > b_exp, a_exp == b_exp is considered in the other case
+            if a_exp == 0x7FFF {
+                if a_frac != 0 {
+                    // Propagate NaN
+                    return f128_propagate_nan(a, b, env);
+                } else {
+                    // Infinity
+                    return a;
+                }
+            }
+
+            r_exp = a_exp;
+
+            // Prepare the Fraction of B to do an addition with A
+            if b_exp != 0 {
+                b_frac |= F128_HIDDEN_BIT;
+            } else {
+                diff_exp -= 1;
+            }
+            let (frac, frac_extra) = f128_shift_right_jam_extra(b_frac, 0, diff_exp);
+            b_frac = frac;
+            r_frac_extra = frac_extra;
+        }
+
+        // The shifted fraction has no hidden bit at its place, so the one of the other is added here
+        r_frac = (a_frac | F128_HIDDEN_BIT) + b_frac;
+        r_exp -= 1;
+        if r_frac < (F128_HIDDEN_BIT << 1) {
+            return f128_round_and_pack(r_sign, r_exp, r_frac, r_frac_extra, env);
+        }
+        r_exp += 1;
+    }
+
+    // The sum carries into the bit above the hidden bit, shift it back
+    let (frac, frac_extra) = f128_shift_right_jam_extra(r_frac, r_frac_extra, 1);
+
+    f128_round_and_pack(r_sign, r_exp, frac, frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f128_add() {
+        // 0.1 + 0.2 = 0.3
+        assert_eq!(crate::soft_f128::f128_add(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), 0x3FFD3333333333333333333333333334);
+        // -0.1 + -0.2 = -0.3
+        assert_eq!(crate::soft_f128::f128_add(0xBFFB999999999999999999999999999A, 0xBFFC999999999999999999999999999A), 0xBFFD3333333333333333333333333334);
+
+        // 1 + 2 = 3
+        assert_eq!(crate::soft_f128::f128_add(0x3FFF0000000000000000000000000000, 0x40000000000000000000000000000000), 0x40008000000000000000000000000000);
+        // -0.1 + 0.2 = 0.1
+        assert_eq!(crate::soft_f128::f128_add(0xBFFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), 0x3FFB999999999999999999999999999A);
+
+        // Subnormals
+        assert_eq!(crate::soft_f128::f128_add(0x00000000000000000000000000000001, 0x00000000000000000000000000000001), 0x00000000000000000000000000000002);
+    }
+
+    #[test]
+    fn test_f128_add_inf_nan() {
+        // Inf + 1 = Inf
+        assert_eq!(crate::soft_f128::f128_add(0x7FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000), 0x7FFF0000000000000000000000000000);
+        // -Inf + Inf = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_add(0xFFFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000)), true);
+        // NaN + 1 = NaN
+        assert_eq!(crate::soft_f128::f128_add(0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x3FFF0000000000000000000000000000), 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF);
+
+        assert_eq!(crate::soft_f128::f128_add(0x0, 0xBFFB999999999999999999999999999A), 0xBFFB999999999999999999999999999A);
+        assert_eq!(crate::soft_f128::f128_add(0x0, 0x80000000000000000000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f128_add_rounding_mode() {
+        // 0.1 + 0.2
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x3FFD3333333333333333333333333334);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::MinMag)), 0x3FFD3333333333333333333333333333);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::Min)), 0x3FFD3333333333333333333333333333);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::Max)), 0x3FFD3333333333333333333333333334);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FFD3333333333333333333333333334);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::Odd)), 0x3FFD3333333333333333333333333333);
+
+        // 1 + 2^-113, a tie
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFF0000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FFF0000000000000000000000000000);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFF0000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFF0000000000000000000000000000);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFF0000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FFF0000000000000000000000000001);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFF0000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FFF0000000000000000000000000001);
+
+        // MAX + MAX, overflow
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, &mut Env::new(RoundingMode::NearEven)), 0x7FFF0000000000000000000000000000);
+        assert_eq!(crate::soft_f128::f128_add_with_env(0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, &mut Env::new(RoundingMode::MinMag)), 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF);
+    }
+
+    #[test]
+    fn test_f128_add_exception_flags() {
+        // 0.1 + 0.2 is inexact
+        let mut env = Env::default();
+        crate::soft_f128::f128_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // MAX + MAX overflows
+        let mut env = Env::default();
+        crate::soft_f128::f128_add_with_env(0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // -Inf + Inf is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_add_with_env(0xFFFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f128_add_nan_policy() {
+        // (qNaN A, qNaN B with a larger payload, sNaN, 1 + sNaN, sNaN + qNaN B, inf - inf) in each policy
+        let cases = [
+            (NanPolicy::X87, 0xFFFF8000000000000000000000000002, 0x7FFF8000000000000000000000000003, 0xFFFF8000000000000000000000000000, 0x7FFF8000000000000000000000000002),
+            (NanPolicy::X86Sse, 0x7FFF8000000000000000000000000001, 0x7FFF8000000000000000000000000003, 0xFFFF8000000000000000000000000000, 0x7FFF8000000000000000000000000003),
+            (NanPolicy::Armv8, 0x7FFF8000000000000000000000000001, 0x7FFF8000000000000000000000000003, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000003),
+            (NanPolicy::Armv8DefaultNan, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000),
+            (NanPolicy::RiscV, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000),
+            (NanPolicy::Wasm, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000),
+        ];
+        for &(nan_policy, qnan_qnan, one_snan, inf_inf, snan_qnan) in cases.iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+
+            assert_eq!(crate::soft_f128::f128_add_with_env(0x7FFF8000000000000000000000000001, 0xFFFF8000000000000000000000000002, &mut env), qnan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+
+            assert_eq!(crate::soft_f128::f128_add_with_env(0x3FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000003, &mut env), one_snan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f128::f128_add_with_env(0x7FFF0000000000000000000000000000, 0xFFFF0000000000000000000000000000, &mut env), inf_inf);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f128::f128_add_with_env(0x7FFF0000000000000000000000000003, 0x7FFF8000000000000000000000000002, &mut env), snan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use super::util::{
+    f128_sign,
+    f128_is_nan,
+    f128_is_signaling_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Equality comparisons are quiet, only a signaling NaN is invalid
+fn f128_is_unordered_quiet(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_nan(a) || f128_is_nan(b) {
+        if f128_is_signaling_nan(a) || f128_is_signaling_nan(b) {
+            env.raise(ExceptionFlags::INVALID);
+        }
+        return true;
+    }
+    false
+}
+
+// Relational comparisons signal, any NaN is invalid
+fn f128_is_unordered_signaling(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_nan(a) || f128_is_nan(b) {
+        env.raise(ExceptionFlags::INVALID);
+        return true;
+    }
+    false
+}
+
+pub fn f128_ne(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f128_ne_with_env(a, b, env))
+}
+
+pub fn f128_ne_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_unordered_quiet(a, b, env) {
+        // Unable to compare, so they are not equal
+        return true;
+    }
+
+    !f128_eq_ordered(a, b)
+}
+
+pub fn f128_eq(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f128_eq_with_env(a, b, env))
+}
+
+pub fn f128_eq_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_unordered_quiet(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f128_eq_ordered(a, b)
+}
+
+fn f128_eq_ordered(a: u128, b: u128) -> bool {
+    a == b || ((a | b) << 1) == 0
+}
+
+pub fn f128_ge(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f128_ge_with_env(a, b, env))
+}
+
+pub fn f128_ge_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    !f128_lt_ordered(a, b)
+}
+
+pub fn f128_lt(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f128_lt_with_env(a, b, env))
+}
+
+pub fn f128_lt_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f128_lt_ordered(a, b)
+}
+
+fn f128_lt_ordered(a: u128, b: u128) -> bool {
+    // Sign
+    let a_sign = f128_sign(a);
+    let b_sign = f128_sign(b);
+
+    if a_sign != b_sign {
+        // Different sign, but +0 and -0 are equal
+        if (a | b) << 1 != 0 {
+            return a_sign == 1;
+        }
+    } else {
+        // Same sign
+        if a != b {
+            if a_sign != 0 {
+                return a > b;
+            } else {
+                return a < b;
+            }
+        }
+    }
+    false
+}
+
+pub fn f128_gt(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f128_gt_with_env(a, b, env))
+}
+
+pub fn f128_gt_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    !f128_le_ordered(a, b)
+}
+
+pub fn f128_le(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f128_le_with_env(a, b, env))
+}
+
+pub fn f128_le_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f128_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f128_le_ordered(a, b)
+}
+
+fn f128_le_ordered(a: u128, b: u128) -> bool {
+    // Sign
+    let a_sign = f128_sign(a);
+    let b_sign = f128_sign(b);
+
+    if a_sign != b_sign {
+        // Different sign, +0 and -0 are equal
+        if (a | b) << 1 == 0 || a_sign == 1 {
+            return true;
+        }
+    } else {
+        // Same sign
+        if a == b {
+            return true;
+        }
+
+        if a_sign != 0 {
+            return a > b;
+        } else {
+            return a < b;
+        }
+    }
+    false
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f128_compare() {
+        // 0.1 and 0.2
+        assert_eq!(crate::soft_f128::f128_eq(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), false);
+        assert_eq!(crate::soft_f128::f128_ne(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), true);
+        assert_eq!(crate::soft_f128::f128_lt(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), true);
+        assert_eq!(crate::soft_f128::f128_le(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), true);
+        assert_eq!(crate::soft_f128::f128_gt(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), false);
+        assert_eq!(crate::soft_f128::f128_ge(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), false);
+
+        // -0.1 and -0.2
+        assert_eq!(crate::soft_f128::f128_lt(0xBFFB999999999999999999999999999A, 0xBFFC999999999999999999999999999A), false);
+        assert_eq!(crate::soft_f128::f128_gt(0xBFFB999999999999999999999999999A, 0xBFFC999999999999999999999999999A), true);
+
+        // 0 and -0
+        assert_eq!(crate::soft_f128::f128_eq(0x00000000000000000000000000000000, 0x80000000000000000000000000000000), true);
+        assert_eq!(crate::soft_f128::f128_lt(0x80000000000000000000000000000000, 0x00000000000000000000000000000000), false);
+        assert_eq!(crate::soft_f128::f128_le(0x80000000000000000000000000000000, 0x00000000000000000000000000000000), true);
+
+        // NaN is unordered
+        assert_eq!(crate::soft_f128::f128_eq(0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000), false);
+        assert_eq!(crate::soft_f128::f128_ne(0x7FFF8000000000000000000000000000, 0x7FFF8000000000000000000000000000), true);
+        assert_eq!(crate::soft_f128::f128_lt(0x7FFF8000000000000000000000000000, 0x3FFF0000000000000000000000000000), false);
+        assert_eq!(crate::soft_f128::f128_ge(0x7FFF8000000000000000000000000000, 0x3FFF0000000000000000000000000000), false);
+    }
+
+    #[test]
+    fn test_f128_compare_exception_flags() {
+        // Equality with a quiet NaN is quiet
+        let mut env = Env::default();
+        crate::soft_f128::f128_eq_with_env(0x7FFF8000000000000000000000000000, 0x3FFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Equality with a signaling NaN is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_ne_with_env(0x7FFF0000000000000000000000000001, 0x3FFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Relations with any NaN are invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_lt_with_env(0x7FFF8000000000000000000000000000, 0x3FFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
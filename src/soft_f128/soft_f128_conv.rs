@@ -0,0 +1,159 @@
+use super::util::{
+    f128_shift_right_jam,
+    f128_pack,
+    f128_sign, f128_exp, f128_frac,
+};
+
+use crate::soft_f32::util::{
+    f32_round_and_pack,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_convert_nan,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f32_to_f128(a: u32) -> u128 {
+    with_thread_env(|env| f32_to_f128_with_env(a, env))
+}
+
+// Every Float32 is exactly representable as a Float128
+pub fn f32_to_f128_with_env(a: u32, env: &mut Env) -> u128 {
+    let sign = f32_sign(a);
+    let mut exp = f32_exp(a);
+    let mut frac = f32_frac(a);
+
+    if exp == 0xFF {
+        if frac != 0 {
+            // NaN, as chosen by the NaN policy
+            let nan = f32_convert_nan(sign, (frac & 0x00400000) != 0, (frac & 0x003FFFFF) as u32, env);
+            return f128_pack(f32_sign(nan), 0x7FFF, (f32_frac(nan) as u128) << 89);
+        }
+        // Infinity
+        return f128_pack(sign, 0x7FFF, 0);
+    }
+
+    if exp == 0 {
+        if frac == 0 {
+            // Zero
+            return f128_pack(sign, 0, 0);
+        }
+        let (norm_exp, norm_frac) = f32_norm_subnormal_frac(frac);
+        exp = norm_exp;
+        frac = norm_frac;
+    }
+
+    f128_pack(sign, exp + 0x3F80, (frac as u128) << 89)
+}
+
+pub fn f128_to_f32(a: u128) -> u32 {
+    with_thread_env(|env| f128_to_f32_with_env(a, env))
+}
+
+pub fn f128_to_f32_with_env(a: u128, env: &mut Env) -> u32 {
+    let sign = f128_sign(a);
+    let mut exp = f128_exp(a);
+    let frac = f128_frac(a);
+
+    if exp == 0x7FFF {
+        if frac != 0 {
+            // NaN, as chosen by the NaN policy, with the high bits of its payload
+            let is_quiet_bit = (frac & 0x0000_8000_0000_0000_0000_0000_0000_0000) != 0;
+            return f32_convert_nan(sign, is_quiet_bit, ((frac >> 89) & 0x003FFFFF) as u32, env);
+        }
+        // Infinity
+        return f32_pack_raw(sign, 0xFF, 0);
+    }
+
+    // Keep 30 bits of the fraction, the rest is sticky
+    let frac32 = f128_shift_right_jam(frac, 82) as i32;
+    if exp == 0 && frac32 == 0 {
+        // Zero
+        return f32_pack_raw(sign, 0, 0);
+    }
+
+    // Any Float128 subnormal is far below the Float32 ones, so the missing hidden bit does not matter
+    exp -= 0x3F81;
+    if exp < -0x1000 {
+        exp = -0x1000;
+    }
+    f32_round_and_pack(sign, exp, frac32 | 0x40000000, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f32_to_f128() {
+        // 1
+        assert_eq!(crate::soft_f128::f32_to_f128(0x3F800000), 0x3FFF0000000000000000000000000000);
+        // 0.1
+        assert_eq!(crate::soft_f128::f32_to_f128(0x3DCCCCCD), 0x3FFB99999A0000000000000000000000);
+        // The smallest subnormal is normal in Float128
+        assert_eq!(crate::soft_f128::f32_to_f128(0x00000001), 0x3F6A0000000000000000000000000000);
+        // -0
+        assert_eq!(crate::soft_f128::f32_to_f128(0x80000000), 0x80000000000000000000000000000000);
+        // Inf
+        assert_eq!(crate::soft_f128::f32_to_f128(0x7F800000), 0x7FFF0000000000000000000000000000);
+
+        // A signaling NaN is quiet and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f128::f32_to_f128_with_env(0x7F800001, &mut env), 0x7FFF8000020000000000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f128_to_f32() {
+        // 1
+        assert_eq!(crate::soft_f128::f128_to_f32(0x3FFF0000000000000000000000000000), 0x3F800000);
+        // 0.1
+        assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x3FFB999999999999999999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x3FFB999999999999999999999999999A, &mut Env::new(RoundingMode::MinMag)), 0x3DCCCCCC);
+        // NaN
+        assert_eq!(crate::soft_f128::f128_to_f32(0xFFFF8000000000000000000000000000), 0xFFC00000);
+    }
+
+    #[test]
+    fn test_f128_to_f32_exception_flags() {
+        // MAX overflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // The smallest subnormal underflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x00000000000000000000000000000001, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f128_conv_nan_policy() {
+        // The default NaN replaces every NaN, and a signaling NaN is invalid
+        for &nan_policy in [NanPolicy::RiscV, NanPolicy::Armv8DefaultNan].iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x7FFF_8000_0200_0000_0000_0000_0000_0000, &mut env), 0x7FC00000);
+            assert_eq!(crate::soft_f128::f32_to_f128_with_env(0xFFC00001, &mut env), 0x7FFF_8000_0000_0000_0000_0000_0000_0000);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+            assert_eq!(crate::soft_f128::f128_to_f32_with_env(0xFFFF_0000_0000_0000_0000_0000_0000_0001, &mut env), 0x7FC00000);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+            env.clear_flags();
+            assert_eq!(crate::soft_f128::f32_to_f128_with_env(0x7F800001, &mut env), 0x7FFF_8000_0000_0000_0000_0000_0000_0000);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+
+        // The quiet bit is inverted in MIPS legacy
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x7FFF_0000_0200_0000_0000_0000_0000_0000, &mut env), 0x7F800001);
+        assert_eq!(crate::soft_f128::f32_to_f128_with_env(0xFF800001, &mut env), 0xFFFF_0000_0200_0000_0000_0000_0000_0000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f128::f128_to_f32_with_env(0x7FFF_8000_0000_0000_0000_0000_0000_0000, &mut env), 0x7FBFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        env.clear_flags();
+        assert_eq!(crate::soft_f128::f32_to_f128_with_env(0x7FC00000, &mut env), 0x7FFF_7FFF_FE00_0000_0000_0000_0000_0000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
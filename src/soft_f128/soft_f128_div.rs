@@ -0,0 +1,160 @@
+use super::util::{
+    f128_round_and_pack,
+    f128_pack_raw,
+    f128_propagate_nan,
+    f128_sign, f128_exp, f128_frac,
+    f128_norm_subnormal_frac,
+    F128_HIDDEN_BIT,
+    f128_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f128_div(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f128_div_with_env(a, b, env))
+}
+
+pub fn f128_div_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f128_sign(a);
+    let b_sign = f128_sign(b);
+    let r_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f128_exp(a);
+    let mut b_exp = f128_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f128_frac(a);
+    let mut b_frac = f128_frac(b);
+
+    if a_exp == 0x7FFF {
+        if a_frac != 0 {
+            return f128_propagate_nan(a, b, env);
+        }
+        if b_exp == 0x7FFF {
+            if b_frac != 0 {
+                return f128_propagate_nan(a, b, env);
+            } else {
+                // Inf / Inf, invalid, return default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f128_default_nan(env);
+            }
+        }
+        // INFINITY
+        return f128_pack_raw(r_sign, 0x7FFF, 0);
+    }
+    if b_exp == 0x7FFF {
+        if b_frac != 0 {
+            return f128_propagate_nan(a, b, env);
+        }
+        return f128_pack_raw(r_sign, 0, 0);
+    }
+
+    if b_exp == 0 {
+        if b_frac == 0 {
+            if (a_exp as u128 | a_frac) == 0 {
+                // 0 / 0, invalid, return default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f128_default_nan(env);
+            }
+            // Infinity
+            env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+            return f128_pack_raw(r_sign, 0x7FFF, 0);
+        }
+
+        let (exp, frac) = f128_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+    if a_exp == 0 {
+        if a_frac == 0 {
+            // Zero
+            return f128_pack_raw(r_sign, 0, 0);
+        }
+
+        let (exp, frac) = f128_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+
+    r_exp = a_exp - b_exp + 0x3FFE;
+
+    a_frac |= F128_HIDDEN_BIT;
+    b_frac |= F128_HIDDEN_BIT;
+    if a_frac < b_frac {
+        r_exp -= 1;
+        a_frac <<= 1;
+    }
+
+    // The quotient is in [1, 2), its first bit is known. The remainder is less than B, which
+    // has 113 bits, so 15 more quotient bits are computed at a time with u128 divisions.
+    let mut rem = a_frac - b_frac;
+    let mut quot: u128 = 1;
+    for _ in 0..8 {
+        let partial_rem = rem << 15;
+        let partial_quot = partial_rem / b_frac;
+        rem = partial_rem - partial_quot * b_frac;
+        quot = (quot << 15) | partial_quot;
+    }
+
+    // The quotient has 121 bits, 8 of them are below the fraction, and the remainder is sticky
+    let r_frac = quot >> 8;
+    let r_frac_extra = (((quot & 0xFF) as u64) << 56) | (rem != 0) as u64;
+
+    f128_round_and_pack(r_sign, r_exp, r_frac, r_frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f128_div() {
+        // 1 / 3 = 0.333...
+        assert_eq!(crate::soft_f128::f128_div(0x3FFF0000000000000000000000000000, 0x40008000000000000000000000000000), 0x3FFD5555555555555555555555555555);
+        // 6 / 3 = 2
+        assert_eq!(crate::soft_f128::f128_div(0x40018000000000000000000000000000, 0x40008000000000000000000000000000), 0x40000000000000000000000000000000);
+        // MIN_NORMAL / 4 = subnormal
+        assert_eq!(crate::soft_f128::f128_div(0x00010000000000000000000000000000, 0x40010000000000000000000000000000), 0x00004000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_div_inf_nan() {
+        // 1 / 0 = Inf
+        assert_eq!(crate::soft_f128::f128_div(0x3FFF0000000000000000000000000000, 0x0), 0x7FFF0000000000000000000000000000);
+        // 0 / 0 = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_div(0x0, 0x0)), true);
+        // Inf / Inf = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_div(0x7FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000)), true);
+        // 1 / Inf = 0
+        assert_eq!(crate::soft_f128::f128_div(0x3FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f128_div_rounding_mode() {
+        // 1 / 3
+        assert_eq!(crate::soft_f128::f128_div_with_env(0x3FFF0000000000000000000000000000, 0x40008000000000000000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FFD5555555555555555555555555555);
+        assert_eq!(crate::soft_f128::f128_div_with_env(0x3FFF0000000000000000000000000000, 0x40008000000000000000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFD5555555555555555555555555555);
+        assert_eq!(crate::soft_f128::f128_div_with_env(0x3FFF0000000000000000000000000000, 0x40008000000000000000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FFD5555555555555555555555555556);
+    }
+
+    #[test]
+    fn test_f128_div_exception_flags() {
+        // 1 / 0 divides by zero
+        let mut env = Env::default();
+        crate::soft_f128::f128_div_with_env(0x3FFF0000000000000000000000000000, 0x0, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // 0 / 0 is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_div_with_env(0x0, 0x0, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // 1 / 3 is inexact
+        let mut env = Env::default();
+        crate::soft_f128::f128_div_with_env(0x3FFF0000000000000000000000000000, 0x40008000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+}
@@ -0,0 +1,156 @@
+use super::util::{
+    f128_round_and_pack,
+    f128_pack_raw,
+    f128_propagate_nan,
+    f128_sign, f128_exp, f128_frac,
+    f128_norm_subnormal_frac,
+    f128_mul_128_to_256,
+    F128_HIDDEN_BIT,
+    f128_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f128_mul(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f128_mul_with_env(a, b, env))
+}
+
+pub fn f128_mul_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f128_sign(a);
+    let b_sign = f128_sign(b);
+    let r_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f128_exp(a);
+    let mut b_exp = f128_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f128_frac(a);
+    let mut b_frac = f128_frac(b);
+    let r_frac;
+    let r_frac_extra;
+
+    if a_exp == 0x7FFF {
+        // NaN
+        if a_frac != 0 || (b_exp == 0x7FFF && b_frac != 0) {
+            return f128_propagate_nan(a, b, env);
+        }
+
+        // Inf
+        if b_exp as u128 | b_frac == 0 {
+            // Inf x 0, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f128_default_nan(env);
+        }
+
+        return f128_pack_raw(r_sign, 0x7FFF, 0);
+    }
+    if b_exp == 0x7FFF {
+        // Nan
+        if b_frac != 0 {
+            return f128_propagate_nan(a, b, env);
+        }
+
+        // Inf
+        if a_exp as u128 | a_frac == 0 {
+            // 0 x Inf, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f128_default_nan(env);
+        }
+
+        return f128_pack_raw(r_sign, 0x7FFF, 0);
+    }
+
+    if a_exp == 0 {
+        if a_frac == 0 {
+            // Zero
+            return f128_pack_raw(r_sign, 0, 0);
+        }
+
+        let (exp, frac) = f128_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    if b_exp == 0 {
+        if b_frac == 0 {
+            // Zero
+            return f128_pack_raw(r_sign, 0, 0);
+        }
+
+        let (exp, frac) = f128_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+
+    r_exp = a_exp + b_exp - 0x4000;
+
+    // The product of the 113-bit fraction of A and the fraction of B at the top of u128
+    // has the hidden bit at bit 111 or 112 of its high half
+    a_frac |= F128_HIDDEN_BIT;
+    b_frac = (b_frac | F128_HIDDEN_BIT) << 15;
+    let (frac_prod_hi, frac_prod_lo) = f128_mul_128_to_256(a_frac, b_frac);
+
+    if frac_prod_hi >= F128_HIDDEN_BIT {
+        r_exp += 1;
+        r_frac = frac_prod_hi;
+        r_frac_extra = ((frac_prod_lo >> 64) as u64) | ((frac_prod_lo as u64) != 0) as u64;
+    } else {
+        r_frac = (frac_prod_hi << 1) | (frac_prod_lo >> 127);
+        r_frac_extra = ((frac_prod_lo >> 63) as u64) | ((frac_prod_lo << 65) != 0) as u64;
+    }
+
+    f128_round_and_pack(r_sign, r_exp, r_frac, r_frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f128_mul() {
+        // 0.1 * 0.2 = 0.02
+        assert_eq!(crate::soft_f128::f128_mul(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), 0x3FF947AE147AE147AE147AE147AE147C);
+        // -0.1 * 0.2 = -0.02
+        assert_eq!(crate::soft_f128::f128_mul(0xBFFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), 0xBFF947AE147AE147AE147AE147AE147C);
+        // 2 * 3 = 6
+        assert_eq!(crate::soft_f128::f128_mul(0x40000000000000000000000000000000, 0x40008000000000000000000000000000), 0x40018000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_mul_inf_nan() {
+        // Inf * 0 = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_mul(0x7FFF0000000000000000000000000000, 0x0)), true);
+        // Inf * -1 = -Inf
+        assert_eq!(crate::soft_f128::f128_mul(0x7FFF0000000000000000000000000000, 0xBFFF0000000000000000000000000000), 0xFFFF0000000000000000000000000000);
+        // NaN * 1 = NaN
+        assert_eq!(crate::soft_f128::f128_mul(0x7FFF8000000000000000000000000001, 0x3FFF0000000000000000000000000000), 0x7FFF8000000000000000000000000001);
+    }
+
+    #[test]
+    fn test_f128_mul_rounding_mode() {
+        // 0.1 * 0.2
+        assert_eq!(crate::soft_f128::f128_mul_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x3FF947AE147AE147AE147AE147AE147C);
+        assert_eq!(crate::soft_f128::f128_mul_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::MinMag)), 0x3FF947AE147AE147AE147AE147AE147B);
+        assert_eq!(crate::soft_f128::f128_mul_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::Max)), 0x3FF947AE147AE147AE147AE147AE147C);
+    }
+
+    #[test]
+    fn test_f128_mul_exception_flags() {
+        // MAX * 2 overflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f128::f128_mul_with_env(0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x40000000000000000000000000000000, &mut env), 0x7FFF0000000000000000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 0.75 * the smallest subnormal underflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f128::f128_mul_with_env(0x3FFE8000000000000000000000000000, 0x00000000000000000000000000000001, &mut env), 0x00000000000000000000000000000001);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // Inf * 0 is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_mul_with_env(0x7FFF0000000000000000000000000000, 0x0, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
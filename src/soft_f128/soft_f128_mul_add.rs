@@ -0,0 +1,300 @@
+use super::util::{
+    f128_round_and_pack,
+    f128_pack_raw, f128_pack,
+    f128_propagate_nan,
+    f128_propagate_nan3,
+    f128_sign, f128_exp, f128_frac,
+    f128_norm_subnormal_frac,
+    f128_count_leading_zero,
+    f128_mul_128_to_256,
+    F128_HIDDEN_BIT,
+    f128_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode, with_thread_env };
+
+// Shift a 256-bit value right, the lost bits are jammed into the least significant bit
+fn f128_shift_right_jam256(hi: u128, lo: u128, dist: i32) -> (u128, u128) {
+    if dist == 0 {
+        (hi, lo)
+    } else if dist < 128 {
+        let is_lost = (lo << (128 - dist)) != 0;
+        (hi >> dist, (hi << (128 - dist)) | (lo >> dist) | is_lost as u128)
+    } else if dist < 256 {
+        let is_lost = lo != 0 || (dist > 128 && (hi << (256 - dist)) != 0);
+        (0, (hi >> (dist - 128)) | is_lost as u128)
+    } else {
+        (0, (hi != 0 || lo != 0) as u128)
+    }
+}
+
+pub fn f128_mul_add(a: u128, b: u128, c: u128) -> u128 {
+    with_thread_env(|env| f128_mul_add_with_env(a, b, c, env))
+}
+
+// Compute A x B + C with a single rounding
+pub fn f128_mul_add_with_env(a: u128, b: u128, c: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f128_sign(a);
+    let b_sign = f128_sign(b);
+    let c_sign = f128_sign(c);
+    let prod_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f128_exp(a);
+    let mut b_exp = f128_exp(b);
+    let mut c_exp = f128_exp(c);
+
+    // Frac
+    let mut a_frac = f128_frac(a);
+    let mut b_frac = f128_frac(b);
+    let mut c_frac = f128_frac(c);
+
+    if a_exp == 0x7FFF || b_exp == 0x7FFF {
+        if (a_exp == 0x7FFF && a_frac != 0) || (b_exp == 0x7FFF && b_frac != 0) {
+            // Propagate NaN
+            return f128_propagate_nan3(a, b, c, env);
+        }
+
+        // Inf x 0 is invalid
+        let is_prod_valid = match a_exp == 0x7FFF {
+            true => b_exp as u128 | b_frac != 0,
+            false => a_exp as u128 | a_frac != 0,
+        };
+        if is_prod_valid {
+            let r = f128_pack_raw(prod_sign, 0x7FFF, 0);
+            if c_exp != 0x7FFF {
+                return r;
+            }
+            if c_frac != 0 {
+                // Propagate NaN
+                return f128_propagate_nan(r, c, env);
+            }
+            if c_sign == prod_sign {
+                return r;
+            }
+        }
+
+        // Inf x 0 or Inf - Inf, invalid, return default NaN unless C is a NaN
+        env.raise(ExceptionFlags::INVALID);
+        if c_exp == 0x7FFF && c_frac != 0 {
+            return match env.nan_policy() {
+                // The default NaN of the invalid product competes with C
+                NanPolicy::X87 | NanPolicy::Armv8 => f128_propagate_nan(f128_default_nan(env), c, env),
+                _ => f128_propagate_nan(0, c, env),
+            };
+        }
+        return f128_default_nan(env);
+    }
+    if c_exp == 0x7FFF {
+        if c_frac != 0 {
+            // Propagate NaN
+            return f128_propagate_nan(0, c, env);
+        }
+        // Infinity
+        return c;
+    }
+
+    if (a_exp as u128 | a_frac) == 0 || (b_exp as u128 | b_frac) == 0 {
+        // Zero product, C is exact unless it is a zero of the other sign
+        if (c_exp as u128 | c_frac) == 0 && c_sign != prod_sign {
+            let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+            return f128_pack(zero_sign, 0, 0);
+        }
+        return c;
+    }
+
+    if a_exp == 0 {
+        let (exp, frac) = f128_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    if b_exp == 0 {
+        let (exp, frac) = f128_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+
+    // The exact product of the 113-bit fractions has 225 or 226 bits,
+    // move it to have its most significant bit at bit 252 or 253
+    let (prod_hi, prod_lo) = f128_mul_128_to_256(a_frac | F128_HIDDEN_BIT, b_frac | F128_HIDDEN_BIT);
+    let mut prod_hi = (prod_hi << 28) | (prod_lo >> 100);
+    let mut prod_lo = prod_lo << 28;
+    let prod_exp = a_exp + b_exp - 0x3FFF;
+
+    if (c_exp as u128 | c_frac) == 0 {
+        // Zero addend, round the product
+        c_exp = prod_exp;
+    } else {
+        if c_exp == 0 {
+            let (exp, frac) = f128_norm_subnormal_frac(c_frac);
+            c_exp = exp;
+            c_frac = frac;
+        }
+        c_frac |= F128_HIDDEN_BIT;
+    }
+
+    // Move the fraction of C to have its most significant bit at bit 252 as well
+    let mut c_hi = c_frac << 12;
+    let mut c_lo: u128 = 0;
+
+    // Align the operand with the smaller exponent, both are then scaled by 2^(r_exp - 0x3FFF - 252)
+    let diff_exp = prod_exp - c_exp;
+    let r_exp;
+    if diff_exp >= 0 {
+        r_exp = prod_exp;
+        let (hi, lo) = f128_shift_right_jam256(c_hi, c_lo, diff_exp);
+        c_hi = hi;
+        c_lo = lo;
+    } else {
+        r_exp = c_exp;
+        let (hi, lo) = f128_shift_right_jam256(prod_hi, prod_lo, -diff_exp);
+        prod_hi = hi;
+        prod_lo = lo;
+    }
+
+    let mut r_sign = prod_sign;
+    let (mut r_hi, mut r_lo);
+    if c_sign == prod_sign {
+        let (lo, carry) = prod_lo.overflowing_add(c_lo);
+        r_lo = lo;
+        r_hi = prod_hi + c_hi + carry as u128;
+    } else {
+        if (prod_hi, prod_lo) == (c_hi, c_lo) {
+            // Exact cancellation, the zero is negative only when rounding toward negative
+            let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+            return f128_pack(zero_sign, 0, 0);
+        }
+        if (prod_hi, prod_lo) < (c_hi, c_lo) {
+            // The magnitude of C is greater
+            r_sign ^= 1;
+            (prod_hi, c_hi) = (c_hi, prod_hi);
+            (prod_lo, c_lo) = (c_lo, prod_lo);
+        }
+        let (lo, borrow) = prod_lo.overflowing_sub(c_lo);
+        r_lo = lo;
+        r_hi = prod_hi - c_hi - borrow as u128;
+    }
+
+    // Find the most significant bit, and move it to the hidden bit of the high half
+    let lead_pos = match r_hi != 0 {
+        true => 255 - f128_count_leading_zero(r_hi),
+        false => 127 - f128_count_leading_zero(r_lo),
+    };
+    let shift_count = lead_pos - 240;
+    if shift_count > 0 {
+        let (hi, lo) = f128_shift_right_jam256(r_hi, r_lo, shift_count);
+        r_hi = hi;
+        r_lo = lo;
+    } else if shift_count < 0 {
+        let dist = -shift_count;
+        if dist >= 128 {
+            r_hi = r_lo << (dist - 128);
+            r_lo = 0;
+        } else {
+            r_hi = (r_hi << dist) | (r_lo >> (128 - dist));
+            r_lo <<= dist;
+        }
+    }
+
+    // The hidden bit is now at bit 112 of the high half, the low half holds the bits to round
+    let r_frac_extra = ((r_lo >> 64) as u64) | ((r_lo as u64) != 0) as u64;
+
+    f128_round_and_pack(r_sign, r_exp + lead_pos - 253, r_hi, r_frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f128_mul_add() {
+        // 0.1 * 10 - 1 = 2^-114, the exact error of 0.1, while 0.1 * 10 rounds to 1
+        assert_eq!(crate::soft_f128::f128_mul(0x3FFB999999999999999999999999999A, 0x40024000000000000000000000000000), 0x3FFF0000000000000000000000000000);
+        assert_eq!(crate::soft_f128::f128_mul_add(0x3FFB999999999999999999999999999A, 0x40024000000000000000000000000000, 0xBFFF0000000000000000000000000000), 0x3F8D0000000000000000000000000000);
+
+        // 0.1 * 0.2 + 0.2 = 0.22
+        assert_eq!(crate::soft_f128::f128_mul_add(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, 0x3FFC999999999999999999999999999A), 0x3FFCC28F5C28F5C28F5C28F5C28F5C29);
+
+        // MAX * 2 - MAX = MAX, without overflow in the product
+        assert_eq!(crate::soft_f128::f128_mul_add(0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x40000000000000000000000000000000, 0xFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF), 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF);
+
+        // 0 * 1 + -0 = 0
+        assert_eq!(crate::soft_f128::f128_mul_add(0x0, 0x3FFF0000000000000000000000000000, 0x80000000000000000000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f128_mul_add_inf_nan() {
+        // Inf * 1 + 1 = Inf
+        assert_eq!(crate::soft_f128::f128_mul_add(0x7FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000), 0x7FFF0000000000000000000000000000);
+        // 1 * 1 + -Inf = -Inf
+        assert_eq!(crate::soft_f128::f128_mul_add(0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0xFFFF0000000000000000000000000000), 0xFFFF0000000000000000000000000000);
+        // Inf * 1 + -Inf = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_mul_add(0x7FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0xFFFF0000000000000000000000000000)), true);
+        // 1 * 1 + NaN = NaN
+        assert_eq!(crate::soft_f128::f128_mul_add(0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0x7FFF8000000000000000000000000001), 0x7FFF8000000000000000000000000001);
+    }
+
+    #[test]
+    fn test_f128_mul_add_rounding_mode() {
+        // 1 * 1 - 1 is -0 when rounding toward negative infinity
+        assert_eq!(crate::soft_f128::f128_mul_add_with_env(0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0xBFFF0000000000000000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x0);
+        assert_eq!(crate::soft_f128::f128_mul_add_with_env(0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0xBFFF0000000000000000000000000000, &mut Env::new(RoundingMode::Min)), 0x80000000000000000000000000000000);
+
+        // 0.1 * 0.2 + 0.2
+        assert_eq!(crate::soft_f128::f128_mul_add_with_env(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A, 0x3FFC999999999999999999999999999A, &mut Env::new(RoundingMode::MinMag)), 0x3FFCC28F5C28F5C28F5C28F5C28F5C29);
+    }
+
+    #[test]
+    fn test_f128_mul_add_exception_flags() {
+        // 0.1 * 10 - 1 is exact
+        let mut env = Env::default();
+        crate::soft_f128::f128_mul_add_with_env(0x3FFB999999999999999999999999999A, 0x40024000000000000000000000000000, 0xBFFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Inf * 0 + 1 is invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f128::f128_mul_add_with_env(0x7FFF0000000000000000000000000000, 0x0, 0x3FFF0000000000000000000000000000, &mut env), 0xFFFF8000000000000000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Inf * 1 - Inf is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_mul_add_with_env(0x7FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, 0xFFFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f128_mul_add_nan_policy() {
+        let nan_env = |nan_policy| {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            env
+        };
+
+        // qNaN x 1 + sNaN
+        let (a, b, c) = (0x7FFF8000000000000000000000000001, 0x3FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000002);
+        let cases = [
+            (NanPolicy::X87, 0x7FFF8000000000000000000000000001),
+            (NanPolicy::X86Sse, 0x7FFF8000000000000000000000000001),
+            // The addend first, and a signaling NaN first
+            (NanPolicy::Armv8, 0x7FFF8000000000000000000000000002),
+            (NanPolicy::Armv8DefaultNan, 0x7FFF8000000000000000000000000000),
+            (NanPolicy::RiscV, 0x7FFF8000000000000000000000000000),
+            (NanPolicy::Wasm, 0x7FFF8000000000000000000000000000),
+        ];
+        for &(nan_policy, r) in cases.iter() {
+            let mut env = nan_env(nan_policy);
+            assert_eq!(crate::soft_f128::f128_mul_add_with_env(a, b, c, &mut env), r);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+
+        // Inf x 0 + qNaN is invalid, ARM gives the default NaN and x86 the addend
+        let (inf, zero, qnan) = (0x7FFF0000000000000000000000000000, 0, 0x7FFF8000000000000000000000000001);
+        let mut env = nan_env(NanPolicy::Armv8);
+        assert_eq!(crate::soft_f128::f128_mul_add_with_env(inf, zero, qnan, &mut env), 0x7FFF8000000000000000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        let mut env = nan_env(NanPolicy::X86Sse);
+        assert_eq!(crate::soft_f128::f128_mul_add_with_env(inf, zero, qnan, &mut env), qnan);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,240 @@
+use super::util::{
+    f128_pack_raw,
+    f128_propagate_nan,
+    f128_sign, f128_exp, f128_frac,
+    f128_count_leading_zero,
+    f128_is_nan,
+    F128_HIDDEN_BIT,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f128_round(a: u128) -> u128 {
+    with_thread_env(|env| f128_round_with_env(a, env))
+}
+
+pub fn f128_round_with_env(a: u128, env: &mut Env) -> u128 {
+    // Exp
+    let a_exp = f128_exp(a);
+
+    // Frac
+    let a_frac = f128_frac(a);
+
+    if a_exp <= 0x3FFE {
+        // |a| < 1, round to 0 or 1
+        if (a << 1) == 0 {
+            return a;
+        }
+        let mut z = a & f128_pack_raw(1, 0, 0);
+        if a_frac != 0 && a_exp == 0x3FFE {
+            z |= f128_pack_raw(0, 0x3FFF, 0);
+        }
+        return z;
+    }
+    if 0x406F <= a_exp {
+        if a_exp == 0x7FFF && a_frac != 0 {
+            return f128_propagate_nan(a, 0, env);
+        }
+        return a;
+    }
+    let last_bit_mask: u128 = 1 << (0x406F - a_exp);
+    let round_bits_mask = last_bit_mask - 1;
+    // By default use near even round mode
+    let mut r = a;
+    r += last_bit_mask >> 1;
+    if (r & round_bits_mask) == 0 {
+        r &= !last_bit_mask;
+    }
+    r &= !round_bits_mask;
+    r
+}
+
+pub fn from_int32(a: i32) -> u128 {
+    with_thread_env(|env| from_int32_with_env(a, env))
+}
+
+pub fn from_int32_with_env(a: i32, env: &mut Env) -> u128 {
+    from_int64_with_env(a as i64, env)
+}
+
+pub fn from_int64(a: i64) -> u128 {
+    with_thread_env(|env| from_int64_with_env(a, env))
+}
+
+pub fn from_int64_with_env(a: i64, _env: &mut Env) -> u128 {
+    // Every i64 fits in the fraction, so the conversion is always exact
+    let sign = if a < 0 { 1 } else { 0 };
+    if a == 0 {
+        return 0;
+    }
+
+    let frac = a.unsigned_abs() as u128;
+    let shift_count = f128_count_leading_zero(frac) - 15;
+    f128_pack_raw(sign, 0x406E - shift_count, frac << shift_count)
+}
+
+pub fn to_int32(a: u128) -> i32 {
+    with_thread_env(|env| to_int32_with_env(a, env))
+}
+
+pub fn to_int32_with_env(a: u128, env: &mut Env) -> i32 {
+    let p = f128_round_with_env(a, env);
+
+    if f128_is_nan(p) {
+        env.raise(ExceptionFlags::INVALID);
+        return i32::MAX;
+    }
+
+    let r = f128_rounded_to_int(a, p, 31, env);
+    match r {
+        Some(r) => r as i32,
+        None => {
+            // Infinity or out of range
+            env.raise(ExceptionFlags::INVALID);
+            if f128_sign(p) != 0 {
+                i32::MIN
+            } else {
+                i32::MAX
+            }
+        }
+    }
+}
+
+pub fn to_int64(a: u128) -> i64 {
+    with_thread_env(|env| to_int64_with_env(a, env))
+}
+
+pub fn to_int64_with_env(a: u128, env: &mut Env) -> i64 {
+    let p = f128_round_with_env(a, env);
+
+    if f128_is_nan(p) {
+        env.raise(ExceptionFlags::INVALID);
+        return i64::MAX;
+    }
+
+    let r = f128_rounded_to_int(a, p, 63, env);
+    match r {
+        Some(r) => r,
+        None => {
+            // Infinity or out of range
+            env.raise(ExceptionFlags::INVALID);
+            if f128_sign(p) != 0 {
+                i64::MIN
+            } else {
+                i64::MAX
+            }
+        }
+    }
+}
+
+// Convert P, the integral value A is rounded to, into an integer of BITS bits and a sign.
+// Return None when it is out of range.
+fn f128_rounded_to_int(a: u128, p: u128, bits: i32, env: &mut Env) -> Option<i64> {
+    let sign = f128_sign(p);
+    let exp = f128_exp(p);
+    let frac = f128_frac(p);
+
+    if exp < 0x3FFF {
+        // +- 0, because it is too tiny
+        if p != a {
+            env.raise(ExceptionFlags::INEXACT);
+        }
+        return Some(0);
+    }
+
+    let shift = exp - 0x3FFF;
+    if shift >= bits {
+        if p == f128_pack_raw(1, 0x3FFF + bits, 0) {
+            // -2^BITS
+            if p != a {
+                env.raise(ExceptionFlags::INEXACT);
+            }
+            return Some(i64::MIN >> (63 - bits));
+        }
+        return None;
+    }
+
+    if p != a {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    let r = ((frac | F128_HIDDEN_BIT) >> (112 - shift)) as i64;
+
+    if sign != 0 {
+        Some(-r)
+    } else {
+        Some(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f128_round() {
+        // round(0.1) = 0
+        assert_eq!(crate::soft_f128::f128_round(0x3FFB999999999999999999999999999A), 0x0);
+        // round(0.5) = 0
+        assert_eq!(crate::soft_f128::f128_round(0x3FFE0000000000000000000000000000), 0x0);
+        // round(-0.75) = -1
+        assert_eq!(crate::soft_f128::f128_round(0xBFFE8000000000000000000000000000), 0xBFFF0000000000000000000000000000);
+        // round(2.5) = 2
+        assert_eq!(crate::soft_f128::f128_round(0x40004000000000000000000000000000), 0x40000000000000000000000000000000);
+        // round(3.5) = 4
+        assert_eq!(crate::soft_f128::f128_round(0x4000C000000000000000000000000000), 0x40010000000000000000000000000000);
+        // round(2^112) = 2^112
+        assert_eq!(crate::soft_f128::f128_round(0x406F0000000000000000000000000000), 0x406F0000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_to_int() {
+        // round(2.5) = 2
+        assert_eq!(crate::soft_f128::to_int32(0x40004000000000000000000000000000), 2);
+        // round(2^30) = 1073741824
+        assert_eq!(crate::soft_f128::to_int32(0x401D0000000000000000000000000000), 0x40000000);
+        // round(-2^31) = -2147483648
+        assert_eq!(crate::soft_f128::to_int32(0xC01E0000000000000000000000000000), i32::MIN);
+
+        // round(-2^63) = -9223372036854775808
+        assert_eq!(crate::soft_f128::to_int64(0xC03E0000000000000000000000000000), i64::MIN);
+        // round(2^63) = 9223372036854775807, out of range
+        assert_eq!(crate::soft_f128::to_int64(0x403E0000000000000000000000000000), i64::MAX);
+    }
+
+    #[test]
+    fn test_int_to_f128() {
+        // from_int32(0) = 0.0
+        assert_eq!(crate::soft_f128::from_int32(0), 0x0);
+        // from_int32(-4) = -4.0
+        assert_eq!(crate::soft_f128::from_int32(-4), 0xC0010000000000000000000000000000);
+        // from_int64(-9223372036854775808) = -2^63
+        assert_eq!(crate::soft_f128::from_int64(i64::MIN), 0xC03E0000000000000000000000000000);
+        // from_int64(9223372036854775807) is exact
+        assert_eq!(crate::soft_f128::from_int64(i64::MAX), 0x403DFFFFFFFFFFFFFFFC000000000000);
+    }
+
+    #[test]
+    fn test_f128_int_exception_flags() {
+        let mut env = Env::default();
+
+        // to_int32(128.2) = 128, inexact
+        assert_eq!(crate::soft_f128::to_int32_with_env(0x40060066666666666666666666666666, &mut env), 128);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // to_int64(-2^63 - 2^-49) is out of range, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f128::to_int64_with_env(0xC03E0000000000010000000000000000, &mut env), i64::MIN);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // to_int64(NaN), invalid
+        env.clear_flags();
+        crate::soft_f128::to_int64_with_env(0x7FFF8000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // from_int64(i64::MAX) is exact
+        env.clear_flags();
+        crate::soft_f128::from_int64_with_env(i64::MAX, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
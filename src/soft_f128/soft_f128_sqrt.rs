@@ -0,0 +1,144 @@
+use super::util::{
+    f128_round_and_pack,
+    f128_propagate_nan,
+    f128_sign, f128_exp, f128_frac,
+    f128_norm_subnormal_frac,
+    F128_HIDDEN_BIT,
+    f128_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Square root of a 256-bit radicand, whose high half is given and low half is 0.
+// Return the root and whether it is inexact.
+fn f128_isqrt(radicand_hi: u128) -> (u128, bool) {
+    let mut root: u128 = 0;
+    let mut rem: u128 = 0;
+
+    // Bring down the bits of the radicand two at a time, the remainder stays below 2 * root + 1
+    for i in (0..128).rev() {
+        let bits = if i >= 64 { (radicand_hi >> ((i - 64) * 2)) & 0x03 } else { 0 };
+        rem = (rem << 2) | bits;
+        let trial = (root << 2) | 1;
+        root <<= 1;
+        if rem >= trial {
+            rem -= trial;
+            root |= 1;
+        }
+    }
+
+    (root, rem != 0)
+}
+
+pub fn f128_sqrt(a: u128) -> u128 {
+    with_thread_env(|env| f128_sqrt_with_env(a, env))
+}
+
+pub fn f128_sqrt_with_env(a: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f128_sign(a);
+
+    // Exp
+    let mut a_exp = f128_exp(a);
+
+    // Frac
+    let mut a_frac = f128_frac(a);
+
+    if a_exp == 0x7FFF {
+        if a_frac != 0 {
+            return f128_propagate_nan(a, 0, env);
+        }
+        if a_sign == 0 {
+            return a;
+        }
+
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f128_default_nan(env);
+    }
+
+    if a_sign != 0 {
+        if a_exp as u128 | a_frac == 0 {
+            // 0 or -0
+            return a;
+        }
+
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f128_default_nan(env);
+    }
+
+    if a_exp == 0 {
+        if a_frac == 0 {
+            return a;
+        }
+        let (exp, frac) = f128_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+
+    let r_exp = ((a_exp - 0x3FFF) >> 1) + 0x3FFE;
+
+    // Make the exponent even, the fraction is then in [1, 4)
+    a_frac |= F128_HIDDEN_BIT;
+    if (a_exp - 0x3FFF) & 1 != 0 {
+        a_frac <<= 1;
+    }
+
+    // The root of the fraction shifted by 128 has 121 bits, 8 of them are below the fraction
+    let (root, is_inexact) = f128_isqrt(a_frac);
+    let r_frac = root >> 8;
+    let r_frac_extra = (((root & 0xFF) as u64) << 56) | is_inexact as u64;
+
+    f128_round_and_pack(0, r_exp, r_frac, r_frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f128_sqrt() {
+        // sqrt(2) = 1.41421356237309504880168872420969808
+        assert_eq!(crate::soft_f128::f128_sqrt(0x40000000000000000000000000000000), 0x3FFF6A09E667F3BCC908B2FB1366EA95);
+        // sqrt(4) = 2
+        assert_eq!(crate::soft_f128::f128_sqrt(0x40010000000000000000000000000000), 0x40000000000000000000000000000000);
+        // sqrt(2^-16494) = 2^-8247
+        assert_eq!(crate::soft_f128::f128_sqrt(0x00000000000000000000000000000001), 0x1FC80000000000000000000000000000);
+        // sqrt(-0) = -0
+        assert_eq!(crate::soft_f128::f128_sqrt(0x80000000000000000000000000000000), 0x80000000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_sqrt_inf_nan() {
+        // sqrt(Inf) = Inf
+        assert_eq!(crate::soft_f128::f128_sqrt(0x7FFF0000000000000000000000000000), 0x7FFF0000000000000000000000000000);
+        // sqrt(-1) = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_sqrt(0xBFFF0000000000000000000000000000)), true);
+    }
+
+    #[test]
+    fn test_f128_sqrt_rounding_mode() {
+        // sqrt(2)
+        assert_eq!(crate::soft_f128::f128_sqrt_with_env(0x40000000000000000000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFF6A09E667F3BCC908B2FB1366EA95);
+        assert_eq!(crate::soft_f128::f128_sqrt_with_env(0x40000000000000000000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FFF6A09E667F3BCC908B2FB1366EA96);
+    }
+
+    #[test]
+    fn test_f128_sqrt_exception_flags() {
+        // sqrt(-1) is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_sqrt_with_env(0xBFFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // sqrt(2) is inexact
+        let mut env = Env::default();
+        crate::soft_f128::f128_sqrt_with_env(0x40000000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // sqrt(4) is exact
+        let mut env = Env::default();
+        crate::soft_f128::f128_sqrt_with_env(0x40010000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
@@ -0,0 +1,168 @@
+use super::util::{
+    f128_shift_right_jam,
+    f128_norm_round_and_pack,
+    f128_pack_raw, f128_pack,
+    f128_propagate_nan,
+    f128_sign, f128_exp, f128_frac,
+    F128_HIDDEN_BIT,
+    f128_default_nan,
+};
+
+use super::soft_f128_add::f128_add_mags;
+
+use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, with_thread_env };
+
+pub fn f128_sub(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f128_sub_with_env(a, b, env))
+}
+
+pub fn f128_sub_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    if f128_sign(a) != f128_sign(b) {
+        // Consider as addition
+        return f128_add_mags(a, b, env);
+    }
+
+    f128_sub_mags(a, b, env)
+}
+
+// Subtract the magnitude of B from the magnitude of A, the result has the sign of A
+pub(crate) fn f128_sub_mags(a: u128, b: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f128_sign(a);
+    let mut r_sign = a_sign;
+
+    // Exp
+    let a_exp = f128_exp(a);
+    let b_exp = f128_exp(b);
+    let r_exp;
+
+    // Frac, with 4 more bits to round
+    let mut a_frac = f128_frac(a) << 4;
+    let mut b_frac = f128_frac(b) << 4;
+    let r_frac;
+
+    let mut diff_exp = a_exp - b_exp;
+
+    if diff_exp == 0 {
+        if a_exp == 0x7FFF {
+            if (a_frac | b_frac) != 0 {
+                // Propagate NaN
+                return f128_propagate_nan(a, b, env);
+            } else {
+                // Inf - Inf, invalid, return the default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f128_default_nan(env);
+            }
+        }
+
+        r_exp = if a_exp == 0 { 1 } else { a_exp };
+        if a_frac == b_frac {
+            // Same, will cause a 0, which is negative only when rounding toward negative
+            let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+            return f128_pack(zero_sign, 0, 0);
+        }
+
+        if a_frac > b_frac {
+            r_frac = a_frac - b_frac;
+        } else {
+            // Fraction of B is greater
+            r_sign ^= 1;
+            r_frac = b_frac - a_frac;
+        }
+    } else if diff_exp > 0 {
+        // Exp of A is greater
+        if a_exp == 0x7FFF {
+            if a_frac != 0 {
+                // Propagate NaN
+                return f128_propagate_nan(a, b, env);
+            }
+            // Infinity
+            return a;
+        }
+
+        if b_exp != 0 {
+            b_frac |= F128_HIDDEN_BIT << 4;
+        } else {
+            diff_exp -= 1;
+        }
+
+        b_frac = f128_shift_right_jam(b_frac, diff_exp);
+        a_frac |= F128_HIDDEN_BIT << 4;   // Add the implicit 1
+
+        r_exp = a_exp;
+        r_frac = a_frac - b_frac;
+    } else {
+        // Exp of B is greater
+        if b_exp == 0x7FFF {
+            if b_frac != 0 {
+                // Propagate NaN
+                return f128_propagate_nan(a, b, env);
+            }
+            // Infinity of the opposite sign
+            return f128_pack_raw(r_sign ^ 1, 0x7FFF, 0);
+        }
+
+        if a_exp != 0 {
+            a_frac |= F128_HIDDEN_BIT << 4;
+        } else {
+            diff_exp += 1;
+        }
+
+        a_frac = f128_shift_right_jam(a_frac, -diff_exp);
+        b_frac |= F128_HIDDEN_BIT << 4;
+
+        r_sign ^= 1;
+        r_exp = b_exp;
+        r_frac = b_frac - a_frac;
+    }
+
+    f128_norm_round_and_pack(r_sign, r_exp - 5, r_frac, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f128_sub() {
+        // 0.1 - 0.2 = -0.1
+        assert_eq!(crate::soft_f128::f128_sub(0x3FFB999999999999999999999999999A, 0x3FFC999999999999999999999999999A), 0xBFFB999999999999999999999999999A);
+        // 3 - 2 = 1
+        assert_eq!(crate::soft_f128::f128_sub(0x40008000000000000000000000000000, 0x40000000000000000000000000000000), 0x3FFF0000000000000000000000000000);
+        // 1 - 1 = 0
+        assert_eq!(crate::soft_f128::f128_sub(0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f128_sub_inf_nan() {
+        // Inf - Inf = NaN
+        assert_eq!(crate::soft_f128::f128_is_nan(crate::soft_f128::f128_sub(0x7FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000)), true);
+        // Inf - 1 = Inf
+        assert_eq!(crate::soft_f128::f128_sub(0x7FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000), 0x7FFF0000000000000000000000000000);
+        // 1 - Inf = -Inf
+        assert_eq!(crate::soft_f128::f128_sub(0x3FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000), 0xFFFF0000000000000000000000000000);
+    }
+
+    #[test]
+    fn test_f128_sub_rounding_mode() {
+        // 1 - 1 is -0 when rounding toward negative infinity
+        assert_eq!(crate::soft_f128::f128_sub_with_env(0x3FFF0000000000000000000000000000, 0x3FFF0000000000000000000000000000, &mut Env::new(RoundingMode::Min)), 0x80000000000000000000000000000000);
+
+        // 2 - 2^-113
+        assert_eq!(crate::soft_f128::f128_sub_with_env(0x40000000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x40000000000000000000000000000000);
+        assert_eq!(crate::soft_f128::f128_sub_with_env(0x40000000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF);
+    }
+
+    #[test]
+    fn test_f128_sub_exception_flags() {
+        // 2 - 2^-113 is inexact
+        let mut env = Env::default();
+        crate::soft_f128::f128_sub_with_env(0x40000000000000000000000000000000, 0x3F8E0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // Inf - Inf is invalid
+        let mut env = Env::default();
+        crate::soft_f128::f128_sub_with_env(0x7FFF0000000000000000000000000000, 0x7FFF0000000000000000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
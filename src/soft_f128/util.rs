@@ -0,0 +1,306 @@
+pub(crate) fn f128_shift_right_jam(a: u128, dist: i32) -> u128 {
+    if dist == 0 {
+        a
+    } else if dist < 128 {
+        if (a << (128 - dist)) != 0 {
+            (a >> dist) | 1
+        } else {
+            a >> dist
+        }
+    } else {
+        if a != 0 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+// Shift the significand and its extra bits right, the lost bits are jammed into the extra bits
+pub(crate) fn f128_shift_right_jam_extra(frac: u128, frac_extra: u64, dist: i32) -> (u128, u64) {
+    if dist == 0 {
+        return (frac, frac_extra);
+    }
+
+    if dist < 64 {
+        let r_frac_extra = ((frac as u64) << (64 - dist)) | (frac_extra != 0) as u64;
+        return (frac >> dist, r_frac_extra);
+    }
+
+    if dist < 192 {
+        let extra_dist = dist - 64;
+        let is_lost = extra_dist != 0 && (frac << (128 - extra_dist)) != 0;
+        let r_frac_extra = ((frac >> extra_dist) as u64) | (is_lost || frac_extra != 0) as u64;
+        let r_frac = if dist < 128 { frac >> dist } else { 0 };
+        return (r_frac, r_frac_extra);
+    }
+
+    (0, (frac != 0 || frac_extra != 0) as u64)
+}
+
+use crate::soft_float::{ RoundingMode, DetectTininess, Env, ExceptionFlags, NanPolicy };
+use crate::soft_f64::util::f64_count_leading_zero;
+
+// The implicit integer bit of the significand
+pub(crate) const F128_HIDDEN_BIT: u128 = 0x0001_0000_0000_0000_0000_0000_0000_0000;
+
+pub(crate) fn f128_sign(a: u128) -> i32 {
+    ((a >> 127) & 0x01) as i32
+}
+
+pub(crate) fn f128_exp(a: u128) -> i32 {
+    ((a >> 112) & 0x7FFF) as i32
+}
+
+pub(crate) fn f128_frac(a: u128) -> u128 {
+    a & 0x0000_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF
+}
+
+pub(crate) fn f128_pack_raw(in_sign: i32, in_exp: i32, in_frac: u128) -> u128 {
+    // Use + rather than |, so that a carry out of the fraction increments the exponent
+    ((in_sign as u128) << 127) | (((in_exp as u128) << 112).wrapping_add(in_frac))
+}
+
+// The default NaN of the NaN policy of the environment
+pub(crate) fn f128_default_nan(env: &Env) -> u128 {
+    match env.nan_policy() {
+        NanPolicy::X87 | NanPolicy::X86Sse => 0xFFFF_8000_0000_0000_0000_0000_0000_0000,
+        NanPolicy::MipsLegacy => 0x7FFF_7FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF,
+        _ => 0x7FFF_8000_0000_0000_0000_0000_0000_0000,
+    }
+}
+
+pub fn f128_is_nan(a: u128) -> bool {
+    let is_exp_nan = f128_exp(a) == 0x7FFF;
+    let is_frac_nan = f128_frac(a) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// A signaling NaN has the most significant bit of its fraction cleared
+pub fn f128_is_signaling_nan(a: u128) -> bool {
+    let is_exp_nan = (a & 0x7FFF_8000_0000_0000_0000_0000_0000_0000) == 0x7FFF_0000_0000_0000_0000_0000_0000_0000;
+    let is_frac_nan = (a & 0x0000_7FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// Whether A is a signaling NaN in the encoding of the NaN policy, where MIPS legacy
+// inverts the quiet bit
+fn f128_is_signaling_nan_in(a: u128, env: &Env) -> bool {
+    match env.nan_policy() {
+        NanPolicy::MipsLegacy => (a & 0x7FFF_8000_0000_0000_0000_0000_0000_0000) == 0x7FFF_8000_0000_0000_0000_0000_0000_0000,
+        _ => f128_is_signaling_nan(a),
+    }
+}
+
+// The NaN result of an operation on A and B, at least one of which is a NaN, chosen by the
+// NaN policy of the environment. A signaling NaN is invalid.
+pub(crate) fn f128_propagate_nan(a: u128, b: u128, env: &mut Env) -> u128 {
+    f128_propagate_nans(&[a, b], env)
+}
+
+// The NaN result of the fused multiply-add A x B + C
+pub(crate) fn f128_propagate_nan3(a: u128, b: u128, c: u128, env: &mut Env) -> u128 {
+    match env.nan_policy() {
+        // The addend comes first
+        NanPolicy::Armv8 => f128_propagate_nans(&[c, a, b], env),
+        _ => f128_propagate_nans(&[a, b, c], env),
+    }
+}
+
+// The NaN result of the operands, in the order of the instruction
+fn f128_propagate_nans(ops: &[u128], env: &mut Env) -> u128 {
+    if env.nan_policy() == NanPolicy::X87 {
+        // Pairwise, in order
+        return ops[1..].iter().fold(ops[0], |r, &op| f128_propagate_nan_x87(r, op, env));
+    }
+
+    let default_nan = f128_default_nan(env);
+    let first_signaling = ops.iter().copied().find(|&op| f128_is_signaling_nan_in(op, env));
+    let first_nan = ops.iter().copied().find(|&op| f128_is_nan(op)).unwrap_or(default_nan);
+    if first_signaling.is_some() {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+    }
+
+    match env.nan_policy() {
+        NanPolicy::X86Sse => first_nan | 0x0000_8000_0000_0000_0000_0000_0000_0000,
+        NanPolicy::Armv8 => first_signaling.unwrap_or(first_nan) | 0x0000_8000_0000_0000_0000_0000_0000_0000,
+        // Quieting a NaN could give infinity
+        NanPolicy::MipsLegacy if first_signaling.is_some() => default_nan,
+        NanPolicy::MipsLegacy => first_nan,
+        _ => default_nan,
+    }
+}
+
+fn f128_propagate_nan_x87(in_a: u128, in_b: u128, env: &mut Env) -> u128 {
+    let a = in_a | 0x0000_8000_0000_0000_0000_0000_0000_0000;
+    let b = in_b | 0x0000_8000_0000_0000_0000_0000_0000_0000;
+
+    let is_a_frac_nan = f128_is_signaling_nan(in_a);
+    let is_b_frac_nan = f128_is_signaling_nan(in_b);
+
+    if is_a_frac_nan | is_b_frac_nan {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+        if is_a_frac_nan {
+            if !is_b_frac_nan {
+                return match f128_is_nan(b) {
+                    true => b,
+                    false => a,
+                };
+            }
+        } else {
+            return match f128_is_nan(a) {
+                true => a,
+                false => b,
+            };
+        }
+    }
+
+    // Return the NaN with the larger magnitude
+    let a_mag = a & 0x7FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF;
+    let b_mag = b & 0x7FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF;
+
+    if a_mag < b_mag {
+        b
+    } else if b_mag < a_mag || a < b {
+        a
+    } else {
+        b
+    }
+}
+
+pub fn f128_pack(in_sign: i32, in_exp: i32, in_frac: u128) -> u128 {
+    (((in_sign & 0x01) as u128) << 127) | (((in_exp & 0x7FFF) as u128) << 112) | f128_frac(in_frac)
+}
+
+// Round the significand and its extra bits, where the hidden bit of the significand is bit 112.
+// The exponent is one less than the biased exponent of the result, as the hidden bit is added to it.
+pub(crate) fn f128_round_and_pack(in_sign: i32, in_exp: i32, in_frac: u128, in_frac_extra: u64, env: &mut Env) -> u128 {
+    let rounding_mode = env.rounding_mode();
+    let detect_tininess = env.detect_tininess();
+
+    let round_near_even = rounding_mode == RoundingMode::NearEven;
+    let round_near = round_near_even || rounding_mode == RoundingMode::NearMaxMag;
+    // Round away from zero only when rounding toward the infinity of the same sign
+    let round_away = match rounding_mode {
+        RoundingMode::Min => in_sign == 1,
+        RoundingMode::Max => in_sign == 0,
+        _ => false,
+    };
+
+    let sign = in_sign;
+    let mut exp = in_exp;
+    let mut frac = in_frac;
+    let mut frac_extra = in_frac_extra;
+
+    let increment = |frac_extra: u64| if round_near {
+        frac_extra >= 0x8000000000000000
+    } else {
+        round_away && frac_extra != 0
+    };
+    let mut do_increment = increment(frac_extra);
+
+    // A negative exponent is seen as a large unsigned one, so that tiny results are caught here as well
+    if (exp as u32) >= 0x7FFD {
+        if exp < 0 {
+            // Tiny result, denormalize it before rounding
+            let is_tiny = detect_tininess == DetectTininess::Before
+                || exp < -1
+                || !do_increment
+                || frac < 0x0001_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF;
+            let (r_frac, r_frac_extra) = f128_shift_right_jam_extra(frac, frac_extra, -exp);
+            frac = r_frac;
+            frac_extra = r_frac_extra;
+            exp = 0;
+            if is_tiny && frac_extra != 0 {
+                // Underflow
+                env.raise(ExceptionFlags::UNDERFLOW);
+            }
+            do_increment = increment(frac_extra);
+        } else if exp > 0x7FFD || (exp == 0x7FFD && frac == 0x0001_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF && do_increment) {
+            // Overflow and inexact, round to infinity or to the largest finite value
+            env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+            if round_near || round_away {
+                return f128_pack_raw(sign, 0x7FFF, 0);
+            }
+            return f128_pack_raw(sign, 0x7FFE, 0x0000_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF);
+        }
+    }
+
+    if frac_extra != 0 {
+        env.raise(ExceptionFlags::INEXACT);
+        if rounding_mode == RoundingMode::Odd {
+            // Inexact, jam the lost bits into the least significant bit
+            return f128_pack_raw(sign, exp, frac | 1);
+        }
+    }
+    if do_increment {
+        frac += 1;
+        if round_near_even && (frac_extra & 0x7FFFFFFFFFFFFFFF) == 0 {
+            // Tie, round to even
+            frac &= !1;
+        }
+    } else if frac == 0 {
+        exp = 0;
+    }
+
+    f128_pack_raw(sign, exp, frac)
+}
+
+pub(crate) fn f128_count_leading_zero(frac: u128) -> i32 {
+    if frac >> 64 == 0 {
+        64 + f64_count_leading_zero(frac as i64)
+    } else {
+        f64_count_leading_zero((frac >> 64) as i64)
+    }
+}
+
+pub(crate) fn f128_norm_round_and_pack(in_sign: i32, in_exp: i32, in_frac: u128, env: &mut Env) -> u128 {
+    let shift_count = f128_count_leading_zero(in_frac) - 15;
+    let sign = in_sign;
+    let mut exp = in_exp - shift_count;
+
+    if shift_count >= 0 {
+        let frac = in_frac << shift_count;
+        if (exp as u32) < 0x7FFD {
+            // Exact, no rounding needed
+            if frac == 0 {
+                exp = 0;
+            }
+            return f128_pack_raw(sign, exp, frac);
+        }
+        f128_round_and_pack(sign, exp, frac, 0, env)
+    } else {
+        let (frac, frac_extra) = f128_shift_right_jam_extra(in_frac, 0, -shift_count);
+        f128_round_and_pack(sign, exp, frac, frac_extra, env)
+    }
+}
+
+pub(crate) fn f128_norm_subnormal_frac(frac: u128) -> (i32, u128) {
+    let shift_count = f128_count_leading_zero(frac) - 15;
+
+    (1 - shift_count, frac << shift_count)
+}
+
+// Multiply two u128 into a 256-bit product, returned as its high and low halves
+pub(crate) fn f128_mul_128_to_256(a: u128, b: u128) -> (u128, u128) {
+    let a_hi = a >> 64;
+    let a_lo = a & 0xFFFFFFFFFFFFFFFF;
+    let b_hi = b >> 64;
+    let b_lo = b & 0xFFFFFFFFFFFFFFFF;
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    // Sum the middle partial products with the carries out of the low one
+    let mid = (lo_lo >> 64) + (hi_lo & 0xFFFFFFFFFFFFFFFF) + (lo_hi & 0xFFFFFFFFFFFFFFFF);
+    let lo = (mid << 64) | (lo_lo & 0xFFFFFFFFFFFFFFFF);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+
+    (hi, lo)
+}
@@ -0,0 +1,260 @@
+mod soft_f16_add;
+mod soft_f16_sub;
+mod soft_f16_mul;
+mod soft_f16_div;
+mod soft_f16_comp;
+mod soft_f16_sqrt;
+mod soft_f16_conv;
+pub(crate) mod util;
+
+// The procedural API works on raw u16 bit patterns of IEEE 754 binary16. Each
+// function uses the environment of the current thread, and has a `_with_env`
+// variant which takes an explicit environment.
+
+// Operations
+pub use soft_f16_add::{ f16_add, f16_add_with_env };
+pub use soft_f16_sub::{ f16_sub, f16_sub_with_env };
+pub use soft_f16_mul::{ f16_mul, f16_mul_with_env };
+pub use soft_f16_div::{ f16_div, f16_div_with_env };
+
+// Comparisons
+pub use soft_f16_comp::{ f16_eq, f16_eq_with_env };
+pub use soft_f16_comp::{ f16_ne, f16_ne_with_env };
+pub use soft_f16_comp::{ f16_lt, f16_lt_with_env };
+pub use soft_f16_comp::{ f16_gt, f16_gt_with_env };
+pub use soft_f16_comp::{ f16_le, f16_le_with_env };
+pub use soft_f16_comp::{ f16_ge, f16_ge_with_env };
+
+// Others
+pub use soft_f16_sqrt::{ f16_sqrt, f16_sqrt_with_env };
+
+// Conversions
+pub use soft_f16_conv::{ f16_to_f32, f16_to_f32_with_env };
+pub use soft_f16_conv::{ f32_to_f16, f32_to_f16_with_env };
+
+// Utilities
+pub use util::{
+    f16_is_nan,
+    f16_is_signaling_nan,
+    f16_pack,
+};
+
+// F16 struct
+#[derive(Clone, Copy, Debug)]
+pub struct F16 {
+    value: u16,
+}
+
+impl F16 {
+    pub const fn from_bits(bits: u16) -> F16 {
+        F16 {
+            value: bits
+        }
+    }
+
+    pub fn to_bits(self) -> u16 {
+        self.value
+    }
+
+    #[deprecated(note = "use from_bits, as F32::from_bits")]
+    pub const fn from_u16(value: u16) -> F16 {
+        F16::from_bits(value)
+    }
+
+    // The bit pattern, as to_bits
+    pub fn value(self) -> u16 {
+        self.value
+    }
+
+    pub fn sqrt(self) -> Self {
+        F16 {
+            value: f16_sqrt(self.value)
+        }
+    }
+
+    pub fn from_f32(value: F32) -> F16 {
+        F16 {
+            value: f32_to_f16(value.value())
+        }
+    }
+
+    pub fn to_f32(self) -> F32 {
+        F32::from_bits(f16_to_f32(self.value))
+    }
+}
+
+use crate::soft_f32::F32;
+use std::ops;
+
+impl ops::Add<F16> for F16 {
+    type Output = F16;
+
+    fn add(self, other: F16) -> F16 {
+        F16 {
+            value: f16_add(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Sub<F16> for F16 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        F16 {
+            value: f16_sub(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Mul<F16> for F16 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        F16 {
+            value: f16_mul(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Div<F16> for F16 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        F16 {
+            value: f16_div(self.value, other.value)
+        }
+    }
+}
+
+use std::cmp;
+
+impl cmp::PartialEq for F16 {
+    // Implement equal with only symmetric and transitive for F16.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
+    // For example, in floating point numbers NaN != NaN,
+    // so floating point types implement PartialEq but not Eq.
+    fn eq(&self, other: &Self) -> bool {
+        f16_eq(self.value, other.value)
+    }
+
+    #[allow(clippy::partialeq_ne_impl)]
+    fn ne(&self, other: &Self) -> bool {
+        f16_ne(self.value, other.value)
+    }
+}
+
+impl cmp::PartialOrd for F16 {
+    // Implement compare with only symmetric and transitive for F16.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if f16_eq(self.value, other.value) { return Some(cmp::Ordering::Equal); }
+        if f16_lt(self.value, other.value) { return Some(cmp::Ordering::Less); }
+        if f16_gt(self.value, other.value) { return Some(cmp::Ordering::Greater); }
+
+        None
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        f16_gt(self.value, other.value)
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        f16_lt(self.value, other.value)
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        f16_ge(self.value, other.value)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        f16_le(self.value, other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_f16_add_with_struct() {
+        let v0_1 = crate::soft_f16::F16::from_bits(0x2E66);
+        let v0_2 = crate::soft_f16::F16::from_bits(0x3266);
+
+        let v0_3 = v0_1 + v0_2;
+
+        assert_eq!(v0_3.value(), 0x34CC);
+    }
+
+    #[test]
+    fn test_f16_sub_with_struct() {
+        let v0_1 = crate::soft_f16::F16::from_bits(0x2E66);
+        let v0_2 = crate::soft_f16::F16::from_bits(0x3266);
+
+        let v0_1_result = v0_2 - v0_1;
+
+        assert_eq!(v0_1_result.value(), 0x2E66);
+    }
+
+    #[test]
+    fn test_f16_mul_with_struct() {
+        let v0_1 = crate::soft_f16::F16::from_bits(0x2E66);
+        let v0_2 = crate::soft_f16::F16::from_bits(0x3266);
+
+        let v0_02 = v0_2 * v0_1;
+
+        assert_eq!(v0_02.value(), 0x251E);
+    }
+
+    #[test]
+    fn test_f16_div_with_struct() {
+        let v0_1 = crate::soft_f16::F16::from_bits(0x2E66);
+        let v0_2 = crate::soft_f16::F16::from_bits(0x3266);
+
+        let v0_5 = v0_1 / v0_2;
+
+        assert_eq!(v0_5.value(), 0x3800);
+    }
+
+    #[test]
+    fn test_f16_compare_with_struct() {
+        let v0_1 = crate::soft_f16::F16::from_bits(0x2E66);
+        let v0_2 = crate::soft_f16::F16::from_bits(0x3266);
+
+        assert_eq!(v0_1 == v0_2, false);
+        assert_eq!(v0_1 != v0_2, true);
+
+        assert_eq!(v0_1 < v0_2, true);
+        assert_eq!(v0_1 <= v0_1, true);
+        assert_eq!(v0_1 > v0_2, false);
+        assert_eq!(v0_1 >= v0_1, true);
+    }
+
+    #[test]
+    fn test_f16_sqrt_with_struct() {
+        // sqrt(4) = 2
+        let v4 = crate::soft_f16::F16::from_bits(0x4400);
+
+        let v2 = v4.sqrt();
+
+        assert_eq!(v2.value(), 0x4000);
+    }
+
+    #[test]
+    fn test_f16_f32_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+
+        let v0_1_f16 = crate::soft_f16::F16::from_f32(v0_1);
+        let v0_1_f32 = crate::soft_f16::F16::from_bits(0x2E66).to_f32();
+
+        assert_eq!(v0_1_f16.value(), 0x2E66);
+        assert_eq!(v0_1_f32.value(), 0x3DCCC000);
+    }
+
+    #[test]
+    fn test_f16_bits_with_struct() {
+        assert_eq!(crate::soft_f16::F16::from_bits(0x3266).to_bits(), 0x3266);
+        #[allow(deprecated)]
+        let v = crate::soft_f16::F16::from_u16(0x3266);
+        assert_eq!(v.to_bits(), 0x3266);
+    }
+}
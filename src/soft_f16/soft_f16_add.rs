@@ -0,0 +1,68 @@
+use super::util::f16_widen;
+use super::soft_f16_conv::f32_to_f16_with_env;
+
+use crate::soft_f32::f32_add_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f16_add(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| f16_add_with_env(a, b, env))
+}
+
+// Add as Float32, the result is rounded only once
+pub fn f16_add_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = f16_widen(a);
+    let b32 = f16_widen(b);
+
+    f32_round_to_narrow(|env| f32_add_with_env(a32, b32, env), f32_to_f16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f16_add() {
+        // 0.1 + 0.2 = 0.3
+        assert_eq!(crate::soft_f16::f16_add(0x2E66, 0x3266), 0x34CC);
+        // 1 + 2 = 3
+        assert_eq!(crate::soft_f16::f16_add(0x3C00, 0x4000), 0x4200);
+        // Subnormals
+        assert_eq!(crate::soft_f16::f16_add(0x0001, 0x0001), 0x0002);
+    }
+
+    #[test]
+    fn test_f16_add_inf_nan() {
+        // Inf + 1 = Inf
+        assert_eq!(crate::soft_f16::f16_add(0x7C00, 0x3C00), 0x7C00);
+        // -Inf + Inf = NaN
+        assert_eq!(crate::soft_f16::f16_add(0x7C00 | 0x8000, 0x7C00), 0xFE00);
+
+        // A signaling NaN is quiet and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x7C01, 0x3C00, &mut env), 0x7E01);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f16_add_rounding_mode() {
+        // 1 + 2^-11, a tie rounded once
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x3C00, 0x1000, &mut Env::new(RoundingMode::NearEven)), 0x3C00);
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x3C00, 0x1000, &mut Env::new(RoundingMode::MinMag)), 0x3C00);
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x3C00, 0x1000, &mut Env::new(RoundingMode::Min)), 0x3C00);
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x3C00, 0x1000, &mut Env::new(RoundingMode::Max)), 0x3C01);
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x3C00, 0x1000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3C01);
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x3C00, 0x1000, &mut Env::new(RoundingMode::Odd)), 0x3C01);
+
+        // MAX + MAX, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x7BFF, 0x7BFF, &mut env), 0x7C00);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        let mut env = Env::new(RoundingMode::MinMag);
+        assert_eq!(crate::soft_f16::f16_add_with_env(0x7BFF, 0x7BFF, &mut env), 0x7BFF);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+    }
+}
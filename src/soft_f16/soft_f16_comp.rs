@@ -0,0 +1,109 @@
+use super::util::f16_widen;
+
+use crate::soft_f32::{
+    f32_eq_with_env,
+    f32_ne_with_env,
+    f32_lt_with_env,
+    f32_gt_with_env,
+    f32_le_with_env,
+    f32_ge_with_env,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+// Widening to Float32 is exact and keeps signaling NaNs, so comparing there is the same
+
+pub fn f16_eq(a: u16, b: u16) -> bool {
+    with_thread_env(|env| f16_eq_with_env(a, b, env))
+}
+
+pub fn f16_eq_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_eq_with_env(f16_widen(a), f16_widen(b), env)
+}
+
+pub fn f16_ne(a: u16, b: u16) -> bool {
+    with_thread_env(|env| f16_ne_with_env(a, b, env))
+}
+
+pub fn f16_ne_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_ne_with_env(f16_widen(a), f16_widen(b), env)
+}
+
+pub fn f16_lt(a: u16, b: u16) -> bool {
+    with_thread_env(|env| f16_lt_with_env(a, b, env))
+}
+
+pub fn f16_lt_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_lt_with_env(f16_widen(a), f16_widen(b), env)
+}
+
+pub fn f16_gt(a: u16, b: u16) -> bool {
+    with_thread_env(|env| f16_gt_with_env(a, b, env))
+}
+
+pub fn f16_gt_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_gt_with_env(f16_widen(a), f16_widen(b), env)
+}
+
+pub fn f16_le(a: u16, b: u16) -> bool {
+    with_thread_env(|env| f16_le_with_env(a, b, env))
+}
+
+pub fn f16_le_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_le_with_env(f16_widen(a), f16_widen(b), env)
+}
+
+pub fn f16_ge(a: u16, b: u16) -> bool {
+    with_thread_env(|env| f16_ge_with_env(a, b, env))
+}
+
+pub fn f16_ge_with_env(a: u16, b: u16, env: &mut Env) -> bool {
+    f32_ge_with_env(f16_widen(a), f16_widen(b), env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f16_compare() {
+        // 0.1 and 0.2
+        assert_eq!(crate::soft_f16::f16_eq(0x2E66, 0x3266), false);
+        assert_eq!(crate::soft_f16::f16_ne(0x2E66, 0x3266), true);
+        assert_eq!(crate::soft_f16::f16_lt(0x2E66, 0x3266), true);
+        assert_eq!(crate::soft_f16::f16_le(0x2E66, 0x3266), true);
+        assert_eq!(crate::soft_f16::f16_gt(0x2E66, 0x3266), false);
+        assert_eq!(crate::soft_f16::f16_ge(0x2E66, 0x3266), false);
+
+        // 0 and -0
+        assert_eq!(crate::soft_f16::f16_eq(0x0000, 0x8000), true);
+        assert_eq!(crate::soft_f16::f16_lt(0x8000, 0x0000), false);
+
+        // Subnormals
+        assert_eq!(crate::soft_f16::f16_lt(0x0001, 0x0002), true);
+
+        // NaN is unordered
+        assert_eq!(crate::soft_f16::f16_eq(0x7E01, 0x7E01), false);
+        assert_eq!(crate::soft_f16::f16_ne(0x7E01, 0x7E01), true);
+        assert_eq!(crate::soft_f16::f16_ge(0x7E01, 0x3C00), false);
+    }
+
+    #[test]
+    fn test_f16_compare_exception_flags() {
+        // Equality with a quiet NaN is quiet
+        let mut env = Env::default();
+        crate::soft_f16::f16_eq_with_env(0x7E01, 0x3C00, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Equality with a signaling NaN is invalid
+        let mut env = Env::default();
+        crate::soft_f16::f16_eq_with_env(0x7C01, 0x3C00, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Ordering with any NaN is invalid
+        let mut env = Env::default();
+        crate::soft_f16::f16_lt_with_env(0x7E01, 0x3C00, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
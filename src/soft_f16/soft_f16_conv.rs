@@ -0,0 +1,144 @@
+use super::util::{
+    f16_round_and_pack,
+    f16_pack_raw,
+    f16_sign, f16_frac,
+    f16_is_nan,
+    f16_widen,
+};
+
+use crate::soft_f32::util::{
+    f32_shift_right_jam,
+    f32_sign, f32_exp, f32_frac,
+    f32_convert_nan,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f16_to_f32(a: u16) -> u32 {
+    with_thread_env(|env| f16_to_f32_with_env(a, env))
+}
+
+// Every Float16 is exactly representable as a Float32
+pub fn f16_to_f32_with_env(a: u16, env: &mut Env) -> u32 {
+    if f16_is_nan(a) {
+        // NaN, as chosen by the NaN policy
+        let frac = f16_frac(a) as u32;
+        return f32_convert_nan(f16_sign(a), (frac & 0x0200) != 0, (frac & 0x01FF) << 13, env);
+    }
+
+    f16_widen(a)
+}
+
+pub fn f32_to_f16(a: u32) -> u16 {
+    with_thread_env(|env| f32_to_f16_with_env(a, env))
+}
+
+pub fn f32_to_f16_with_env(a: u32, env: &mut Env) -> u16 {
+    let sign = f32_sign(a);
+    let exp = f32_exp(a);
+    let frac = f32_frac(a);
+
+    if exp == 0xFF {
+        if frac != 0 {
+            // NaN, as chosen by the NaN policy, with the high bits of its payload
+            let nan = f32_convert_nan(sign, (frac & 0x00400000) != 0, (frac & 0x003FFFFF) as u32, env);
+            return match f32_frac(nan) >> 13 {
+                // A MIPS legacy quiet NaN whose payload is too low for Float16
+                0 => f16_pack_raw(f32_sign(nan), 0x1F, 0x01FF),
+                nan_frac => f16_pack_raw(f32_sign(nan), 0x1F, nan_frac),
+            };
+        }
+        // Infinity
+        return f16_pack_raw(sign, 0x1F, 0);
+    }
+
+    // Keep 14 bits of the fraction, the rest is sticky
+    let frac16 = f32_shift_right_jam(frac, 9);
+    if exp == 0 && frac16 == 0 {
+        // Zero
+        return f16_pack_raw(sign, 0, 0);
+    }
+
+    // Any Float32 subnormal is far below the Float16 ones, so the missing hidden bit does not matter
+    f16_round_and_pack(sign, exp - 0x71, frac16 | 0x4000, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f16_to_f32() {
+        // 1
+        assert_eq!(crate::soft_f16::f16_to_f32(0x3C00), 0x3F800000);
+        // 0.1
+        assert_eq!(crate::soft_f16::f16_to_f32(0x2E66), 0x3DCCC000);
+        // The smallest subnormal is normal in Float32
+        assert_eq!(crate::soft_f16::f16_to_f32(0x0001), 0x33800000);
+        // -0
+        assert_eq!(crate::soft_f16::f16_to_f32(0x8000), 0x80000000);
+        // -Inf
+        assert_eq!(crate::soft_f16::f16_to_f32(0xFC00), 0xFF800000);
+
+        // A signaling NaN is quiet and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_to_f32_with_env(0x7C01, &mut env), 0x7FC02000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_to_f16() {
+        // 1
+        assert_eq!(crate::soft_f16::f32_to_f16(0x3F800000), 0x3C00);
+        // 0.1
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x2E66);
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::Max)), 0x2E67);
+        // NaN
+        assert_eq!(crate::soft_f16::f32_to_f16(0xFFC00000), 0xFE00);
+        // Inf
+        assert_eq!(crate::soft_f16::f32_to_f16(0x7F800000), 0x7C00);
+
+        // 65520 rounds to infinity, but not toward zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x477FF000, &mut env), 0x7C00);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x477FF000, &mut Env::new(RoundingMode::MinMag)), 0x7BFF);
+
+        // 2^-25 is a tie between zero and the smallest subnormal
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x33000000, &mut env), 0x0000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x33000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x0001);
+    }
+
+    #[test]
+    fn test_f16_conv_nan_policy() {
+        // The default NaN replaces every NaN, and a signaling NaN is invalid
+        for &nan_policy in [NanPolicy::RiscV, NanPolicy::Armv8DefaultNan].iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            assert_eq!(crate::soft_f16::f16_to_f32_with_env(0x7E01, &mut env), 0x7FC00000);
+            assert_eq!(crate::soft_f16::f32_to_f16_with_env(0xFFC01234, &mut env), 0x7E00);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+            assert_eq!(crate::soft_f16::f16_to_f32_with_env(0xFC01, &mut env), 0x7FC00000);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+            env.clear_flags();
+            assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x7F800001, &mut env), 0x7E00);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+
+        // The quiet bit is inverted in MIPS legacy
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_f16::f16_to_f32_with_env(0x7C01, &mut env), 0x7F802000);
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0xFF802000, &mut env), 0xFC01);
+        // A payload too low for Float16 gives the default NaN
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x7F800001, &mut env), 0x7DFF);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f16::f16_to_f32_with_env(0x7E00, &mut env), 0x7FBFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        env.clear_flags();
+        assert_eq!(crate::soft_f16::f32_to_f16_with_env(0x7FC00000, &mut env), 0x7DFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
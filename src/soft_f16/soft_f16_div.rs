@@ -0,0 +1,48 @@
+use super::util::f16_widen;
+use super::soft_f16_conv::f32_to_f16_with_env;
+
+use crate::soft_f32::f32_div_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f16_div(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| f16_div_with_env(a, b, env))
+}
+
+// Divide as Float32, the result is rounded only once
+pub fn f16_div_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = f16_widen(a);
+    let b32 = f16_widen(b);
+
+    f32_round_to_narrow(|env| f32_div_with_env(a32, b32, env), f32_to_f16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f16_div() {
+        // 0.1 / 0.2 = 0.5
+        assert_eq!(crate::soft_f16::f16_div(0x2E66, 0x3266), 0x3800);
+        // 3 / -1 = -3
+        assert_eq!(crate::soft_f16::f16_div(0x4200, 0xBC00), 0x4200 | 0x8000);
+        // 0 / 0 = NaN
+        assert_eq!(crate::soft_f16::f16_div(0x0000, 0x0000), 0xFE00);
+    }
+
+    #[test]
+    fn test_f16_div_rounding_mode() {
+        // 1 / 3
+        assert_eq!(crate::soft_f16::f16_div_with_env(0x3C00, 0x4200, &mut Env::new(RoundingMode::NearEven)), 0x3555);
+        assert_eq!(crate::soft_f16::f16_div_with_env(0x3C00, 0x4200, &mut Env::new(RoundingMode::MinMag)), 0x3555);
+        assert_eq!(crate::soft_f16::f16_div_with_env(0x3C00, 0x4200, &mut Env::new(RoundingMode::Max)), 0x3556);
+
+        // 1 / 0 = Inf
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_div_with_env(0x3C00, 0x0000, &mut env), 0x7C00);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+    }
+}
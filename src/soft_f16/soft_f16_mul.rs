@@ -0,0 +1,48 @@
+use super::util::f16_widen;
+use super::soft_f16_conv::f32_to_f16_with_env;
+
+use crate::soft_f32::f32_mul_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f16_mul(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| f16_mul_with_env(a, b, env))
+}
+
+// Multiply as Float32, the result is rounded only once
+pub fn f16_mul_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = f16_widen(a);
+    let b32 = f16_widen(b);
+
+    f32_round_to_narrow(|env| f32_mul_with_env(a32, b32, env), f32_to_f16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f16_mul() {
+        // 0.1 * 0.2 = 0.02
+        assert_eq!(crate::soft_f16::f16_mul(0x2E66, 0x3266), 0x251E);
+        // -1 * 3 = -3
+        assert_eq!(crate::soft_f16::f16_mul(0xBC00, 0x4200), 0x4200 | 0x8000);
+        // 0 * Inf = NaN
+        assert_eq!(crate::soft_f16::f16_mul(0x0000, 0x7C00), 0xFE00);
+    }
+
+    #[test]
+    fn test_f16_mul_underflow() {
+        // Half of the smallest subnormal is a tie, rounded to zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_mul_with_env(0x0001, 0x3800, &mut env), 0x0000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // 1.5 times the smallest subnormal is a tie, rounded to even
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_mul_with_env(0x0003, 0x3800, &mut env), 0x0002);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+}
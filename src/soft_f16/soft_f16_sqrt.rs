@@ -0,0 +1,48 @@
+use super::util::f16_widen;
+use super::soft_f16_conv::f32_to_f16_with_env;
+
+use crate::soft_f32::f32_sqrt_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f16_sqrt(a: u16) -> u16 {
+    with_thread_env(|env| f16_sqrt_with_env(a, env))
+}
+
+// Square root as Float32, the result is rounded only once
+pub fn f16_sqrt_with_env(a: u16, env: &mut Env) -> u16 {
+    let a32 = f16_widen(a);
+
+    f32_round_to_narrow(|env| f32_sqrt_with_env(a32, env), f32_to_f16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f16_sqrt() {
+        // sqrt(4) = 2
+        assert_eq!(crate::soft_f16::f16_sqrt(0x4400), 0x4000);
+        // sqrt(-0) = -0
+        assert_eq!(crate::soft_f16::f16_sqrt(0x8000), 0x8000);
+        // sqrt(Inf) = Inf
+        assert_eq!(crate::soft_f16::f16_sqrt(0x7C00), 0x7C00);
+    }
+
+    #[test]
+    fn test_f16_sqrt_rounding_mode() {
+        // sqrt(2)
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_sqrt_with_env(0x4000, &mut env), 0x3DA8);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f16::f16_sqrt_with_env(0x4000, &mut Env::new(RoundingMode::Max)), 0x3DA9);
+
+        // sqrt(-1) = NaN
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f16::f16_sqrt_with_env(0xBC00, &mut env), 0xFE00);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,45 @@
+use super::util::f16_widen;
+use super::soft_f16_conv::f32_to_f16_with_env;
+
+use crate::soft_f32::f32_sub_with_env;
+use crate::soft_f32::util::f32_round_to_narrow;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f16_sub(a: u16, b: u16) -> u16 {
+    with_thread_env(|env| f16_sub_with_env(a, b, env))
+}
+
+// Subtract as Float32, the result is rounded only once
+pub fn f16_sub_with_env(a: u16, b: u16, env: &mut Env) -> u16 {
+    let a32 = f16_widen(a);
+    let b32 = f16_widen(b);
+
+    f32_round_to_narrow(|env| f32_sub_with_env(a32, b32, env), f32_to_f16_with_env, env)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, RoundingMode };
+
+    #[test]
+    fn test_f16_sub() {
+        // 0.2 - 0.1 = 0.1
+        assert_eq!(crate::soft_f16::f16_sub(0x3266, 0x2E66), 0x2E66);
+        // 2 - 3 = -1
+        assert_eq!(crate::soft_f16::f16_sub(0x4000, 0x4200), 0xBC00);
+        // Inf - Inf = NaN
+        assert_eq!(crate::soft_f16::f16_sub(0x7C00, 0x7C00), 0xFE00);
+    }
+
+    #[test]
+    fn test_f16_sub_zero_sign() {
+        // 1 - 1 is -0 only when rounding toward -Inf
+        assert_eq!(crate::soft_f16::f16_sub_with_env(0x3C00, 0x3C00, &mut Env::new(RoundingMode::NearEven)), 0x0000);
+        assert_eq!(crate::soft_f16::f16_sub_with_env(0x3C00, 0x3C00, &mut Env::new(RoundingMode::Min)), 0x8000);
+        assert_eq!(crate::soft_f16::f16_sub_with_env(0x3C00, 0x3C00, &mut Env::new(RoundingMode::Odd)), 0x0000);
+        // -0 - 0 = -0
+        assert_eq!(crate::soft_f16::f16_sub_with_env(0x8000, 0x0000, &mut Env::new(RoundingMode::NearEven)), 0x8000);
+    }
+}
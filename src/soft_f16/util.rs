@@ -0,0 +1,138 @@
+use crate::soft_float::{ RoundingMode, DetectTininess, Env, ExceptionFlags };
+use crate::soft_f32::util::{
+    f32_shift_right_jam,
+    f32_count_leading_zero,
+    f32_pack,
+};
+
+pub(crate) fn f16_sign(a: u16) -> i32 {
+    ((a >> 15) & 0x01) as i32
+}
+
+pub(crate) fn f16_exp(a: u16) -> i32 {
+    ((a >> 10) & 0x1F) as i32
+}
+
+pub(crate) fn f16_frac(a: u16) -> i32 {
+    (a & 0x03FF) as i32
+}
+
+pub(crate) fn f16_pack_raw(in_sign: i32, in_exp: i32, in_frac: i32) -> u16 {
+    // Use + rather than |, so that a carry out of the fraction increments the exponent
+    ((in_sign << 15) | ((in_exp << 10) + in_frac)) as u16
+}
+
+pub fn f16_is_nan(a: u16) -> bool {
+    let is_exp_nan = (a & 0x7C00) == 0x7C00;
+    let is_frac_nan = (a & 0x03FF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// A signaling NaN has the most significant bit of its fraction cleared
+pub fn f16_is_signaling_nan(a: u16) -> bool {
+    let is_exp_nan = (a & 0x7E00) == 0x7C00;
+    let is_frac_nan = (a & 0x01FF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+pub fn f16_pack(in_sign: i32, in_exp: i32, in_frac: i32) -> u16 {
+    (((in_sign & 0x01) << 15) | ((in_exp & 0x1F) << 10) | (in_frac & 0x03FF)) as u16
+}
+
+// Round the significand, where the hidden bit is bit 14.
+// The exponent is one less than the biased exponent of the result, as the hidden bit is added to it.
+pub(crate) fn f16_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i32, env: &mut Env) -> u16 {
+    let rounding_mode = env.rounding_mode();
+    let detect_tininess = env.detect_tininess();
+
+    let round_near_even = rounding_mode == RoundingMode::NearEven;
+    let mut round_increment = 0x08;
+
+    let sign = in_sign;
+    let mut exp = in_exp;
+    let mut frac = in_frac;
+
+    match rounding_mode {
+        RoundingMode::NearEven | RoundingMode::NearMaxMag => { /* Do nothing */ }
+        _ => {
+            // Round away from zero only when rounding toward the infinity of the same sign
+            let round_away = match rounding_mode {
+                RoundingMode::Min => sign == 1,
+                RoundingMode::Max => sign == 0,
+                _ => false,
+            };
+            round_increment = if round_away { 0x0F } else { 0 };
+        }
+    }
+    let mut round_bits = frac & 0x0F;
+
+    // A negative exponent is seen as a large unsigned one, so that tiny results are caught here as well
+    if (exp as u32) >= 0x1D {
+        if exp < 0 {
+            // Tiny result, denormalize it before rounding
+            let is_tiny = detect_tininess == DetectTininess::Before
+                || exp < -1
+                || frac + round_increment < 0x8000;
+            frac = f32_shift_right_jam(frac, -exp);
+            exp = 0;
+            round_bits = frac & 0x0F;
+            if is_tiny && round_bits != 0 {
+                // Underflow
+                env.raise(ExceptionFlags::UNDERFLOW);
+            }
+        } else if exp > 0x1D || frac + round_increment >= 0x8000 {
+            // Overflow and inexact, round to infinity or to the largest finite value
+            env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+            return f16_pack_raw(sign, 0x1F, 0) - (round_increment == 0) as u16;
+        }
+    }
+
+    let mut r_frac = (frac + round_increment) >> 4;
+
+    if round_bits != 0 {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    if round_bits != 0 && rounding_mode == RoundingMode::Odd {
+        // Inexact, jam the lost bits into the least significant bit
+        r_frac |= 1;
+    } else if round_near_even && round_bits == 0x08 {
+        // Tie, round to even
+        r_frac &= !1;
+    }
+    if r_frac == 0 { exp = 0; }
+
+    f16_pack_raw(sign, exp, r_frac)
+}
+
+pub(crate) fn f16_norm_subnormal_frac(frac: i32) -> (i32, i32) {
+    let shift_count = f32_count_leading_zero(frac) - 21;
+
+    (1 - shift_count, frac << shift_count)
+}
+
+// Widen to Float32 exactly, a signaling NaN is kept signaling
+pub(crate) fn f16_widen(a: u16) -> u32 {
+    let sign = f16_sign(a);
+    let mut exp = f16_exp(a);
+    let mut frac = f16_frac(a);
+
+    if exp == 0x1F {
+        // Infinity or NaN
+        return f32_pack(sign, 0xFF, frac << 13);
+    }
+
+    if exp == 0 {
+        if frac == 0 {
+            // Zero
+            return f32_pack(sign, 0, 0);
+        }
+        let (norm_exp, norm_frac) = f16_norm_subnormal_frac(frac);
+        exp = norm_exp;
+        frac = norm_frac;
+    }
+
+    f32_pack(sign, exp + 0x70, frac << 13)
+}
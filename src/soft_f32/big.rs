@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+
+// Big unsigned integers for the conversions between Float32 and decimal strings.
+//
+// A big integer has 640 bits in little-endian 32 bits limbs. It holds 2^24 x 5^149 and
+// 10^45 x 2^176 for the formatting, and 10^166 x 2^31 for the parsing, the largest values they
+// take. The operations are const fns taking and returning values, so that a decimal string can
+// be parsed in a constant, and an operation which overflows is truncated.
+
+const BIG_LIMBS: usize = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Big([u32; BIG_LIMBS]);
+
+impl Big {
+    pub(crate) const fn from_u64(n: u64) -> Big {
+        let mut r = [0; BIG_LIMBS];
+        r[0] = n as u32;
+        r[1] = (n >> 32) as u32;
+        Big(r)
+    }
+
+    pub(crate) const fn is_zero(self) -> bool {
+        let mut i = 0;
+        while i < BIG_LIMBS {
+            if self.0[i] != 0 {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    // The number of significant bits, 0 for zero
+    pub(crate) const fn bit_len(self) -> i32 {
+        let mut i = BIG_LIMBS;
+        while i > 0 {
+            i -= 1;
+            if self.0[i] != 0 {
+                return 32 * i as i32 + 32 - self.0[i].leading_zeros() as i32;
+            }
+        }
+        0
+    }
+
+    pub(crate) const fn cmp(self, other: Big) -> Ordering {
+        let mut i = BIG_LIMBS;
+        while i > 0 {
+            i -= 1;
+            if self.0[i] != other.0[i] {
+                return if self.0[i] < other.0[i] { Ordering::Less } else { Ordering::Greater };
+            }
+        }
+        Ordering::Equal
+    }
+
+    pub(crate) const fn add(self, other: Big) -> Big {
+        let mut r = [0; BIG_LIMBS];
+        let mut carry = 0u64;
+        let mut i = 0;
+        while i < BIG_LIMBS {
+            let t = self.0[i] as u64 + other.0[i] as u64 + carry;
+            r[i] = t as u32;
+            carry = t >> 32;
+            i += 1;
+        }
+        Big(r)
+    }
+
+    // self - other, with self >= other
+    pub(crate) const fn sub(self, other: Big) -> Big {
+        let mut r = [0; BIG_LIMBS];
+        let mut borrow = 0i64;
+        let mut i = 0;
+        while i < BIG_LIMBS {
+            let t = self.0[i] as i64 - other.0[i] as i64 - borrow;
+            r[i] = t as u32;
+            borrow = (t < 0) as i64;
+            i += 1;
+        }
+        Big(r)
+    }
+
+    // self x n + add
+    pub(crate) const fn mul_add_small(self, n: u32, add: u32) -> Big {
+        let mut r = [0; BIG_LIMBS];
+        let mut carry = add as u64;
+        let mut i = 0;
+        while i < BIG_LIMBS {
+            let t = self.0[i] as u64 * n as u64 + carry;
+            r[i] = t as u32;
+            carry = t >> 32;
+            i += 1;
+        }
+        Big(r)
+    }
+
+    pub(crate) const fn mul_small(self, n: u32) -> Big {
+        self.mul_add_small(n, 0)
+    }
+
+    pub(crate) const fn mul_pow2(self, n: i32) -> Big {
+        let limbs = (n / 32) as usize;
+        let bits = n % 32;
+        let mut r = [0; BIG_LIMBS];
+        let mut i = limbs;
+        while i < BIG_LIMBS {
+            let hi = self.0[i - limbs];
+            let lo = if i > limbs { self.0[i - limbs - 1] } else { 0 };
+            r[i] = match bits {
+                0 => hi,
+                _ => (hi << bits) | (lo >> (32 - bits)),
+            };
+            i += 1;
+        }
+        Big(r)
+    }
+
+    // Multiply by 5^n or 10^n, 5^13 being the largest power of 5 in 32 bits
+    pub(crate) const fn mul_pow5(self, mut n: i32) -> Big {
+        let mut r = self;
+        while n >= 13 {
+            r = r.mul_small(1220703125);
+            n -= 13;
+        }
+        r.mul_small(5u32.pow(n as u32))
+    }
+
+    pub(crate) const fn mul_pow10(self, n: i32) -> Big {
+        self.mul_pow5(n).mul_pow2(n)
+    }
+
+    // The quotient and the remainder of the division by n
+    pub(crate) const fn div_rem_small(self, n: u32) -> (Big, u32) {
+        let mut r = [0; BIG_LIMBS];
+        let mut rem = 0u64;
+        let mut i = BIG_LIMBS;
+        while i > 0 {
+            i -= 1;
+            let t = (rem << 32) | self.0[i] as u64;
+            r[i] = (t / n as u64) as u32;
+            rem = t % n as u64;
+        }
+        (Big(r), rem as u32)
+    }
+
+    // The decimal digits, most significant first
+    pub(crate) fn to_digits(self) -> Vec<u8> {
+        let mut n = self;
+        let mut chunks = Vec::new();
+        while !n.is_zero() {
+            let (q, chunk) = n.div_rem_small(1000000000);
+            chunks.push(chunk);
+            n = q;
+        }
+
+        let mut s = String::new();
+        for (i, chunk) in chunks.iter().rev().enumerate() {
+            match i {
+                0 => s.push_str(&chunk.to_string()),
+                _ => s.push_str(&format!("{:09}", chunk)),
+            }
+        }
+        s.into_bytes()
+    }
+}
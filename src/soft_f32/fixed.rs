@@ -0,0 +1,247 @@
+use super::util::{
+    f32_round_and_pack,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+};
+
+use crate::soft_f128::util::{
+    f128_shift_right_jam,
+    f128_count_leading_zero,
+};
+
+use crate::soft_float::Env;
+
+// Fixed-point arithmetic for the elementary functions.
+//
+// A fixed-point value is an i128 with 62 fraction bits. Products are taken on values below 2
+// in magnitude, so that they fit in 126 bits. Each operation truncates, with an error of at most
+// 2^-62, and the evaluations below stay within a relative error of 2^-56. Rounding the result
+// to Float32 is thus correct unless the exact value is within 2^-56 of a rounding boundary.
+
+pub(crate) const FIXED_FRAC_BITS: i32 = 62;
+pub(crate) const FIXED_ONE: i128 = 1 << FIXED_FRAC_BITS;
+
+// Constants are truncated, the _LO constants hold the next 62 bits
+pub(crate) const FIXED_LN2: i128 = 0x2C5C85FDF473DE6A;
+pub(crate) const FIXED_LN2_LO: i128 = 0x3C9E3B39803F2F6A;
+pub(crate) const FIXED_LOG2_E: i128 = 0x5C551D94AE0BF85D;
+pub(crate) const FIXED_LOG10_E: i128 = 0x1BCB7B1526E50E32;
+pub(crate) const FIXED_LOG10_2: i128 = 0x134413509F79FEF3;
+pub(crate) const FIXED_LOG10_2_LO: i128 = 0x047C4ACD605BE48B;
+
+pub(crate) const FIXED_PI: i128 = 0xC90FDAA22168C234;
+pub(crate) const FIXED_PI_2: i128 = 0x6487ED5110B4611A;
+pub(crate) const FIXED_PI_4: i128 = 0x3243F6A8885A308D;
+pub(crate) const FIXED_SQRT_2: i128 = 0x5A827999FCEF3242;
+
+// atan(i / 8) for i in 0 to 8
+const FIXED_ATAN_EIGHTHS: [i128; 9] = [
+    0x0000000000000000, 0x07F56EA6AB0BDB71, 0x0FADBAFC96406EB1,
+    0x16F61941E4DEF08E, 0x1DAC670561BB4F68, 0x23C01757BDFD67E6,
+    0x292F1F464D3DC249, 0x2E014F8AF08C679C, 0x3243F6A8885A308D,
+];
+
+// A value scaled by 2^k whose square is below this is small enough that the terms of its series
+// past the first are below the error of the evaluation. Such a result is moved by one unit
+// toward the side of the next term, so that it rounds as the exact value when the argument is exact.
+pub(crate) const FIXED_TINY_SQUARE: i128 = 64;
+
+// ln(10) with 100 fraction bits, to be multiplied by a 24 bits significand
+pub(crate) const LN10_Q100: i128 = 0x24D763776AAA2B05BA95B58AE0;
+
+pub(crate) fn fixed_mul(a: i128, b: i128) -> i128 {
+    (a * b) >> FIXED_FRAC_BITS
+}
+
+// The square of a value scaled by 2^k, without scale
+pub(crate) fn fixed_square(a: i128, k: i32) -> i128 {
+    fixed_mul(a, a).checked_shr(2 * k as u32).unwrap_or(0)
+}
+
+// Value of the Float32 given by its biased exponent and significand with the hidden bit,
+// that is frac x 2^(exp - 150), as a fixed-point value. The low bits are truncated.
+pub(crate) fn fixed_from_f32(exp: i32, frac: i32) -> i128 {
+    let shift = exp - 150 + FIXED_FRAC_BITS;
+    if shift >= 0 {
+        (frac as i128) << shift
+    } else if shift > -128 {
+        (frac as i128) >> -shift
+    } else {
+        0
+    }
+}
+
+// Split the finite and nonzero A into its sign, and m and e where |A| = m x 2^e and
+// the fixed-point m is in [1, 2)
+pub(crate) fn f32_to_fixed_parts(a: u32) -> (i32, i128, i32) {
+    let mut exp = f32_exp(a);
+    let mut frac = f32_frac(a);
+    if exp == 0 {
+        let (norm_exp, norm_frac) = f32_norm_subnormal_frac(frac);
+        exp = norm_exp;
+        frac = norm_frac;
+    }
+
+    (f32_sign(a), ((frac | 0x00800000) as i128) << (FIXED_FRAC_BITS - 23), exp - 0x7F)
+}
+
+// e^r for |r| <= 0.35, with the Taylor series. The terms fall below 2^-62 after the 18th.
+pub(crate) fn fixed_exp(r: i128) -> i128 {
+    let mut sum = FIXED_ONE;
+    let mut term = FIXED_ONE;
+    for k in 1..=18 {
+        term = fixed_mul(term, r) / k;
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+    sum
+}
+
+// t x (1 + t^2 / 3 + t^4 / 5 + ...) for t scaled by 2^k, with the scale of t. The signs of the
+// terms alternate when negate is set. |t| <= 1/4, so that t^42 / 43 is already below 2^-68.
+pub(crate) fn fixed_odd_series(t: i128, k: i32, negate: bool) -> i128 {
+    let t2 = fixed_square(t, k);
+    if t2 < FIXED_TINY_SQUARE {
+        return if negate { t - t.signum() } else { t + t.signum() };
+    }
+    let t2 = if negate { -t2 } else { t2 };
+
+    let mut sum = FIXED_ONE;
+    let mut power = FIXED_ONE;
+    for n in (3..=41).step_by(2) {
+        power = fixed_mul(power, t2);
+        if power == 0 {
+            break;
+        }
+        sum += power / n;
+    }
+
+    fixed_mul(t, sum)
+}
+
+// ln(1 + x) for x scaled by 2^k, where 1 + x is in [1/sqrt(2), sqrt(2)], with the series
+// ln(1 + x) = 2 x (t + t^3 / 3 + t^5 / 5 + ...) where t = x / (2 + x).
+//
+// The result has the scale of x, so that the relative error stays small when x is close to 0.
+pub(crate) fn fixed_ln_1p(x: i128, k: i32) -> i128 {
+    let t = (x << FIXED_FRAC_BITS) / (2 * FIXED_ONE + (x >> k));
+
+    // |t| <= 0.172
+    2 * fixed_odd_series(t, k, false)
+}
+
+// ln(m) for a fixed-point m in [1/sqrt(2), sqrt(2)].
+//
+// The result is returned as (v, k) where ln(m) = v x 2^-k, as m - 1 is scaled by 2^k so that
+// the relative error stays small when m is close to 1.
+pub(crate) fn fixed_ln(m: i128) -> (i128, i32) {
+    let num = m - FIXED_ONE;
+    if num == 0 {
+        return (0, 0);
+    }
+
+    // Scale |m - 1| to [2^61, 2^62)
+    let k = f128_count_leading_zero(num.unsigned_abs()) - 66;
+
+    (fixed_ln_1p(num << k, k), k)
+}
+
+// ln(v x 2^e) for a positive fixed-point v, without scale. The error is relative when the
+// result is not close to 0.
+pub(crate) fn fixed_ln_abs(v: i128, e: i32) -> i128 {
+    // Move v to [1/sqrt(2), sqrt(2)]
+    let lead_pos = 127 - f128_count_leading_zero(v as u128);
+    let mut e = e + lead_pos - FIXED_FRAC_BITS;
+    let mut m = match lead_pos > FIXED_FRAC_BITS {
+        true => v >> (lead_pos - FIXED_FRAC_BITS),
+        false => v << (FIXED_FRAC_BITS - lead_pos),
+    };
+    if m > FIXED_SQRT_2 {
+        m >>= 1;
+        e += 1;
+    }
+
+    let (ln_m, k) = fixed_ln(m);
+    let e = e as i128;
+    e * FIXED_LN2 + ((e * FIXED_LN2_LO) >> FIXED_FRAC_BITS) + (ln_m >> k)
+}
+
+// Floor of the square root
+pub(crate) fn fixed_isqrt(v: u128) -> u128 {
+    if v == 0 {
+        return 0;
+    }
+
+    // Newton-Raphson iteration from above
+    let mut x = 1u128 << ((128 - f128_count_leading_zero(v) + 1) / 2);
+    loop {
+        let y = (x + v / x) >> 1;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+// Square root of the positive value v with 124 fraction bits. The result is returned as (s, k)
+// where the fixed-point s is scaled by 2^k, so that the relative error stays small when v is small.
+pub(crate) fn fixed_sqrt(v: i128) -> (i128, i32) {
+    // Scale v by an even power of 2, up to [2^124, 2^126)
+    let shift = (f128_count_leading_zero(v as u128) - 2) & !1;
+
+    (fixed_isqrt((v as u128) << shift) as i128, shift / 2)
+}
+
+// atan(t) for t in [0, 1] scaled by 2^k, where t is at least 2^61 x 2^-k.
+//
+// The result is returned as (v, k), with the scale of t when t is less than 1/4, and without
+// scale otherwise. There, t is moved to u in [-1/16, 1/16] with the nearest c = i / 8 and
+// atan(t) = atan(c) + atan(u) where u = (t - c) / (1 + t x c).
+pub(crate) fn fixed_atan(t: i128, k: i32) -> (i128, i32) {
+    if k >= 3 {
+        return (fixed_odd_series(t, k, true), k);
+    }
+
+    let t = t >> k;
+    let i = ((t + (FIXED_ONE >> 4)) >> (FIXED_FRAC_BITS - 3)) as usize;
+    let c = (i as i128) << (FIXED_FRAC_BITS - 3);
+    let u = ((t - c) << FIXED_FRAC_BITS) / (FIXED_ONE + fixed_mul(t, c));
+
+    (FIXED_ATAN_EIGHTHS[i] + fixed_odd_series(u, 0, true), 0)
+}
+
+// Split the fixed-point y into n and r with y = n x ln(2) + r, where n is the nearest integer
+// to y / ln(2) and |r| <= 0.35.
+pub(crate) fn fixed_reduce_ln2(y: i128) -> (i32, i128) {
+    // The estimation of y / ln(2) only needs to be close
+    let n = ((((y >> 20) * FIXED_LOG2_E) >> (2 * FIXED_FRAC_BITS - 20 - 1)) + 1) >> 1;
+    let r = y - n * FIXED_LN2 - ((n * FIXED_LN2_LO) >> FIXED_FRAC_BITS);
+
+    (n as i32, r)
+}
+
+// Round the value v x 2^scale to Float32. An inexact value is an approximation with the
+// accuracy above, and gets a sticky bit so that it is never mistaken for an exact one.
+pub(crate) fn f32_round_fixed(v: i128, scale: i32, is_exact: bool, env: &mut Env) -> u32 {
+    let sign = (v < 0) as i32;
+    let mag = v.unsigned_abs();
+    if mag == 0 {
+        // Zero
+        return f32_pack_raw(sign, 0, 0);
+    }
+
+    // Move the most significant bit to the hidden bit
+    let lead_pos = 127 - f128_count_leading_zero(mag);
+    let mut frac = match lead_pos > 30 {
+        true => f128_shift_right_jam(mag, lead_pos - 30),
+        false => mag << (30 - lead_pos),
+    } as i32;
+    if !is_exact {
+        frac |= 1;
+    }
+
+    f32_round_and_pack(sign, 0x7E + lead_pos + scale, frac, env)
+}
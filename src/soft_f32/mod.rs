@@ -1,57 +1,577 @@
-
 mod soft_f32_add;
 mod soft_f32_sub;
 mod soft_f32_mul;
 mod soft_f32_div;
+mod soft_f32_rem;
+mod soft_f32_mul_add;
 mod soft_f32_comp;
 mod soft_f32_sqrt;
 mod soft_f32_round;
-mod util;
+mod soft_f32_scale;
+mod soft_f32_next;
+mod soft_f32_conv;
+mod soft_f32_log;
+mod soft_f32_exp;
+mod soft_f32_trig;
+mod soft_f32_inv_trig;
+mod soft_f32_hyper;
+mod soft_f32_pow;
+mod soft_f32_cbrt;
+mod soft_f32_hypot;
+mod soft_f32_fmt;
+mod soft_f32_parse;
+mod soft_f32_hex;
+#[cfg(feature = "correctly-rounded")]
+mod soft_f32_cr;
+mod fixed;
+mod big;
+#[cfg(feature = "correctly-rounded")]
+mod wide;
+pub(crate) mod util;
+
+// The procedural API works on raw u32 bit patterns. Each function uses the
+// environment of the current thread, and has a `_with_env` variant which
+// takes an explicit environment.
 
 // Operations
-use soft_f32_add::f32_add;
-use soft_f32_sub::f32_sub;
-use soft_f32_mul::f32_mul;
-use soft_f32_div::f32_div;
+pub use soft_f32_add::{ f32_add, f32_add_with_env };
+pub use soft_f32_sub::{ f32_sub, f32_sub_with_env };
+pub use soft_f32_mul::{ f32_mul, f32_mul_with_env };
+pub use soft_f32_div::{ f32_div, f32_div_with_env };
+pub use soft_f32_rem::{ f32_remainder, f32_remainder_with_env };
+pub use soft_f32_rem::{ f32_fmod, f32_fmod_with_env };
+pub use soft_f32_rem::{ f32_remquo, f32_remquo_with_env };
+pub use soft_f32_mul_add::{ f32_mul_add, f32_mul_add_with_env };
+pub use soft_f32_mul_add::{ f32_mul_sub, f32_mul_sub_with_env };
+pub use soft_f32_mul_add::{ f32_neg_mul_add, f32_neg_mul_add_with_env };
+pub use soft_f32_mul_add::{ f32_neg_mul_sub, f32_neg_mul_sub_with_env };
 
 // Comparisons
-use soft_f32_comp::f32_eq;
-use soft_f32_comp::f32_ne;
-use soft_f32_comp::f32_lt;
-use soft_f32_comp::f32_gt;
-use soft_f32_comp::f32_le;
-use soft_f32_comp::f32_ge;
+pub use soft_f32_comp::{ f32_eq, f32_eq_with_env };
+pub use soft_f32_comp::{ f32_ne, f32_ne_with_env };
+pub use soft_f32_comp::{ f32_lt, f32_lt_with_env };
+pub use soft_f32_comp::{ f32_gt, f32_gt_with_env };
+pub use soft_f32_comp::{ f32_le, f32_le_with_env };
+pub use soft_f32_comp::{ f32_ge, f32_ge_with_env };
+pub use soft_f32_comp::{ f32_min, f32_min_with_env };
+pub use soft_f32_comp::{ f32_max, f32_max_with_env };
+pub use soft_f32_comp::{ f32_minimum, f32_minimum_with_env };
+pub use soft_f32_comp::{ f32_maximum, f32_maximum_with_env };
+pub use soft_f32_comp::{ f32_minimum_number, f32_minimum_number_with_env };
+pub use soft_f32_comp::{ f32_maximum_number, f32_maximum_number_with_env };
+pub use soft_f32_comp::{ f32_min_num, f32_min_num_with_env };
+pub use soft_f32_comp::{ f32_max_num, f32_max_num_with_env };
 
 // Others
-use soft_f32_sqrt::f32_sqrt;
-use soft_f32_round::f32_round;
+pub use soft_f32_sqrt::{ f32_sqrt, f32_sqrt_with_env };
+pub use soft_f32_round::{ f32_round_to_int, f32_round_to_int_with_env };
+pub use soft_f32_round::{ f32_floor, f32_floor_with_env };
+pub use soft_f32_round::{ f32_ceil, f32_ceil_with_env };
+pub use soft_f32_round::{ f32_trunc, f32_trunc_with_env };
+pub use soft_f32_round::{ f32_round, f32_round_with_env };
+pub use soft_f32_round::{ f32_round_ties_away, f32_round_ties_away_with_env };
+pub use soft_f32_round::{ f32_round_ties_even, f32_round_ties_even_with_env };
+pub use soft_f32_round::{ f32_rint, f32_rint_with_env };
+pub use soft_f32_round::{ f32_nearbyint, f32_nearbyint_with_env };
+pub use soft_f32_scale::{ f32_scalbn, f32_scalbn_with_env };
+pub use soft_f32_scale::{ f32_frexp, f32_frexp_with_env };
+pub use soft_f32_scale::{ f32_logb, f32_logb_with_env };
+pub use soft_f32_scale::{ f32_ilogb, f32_ilogb_with_env };
+pub use soft_f32_scale::{ f32_modf, f32_modf_with_env };
+pub use soft_f32_scale::{ FP_ILOGB0, FP_ILOGBNAN };
+pub use soft_f32_next::{ f32_next_up, f32_next_up_with_env };
+pub use soft_f32_next::{ f32_next_down, f32_next_down_with_env };
+pub use soft_f32_next::{ f32_next_after, f32_next_after_with_env };
+pub use soft_f32_next::{ f32_ulp, f32_ulp_with_env };
+pub use soft_f32_cbrt::{ f32_cbrt, f32_cbrt_with_env };
+pub use soft_f32_hypot::{ f32_hypot, f32_hypot_with_env };
+
+// Elementary functions
+pub use soft_f32_log::{ f32_log2, f32_log2_with_env };
+pub use soft_f32_log::{ f32_ln, f32_ln_with_env };
+pub use soft_f32_log::{ f32_log10, f32_log10_with_env };
+pub use soft_f32_log::{ f32_log1p, f32_log1p_with_env };
+pub use soft_f32_exp::{ f32_exp2, f32_exp2_with_env };
+pub use soft_f32_exp::{ f32_exp, f32_exp_with_env };
+pub use soft_f32_exp::{ f32_exp10, f32_exp10_with_env };
+pub use soft_f32_exp::{ f32_expm1, f32_expm1_with_env };
+pub use soft_f32_pow::{ f32_powf, f32_powf_with_env };
+pub use soft_f32_pow::{ f32_powi, f32_powi_with_env };
+pub use soft_f32_trig::{ f32_sin, f32_sin_with_env };
+pub use soft_f32_trig::{ f32_cos, f32_cos_with_env };
+pub use soft_f32_trig::{ f32_tan, f32_tan_with_env };
+pub use soft_f32_trig::{ f32_sincos, f32_sincos_with_env };
+pub use soft_f32_inv_trig::{ f32_asin, f32_asin_with_env };
+pub use soft_f32_inv_trig::{ f32_acos, f32_acos_with_env };
+pub use soft_f32_inv_trig::{ f32_atan, f32_atan_with_env };
+pub use soft_f32_inv_trig::{ f32_atan2, f32_atan2_with_env };
+pub use soft_f32_hyper::{ f32_sinh, f32_sinh_with_env };
+pub use soft_f32_hyper::{ f32_cosh, f32_cosh_with_env };
+pub use soft_f32_hyper::{ f32_tanh, f32_tanh_with_env };
+pub use soft_f32_hyper::{ f32_asinh, f32_asinh_with_env };
+pub use soft_f32_hyper::{ f32_acosh, f32_acosh_with_env };
+pub use soft_f32_hyper::{ f32_atanh, f32_atanh_with_env };
+
+// Correctly rounded elementary functions
+#[cfg(feature = "correctly-rounded")]
+pub use soft_f32_cr::{ f32_exp_cr, f32_exp_cr_with_env };
+#[cfg(feature = "correctly-rounded")]
+pub use soft_f32_cr::{ f32_ln_cr, f32_ln_cr_with_env };
+#[cfg(feature = "correctly-rounded")]
+pub use soft_f32_cr::{ f32_sin_cr, f32_sin_cr_with_env };
+#[cfg(feature = "correctly-rounded")]
+pub use soft_f32_cr::{ f32_cos_cr, f32_cos_cr_with_env };
+#[cfg(feature = "correctly-rounded")]
+pub use soft_f32_cr::{ f32_powf_cr, f32_powf_cr_with_env };
+
+// Conversions
+pub use soft_f32_round::{ from_int32, from_int32_with_env };
+pub use soft_f32_round::{ to_int32, to_int32_with_env };
+pub use soft_f32_conv::{ f32_to_i32, f32_to_i32_with_env };
+pub use soft_f32_conv::{ f32_to_u32, f32_to_u32_with_env };
+pub use soft_f32_conv::{ f32_to_i64, f32_to_i64_with_env };
+pub use soft_f32_conv::{ f32_to_u64, f32_to_u64_with_env };
+pub use soft_f32_conv::{ f32_to_i128, f32_to_i128_with_env };
+pub use soft_f32_conv::{ f32_to_u128, f32_to_u128_with_env };
+pub use soft_f32_conv::{ i32_to_f32, i32_to_f32_with_env };
+pub use soft_f32_conv::{ u32_to_f32, u32_to_f32_with_env };
+pub use soft_f32_conv::{ i64_to_f32, i64_to_f32_with_env };
+pub use soft_f32_conv::{ u64_to_f32, u64_to_f32_with_env };
+pub use soft_f32_conv::{ i128_to_f32, i128_to_f32_with_env };
+pub use soft_f32_conv::{ u128_to_f32, u128_to_f32_with_env };
+pub use soft_f32_parse::{ f32_from_str, f32_from_str_with_env, f32_from_str_const };
+pub use soft_f32_parse::ParseF32Error;
+pub use soft_f32_hex::{ f32_from_hex_str, f32_from_hex_str_with_env, f32_from_hex_str_const };
+pub use soft_f32_hex::f32_to_hex_string;
 
 // Utilities
 pub use util::{
-    f32_is_nan
+    f32_is_nan,
+    f32_is_signaling_nan,
 };
+pub use soft_f32_next::f32_ulp_distance;
 
 // F32 struct
+#[derive(Clone, Copy)]
 pub struct F32 {
     value: u32,
 }
 
 impl F32 {
-    pub fn from_u32(value: u32) -> F32 {
+    // The F32 with the bit pattern BITS. From<u32> converts the numeric value instead.
+    pub const fn from_bits(bits: u32) -> F32 {
         F32 {
-            value: value
+            value: bits
         }
     }
 
+    pub fn to_bits(self) -> u32 {
+        self.value
+    }
+
+    #[deprecated(note = "from_u32 reads a bit pattern unlike From<u32>, use from_bits")]
+    pub const fn from_u32(value: u32) -> F32 {
+        F32::from_bits(value)
+    }
+
+    // The bit pattern, as to_bits
     pub fn value(self) -> u32 {
         self.value
     }
 
+    // Parse a C99 hexadecimal string such as 0x1.99999ap-4
+    pub fn from_hex_str(s: &str) -> Result<F32, ParseF32Error> {
+        Ok(F32 {
+            value: f32_from_hex_str(s)?
+        })
+    }
+
+    // The C99 %a form, such as 0x1.99999ap-4
+    pub fn to_hex_string(&self) -> String {
+        f32_to_hex_string(self.value)
+    }
+
     pub fn sqrt(self) -> Self {
         F32 {
             value: f32_sqrt(self.value)
         }
     }
+
+    // IEEE 754 remainder, with the quotient rounded to nearest
+    pub fn remainder(self, other: Self) -> Self {
+        F32 {
+            value: f32_remainder(self.value, other.value)
+        }
+    }
+
+    // C fmod, with the quotient truncated as in self % other
+    pub fn fmod(self, other: Self) -> Self {
+        F32 {
+            value: f32_fmod(self.value, other.value)
+        }
+    }
+
+    // The IEEE 754 remainder and the low bits of the quotient
+    pub fn remquo(self, other: Self) -> (Self, i32) {
+        let (value, quo) = f32_remquo(self.value, other.value);
+        (F32 { value }, quo)
+    }
+
+    // The minimum with the behavior of soft_float::MinMax of the environment
+    pub fn min(self, other: Self) -> Self {
+        F32 {
+            value: f32_min(self.value, other.value)
+        }
+    }
+
+    // The maximum with the behavior of soft_float::MinMax of the environment
+    pub fn max(self, other: Self) -> Self {
+        F32 {
+            value: f32_max(self.value, other.value)
+        }
+    }
+
+    pub fn minimum(self, other: Self) -> Self {
+        F32 {
+            value: f32_minimum(self.value, other.value)
+        }
+    }
+
+    pub fn maximum(self, other: Self) -> Self {
+        F32 {
+            value: f32_maximum(self.value, other.value)
+        }
+    }
+
+    pub fn minimum_number(self, other: Self) -> Self {
+        F32 {
+            value: f32_minimum_number(self.value, other.value)
+        }
+    }
+
+    pub fn maximum_number(self, other: Self) -> Self {
+        F32 {
+            value: f32_maximum_number(self.value, other.value)
+        }
+    }
+
+    pub fn min_num(self, other: Self) -> Self {
+        F32 {
+            value: f32_min_num(self.value, other.value)
+        }
+    }
+
+    pub fn max_num(self, other: Self) -> Self {
+        F32 {
+            value: f32_max_num(self.value, other.value)
+        }
+    }
+
+    pub fn floor(self) -> Self {
+        F32 {
+            value: f32_floor(self.value)
+        }
+    }
+
+    pub fn ceil(self) -> Self {
+        F32 {
+            value: f32_ceil(self.value)
+        }
+    }
+
+    pub fn trunc(self) -> Self {
+        F32 {
+            value: f32_trunc(self.value)
+        }
+    }
+
+    // Round half away from zero
+    pub fn round(self) -> Self {
+        F32 {
+            value: f32_round_ties_away(self.value)
+        }
+    }
+
+    pub fn round_ties_even(self) -> Self {
+        F32 {
+            value: f32_round_ties_even(self.value)
+        }
+    }
+
+    // Round in the current rounding mode, raising inexact
+    pub fn rint(self) -> Self {
+        F32 {
+            value: f32_rint(self.value)
+        }
+    }
+
+    // Round in the current rounding mode, without raising inexact
+    pub fn nearbyint(self) -> Self {
+        F32 {
+            value: f32_nearbyint(self.value)
+        }
+    }
+
+    // self x 2^n, as ldexp
+    pub fn scalbn(self, n: i32) -> Self {
+        F32 {
+            value: f32_scalbn(self.value, n)
+        }
+    }
+
+    // The significand in [0.5, 1) and the exponent
+    pub fn frexp(self) -> (Self, i32) {
+        let (value, exp) = f32_frexp(self.value);
+        (F32 { value }, exp)
+    }
+
+    pub fn logb(self) -> Self {
+        F32 {
+            value: f32_logb(self.value)
+        }
+    }
+
+    pub fn ilogb(self) -> i32 {
+        f32_ilogb(self.value)
+    }
+
+    // The fractional and integral parts
+    pub fn modf(self) -> (Self, Self) {
+        let (fract, int) = f32_modf(self.value);
+        (F32 { value: fract }, F32 { value: int })
+    }
+
+    pub fn next_up(self) -> Self {
+        F32 {
+            value: f32_next_up(self.value)
+        }
+    }
+
+    pub fn next_down(self) -> Self {
+        F32 {
+            value: f32_next_down(self.value)
+        }
+    }
+
+    // The next value after self in the direction of toward
+    pub fn next_after(self, toward: Self) -> Self {
+        F32 {
+            value: f32_next_after(self.value, toward.value)
+        }
+    }
+
+    pub fn ulp(self) -> Self {
+        F32 {
+            value: f32_ulp(self.value)
+        }
+    }
+
+    // The number of values from self to other, None when either is NaN
+    pub fn ulp_distance(self, other: Self) -> Option<u32> {
+        f32_ulp_distance(self.value, other.value)
+    }
+
+    pub fn cbrt(self) -> Self {
+        F32 {
+            value: f32_cbrt(self.value)
+        }
+    }
+
+    // Compute sqrt(self^2 + other^2) without intermediate overflow
+    pub fn hypot(self, other: Self) -> Self {
+        F32 {
+            value: f32_hypot(self.value, other.value)
+        }
+    }
+
+    pub fn log2(self) -> Self {
+        F32 {
+            value: f32_log2(self.value)
+        }
+    }
+
+    pub fn ln(self) -> Self {
+        F32 {
+            value: f32_ln(self.value)
+        }
+    }
+
+    pub fn log10(self) -> Self {
+        F32 {
+            value: f32_log10(self.value)
+        }
+    }
+
+    // Compute ln(1 + self), accurately for self close to 0
+    pub fn log1p(self) -> Self {
+        F32 {
+            value: f32_log1p(self.value)
+        }
+    }
+
+    pub fn exp2(self) -> Self {
+        F32 {
+            value: f32_exp2(self.value)
+        }
+    }
+
+    pub fn exp(self) -> Self {
+        F32 {
+            value: f32_exp(self.value)
+        }
+    }
+
+    pub fn exp10(self) -> Self {
+        F32 {
+            value: f32_exp10(self.value)
+        }
+    }
+
+    // Compute e^self - 1, accurately for self close to 0
+    pub fn expm1(self) -> Self {
+        F32 {
+            value: f32_expm1(self.value)
+        }
+    }
+
+    pub fn powf(self, n: Self) -> Self {
+        F32 {
+            value: f32_powf(self.value, n.value)
+        }
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+        F32 {
+            value: f32_powi(self.value, n)
+        }
+    }
+
+    pub fn sin(self) -> Self {
+        F32 {
+            value: f32_sin(self.value)
+        }
+    }
+
+    pub fn cos(self) -> Self {
+        F32 {
+            value: f32_cos(self.value)
+        }
+    }
+
+    pub fn tan(self) -> Self {
+        F32 {
+            value: f32_tan(self.value)
+        }
+    }
+
+    // Compute (sin(self), cos(self)) with a single argument reduction
+    pub fn sincos(self) -> (Self, Self) {
+        let (sin, cos) = f32_sincos(self.value);
+        (F32 { value: sin }, F32 { value: cos })
+    }
+
+    pub fn asin(self) -> Self {
+        F32 {
+            value: f32_asin(self.value)
+        }
+    }
+
+    pub fn acos(self) -> Self {
+        F32 {
+            value: f32_acos(self.value)
+        }
+    }
+
+    pub fn atan(self) -> Self {
+        F32 {
+            value: f32_atan(self.value)
+        }
+    }
+
+    // Compute the angle of the point (other, self)
+    pub fn atan2(self, other: Self) -> Self {
+        F32 {
+            value: f32_atan2(self.value, other.value)
+        }
+    }
+
+    pub fn sinh(self) -> Self {
+        F32 {
+            value: f32_sinh(self.value)
+        }
+    }
+
+    pub fn cosh(self) -> Self {
+        F32 {
+            value: f32_cosh(self.value)
+        }
+    }
+
+    pub fn tanh(self) -> Self {
+        F32 {
+            value: f32_tanh(self.value)
+        }
+    }
+
+    pub fn asinh(self) -> Self {
+        F32 {
+            value: f32_asinh(self.value)
+        }
+    }
+
+    pub fn acosh(self) -> Self {
+        F32 {
+            value: f32_acosh(self.value)
+        }
+    }
+
+    pub fn atanh(self) -> Self {
+        F32 {
+            value: f32_atanh(self.value)
+        }
+    }
+
+    // Compute self x a + b with a single rounding
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        F32 {
+            value: f32_mul_add(self.value, a.value, b.value)
+        }
+    }
+
+    #[cfg(feature = "correctly-rounded")]
+    pub fn exp_cr(self) -> Self {
+        F32 {
+            value: f32_exp_cr(self.value)
+        }
+    }
+
+    #[cfg(feature = "correctly-rounded")]
+    pub fn ln_cr(self) -> Self {
+        F32 {
+            value: f32_ln_cr(self.value)
+        }
+    }
+
+    #[cfg(feature = "correctly-rounded")]
+    pub fn sin_cr(self) -> Self {
+        F32 {
+            value: f32_sin_cr(self.value)
+        }
+    }
+
+    #[cfg(feature = "correctly-rounded")]
+    pub fn cos_cr(self) -> Self {
+        F32 {
+            value: f32_cos_cr(self.value)
+        }
+    }
+
+    #[cfg(feature = "correctly-rounded")]
+    pub fn powf_cr(self, n: Self) -> Self {
+        F32 {
+            value: f32_powf_cr(self.value, n.value)
+        }
+    }
 }
 
 use std::ops;
@@ -61,7 +581,7 @@ impl ops::Add<F32> for F32 {
 
     fn add(self, other: F32) -> F32 {
         F32 {
-            value: crate::soft_f32::soft_f32_add::f32_add(self.value, other.value)
+            value: f32_add(self.value, other.value)
         }
     }
 }
@@ -71,7 +591,7 @@ impl ops::Sub<F32> for F32 {
 
     fn sub(self, other: Self) -> Self {
         F32 {
-            value: crate::soft_f32::soft_f32_sub::f32_sub(self.value, other.value)
+            value: f32_sub(self.value, other.value)
         }
     }
 }
@@ -81,7 +601,7 @@ impl ops::Mul<F32> for F32 {
 
     fn mul(self, other: Self) -> Self {
         F32 {
-            value: crate::soft_f32::soft_f32_mul::f32_mul(self.value, other.value)
+            value: f32_mul(self.value, other.value)
         }
     }
 }
@@ -91,7 +611,209 @@ impl ops::Div<F32> for F32 {
 
     fn div(self, other: Self) -> Self {
         F32 {
-            value: crate::soft_f32::soft_f32_div::f32_div(self.value, other.value)
+            value: f32_div(self.value, other.value)
+        }
+    }
+}
+
+// The remainder of the truncated division, as for the primitive f32
+impl ops::Rem<F32> for F32 {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        F32 {
+            value: f32_fmod(self.value, other.value)
+        }
+    }
+}
+
+use crate::soft_float::{ Env, RoundingMode };
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Error of a checked conversion from `F32` to an integer, when the value is not an integer,
+/// is out of the range of the integer type, or is a NaN or an infinity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromF32Error(());
+
+impl fmt::Display for TryFromF32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("F32 is not exactly representable in the integer type")
+    }
+}
+
+impl std::error::Error for TryFromF32Error {}
+
+// Decimal formatting, the same as the primitive f32
+
+impl fmt::Display for F32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        soft_f32_fmt::f32_fmt_display(self.value, f)
+    }
+}
+
+impl fmt::Debug for F32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        soft_f32_fmt::f32_fmt_debug(self.value, f)
+    }
+}
+
+impl fmt::LowerExp for F32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        soft_f32_fmt::f32_fmt_exp(self.value, false, f)
+    }
+}
+
+impl fmt::UpperExp for F32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        soft_f32_fmt::f32_fmt_exp(self.value, true, f)
+    }
+}
+
+// Decimal parsing, correctly rounded in the current rounding mode
+
+impl std::str::FromStr for F32 {
+    type Err = ParseF32Error;
+
+    fn from_str(s: &str) -> Result<F32, ParseF32Error> {
+        Ok(F32 {
+            value: f32_from_str(s)?
+        })
+    }
+}
+
+// Integers are rounded with the environment of the current thread
+
+impl From<i32> for F32 {
+    fn from(value: i32) -> F32 {
+        F32 {
+            value: i32_to_f32(value)
+        }
+    }
+}
+
+// The numeric value of the integer, not its bits as F32::from_bits
+impl From<u32> for F32 {
+    fn from(value: u32) -> F32 {
+        F32 {
+            value: u32_to_f32(value)
+        }
+    }
+}
+
+// Lossy above 2^24 in magnitude, rounded in the rounding mode of the current thread, raising inexact
+impl From<i64> for F32 {
+    fn from(value: i64) -> F32 {
+        F32 {
+            value: i64_to_f32(value)
+        }
+    }
+}
+
+// Lossy above 2^24 in magnitude, rounded in the rounding mode of the current thread, raising inexact
+impl From<u64> for F32 {
+    fn from(value: u64) -> F32 {
+        F32 {
+            value: u64_to_f32(value)
+        }
+    }
+}
+
+// Lossy above 2^24 in magnitude, rounded in the rounding mode of the current thread, raising inexact
+impl From<i128> for F32 {
+    fn from(value: i128) -> F32 {
+        F32 {
+            value: i128_to_f32(value)
+        }
+    }
+}
+
+// Lossy above 2^24 in magnitude, rounded in the rounding mode of the current thread, raising inexact, and
+// overflow when u128::MAX rounds up to 2^128
+impl From<u128> for F32 {
+    fn from(value: u128) -> F32 {
+        F32 {
+            value: u128_to_f32(value)
+        }
+    }
+}
+
+// Only integers in range convert, without raising any flag
+
+impl TryFrom<F32> for i32 {
+    type Error = TryFromF32Error;
+
+    fn try_from(value: F32) -> Result<i32, TryFromF32Error> {
+        let mut env = Env::default();
+        let r = f32_to_i32_with_env(value.value, RoundingMode::MinMag, true, &mut env);
+        match env.flags().is_empty() {
+            true => Ok(r),
+            false => Err(TryFromF32Error(())),
+        }
+    }
+}
+
+impl TryFrom<F32> for u32 {
+    type Error = TryFromF32Error;
+
+    fn try_from(value: F32) -> Result<u32, TryFromF32Error> {
+        let mut env = Env::default();
+        let r = f32_to_u32_with_env(value.value, RoundingMode::MinMag, true, &mut env);
+        match env.flags().is_empty() {
+            true => Ok(r),
+            false => Err(TryFromF32Error(())),
+        }
+    }
+}
+
+impl TryFrom<F32> for i64 {
+    type Error = TryFromF32Error;
+
+    fn try_from(value: F32) -> Result<i64, TryFromF32Error> {
+        let mut env = Env::default();
+        let r = f32_to_i64_with_env(value.value, RoundingMode::MinMag, true, &mut env);
+        match env.flags().is_empty() {
+            true => Ok(r),
+            false => Err(TryFromF32Error(())),
+        }
+    }
+}
+
+impl TryFrom<F32> for u64 {
+    type Error = TryFromF32Error;
+
+    fn try_from(value: F32) -> Result<u64, TryFromF32Error> {
+        let mut env = Env::default();
+        let r = f32_to_u64_with_env(value.value, RoundingMode::MinMag, true, &mut env);
+        match env.flags().is_empty() {
+            true => Ok(r),
+            false => Err(TryFromF32Error(())),
+        }
+    }
+}
+
+impl TryFrom<F32> for i128 {
+    type Error = TryFromF32Error;
+
+    fn try_from(value: F32) -> Result<i128, TryFromF32Error> {
+        let mut env = Env::default();
+        let r = f32_to_i128_with_env(value.value, RoundingMode::MinMag, true, &mut env);
+        match env.flags().is_empty() {
+            true => Ok(r),
+            false => Err(TryFromF32Error(())),
+        }
+    }
+}
+
+impl TryFrom<F32> for u128 {
+    type Error = TryFromF32Error;
+
+    fn try_from(value: F32) -> Result<u128, TryFromF32Error> {
+        let mut env = Env::default();
+        let r = f32_to_u128_with_env(value.value, RoundingMode::MinMag, true, &mut env);
+        match env.flags().is_empty() {
+            true => Ok(r),
+            false => Err(TryFromF32Error(())),
         }
     }
 }
@@ -108,6 +830,7 @@ impl cmp::PartialEq for F32 {
         f32_eq(self.value, other.value)
     }
 
+    #[allow(clippy::partialeq_ne_impl)]
     fn ne(&self, other: &Self) -> bool {
         f32_ne(self.value, other.value)
     }
@@ -146,8 +869,8 @@ impl cmp::PartialOrd for F32 {
 mod tests {
     #[test]
     fn test_f32_add_with_struct() {
-        let v0_1 = crate::soft_f32::F32::from_u32(0x3DCCCCCD);
-        let v0_2 = crate::soft_f32::F32::from_u32(0x3E4CCCCD);
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v0_2 = crate::soft_f32::F32::from_bits(0x3E4CCCCD);
 
         let v0_3 = v0_1 + v0_2;
 
@@ -156,8 +879,8 @@ mod tests {
 
     #[test]
     fn test_f32_sub_with_struct() {
-        let v0_1 = crate::soft_f32::F32::from_u32(0x3DCCCCCD);
-        let v0_2 = crate::soft_f32::F32::from_u32(0x3E4CCCCD);
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v0_2 = crate::soft_f32::F32::from_bits(0x3E4CCCCD);
 
         let v0_1_result = v0_2 - v0_1;
 
@@ -166,8 +889,8 @@ mod tests {
 
     #[test]
     fn test_f32_mul_with_struct() {
-        let v0_1 = crate::soft_f32::F32::from_u32(0x3DCCCCCD);
-        let v0_2 = crate::soft_f32::F32::from_u32(0x3E4CCCCD);
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v0_2 = crate::soft_f32::F32::from_bits(0x3E4CCCCD);
 
         let v0_02 = v0_2 * v0_1;
 
@@ -176,18 +899,115 @@ mod tests {
 
     #[test]
     fn test_f32_div_with_struct() {
-        let v0_1 = crate::soft_f32::F32::from_u32(0x3DCCCCCD);
-        let v0_2 = crate::soft_f32::F32::from_u32(0x3E4CCCCD);
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v0_2 = crate::soft_f32::F32::from_bits(0x3E4CCCCD);
 
         let v0_5 = v0_1 / v0_2;
 
         assert_eq!(v0_5.value(), 0x3F000000);
     }
 
+    #[test]
+    fn test_f32_rem_with_struct() {
+        // -5 % 3 = -2
+        let v_5 = crate::soft_f32::F32::from_bits(0xC0A00000);
+        let v3 = crate::soft_f32::F32::from_bits(0x40400000);
+        assert_eq!((v_5 % v3).value(), 0xC0000000);
+
+        // remainder(5, 3) = -1
+        let v5 = crate::soft_f32::F32::from_bits(0x40A00000);
+        let v3 = crate::soft_f32::F32::from_bits(0x40400000);
+        assert_eq!(v5.remainder(v3).value(), 0xBF800000);
+
+        // fmod(5, 3) = 2
+        let v5 = crate::soft_f32::F32::from_bits(0x40A00000);
+        let v3 = crate::soft_f32::F32::from_bits(0x40400000);
+        assert_eq!(v5.fmod(v3).value(), 0x40000000);
+
+        // remquo(-7, 2) = (1, -4)
+        let v_7 = crate::soft_f32::F32::from_bits(0xC0E00000);
+        let v2 = crate::soft_f32::F32::from_bits(0x40000000);
+        let (r, quo) = v_7.remquo(v2);
+        assert_eq!((r.value(), quo), (0x3F800000, -4));
+    }
+
+    #[test]
+    fn test_f32_scale_with_struct() {
+        // scalbn(1.5, 4) = 24
+        let v1_5 = crate::soft_f32::F32::from_bits(0x3FC00000);
+        assert_eq!(v1_5.scalbn(4).value(), 0x41C00000);
+
+        // frexp(24) = (0.75, 5), logb(24) = 4 and ilogb(24) = 4
+        let v24 = crate::soft_f32::F32::from_bits(0x41C00000);
+        let (m, e) = v24.frexp();
+        assert_eq!((m.value(), e), (0x3F400000, 5));
+        assert_eq!(v24.logb().value(), 0x40800000);
+        assert_eq!(v24.ilogb(), 4);
+
+        // modf(-2.5) = (-0.5, -2)
+        let v_2_5 = crate::soft_f32::F32::from_bits(0xC0200000);
+        let (fract, int) = v_2_5.modf();
+        assert_eq!((fract.value(), int.value()), (0xBF000000, 0xC0000000));
+    }
+
+    #[test]
+    fn test_f32_next_with_struct() {
+        // next_up(1) = 1.0000001, next_down(1) = 0.99999994 and next_after(1, 0) = 0.99999994
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.next_up().value(), 0x3F800001);
+        assert_eq!(v1.next_down().value(), 0x3F7FFFFF);
+        assert_eq!(v1.next_after(crate::soft_f32::F32::from_bits(0)).value(), 0x3F7FFFFF);
+
+        // ulp(1) = 2^-23
+        assert_eq!(v1.ulp().value(), 0x34000000);
+
+        // 1 and 1.0000002 are 2 ULPs apart
+        assert_eq!(v1.ulp_distance(crate::soft_f32::F32::from_bits(0x3F800002)), Some(2));
+    }
+
+    #[test]
+    fn test_f32_min_max_with_struct() {
+        // min(-0, 0) = -0 and max(-0, 0) = 0
+        let v_0 = crate::soft_f32::F32::from_bits(0x80000000);
+        let v0 = crate::soft_f32::F32::from_bits(0x00000000);
+        assert_eq!(v0.min(v_0).value(), 0x80000000);
+        assert_eq!(v_0.max(v0).value(), 0x00000000);
+
+        // minimum(NaN, 1) = NaN, minimum_number(NaN, 1) = 1 and min_num(NaN, 1) = 1
+        let nan = crate::soft_f32::F32::from_bits(0x7FC00000);
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(nan.minimum(v1).value(), 0x7FC00000);
+        assert_eq!(nan.minimum_number(v1).value(), 0x3F800000);
+        assert_eq!(nan.min_num(v1).value(), 0x3F800000);
+
+        // maximum(NaN, 1) = NaN, maximum_number(NaN, 1) = 1 and max_num(NaN, 1) = 1
+        assert_eq!(nan.maximum(v1).value(), 0x7FC00000);
+        assert_eq!(nan.maximum_number(v1).value(), 0x3F800000);
+        assert_eq!(nan.max_num(v1).value(), 0x3F800000);
+    }
+
+    #[test]
+    fn test_f32_round_to_int_with_struct() {
+        // -2.5 rounds to -3, -2, -3, -2 and -2
+        let v = crate::soft_f32::F32::from_bits(0xC0200000);
+        assert_eq!(v.floor().value(), 0xC0400000);
+        assert_eq!(v.ceil().value(), 0xC0000000);
+        assert_eq!(v.round().value(), 0xC0400000);
+        assert_eq!(v.round_ties_even().value(), 0xC0000000);
+        assert_eq!(v.trunc().value(), 0xC0000000);
+
+        // rint raises inexact, nearbyint does not
+        crate::soft_float::clear_exception_flags();
+        assert_eq!(v.nearbyint().value(), 0xC0000000);
+        assert_eq!(crate::soft_float::get_exception_flags(), crate::soft_float::ExceptionFlags::empty());
+        assert_eq!(v.rint().value(), 0xC0000000);
+        assert_eq!(crate::soft_float::get_exception_flags(), crate::soft_float::ExceptionFlags::INEXACT);
+    }
+
     #[test]
     fn test_f32_compare_with_struct() {
-        let v0_1 = crate::soft_f32::F32::from_u32(0x3DCCCCCD);
-        let v0_2 = crate::soft_f32::F32::from_u32(0x3E4CCCCD);
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v0_2 = crate::soft_f32::F32::from_bits(0x3E4CCCCD);
 
         assert_eq!(v0_1 == v0_2, false);
         assert_eq!(v0_1 != v0_2, true);
@@ -203,13 +1023,227 @@ mod tests {
         assert_eq!(v0_1 >= v0_1, true);
     }
 
+    #[test]
+    fn test_f32_rounding_mode_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v0_2 = crate::soft_f32::F32::from_bits(0x3E4CCCCD);
+
+        // Operators use the rounding mode of the current thread
+        crate::soft_float::set_rounding_mode(crate::soft_float::RoundingMode::MinMag);
+        let v0_3 = v0_1 + v0_2;
+        crate::soft_float::set_rounding_mode(crate::soft_float::RoundingMode::NearEven);
+
+        assert_eq!(v0_3.value(), 0x3E999999);
+    }
+
+    #[test]
+    fn test_f32_exception_flags_with_struct() {
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        let v0 = crate::soft_f32::F32::from_bits(0x00000000);
+
+        // Operators raise flags in the environment of the current thread
+        let inf = v1 / v0;
+        assert_eq!(inf.value(), 0x7F800000);
+        assert_eq!(crate::soft_float::get_exception_flags(), crate::soft_float::ExceptionFlags::DIVIDE_BY_ZERO);
+
+        crate::soft_float::clear_exception_flags();
+        assert_eq!(crate::soft_float::get_exception_flags().is_empty(), true);
+    }
+
+    #[test]
+    fn test_f32_mul_add_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+        let v10 = crate::soft_f32::F32::from_bits(0x41200000);
+        let v_1 = crate::soft_f32::F32::from_bits(0xBF800000);
+
+        // 0.1 * 10 - 1, rounded once
+        let r = v0_1.mul_add(v10, v_1);
+
+        assert_eq!(r.value(), 0x32800000);
+    }
+
+    #[test]
+    fn test_f32_int_with_struct() {
+        use std::convert::TryFrom;
+
+        let v4 = crate::soft_f32::F32::from(4i32);
+        assert_eq!(v4.value(), 0x40800000);
+        // u64::MAX rounds to 2^64 in the rounding mode of the thread, raising inexact
+        crate::soft_float::clear_exception_flags();
+        assert_eq!(crate::soft_f32::F32::from(u64::MAX).value(), 0x5F800000);
+        assert_eq!(crate::soft_float::get_exception_flags(), crate::soft_float::ExceptionFlags::INEXACT);
+        crate::soft_float::set_rounding_mode(crate::soft_float::RoundingMode::MinMag);
+        assert_eq!(crate::soft_f32::F32::from(u64::MAX).value(), 0x5F7FFFFF);
+        crate::soft_float::set_rounding_mode(crate::soft_float::RoundingMode::NearEven);
+
+        // From<u32> converts the value, from_bits reads the bits
+        assert_eq!(crate::soft_f32::F32::from(0x3F800000u32).to_bits(), 0x4E7E0000);
+        assert_eq!(crate::soft_f32::F32::from_bits(0x3F800000).to_bits(), 0x3F800000);
+        #[allow(deprecated)]
+        let v1 = crate::soft_f32::F32::from_u32(0x3F800000);
+        assert_eq!(v1.to_bits(), 0x3F800000);
+
+        // Only integers in range convert
+        assert_eq!(i32::try_from(crate::soft_f32::F32::from_bits(0xC0800000)), Ok(-4));
+        assert_eq!(u32::try_from(crate::soft_f32::F32::from_bits(0xC0800000)).is_err(), true);
+        assert_eq!(i64::try_from(crate::soft_f32::F32::from_bits(0x3FC00000)).is_err(), true);
+        assert_eq!(u128::try_from(crate::soft_f32::F32::from_bits(0x7FC00000)).is_err(), true);
+    }
+
     #[test]
     fn test_f32_sqrt_with_struct() {
         // sqrt(0.01) = 0.1
-        let v0_01 = crate::soft_f32::F32::from_u32(0x3C23D70A);
+        let v0_01 = crate::soft_f32::F32::from_bits(0x3C23D70A);
 
         let v0_1 = v0_01.sqrt();
 
         assert_eq!(v0_1.value(), 0x3DCCCCCD);
     }
+
+    #[test]
+    fn test_f32_log_exp_with_struct() {
+        // log2(8) = 3
+        let v8 = crate::soft_f32::F32::from_bits(0x41000000);
+        assert_eq!(v8.log2().value(), 0x40400000);
+
+        // ln(e^1) = 0.99999994, as e^1 rounds below e
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.exp().ln().value(), 0x3F7FFFFF);
+
+        // 10^log10(1000) = 1000
+        let v1000 = crate::soft_f32::F32::from_bits(0x447A0000);
+        assert_eq!(v1000.log10().exp10().value(), 0x447A0000);
+
+        // 2^0.5 = 1.4142135
+        let v0_5 = crate::soft_f32::F32::from_bits(0x3F000000);
+        assert_eq!(v0_5.exp2().value(), 0x3FB504F3);
+    }
+
+    #[test]
+    fn test_f32_trig_with_struct() {
+        // sin(1) = 0.84147096
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.sin().value(), 0x3F576AA4);
+
+        // cos(1e30) = -0.6116048
+        let v1e30 = crate::soft_f32::F32::from_bits(0x7149F2CA);
+        assert_eq!(v1e30.cos().value(), 0xBF1C9222);
+
+        // tan(-2) = 2.1850398
+        let v_2 = crate::soft_f32::F32::from_bits(0xC0000000);
+        assert_eq!(v_2.tan().value(), 0x400BD7B1);
+
+        // sincos(0.5) = (0.47942555, 0.87758255)
+        let v0_5 = crate::soft_f32::F32::from_bits(0x3F000000);
+        let (sin, cos) = v0_5.sincos();
+        assert_eq!(sin.value(), 0x3EF57744);
+        assert_eq!(cos.value(), 0x3F60A940);
+    }
+
+    #[test]
+    fn test_f32_inv_trig_with_struct() {
+        // asin(0.5) = 0.5235988
+        let v0_5 = crate::soft_f32::F32::from_bits(0x3F000000);
+        assert_eq!(v0_5.asin().value(), 0x3F060A92);
+
+        // acos(-1) = 3.1415927
+        let v_1 = crate::soft_f32::F32::from_bits(0xBF800000);
+        assert_eq!(v_1.acos().value(), 0x40490FDB);
+
+        // atan(1) = 0.7853982
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.atan().value(), 0x3F490FDB);
+
+        // atan2(2, 1) = 1.1071488
+        let v2 = crate::soft_f32::F32::from_bits(0x40000000);
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v2.atan2(v1).value(), 0x3F8DB70D);
+    }
+
+    #[test]
+    fn test_f32_hyper_with_struct() {
+        // sinh(1) = 1.1752012
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.sinh().value(), 0x3F966CFE);
+
+        // cosh(0.5) = 1.127626
+        let v0_5 = crate::soft_f32::F32::from_bits(0x3F000000);
+        assert_eq!(v0_5.cosh().value(), 0x3F90560C);
+
+        // tanh(-0.5) = -0.46211717
+        let v_0_5 = crate::soft_f32::F32::from_bits(0xBF000000);
+        assert_eq!(v_0_5.tanh().value(), 0xBEEC9A9F);
+
+        // asinh(1) = 0.8813736
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.asinh().value(), 0x3F61A1B3);
+
+        // acosh(2) = 1.316958
+        let v2 = crate::soft_f32::F32::from_bits(0x40000000);
+        assert_eq!(v2.acosh().value(), 0x3FA89214);
+
+        // atanh(0.5) = 0.54930615
+        let v0_5 = crate::soft_f32::F32::from_bits(0x3F000000);
+        assert_eq!(v0_5.atanh().value(), 0x3F0C9F54);
+    }
+
+    #[test]
+    fn test_f32_pow_with_struct() {
+        // 2^10 = 1024
+        let v2 = crate::soft_f32::F32::from_bits(0x40000000);
+        let v10 = crate::soft_f32::F32::from_bits(0x41200000);
+        assert_eq!(v2.powf(v10).value(), 0x44800000);
+
+        // 3^5 = 243
+        let v3 = crate::soft_f32::F32::from_bits(0x40400000);
+        assert_eq!(v3.powi(5).value(), 0x43730000);
+
+        // cbrt(-125) = -5
+        let v_125 = crate::soft_f32::F32::from_bits(0xC2FA0000);
+        assert_eq!(v_125.cbrt().value(), 0xC0A00000);
+
+        // hypot(3, 4) = 5
+        let v3 = crate::soft_f32::F32::from_bits(0x40400000);
+        let v4 = crate::soft_f32::F32::from_bits(0x40800000);
+        assert_eq!(v3.hypot(v4).value(), 0x40A00000);
+
+        // log1p(1e-7) = 9.9999994e-8
+        let v1e_7 = crate::soft_f32::F32::from_bits(0x33D6BF95);
+        assert_eq!(v1e_7.log1p().value(), 0x33D6BF94);
+
+        // expm1(1e-7) = 1.0000001e-7
+        let v1e_7 = crate::soft_f32::F32::from_bits(0x33D6BF95);
+        assert_eq!(v1e_7.expm1().value(), 0x33D6BF96);
+    }
+
+    #[test]
+    #[cfg(feature = "correctly-rounded")]
+    fn test_f32_cr_with_struct() {
+        // ln(58037908) = 17.876608, where f64 rounds twice to 17.876606
+        let v = crate::soft_f32::F32::from_bits(0x4C5D65A5);
+        assert_eq!(v.ln_cr().value(), 0x418F034B);
+
+        // e^1 = 2.7182817
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.exp_cr().value(), 0x402DF854);
+
+        // sin(1e30) = -0.79116344 and cos(1) = 0.5403023
+        let v1e30 = crate::soft_f32::F32::from_bits(0x7149F2CA);
+        assert_eq!(v1e30.sin_cr().value(), 0xBF4A89B0);
+        let v1 = crate::soft_f32::F32::from_bits(0x3F800000);
+        assert_eq!(v1.cos_cr().value(), 0x3F0A5140);
+
+        // 2^10 = 1024
+        let v2 = crate::soft_f32::F32::from_bits(0x40000000);
+        let v10 = crate::soft_f32::F32::from_bits(0x41200000);
+        assert_eq!(v2.powf_cr(v10).value(), 0x44800000);
+    }
+
+    #[test]
+    fn test_f32_hex_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_hex_str("0x1.99999ap-4").unwrap();
+
+        assert_eq!(v0_1.to_hex_string(), "0x1.99999ap-4");
+        assert_eq!(v0_1.value(), 0x3DCCCCCD);
+    }
 }
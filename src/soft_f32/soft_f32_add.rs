@@ -1,28 +1,42 @@
 use super::util::{
     f32_shift_right_jam,
-    f32_norm_round_and_pack,
     f32_round_and_pack,
-    f32_pack_raw, f32_pack,
+    f32_pack_raw,
     f32_propagate_nan,
     f32_sign, f32_exp, f32_frac,
+    f32_flush_input, f32_flush_result,
 };
 
-use crate::soft_f32::f32_sub;
+use super::soft_f32_sub::f32_sub_mags;
+
+use crate::soft_float::{ Env, with_thread_env };
 
 pub fn f32_add(a: u32, b: u32) -> u32 {
-    // Sign
-    let mut a_sign = f32_sign(a);
-    let mut b_sign = f32_sign(b);
-    let mut r_sign;
+    with_thread_env(|env| f32_add_with_env(a, b, env))
+}
 
-    if a_sign != b_sign {
-        // Consider as substraction
-        return f32_sub(a, b);
-    }
+pub fn f32_add_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| {
+        if f32_sign(a) != f32_sign(b) {
+            // Consider as substraction
+            return f32_sub_mags(a, b, env);
+        }
+
+        f32_add_mags(a, b, env)
+    }, env)
+}
+
+// Add the magnitudes of A and B, which have the same sign
+pub(crate) fn f32_add_mags(a: u32, b: u32, env: &mut Env) -> u32 {
+    // Sign
+    let a_sign = f32_sign(a);
+    let r_sign;
 
     // Exp
-    let mut a_exp = f32_exp(a);
-    let mut b_exp = f32_exp(b);
+    let a_exp = f32_exp(a);
+    let b_exp = f32_exp(b);
     let mut r_exp;
 
     // Frac
@@ -34,6 +48,7 @@ pub fn f32_add(a: u32, b: u32) -> u32 {
 
     if diff_exp == 0 {
         if a_exp == 0 {
+            // Subnormal, a carry makes a normal number
             r_sign = a_sign;
             r_exp = a_exp;
             r_frac = a_frac + b_frac;
@@ -43,12 +58,10 @@ pub fn f32_add(a: u32, b: u32) -> u32 {
         if a_exp == 0xFF {
             if (a_frac | b_frac) != 0 {
                 // Propagate NaN
-                return f32_propagate_nan(a, b);
+                return f32_propagate_nan(a, b, env);
             } else {
-                r_sign = a_sign;
-                r_exp = a_exp;
-                r_frac = a_frac + b_frac;
-                return f32_pack_raw(r_sign, r_exp, r_frac);
+                // Infinity
+                return a;
             }
         }
 
@@ -71,11 +84,11 @@ pub fn f32_add(a: u32, b: u32) -> u32 {
         if diff_exp < 0 {
             // a_exp < b_exp
             if b_exp == 0xFF {
-                if b_sign != 0 {
+                if b_frac != 0 {
                     // Propagate NaN
-                    return f32_propagate_nan(a, b);
+                    return f32_propagate_nan(a, b, env);
                 } else {
-                    // Return a NaN
+                    // Infinity
                     return f32_pack_raw(r_sign, 0xFF, 0);
                 }
             }
@@ -92,12 +105,12 @@ pub fn f32_add(a: u32, b: u32) -> u32 {
         } else {
             // a_exp > b_exp, a_exp == b_exp is considered in the other case
             if a_exp == 0xFF {
-                if a_sign != 0 {
+                if a_frac != 0 {
                     // Propagate NaN
-                    return f32_propagate_nan(a, b);
+                    return f32_propagate_nan(a, b, env);
                 } else {
-                    // Return a NaN
-                    return f32_pack_raw(a_sign, a_exp, a_frac);
+                    // Infinity
+                    return a;
                 }
             }
 
@@ -120,11 +133,13 @@ pub fn f32_add(a: u32, b: u32) -> u32 {
         }
     }
 
-    f32_round_and_pack(r_sign, r_exp, r_frac)
+    f32_round_and_pack(r_sign, r_exp, r_frac, env)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode, FlushToZero };
+
     #[test]
     fn test_f32_add() {
         // 0.1 + 0.2 = 0.3
@@ -183,10 +198,209 @@ mod tests {
         assert_eq!(crate::soft_f32::f32_add(0x0, 0x0), 0x0);
         assert_eq!(crate::soft_f32::f32_add(0x0, 0x80000000), 0x0);
 
-        // FIXME: 0x8DEB8000
-        // assert_eq!(crate::soft_f32::f32_add(0x3FFD70A4, 0xC0000000), 0xBCA45700);
-        // FIXME: 0x8DEB8000
-        // assert_eq!(crate::soft_f32::f32_add(0x400147AE, 0xC0000000), 0x3CA45700);
+        // 1.98 + -2 = -0.02
+        assert_eq!(crate::soft_f32::f32_add(0x3FFD70A4, 0xC0000000), 0xBCA3D700);
+        // 2.02 + -2 = 0.02
+        assert_eq!(crate::soft_f32::f32_add(0x400147AE, 0xC0000000), 0x3CA3D700);
+    }
+
+    #[test]
+    fn test_f32_add_rounding_mode() {
+        // 0.1 + 0.2
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3E99999A);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3E999999);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Min)), 0x3E999999);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Max)), 0x3E99999A);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0x3E99999A);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Odd)), 0x3E999999);
+
+        // -0.1 + -0.2
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xBDCCCCCD, 0xBE4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0xBE99999A);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xBDCCCCCD, 0xBE4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0xBE999999);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xBDCCCCCD, 0xBE4CCCCD, &mut Env::new(RoundingMode::Min)), 0xBE99999A);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xBDCCCCCD, 0xBE4CCCCD, &mut Env::new(RoundingMode::Max)), 0xBE999999);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xBDCCCCCD, 0xBE4CCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0xBE99999A);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xBDCCCCCD, 0xBE4CCCCD, &mut Env::new(RoundingMode::Odd)), 0xBE999999);
+
+        // 1 + 2^-24, a tie
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x33800000, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x33800000, &mut Env::new(RoundingMode::MinMag)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x33800000, &mut Env::new(RoundingMode::Min)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x33800000, &mut Env::new(RoundingMode::Max)), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x33800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x33800000, &mut Env::new(RoundingMode::Odd)), 0x3F800001);
+
+        // (1 + 2^-23) + 2^-24, a tie
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800001, 0x33800000, &mut Env::new(RoundingMode::NearEven)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800001, 0x33800000, &mut Env::new(RoundingMode::MinMag)), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800001, 0x33800000, &mut Env::new(RoundingMode::Min)), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800001, 0x33800000, &mut Env::new(RoundingMode::Max)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800001, 0x33800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800001, 0x33800000, &mut Env::new(RoundingMode::Odd)), 0x3F800001);
+
+        // MAX + MAX, overflow
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut Env::new(RoundingMode::NearEven)), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut Env::new(RoundingMode::MinMag)), 0x7F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut Env::new(RoundingMode::Min)), 0x7F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut Env::new(RoundingMode::Max)), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut Env::new(RoundingMode::NearMaxMag)), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut Env::new(RoundingMode::Odd)), 0x7F7FFFFF);
+
+        // -MAX + -MAX, overflow
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xFF7FFFFF, 0xFF7FFFFF, &mut Env::new(RoundingMode::NearEven)), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xFF7FFFFF, 0xFF7FFFFF, &mut Env::new(RoundingMode::MinMag)), 0xFF7FFFFF);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xFF7FFFFF, 0xFF7FFFFF, &mut Env::new(RoundingMode::Min)), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xFF7FFFFF, 0xFF7FFFFF, &mut Env::new(RoundingMode::Max)), 0xFF7FFFFF);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xFF7FFFFF, 0xFF7FFFFF, &mut Env::new(RoundingMode::NearMaxMag)), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0xFF7FFFFF, 0xFF7FFFFF, &mut Env::new(RoundingMode::Odd)), 0xFF7FFFFF);
+
+        // 0.1 + -0.1
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::Min)), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::Max)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::Odd)), 0x00000000);
+    }
+
+    #[test]
+    fn test_f32_add_exception_flags() {
+        let mut env = Env::default();
+
+        // 1 + 1 = 2, exact
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x3F800000, &mut env), 0x40000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 0.1 + 0.2 = 0.3, inexact
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut env), 0x3E99999A);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // MAX + MAX = Inf, overflow
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // Inf + -Inf = NaN, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_add_with_env(0x7F800000, 0xFF800000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // qNaN + 1 = NaN, quiet
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7FC00001, 0x3F800000, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // sNaN + 1 = qNaN, invalid
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F800001, 0x3F800000, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_add_subnormal() {
+        let mut env = Env::default();
+
+        // Subnormal + subnormal = subnormal, exact
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x00000001, 0x00000001, &mut env), 0x00000002);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Largest subnormal + smallest subnormal = smallest normal
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x007FFFFF, 0x00000001, &mut env), 0x00800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Smallest normal + -smallest normal with one more ulp = smallest subnormal
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x00800001, 0x80800000, &mut env), 0x00000001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_add_nan_policy() {
+        // (qNaN A, qNaN B with a larger payload, sNaN, 1 + sNaN, sNaN + qNaN B, inf - inf) in each policy
+        let cases = [
+            (NanPolicy::X87, 0xFFC00002, 0x7FC00003, 0xFFC00000, 0x7FC00002),
+            (NanPolicy::X86Sse, 0x7FC00001, 0x7FC00003, 0xFFC00000, 0x7FC00003),
+            (NanPolicy::Armv8, 0x7FC00001, 0x7FC00003, 0x7FC00000, 0x7FC00003),
+            (NanPolicy::Armv8DefaultNan, 0x7FC00000, 0x7FC00000, 0x7FC00000, 0x7FC00000),
+            (NanPolicy::RiscV, 0x7FC00000, 0x7FC00000, 0x7FC00000, 0x7FC00000),
+            (NanPolicy::Wasm, 0x7FC00000, 0x7FC00000, 0x7FC00000, 0x7FC00000),
+        ];
+        for &(nan_policy, qnan_qnan, one_snan, inf_inf, snan_qnan) in cases.iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+
+            assert_eq!(crate::soft_f32::f32_add_with_env(0x7FC00001, 0xFFC00002, &mut env), qnan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+
+            assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x7F800003, &mut env), one_snan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f32::f32_add_with_env(0x7F800000, 0xFF800000, &mut env), inf_inf);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f32::f32_add_with_env(0x7F800003, 0x7FC00002, &mut env), snan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
     }
-}
 
+    #[test]
+    fn test_f32_add_nan_policy_mips_legacy() {
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+
+        // The quiet bit is clear in a quiet NaN, which propagates unchanged
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F800001, 0x7F800002, &mut env), 0x7F800001);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0xFF800002, &mut env), 0xFF800002);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // A signaling NaN gives the default NaN
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x7F800001, 0x7FC00000, &mut env), 0x7FBFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Inf - inf gives the default NaN
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x7F800000, 0x7F800000, &mut env), 0x7FBFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_add_flush_to_zero() {
+        // (2^-126 + 1 ulp) - 2^-126 = 2^-149, exact but flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x00800001, 0x00800000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        // -(2^-126 + 1 ulp) + 2^-126 = -2^-149
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x80800001, 0x00800000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // Flags raised before are kept
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        env.raise(ExceptionFlags::INVALID);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x00400000, 0x00000001, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID | ExceptionFlags::UNDERFLOW);
+
+        // Normal results are kept
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x00400000, 0x00400000, &mut env), 0x00800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_add_denormals_are_zero() {
+        // 2^-149 + 2^-149 = 0
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x00000001, 0x00000001, &mut env), 0x00000000);
+        // -2^-149 + -2^-149 = -0
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x80000001, 0x80000001, &mut env), 0x80000000);
+        // 1 + 2^-149 = 1, exact
+        assert_eq!(crate::soft_f32::f32_add_with_env(0x3F800000, 0x007FFFFF, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
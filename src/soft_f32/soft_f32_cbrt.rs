@@ -0,0 +1,111 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    f32_to_fixed_parts,
+    f32_round_fixed,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+// Floor of the cube root, for v below 2^120
+fn f32_icbrt(v: u128) -> u128 {
+    let mut root = 0;
+    for bit in (0..40).rev() {
+        let t = root | (1 << bit);
+        if t * t * t <= v {
+            root = t;
+        }
+    }
+
+    root
+}
+
+pub fn f32_cbrt(a: u32) -> u32 {
+    with_thread_env(|env| f32_cbrt_with_env(a, env))
+}
+
+// Cube root, correctly rounded. Unlike f32_sqrt, it is defined for negative numbers.
+pub fn f32_cbrt_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_cbrt_unflushed(a, env), env)
+}
+
+fn f32_cbrt_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 || a & 0x7FFFFFFF == 0x7F800000 {
+        // Zero and infinity
+        return a;
+    }
+
+    // |A| = frac x 2^(3q + r) with r in [0, 2]
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    let frac = (m >> 39) as u128;
+    let q = (e - 23).div_euclid(3);
+    let r = (e - 23).rem_euclid(3);
+
+    // cbrt(frac x 2^r x 2^90) = cbrt(|A|) x 2^(30 - q) has at least 37 bits, the floor and
+    // whether it is exact round as the exact cube root
+    let v = frac << (r + 90);
+    let root = f32_icbrt(v);
+    let is_exact = root * root * root == v;
+
+    let root = root as i128;
+    f32_round_fixed(if a_sign == 1 { -root } else { root }, q - 30, is_exact, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f32_cbrt() {
+        // cbrt(8) = 2
+        assert_eq!(crate::soft_f32::f32_cbrt(0x41000000), 0x40000000);
+        // cbrt(-125) = -5
+        assert_eq!(crate::soft_f32::f32_cbrt(0xC2FA0000), 0xC0A00000);
+        // cbrt(2) = 1.2599211
+        assert_eq!(crate::soft_f32::f32_cbrt(0x40000000), 0x3FA14518);
+        // cbrt(2^-149) = 1.1190347e-15
+        assert_eq!(crate::soft_f32::f32_cbrt(0x00000001), 0x26A14518);
+        // cbrt(3.4028235e38) = 6.9814636e12
+        assert_eq!(crate::soft_f32::f32_cbrt(0x7F7FFFFF), 0x54CB2FF5);
+
+        // cbrt(-0) = -0
+        assert_eq!(crate::soft_f32::f32_cbrt(0x80000000), 0x80000000);
+        // cbrt(-Inf) = -Inf
+        assert_eq!(crate::soft_f32::f32_cbrt(0xFF800000), 0xFF800000);
+        // cbrt(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_cbrt(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_cbrt_exception_flags() {
+        // cbrt(-125) = -5, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0xC2FA0000, &mut env), 0xC0A00000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // cbrt(2), inexact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut env), 0x3FA14518);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_cbrt_rounding_mode() {
+        // cbrt(2)
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut Env::new(RoundingMode::NearEven)), 0x3FA14518);
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut Env::new(RoundingMode::MinMag)), 0x3FA14517);
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut Env::new(RoundingMode::Min)), 0x3FA14517);
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut Env::new(RoundingMode::Max)), 0x3FA14518);
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FA14518);
+        assert_eq!(crate::soft_f32::f32_cbrt_with_env(0x40000000, &mut Env::new(RoundingMode::Odd)), 0x3FA14517);
+    }
+}
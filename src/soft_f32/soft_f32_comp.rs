@@ -1,48 +1,104 @@
 use super::util::{
-    f32_sign, f32_exp, f32_frac,
+    f32_propagate_nan,
+    f32_sign,
     f32_is_nan,
+    f32_is_signaling_nan_in,
+    f32_flush_input,
 };
 
-pub fn f32_ne(a: u32, b: u32) -> bool {
+use crate::soft_float::{ Env, ExceptionFlags, MinMax, with_thread_env };
+
+// Equality comparisons are quiet, only a signaling NaN is invalid
+fn f32_is_unordered_quiet(a: u32, b: u32, env: &mut Env) -> bool {
     if f32_is_nan(a) || f32_is_nan(b) {
-        // Unable to compare
-        return false;
+        if f32_is_signaling_nan_in(a, env) || f32_is_signaling_nan_in(b, env) {
+            env.raise(ExceptionFlags::INVALID);
+        }
+        return true;
     }
+    false
+}
 
-    !f32_eq(a, b)
+// Relational comparisons signal, any NaN is invalid
+fn f32_is_unordered_signaling(a: u32, b: u32, env: &mut Env) -> bool {
+    if f32_is_nan(a) || f32_is_nan(b) {
+        env.raise(ExceptionFlags::INVALID);
+        return true;
+    }
+    false
+}
+
+pub fn f32_ne(a: u32, b: u32) -> bool {
+    with_thread_env(|env| f32_ne_with_env(a, b, env))
+}
+
+pub fn f32_ne_with_env(a: u32, b: u32, env: &mut Env) -> bool {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if f32_is_unordered_quiet(a, b, env) {
+        // Unable to compare, so they are not equal
+        return true;
+    }
+
+    !f32_eq_ordered(a, b)
 }
 
 pub fn f32_eq(a: u32, b: u32) -> bool {
-    if f32_is_nan(a) || f32_is_nan(b) {
+    with_thread_env(|env| f32_eq_with_env(a, b, env))
+}
+
+pub fn f32_eq_with_env(a: u32, b: u32, env: &mut Env) -> bool {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if f32_is_unordered_quiet(a, b, env) {
         // Unable to compare
         return false;
     }
 
+    f32_eq_ordered(a, b)
+}
+
+fn f32_eq_ordered(a: u32, b: u32) -> bool {
     a == b || ((a | b) << 1) == 0
 }
 
 pub fn f32_ge(a: u32, b: u32) -> bool {
-    if f32_is_nan(a) || f32_is_nan(b) {
+    with_thread_env(|env| f32_ge_with_env(a, b, env))
+}
+
+pub fn f32_ge_with_env(a: u32, b: u32, env: &mut Env) -> bool {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if f32_is_unordered_signaling(a, b, env) {
         // Unable to compare
         return false;
     }
 
-    !f32_lt(a, b)
+    !f32_lt_ordered(a, b)
 }
 
 pub fn f32_lt(a: u32, b: u32) -> bool {
-    if f32_is_nan(a) || f32_is_nan(b) {
+    with_thread_env(|env| f32_lt_with_env(a, b, env))
+}
+
+pub fn f32_lt_with_env(a: u32, b: u32, env: &mut Env) -> bool {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if f32_is_unordered_signaling(a, b, env) {
         // Unable to compare
         return false;
     }
 
+    f32_lt_ordered(a, b)
+}
+
+fn f32_lt_ordered(a: u32, b: u32) -> bool {
     // Sign
-    let mut a_sign = f32_sign(a);
-    let mut b_sign = f32_sign(b);
+    let a_sign = f32_sign(a);
+    let b_sign = f32_sign(b);
 
     if a_sign != b_sign {
-        // Different sign
-        // FIXME: Add a test for this case
+        // Different sign, but +0 and -0 are equal
         if (a | b) << 1 != 0 {
             return a_sign == 1;
         }
@@ -60,27 +116,42 @@ pub fn f32_lt(a: u32, b: u32) -> bool {
 }
 
 pub fn f32_gt(a: u32, b: u32) -> bool {
-    if f32_is_nan(a) || f32_is_nan(b) {
+    with_thread_env(|env| f32_gt_with_env(a, b, env))
+}
+
+pub fn f32_gt_with_env(a: u32, b: u32, env: &mut Env) -> bool {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if f32_is_unordered_signaling(a, b, env) {
         // Unable to compare
         return false;
     }
 
-    !f32_le(a, b)
+    !f32_le_ordered(a, b)
 }
 
 pub fn f32_le(a: u32, b: u32) -> bool {
-    if f32_is_nan(a) || f32_is_nan(b) {
+    with_thread_env(|env| f32_le_with_env(a, b, env))
+}
+
+pub fn f32_le_with_env(a: u32, b: u32, env: &mut Env) -> bool {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if f32_is_unordered_signaling(a, b, env) {
         // Unable to compare
         return false;
     }
 
+    f32_le_ordered(a, b)
+}
+
+fn f32_le_ordered(a: u32, b: u32) -> bool {
     // Sign
-    let mut a_sign = f32_sign(a);
-    let mut b_sign = f32_sign(b);
+    let a_sign = f32_sign(a);
+    let b_sign = f32_sign(b);
 
     if a_sign != b_sign {
-        // Different sign
-        // FIXME: Add a test for this case
+        // Different sign, +0 and -0 are equal
         if (a | b) << 1 == 0 || a_sign == 1 {
             return true;
         }
@@ -99,8 +170,134 @@ pub fn f32_le(a: u32, b: u32) -> bool {
     false
 }
 
+// A < B for ordered A and B, with -0 < +0
+fn f32_lt_signed_zero(a: u32, b: u32) -> bool {
+    f32_lt_ordered(a, b) || (f32_sign(a) > f32_sign(b) && ((a | b) << 1) == 0)
+}
+
+// The minimum or the maximum of A and B with the given behavior
+fn f32_min_max(a: u32, b: u32, is_max: bool, min_max: MinMax, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    if min_max == MinMax::Select {
+        // The first operand only when it is strictly less (greater)
+        if f32_is_unordered_signaling(a, b, env) {
+            return b;
+        }
+        let is_a = match is_max {
+            true => f32_lt_ordered(b, a),
+            false => f32_lt_ordered(a, b),
+        };
+        return if is_a { a } else { b };
+    }
+
+    let is_a_nan = f32_is_nan(a);
+    let is_b_nan = f32_is_nan(b);
+    if is_a_nan || is_b_nan {
+        let is_signaling = f32_is_signaling_nan_in(a, env) || f32_is_signaling_nan_in(b, env);
+        match min_max {
+            MinMax::Propagate => return f32_propagate_nan(a, b, env),
+            MinMax::Num2008 if is_signaling => return f32_propagate_nan(a, b, env),
+            // RISC-V canonical NaN
+            MinMax::NumberCanonicalNan if is_a_nan && is_b_nan => {
+                if is_signaling {
+                    env.raise(ExceptionFlags::INVALID);
+                }
+                return 0x7FC00000;
+            }
+            _ => {}
+        }
+        if is_a_nan && is_b_nan {
+            return f32_propagate_nan(a, b, env);
+        }
+        // The number, but a signaling NaN is still invalid
+        if is_signaling {
+            env.raise(ExceptionFlags::INVALID);
+        }
+        return if is_a_nan { b } else { a };
+    }
+
+    if f32_lt_signed_zero(a, b) != is_max { a } else { b }
+}
+
+pub fn f32_min(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_min_with_env(a, b, env))
+}
+
+// The minimum with the behavior of the environment, see soft_float::MinMax
+pub fn f32_min_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, false, env.min_max(), env)
+}
+
+pub fn f32_max(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_max_with_env(a, b, env))
+}
+
+// The maximum with the behavior of the environment, see soft_float::MinMax
+pub fn f32_max_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, true, env.min_max(), env)
+}
+
+pub fn f32_minimum(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_minimum_with_env(a, b, env))
+}
+
+// IEEE 754-2019 minimum, NaN when either operand is NaN, and -0 < +0
+pub fn f32_minimum_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, false, MinMax::Propagate, env)
+}
+
+pub fn f32_maximum(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_maximum_with_env(a, b, env))
+}
+
+// IEEE 754-2019 maximum, NaN when either operand is NaN, and -0 < +0
+pub fn f32_maximum_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, true, MinMax::Propagate, env)
+}
+
+pub fn f32_minimum_number(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_minimum_number_with_env(a, b, env))
+}
+
+// IEEE 754-2019 minimumNumber, the other operand when one is NaN, and -0 < +0
+pub fn f32_minimum_number_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, false, MinMax::Number, env)
+}
+
+pub fn f32_maximum_number(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_maximum_number_with_env(a, b, env))
+}
+
+// IEEE 754-2019 maximumNumber, the other operand when one is NaN, and -0 < +0
+pub fn f32_maximum_number_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, true, MinMax::Number, env)
+}
+
+pub fn f32_min_num(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_min_num_with_env(a, b, env))
+}
+
+// IEEE 754-2008 minNum, the other operand when one is a quiet NaN, and NaN when one is a
+// signaling NaN. -0 < +0, which 2008 leaves open.
+pub fn f32_min_num_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, false, MinMax::Num2008, env)
+}
+
+pub fn f32_max_num(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_max_num_with_env(a, b, env))
+}
+
+// IEEE 754-2008 maxNum, the other operand when one is a quiet NaN, and NaN when one is a
+// signaling NaN. -0 < +0, which 2008 leaves open.
+pub fn f32_max_num_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_min_max(a, b, true, MinMax::Num2008, env)
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, MinMax, NanPolicy };
+
     #[test]
     fn test_f32_eq() {
         // 0.3 == 0.2 - false
@@ -223,4 +420,195 @@ mod tests {
         // Nan <= Nan - false
         assert_eq!(crate::soft_f32::f32_le(0xFFFFFFFF, 0xFFFFFFFF), false);
     }
+
+    #[test]
+    fn test_f32_compare_exception_flags() {
+        let mut env = Env::default();
+
+        // 0.1 < 0.2 - true
+        assert_eq!(crate::soft_f32::f32_lt_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut env), true);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // -0 == 0 - true
+        assert_eq!(crate::soft_f32::f32_eq_with_env(0x80000000, 0x00000000, &mut env), true);
+        assert_eq!(crate::soft_f32::f32_le_with_env(0x80000000, 0x00000000, &mut env), true);
+        assert_eq!(crate::soft_f32::f32_lt_with_env(0x80000000, 0x00000000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // qNaN == 1 - false, quiet
+        assert_eq!(crate::soft_f32::f32_eq_with_env(0x7FC00000, 0x3F800000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // qNaN != qNaN - true, quiet
+        assert_eq!(crate::soft_f32::f32_ne_with_env(0x7FC00000, 0x7FC00000, &mut env), true);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // sNaN == 1 - false, invalid
+        assert_eq!(crate::soft_f32::f32_eq_with_env(0x7F800001, 0x3F800000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // qNaN < 1 - false, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_lt_with_env(0x7FC00000, 0x3F800000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // 1 >= qNaN - false, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_ge_with_env(0x3F800000, 0x7FC00000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_minimum_maximum() {
+        // minimum(1, 2) = 1 and maximum(1, 2) = 2
+        assert_eq!(crate::soft_f32::f32_minimum(0x3F800000, 0x40000000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_maximum(0x3F800000, 0x40000000), 0x40000000);
+        // minimum(-inf, -3.4028235e38) = -inf
+        assert_eq!(crate::soft_f32::f32_minimum(0xFF7FFFFF, 0xFF800000), 0xFF800000);
+        // -0 < +0, in both orders
+        assert_eq!(crate::soft_f32::f32_minimum(0x00000000, 0x80000000), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_minimum(0x80000000, 0x00000000), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_maximum(0x00000000, 0x80000000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_maximum(0x80000000, 0x00000000), 0x00000000);
+        // A NaN propagates
+        assert_eq!(crate::soft_f32::f32_minimum(0x7FC00001, 0x3F800000), 0x7FC00001);
+        assert_eq!(crate::soft_f32::f32_maximum(0x3F800000, 0xFFC00002), 0xFFC00002);
+    }
+
+    #[test]
+    fn test_f32_minimum_maximum_number() {
+        // minimum_number(-1, 2) = -1 and maximum_number(-1, 2) = 2
+        assert_eq!(crate::soft_f32::f32_minimum_number(0xBF800000, 0x40000000), 0xBF800000);
+        assert_eq!(crate::soft_f32::f32_maximum_number(0xBF800000, 0x40000000), 0x40000000);
+        // -0 < +0
+        assert_eq!(crate::soft_f32::f32_minimum_number(0x00000000, 0x80000000), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_maximum_number(0x00000000, 0x80000000), 0x00000000);
+        // A NaN is ignored, even a signaling one
+        assert_eq!(crate::soft_f32::f32_minimum_number(0x7FC00000, 0x3F800000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_maximum_number(0x3F800000, 0x7F800001), 0x3F800000);
+        // Two NaNs give NaN
+        assert_eq!(crate::soft_f32::f32_minimum_number(0x7FC00001, 0x7FC00002), 0x7FC00002);
+    }
+
+    #[test]
+    fn test_f32_min_max_num() {
+        // min_num(3, 2) = 2 and max_num(3, 2) = 3
+        assert_eq!(crate::soft_f32::f32_min_num(0x40400000, 0x40000000), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_max_num(0x40400000, 0x40000000), 0x40400000);
+        // A quiet NaN is ignored
+        assert_eq!(crate::soft_f32::f32_min_num(0x40400000, 0x7FC00000), 0x40400000);
+        assert_eq!(crate::soft_f32::f32_max_num(0xFFC00000, 0x40400000), 0x40400000);
+        // A signaling NaN gives the quiet NaN, and is invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_min_num_with_env(0x40400000, 0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_min_max_env() {
+        // Number is the default
+        assert_eq!(crate::soft_f32::f32_min(0x7FC00000, 0x3F800000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_max(0x80000000, 0x00000000), 0x00000000);
+
+        let mut env = Env::default();
+        env.set_min_max(MinMax::Propagate);
+        assert_eq!(crate::soft_f32::f32_min_with_env(0x7FC00000, 0x3F800000, &mut env), 0x7FC00000);
+
+        env.set_min_max(MinMax::Num2008);
+        assert_eq!(crate::soft_f32::f32_max_with_env(0x7FC00000, 0x3F800000, &mut env), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_max_with_env(0x7F800001, 0x3F800000, &mut env), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_min_max_x86() {
+        let mut env = Env::default();
+        env.set_min_max(MinMax::Select);
+
+        // min(1, 2) = 1 and max(1, 2) = 2
+        assert_eq!(crate::soft_f32::f32_min_with_env(0x3F800000, 0x40000000, &mut env), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_max_with_env(0x3F800000, 0x40000000, &mut env), 0x40000000);
+        // Zeros give the second operand
+        assert_eq!(crate::soft_f32::f32_min_with_env(0x80000000, 0x00000000, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_max_with_env(0x00000000, 0x80000000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // A NaN gives the second operand, unchanged, and any NaN is invalid
+        assert_eq!(crate::soft_f32::f32_min_with_env(0x7FC00000, 0x3F800000, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_max_with_env(0x3F800000, 0x7F800001, &mut env), 0x7F800001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_min_max_riscv() {
+        let mut env = Env::default();
+        env.set_min_max(MinMax::NumberCanonicalNan);
+
+        // -0 < +0
+        assert_eq!(crate::soft_f32::f32_min_with_env(0x00000000, 0x80000000, &mut env), 0x80000000);
+        // A NaN is ignored, quietly unless signaling
+        assert_eq!(crate::soft_f32::f32_max_with_env(0xFFC00000, 0xBF800000, &mut env), 0xBF800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f32::f32_max_with_env(0x7F800001, 0xBF800000, &mut env), 0xBF800000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Two NaNs give the canonical NaN
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_min_with_env(0xFFC00001, 0xFFC00002, &mut env), 0x7FC00000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_min_max_exception_flags() {
+        // Numbers and quiet NaNs are quiet
+        let mut env = Env::default();
+        crate::soft_f32::f32_minimum_with_env(0x7FC00000, 0x3F800000, &mut env);
+        crate::soft_f32::f32_maximum_number_with_env(0x7FC00000, 0x3F800000, &mut env);
+        crate::soft_f32::f32_max_num_with_env(0x00000000, 0x80000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // A signaling NaN is invalid
+        assert_eq!(crate::soft_f32::f32_maximum_with_env(0x7F800001, 0x3F800000, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        env.clear_flags();
+        crate::soft_f32::f32_minimum_number_with_env(0x3F800000, 0xFF800001, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_compare_nan_policy() {
+        // MIPS legacy sets the quiet bit in a signaling NaN
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_f32::f32_eq_with_env(0x7F800001, 0x3F800000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f32::f32_eq_with_env(0x7FC00000, 0x3F800000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_compare_denormals_are_zero() {
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+
+        // 2^-149 == -0 - true
+        assert_eq!(crate::soft_f32::f32_eq_with_env(0x00000001, 0x80000000, &mut env), true);
+        assert_eq!(crate::soft_f32::f32_ne_with_env(0x00000001, 0x80000000, &mut env), false);
+        // -2^-149 < 2^-149 - false
+        assert_eq!(crate::soft_f32::f32_lt_with_env(0x80000001, 0x00000001, &mut env), false);
+        assert_eq!(crate::soft_f32::f32_le_with_env(0x80000001, 0x00000001, &mut env), true);
+        assert_eq!(crate::soft_f32::f32_gt_with_env(0x007FFFFF, 0x00000000, &mut env), false);
+        assert_eq!(crate::soft_f32::f32_ge_with_env(0x80000000, 0x007FFFFF, &mut env), true);
+        // 2^-126 > 2^-149 - true
+        assert_eq!(crate::soft_f32::f32_gt_with_env(0x00800000, 0x007FFFFF, &mut env), true);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // The minimum is a zero
+        assert_eq!(crate::soft_f32::f32_minimum_with_env(0x00000001, 0x3F800000, &mut env), 0x00000000);
+
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_lt_with_env(0x80000001, 0x00000001, &mut env), true);
+    }
 }
@@ -0,0 +1,338 @@
+use super::util::{
+    f32_round_and_pack,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_flush_input,
+};
+
+use crate::soft_f128::util::{
+    f128_shift_right_jam,
+    f128_count_leading_zero,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, IntOverflow, RoundingMode, with_thread_env };
+
+// Round A to an integer, returned as its sign and magnitude. The magnitude is None for NaN
+// and infinity, and the flag tells whether the rounding was inexact.
+fn f32_round_to_magnitude(a: u32, rounding_mode: RoundingMode) -> (i32, Option<u128>, bool) {
+    let sign = f32_sign(a);
+    let exp = f32_exp(a);
+    let frac = f32_frac(a);
+
+    if exp == 0xFF {
+        // NaN or infinity
+        return (sign, None, false);
+    }
+    if exp == 0 && frac == 0 {
+        // Zero
+        return (sign, Some(0), false);
+    }
+
+    // The value is sig x 2^(exp - 0x96), a subnormal has no hidden bit
+    let (exp, sig) = match exp {
+        0 => (1, frac as u128),
+        _ => (exp, (frac | 0x00800000) as u128),
+    };
+    if exp >= 0x96 {
+        // Integer, at most 2^128 - 2^104
+        return (sign, Some(sig << (exp - 0x96)), false);
+    }
+
+    // Any value below 1/4 rounds the same way, so the distance can be limited
+    let (sig, dist) = match 0x96 - exp {
+        dist if dist > 26 => (1, 26),
+        dist => (sig, dist),
+    };
+    let r_int = sig >> dist;
+    let rem = sig & ((1 << dist) - 1);
+    let half = 1 << (dist - 1);
+
+    let is_increment = match rounding_mode {
+        RoundingMode::NearEven => rem > half || (rem == half && (r_int & 1) != 0),
+        RoundingMode::NearMaxMag => rem >= half,
+        RoundingMode::MinMag => false,
+        RoundingMode::Min => sign == 1 && rem != 0,
+        RoundingMode::Max => sign == 0 && rem != 0,
+        // Inexact, jam the lost bits into the least significant bit
+        RoundingMode::Odd => rem != 0 && (r_int & 1) == 0,
+    };
+
+    (sign, Some(r_int + is_increment as u128), rem != 0)
+}
+
+// Convert A to an integer of `width` bits, returned as its two's complement bits
+fn f32_to_int(a: u32, rounding_mode: RoundingMode, exact: bool, width: u32, is_signed: bool, env: &mut Env) -> u128 {
+    let a = f32_flush_input(a, env);
+    let (sign, r_mag, is_inexact) = f32_round_to_magnitude(a, rounding_mode);
+
+    // The largest magnitude of each sign
+    let (max_pos, max_neg) = match is_signed {
+        true => ((1u128 << (width - 1)) - 1, 1u128 << (width - 1)),
+        false => (u128::MAX >> (128 - width), 0),
+    };
+    let min_bits = match is_signed {
+        true => max_neg.wrapping_neg(),
+        false => 0,
+    };
+
+    let is_nan = r_mag.is_none() && f32_frac(a) != 0;
+    let is_valid = match r_mag {
+        Some(r_mag) => r_mag <= if sign == 1 { max_neg } else { max_pos },
+        None => false,
+    };
+    if !is_valid {
+        env.raise(ExceptionFlags::INVALID);
+        return match env.int_overflow() {
+            IntOverflow::Saturate if is_nan => 0,
+            IntOverflow::Saturate | IntOverflow::SaturateNanMax => {
+                if sign == 1 && !is_nan { min_bits } else { max_pos }
+            }
+            IntOverflow::Indefinite => if is_signed { min_bits } else { max_pos },
+        };
+    }
+
+    if exact && is_inexact {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    let r_mag = r_mag.unwrap_or(0);
+    if sign == 1 { r_mag.wrapping_neg() } else { r_mag }
+}
+
+// Convert an integer given as its sign and magnitude to Float32
+fn f32_from_int(sign: i32, mag: u128, env: &mut Env) -> u32 {
+    if mag == 0 {
+        // Zero
+        return f32_pack_raw(0, 0, 0);
+    }
+
+    // Move the most significant bit to the hidden bit
+    let lead_pos = 127 - f128_count_leading_zero(mag);
+    let frac = match lead_pos > 30 {
+        true => f128_shift_right_jam(mag, lead_pos - 30),
+        false => mag << (30 - lead_pos),
+    };
+
+    f32_round_and_pack(sign, 0x7E + lead_pos, frac as i32, env)
+}
+
+pub fn f32_to_i32(a: u32, rounding_mode: RoundingMode, exact: bool) -> i32 {
+    with_thread_env(|env| f32_to_i32_with_env(a, rounding_mode, exact, env))
+}
+
+// Round A to an integer with the given rounding mode, inexact is raised only when exact is set
+pub fn f32_to_i32_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> i32 {
+    f32_to_int(a, rounding_mode, exact, 32, true, env) as i32
+}
+
+pub fn f32_to_u32(a: u32, rounding_mode: RoundingMode, exact: bool) -> u32 {
+    with_thread_env(|env| f32_to_u32_with_env(a, rounding_mode, exact, env))
+}
+
+pub fn f32_to_u32_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> u32 {
+    f32_to_int(a, rounding_mode, exact, 32, false, env) as u32
+}
+
+pub fn f32_to_i64(a: u32, rounding_mode: RoundingMode, exact: bool) -> i64 {
+    with_thread_env(|env| f32_to_i64_with_env(a, rounding_mode, exact, env))
+}
+
+pub fn f32_to_i64_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> i64 {
+    f32_to_int(a, rounding_mode, exact, 64, true, env) as i64
+}
+
+pub fn f32_to_u64(a: u32, rounding_mode: RoundingMode, exact: bool) -> u64 {
+    with_thread_env(|env| f32_to_u64_with_env(a, rounding_mode, exact, env))
+}
+
+pub fn f32_to_u64_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> u64 {
+    f32_to_int(a, rounding_mode, exact, 64, false, env) as u64
+}
+
+pub fn f32_to_i128(a: u32, rounding_mode: RoundingMode, exact: bool) -> i128 {
+    with_thread_env(|env| f32_to_i128_with_env(a, rounding_mode, exact, env))
+}
+
+pub fn f32_to_i128_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> i128 {
+    f32_to_int(a, rounding_mode, exact, 128, true, env) as i128
+}
+
+pub fn f32_to_u128(a: u32, rounding_mode: RoundingMode, exact: bool) -> u128 {
+    with_thread_env(|env| f32_to_u128_with_env(a, rounding_mode, exact, env))
+}
+
+pub fn f32_to_u128_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> u128 {
+    f32_to_int(a, rounding_mode, exact, 128, false, env)
+}
+
+pub fn i32_to_f32(a: i32) -> u32 {
+    with_thread_env(|env| i32_to_f32_with_env(a, env))
+}
+
+pub fn i32_to_f32_with_env(a: i32, env: &mut Env) -> u32 {
+    f32_from_int((a < 0) as i32, a.unsigned_abs() as u128, env)
+}
+
+pub fn u32_to_f32(a: u32) -> u32 {
+    with_thread_env(|env| u32_to_f32_with_env(a, env))
+}
+
+pub fn u32_to_f32_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_from_int(0, a as u128, env)
+}
+
+pub fn i64_to_f32(a: i64) -> u32 {
+    with_thread_env(|env| i64_to_f32_with_env(a, env))
+}
+
+pub fn i64_to_f32_with_env(a: i64, env: &mut Env) -> u32 {
+    f32_from_int((a < 0) as i32, a.unsigned_abs() as u128, env)
+}
+
+pub fn u64_to_f32(a: u64) -> u32 {
+    with_thread_env(|env| u64_to_f32_with_env(a, env))
+}
+
+pub fn u64_to_f32_with_env(a: u64, env: &mut Env) -> u32 {
+    f32_from_int(0, a as u128, env)
+}
+
+pub fn i128_to_f32(a: i128) -> u32 {
+    with_thread_env(|env| i128_to_f32_with_env(a, env))
+}
+
+pub fn i128_to_f32_with_env(a: i128, env: &mut Env) -> u32 {
+    f32_from_int((a < 0) as i32, a.unsigned_abs(), env)
+}
+
+pub fn u128_to_f32(a: u128) -> u32 {
+    with_thread_env(|env| u128_to_f32_with_env(a, env))
+}
+
+pub fn u128_to_f32_with_env(a: u128, env: &mut Env) -> u32 {
+    f32_from_int(0, a, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, IntOverflow, RoundingMode };
+
+    #[test]
+    fn test_f32_to_int_rounding_mode() {
+        // 2.5
+        assert_eq!(crate::soft_f32::f32_to_i32(0x40200000, RoundingMode::NearEven, false), 2);
+        assert_eq!(crate::soft_f32::f32_to_i32(0x40200000, RoundingMode::NearMaxMag, false), 3);
+        assert_eq!(crate::soft_f32::f32_to_i32(0x40200000, RoundingMode::MinMag, false), 2);
+        assert_eq!(crate::soft_f32::f32_to_i32(0x40200000, RoundingMode::Min, false), 2);
+        assert_eq!(crate::soft_f32::f32_to_i32(0x40200000, RoundingMode::Max, false), 3);
+        assert_eq!(crate::soft_f32::f32_to_i32(0x40200000, RoundingMode::Odd, false), 3);
+
+        // -0.75
+        assert_eq!(crate::soft_f32::f32_to_i64(0xBF400000, RoundingMode::NearEven, false), -1);
+        assert_eq!(crate::soft_f32::f32_to_i64(0xBF400000, RoundingMode::MinMag, false), 0);
+        assert_eq!(crate::soft_f32::f32_to_i64(0xBF400000, RoundingMode::Min, false), -1);
+        assert_eq!(crate::soft_f32::f32_to_i64(0xBF400000, RoundingMode::Max, false), 0);
+
+        // The smallest subnormal
+        assert_eq!(crate::soft_f32::f32_to_u32(0x00000001, RoundingMode::Max, false), 1);
+        assert_eq!(crate::soft_f32::f32_to_u32(0x00000001, RoundingMode::NearEven, false), 0);
+    }
+
+    #[test]
+    fn test_f32_to_int_range() {
+        // -2^31 is the smallest i32
+        assert_eq!(crate::soft_f32::f32_to_i32(0xCF000000, RoundingMode::NearEven, true), i32::MIN);
+        // 2^32 - 256 is the largest Float32 below 2^32
+        assert_eq!(crate::soft_f32::f32_to_u32(0x4F7FFFFF, RoundingMode::NearEven, true), 0xFFFFFF00);
+        // 2^63
+        assert_eq!(crate::soft_f32::f32_to_u64(0x5F000000, RoundingMode::NearEven, true), 0x8000000000000000);
+        // MAX fits in u128, and -2^127 is the smallest i128
+        assert_eq!(crate::soft_f32::f32_to_u128(0x7F7FFFFF, RoundingMode::NearEven, true), 0xFFFFFF00000000000000000000000000);
+        assert_eq!(crate::soft_f32::f32_to_i128(0xFF000000, RoundingMode::NearEven, true), i128::MIN);
+        // -0.5 rounds to -0, which is a valid u32
+        assert_eq!(crate::soft_f32::f32_to_u32(0xBF000000, RoundingMode::NearEven, true), 0);
+    }
+
+    #[test]
+    fn test_f32_to_int_invalid() {
+        // Saturate, NaN gives 0
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x4F000000, RoundingMode::NearEven, true, &mut env), i32::MAX);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0xFF800000, RoundingMode::NearEven, true, &mut env), i32::MIN);
+        assert_eq!(crate::soft_f32::f32_to_u32_with_env(0xBF800000, RoundingMode::NearEven, true, &mut env), 0);
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x7FC00000, RoundingMode::NearEven, true, &mut env), 0);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Saturate, NaN gives the largest integer
+        let mut env = Env::default();
+        env.set_int_overflow(IntOverflow::SaturateNanMax);
+        assert_eq!(crate::soft_f32::f32_to_i64_with_env(0xFF800000, RoundingMode::NearEven, true, &mut env), i64::MIN);
+        assert_eq!(crate::soft_f32::f32_to_i64_with_env(0xFFC00000, RoundingMode::NearEven, true, &mut env), i64::MAX);
+        assert_eq!(crate::soft_f32::f32_to_u64_with_env(0xFFC00000, RoundingMode::NearEven, true, &mut env), u64::MAX);
+
+        // The integer indefinite value
+        let mut env = Env::default();
+        env.set_int_overflow(IntOverflow::Indefinite);
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x4F000000, RoundingMode::NearEven, true, &mut env), i32::MIN);
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x7FC00000, RoundingMode::NearEven, true, &mut env), i32::MIN);
+        assert_eq!(crate::soft_f32::f32_to_u32_with_env(0xBF800000, RoundingMode::NearEven, true, &mut env), u32::MAX);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_to_int_exact() {
+        // 1.5 is inexact only when exact is set
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x3FC00000, RoundingMode::MinMag, false, &mut env), 1);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x3FC00000, RoundingMode::MinMag, true, &mut env), 1);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // 4 is exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_to_u128_with_env(0x40800000, RoundingMode::MinMag, true, &mut env), 4);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_int_to_f32() {
+        assert_eq!(crate::soft_f32::i32_to_f32(0), 0x00000000);
+        assert_eq!(crate::soft_f32::i32_to_f32(-4), 0xC0800000);
+        assert_eq!(crate::soft_f32::i32_to_f32(i32::MIN), 0xCF000000);
+        assert_eq!(crate::soft_f32::u32_to_f32(u32::MAX), 0x4F800000);
+        assert_eq!(crate::soft_f32::i64_to_f32(i64::MIN), 0xDF000000);
+        assert_eq!(crate::soft_f32::u64_to_f32(u64::MAX), 0x5F800000);
+        assert_eq!(crate::soft_f32::i128_to_f32(i128::MIN), 0xFF000000);
+
+        // u128::MAX rounds to 2^128, which overflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::u128_to_f32_with_env(u128::MAX, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f32::u128_to_f32_with_env(u128::MAX, &mut Env::new(RoundingMode::MinMag)), 0x7F7FFFFF);
+    }
+
+    #[test]
+    fn test_int_to_f32_rounding_mode() {
+        // 2^24 + 1 is a tie
+        assert_eq!(crate::soft_f32::u32_to_f32_with_env(0x01000001, &mut Env::new(RoundingMode::NearEven)), 0x4B800000);
+        assert_eq!(crate::soft_f32::u32_to_f32_with_env(0x01000001, &mut Env::new(RoundingMode::NearMaxMag)), 0x4B800001);
+        assert_eq!(crate::soft_f32::i64_to_f32_with_env(-0x01000001, &mut Env::new(RoundingMode::Min)), 0xCB800001);
+        assert_eq!(crate::soft_f32::i64_to_f32_with_env(-0x01000001, &mut Env::new(RoundingMode::Max)), 0xCB800000);
+
+        let mut env = Env::default();
+        crate::soft_f32::u32_to_f32_with_env(0x01000001, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_to_int_denormals_are_zero() {
+        // A subnormal operand is zero, which is exact
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_to_i32_with_env(0x00000001, RoundingMode::Max, true, &mut env), 0);
+        assert_eq!(crate::soft_f32::f32_to_u64_with_env(0x00000001, RoundingMode::Max, true, &mut env), 0);
+        assert_eq!(crate::soft_f32::f32_to_i128_with_env(0x80000001, RoundingMode::Min, true, &mut env), 0);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
@@ -0,0 +1,1116 @@
+use super::util::{
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_reduce_ln2,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_SQRT_2,
+};
+
+use super::wide::{
+    wide_exp,
+    wide_ln,
+    wide_sin_cos,
+    wide_round,
+    wide_is_ambiguous,
+    Wide,
+    WIDE_FRAC_BITS,
+    WIDE_LN2,
+    WIDE_PI_2,
+};
+
+use super::soft_f32_exp::f32_exp_fixed;
+use super::soft_f32_log::f32_ln_fixed;
+use super::soft_f32_trig::{ f32_sin_fixed, f32_cos_fixed, two_over_pi_bits };
+use super::soft_f32_pow::{ f32_powf_fixed, f32_odd_parts, POW_LIMIT };
+
+use crate::soft_f128::util::f128_count_leading_zero;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+// Correctly rounded elementary functions, in every rounding mode.
+//
+// The fixed-point evaluation of the other functions is rounded when it is far enough from every
+// rounding boundary for its error to matter, and the function is otherwise evaluated again with
+// wide fixed-point values (Ziv's strategy). As this happens for about one argument in 2^25,
+// these functions are as fast as the others on average.
+
+// The relative error of the fixed-point evaluation of e^x, ln(x), sin(x) and cos(x) is below
+// 2^-56, and that of x^y below 2^-47, with a margin
+const FIXED_ERROR_BITS: i32 = 50;
+const POW_ERROR_BITS: i32 = 42;
+
+// Whether the fixed-point v, within a relative error of 2^-err_bits of the exact value, is too
+// close to a rounding boundary to decide the rounding of the exact value. The boundaries are the
+// representable values and the midpoints between them, of which those of the subnormal and
+// overflowing results are a subset for a 24 bits significand.
+fn fixed_is_ambiguous(v: i128, err_bits: i32) -> bool {
+    let mag = v.unsigned_abs();
+    if mag == 0 {
+        // An exact zero
+        return false;
+    }
+
+    let lead_pos = 127 - f128_count_leading_zero(mag);
+    if lead_pos <= err_bits {
+        return true;
+    }
+
+    let grid = 1 << (lead_pos - 24);
+    let err = 1 << (lead_pos - err_bits);
+    let low = mag & (grid - 1);
+    low <= err || grid - low <= err
+}
+
+// Round the fixed-point evaluation, or the wide one when it is too close to a rounding boundary.
+// The result of the special cases is passed as an error.
+fn f32_round_cr<F>(fixed: Result<(i128, i32), u32>, err_bits: i32, wide: F, env: &mut Env) -> u32
+where
+    F: FnOnce() -> (Wide, i32),
+{
+    match fixed {
+        Ok((v, scale)) if !fixed_is_ambiguous(v, err_bits) => f32_round_fixed(v, scale, false, env),
+        Ok(_) => {
+            let (v, scale) = wide();
+            debug_assert!(!wide_is_ambiguous(v));
+            wide_round(v, scale, env)
+        }
+        Err(r) => r,
+    }
+}
+
+// v x 2^e, exactly unless low bits are shifted out
+fn wide_scale(v: Wide, e: i32) -> Wide {
+    if e >= 0 { v.shl(e as u32) } else { v.shr(-e as u32) }
+}
+
+// ln|A| = e x ln(2) + ln(m) with m in [1/sqrt(2), sqrt(2)], for a finite and nonzero A
+fn wide_ln_abs(a: u32) -> Wide {
+    let (_, mut m, mut e) = f32_to_fixed_parts(a);
+    if m > FIXED_SQRT_2 {
+        m >>= 1;
+        e += 1;
+    }
+
+    Wide::from_int(e as i64).mul(WIDE_LN2) + wide_ln(Wide::from_fixed(m))
+}
+
+// e^t as v x 2^n, where t = n x ln(2) + r and |r| <= 0.35, for |t| <= 512
+fn wide_exp_scaled(t: Wide) -> (Wide, i32) {
+    let (n, _) = fixed_reduce_ln2(t.to_fixed());
+    let r = t - Wide::from_int(n as i64).mul(WIDE_LN2);
+
+    (wide_exp(r), n)
+}
+
+// Reduce |A| = j x pi / 2 + r as f32_reduce_pi_2 does, with 256 bits of 2 / pi after the
+// first skip bits. The result is (j mod 4, r) for a normal A.
+fn wide_reduce_pi_2(a: u32) -> (i32, Wide) {
+    let (_, m, e) = f32_to_fixed_parts(a);
+    if a & 0x7FFFFFFF <= 0x3F490FDA {
+        // |A| < pi / 4
+        return (0, wide_scale(Wide::from_fixed(m), e));
+    }
+
+    // |A| = frac x 2^shift, and the bits of 2 / pi before the first skip ones are multiples
+    // of 4 in the product
+    let frac = (m >> (FIXED_FRAC_BITS - 23)) as u128;
+    let shift = e - 23;
+    let skip = (shift - 2).max(0);
+
+    // 320 bits product, with 256 + skip - shift fraction bits
+    let mut p = [0u64; 5];
+    for i in 0..4 {
+        let t = frac * two_over_pi_bits(skip + 64 * (3 - i as i32)) as u128 + p[i] as u128;
+        p[i] = t as u64;
+        p[i + 1] = (t >> 64) as u64;
+    }
+
+    // |A| x 2 / pi as a wide value
+    let dist = (256 + skip - shift - WIDE_FRAC_BITS) as usize;
+    let (words, bits) = (dist / 64, dist % 64);
+    let limb = |i: usize| if i < 5 { p[i] } else { 0 };
+    let mut limbs = [0u64; 4];
+    for (i, l) in limbs.iter_mut().enumerate() {
+        let hi = if bits == 0 { 0 } else { limb(i + words + 1) << (64 - bits) };
+        *l = (limb(i + words) >> bits) | hi;
+    }
+    let v = Wide::from_limbs(limbs);
+
+    // Round to the nearest j, leaving f in [-0.5, 0.5]
+    let j = (v + Wide::from_int(1).shr(1)).floor();
+    let f = v - Wide::from_int(j);
+
+    ((j & 3) as i32, f.mul(WIDE_PI_2))
+}
+
+fn f32_exp_wide(a: u32) -> (Wide, i32) {
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    let x = match e >= 8 {
+        // |A| >= 256, where the result overflows or underflows
+        true => Wide::from_int(256),
+        false => wide_scale(Wide::from_fixed(m), e),
+    };
+
+    wide_exp_scaled(if a_sign == 1 { -x } else { x })
+}
+
+fn f32_ln_wide(a: u32) -> (Wide, i32) {
+    (wide_ln_abs(a), 0)
+}
+
+fn f32_sin_wide(a: u32) -> (Wide, i32) {
+    let (j, r) = wide_reduce_pi_2(a);
+    let (sin_r, cos_r) = wide_sin_cos(r);
+    let v = match j {
+        0 => sin_r,
+        1 => cos_r,
+        2 => -sin_r,
+        _ => -cos_r,
+    };
+
+    (if a >> 31 == 1 { -v } else { v }, 0)
+}
+
+fn f32_cos_wide(a: u32) -> (Wide, i32) {
+    let (j, r) = wide_reduce_pi_2(a);
+    let (sin_r, cos_r) = wide_sin_cos(r);
+    let v = match j {
+        0 => cos_r,
+        1 => -sin_r,
+        2 => -cos_r,
+        _ => sin_r,
+    };
+
+    (v, 0)
+}
+
+fn f32_powf_wide(a: u32, b: u32) -> (Wide, i32) {
+    let (y_sign, y_m, y_e) = f32_odd_parts(b);
+
+    // Y x ln|X|, limited to 512 as in f32_powf
+    let s = wide_ln_abs(a).mul_int(y_m as u64);
+    let t = match y_e >= 0 {
+        true if y_e > 40 || s.to_fixed().abs() > POW_LIMIT >> y_e => match s.is_negative() {
+            true => Wide::from_int(-512),
+            false => Wide::from_int(512),
+        },
+        _ => wide_scale(s, y_e),
+    };
+    // A huge Y which is not an integer overflows or underflows all the same
+    let t = match t.floor() {
+        n if n >= 512 => Wide::from_int(512),
+        n if n < -512 => Wide::from_int(-512),
+        _ => t,
+    };
+    let (v, n) = wide_exp_scaled(if y_sign == 1 { -t } else { t });
+
+    // The result is negative only for a negative X and an odd Y
+    (if y_e == 0 && a >> 31 == 1 { -v } else { v }, n)
+}
+
+pub fn f32_exp_cr(a: u32) -> u32 {
+    with_thread_env(|env| f32_exp_cr_with_env(a, env))
+}
+
+// Natural exponential, correctly rounded
+pub fn f32_exp_cr_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_exp_cr_unflushed(a, env), env)
+}
+
+fn f32_exp_cr_unflushed(a: u32, env: &mut Env) -> u32 {
+    f32_round_cr(f32_exp_fixed(a, env), FIXED_ERROR_BITS, || f32_exp_wide(a), env)
+}
+
+pub fn f32_ln_cr(a: u32) -> u32 {
+    with_thread_env(|env| f32_ln_cr_with_env(a, env))
+}
+
+// Natural logarithm, correctly rounded
+pub fn f32_ln_cr_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_ln_cr_unflushed(a, env), env)
+}
+
+fn f32_ln_cr_unflushed(a: u32, env: &mut Env) -> u32 {
+    f32_round_cr(f32_ln_fixed(a, env), FIXED_ERROR_BITS, || f32_ln_wide(a), env)
+}
+
+pub fn f32_sin_cr(a: u32) -> u32 {
+    with_thread_env(|env| f32_sin_cr_with_env(a, env))
+}
+
+// Sine, correctly rounded
+pub fn f32_sin_cr_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_sin_cr_unflushed(a, env), env)
+}
+
+fn f32_sin_cr_unflushed(a: u32, env: &mut Env) -> u32 {
+    f32_round_cr(f32_sin_fixed(a, env), FIXED_ERROR_BITS, || f32_sin_wide(a), env)
+}
+
+pub fn f32_cos_cr(a: u32) -> u32 {
+    with_thread_env(|env| f32_cos_cr_with_env(a, env))
+}
+
+// Cosine, correctly rounded
+pub fn f32_cos_cr_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_cos_cr_unflushed(a, env), env)
+}
+
+fn f32_cos_cr_unflushed(a: u32, env: &mut Env) -> u32 {
+    f32_round_cr(f32_cos_fixed(a, env), FIXED_ERROR_BITS, || f32_cos_wide(a), env)
+}
+
+pub fn f32_powf_cr(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_powf_cr_with_env(a, b, env))
+}
+
+// A to the power of B with the special cases of f32_powf, correctly rounded
+pub fn f32_powf_cr_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| f32_powf_cr_unflushed(a, b, env), env)
+}
+
+fn f32_powf_cr_unflushed(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_round_cr(f32_powf_fixed(a, b, env), POW_ERROR_BITS, || f32_powf_wide(a, b), env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    use super::*;
+
+    const MODES: [RoundingMode; 6] = [
+        RoundingMode::NearEven, RoundingMode::MinMag, RoundingMode::Min,
+        RoundingMode::Max, RoundingMode::NearMaxMag, RoundingMode::Odd,
+    ];
+
+    // An independent reference, which shares no code with the crate: binary floating-point values
+    // with up to 704 significant bits, where exp, ln, sin and cos are evaluated by their series
+    mod reference {
+        use std::cmp::Ordering;
+        use std::sync::OnceLock;
+
+        use crate::soft_float::{ ExceptionFlags, RoundingMode };
+
+        use super::MODES;
+
+        // The significant bits of a value
+        const P: u32 = 704;
+
+        // An unsigned integer, with little-endian limbs and no leading zero limb
+        #[derive(Clone, Debug)]
+        struct Nat(Vec<u64>);
+
+        impl Nat {
+            fn from_u128(v: u128) -> Nat {
+                Nat(vec![v as u64, (v >> 64) as u64]).trim()
+            }
+
+            fn trim(mut self) -> Nat {
+                while self.0.last() == Some(&0) {
+                    self.0.pop();
+                }
+                self
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0.is_empty()
+            }
+
+            fn low_limb(&self) -> u64 {
+                self.0.first().copied().unwrap_or(0)
+            }
+
+            fn bits(&self) -> u32 {
+                self.0.last().map_or(0, |&top| self.0.len() as u32 * 64 - top.leading_zeros())
+            }
+
+            fn shl(&self, n: u32) -> Nat {
+                let (limbs, bits) = ((n / 64) as usize, n % 64);
+                let mut r = vec![0; limbs + self.0.len() + 1];
+                for (i, &l) in self.0.iter().enumerate() {
+                    r[i + limbs] |= l << bits;
+                    if bits != 0 {
+                        r[i + limbs + 1] |= l >> (64 - bits);
+                    }
+                }
+                Nat(r).trim()
+            }
+
+            fn shr(&self, n: u32) -> Nat {
+                let (limbs, bits) = ((n / 64) as usize, n % 64);
+                let r = (limbs..self.0.len()).map(|i| match (bits, self.0.get(i + 1)) {
+                    (0, _) | (_, None) => self.0[i] >> bits,
+                    (_, Some(&hi)) => (self.0[i] >> bits) | (hi << (64 - bits)),
+                }).collect();
+                Nat(r).trim()
+            }
+
+            // The low N bits
+            fn low(&self, n: u32) -> Nat {
+                let mut r: Vec<u64> = self.0.iter().copied().take(n.div_ceil(64) as usize).collect();
+                if !n.is_multiple_of(64) && r.len() == n.div_ceil(64) as usize {
+                    *r.last_mut().unwrap() &= (1 << (n % 64)) - 1;
+                }
+                Nat(r).trim()
+            }
+
+            fn add(&self, other: &Nat) -> Nat {
+                let mut r = Vec::with_capacity(self.0.len().max(other.0.len()) + 1);
+                let mut carry = 0;
+                for i in 0..self.0.len().max(other.0.len()) {
+                    let s = self.0.get(i).copied().unwrap_or(0) as u128 + other.0.get(i).copied().unwrap_or(0) as u128 + carry;
+                    r.push(s as u64);
+                    carry = s >> 64;
+                }
+                r.push(carry as u64);
+                Nat(r).trim()
+            }
+
+            // SELF - OTHER, where OTHER is not above SELF
+            fn sub(&self, other: &Nat) -> Nat {
+                let mut r = Vec::with_capacity(self.0.len());
+                let mut borrow = 0;
+                for (i, &l) in self.0.iter().enumerate() {
+                    let d = l as i128 - other.0.get(i).copied().unwrap_or(0) as i128 - borrow;
+                    r.push(d as u64);
+                    borrow = (d < 0) as i128;
+                }
+                Nat(r).trim()
+            }
+
+            fn mul(&self, other: &Nat) -> Nat {
+                let mut r = vec![0; self.0.len() + other.0.len()];
+                for (i, &a) in self.0.iter().enumerate() {
+                    let mut carry = 0;
+                    for (j, &b) in other.0.iter().enumerate() {
+                        let t = a as u128 * b as u128 + r[i + j] as u128 + carry;
+                        r[i + j] = t as u64;
+                        carry = t >> 64;
+                    }
+                    r[i + other.0.len()] = carry as u64;
+                }
+                Nat(r).trim()
+            }
+
+            fn div_small(&self, d: u64) -> Nat {
+                let mut r = vec![0; self.0.len()];
+                let mut rem = 0;
+                for (q, &l) in r.iter_mut().zip(self.0.iter()).rev() {
+                    let cur = (rem << 64) | l as u128;
+                    *q = (cur / d as u128) as u64;
+                    rem = cur % d as u128;
+                }
+                Nat(r).trim()
+            }
+
+            fn cmp(&self, other: &Nat) -> Ordering {
+                self.0.len().cmp(&other.0.len()).then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+            }
+
+            // |SELF - OTHER| <= TOL
+            fn is_near(&self, other: &Nat, tol: &Nat) -> bool {
+                let d = match self.cmp(other) {
+                    Ordering::Less => other.sub(self),
+                    _ => self.sub(other),
+                };
+                d.cmp(tol) != Ordering::Greater
+            }
+        }
+
+        // The value -1^NEG x M x 2^E, where M is truncated to P bits
+        #[derive(Clone, Debug)]
+        pub(super) struct Float {
+            neg: bool,
+            m: Nat,
+            e: i64,
+        }
+
+        impl Float {
+            fn new(neg: bool, m: Nat, e: i64) -> Float {
+                match m.bits().saturating_sub(P) {
+                    0 => Float { neg, m, e },
+                    d => Float { neg, m: m.shr(d), e: e + d as i64 },
+                }
+            }
+
+            fn from_int(v: i64) -> Float {
+                Float::new(v < 0, Nat::from_u128(v.unsigned_abs() as u128), 0)
+            }
+
+            // A finite Float32
+            pub(super) fn from_f32(a: u32) -> Float {
+                let (m, e) = match (a >> 23) & 0xFF {
+                    0 => (a & 0x007FFFFF, -149),
+                    exp => ((a & 0x007FFFFF) | 0x00800000, exp as i64 - 150),
+                };
+                Float::new(a >> 31 != 0, Nat::from_u128(m as u128), e)
+            }
+
+            // A finite Float64
+            fn from_f64(v: f64) -> Float {
+                let bits = v.to_bits();
+                let (m, e) = match (bits >> 52) & 0x7FF {
+                    0 => (bits & 0x000FFFFFFFFFFFFF, -1074),
+                    exp => ((bits & 0x000FFFFFFFFFFFFF) | 0x0010000000000000, exp as i64 - 1075),
+                };
+                Float::new(bits >> 63 != 0, Nat::from_u128(m as u128), e)
+            }
+
+            // An approximation, for values which are neither huge nor tiny
+            fn to_f64(&self) -> f64 {
+                let shift = self.m.bits().saturating_sub(64);
+                let v = self.m.shr(shift).low_limb() as f64 * 2f64.powi((self.e + shift as i64) as i32);
+                if self.neg { -v } else { v }
+            }
+
+            fn is_zero(&self) -> bool {
+                self.m.is_zero()
+            }
+
+            // The exponent of the most significant bit
+            fn exponent(&self) -> i64 {
+                self.e + self.m.bits() as i64 - 1
+            }
+
+            fn neg(&self) -> Float {
+                Float { neg: !self.neg, ..self.clone() }
+            }
+
+            fn mul_pow2(&self, n: i64) -> Float {
+                Float { e: self.e + n, ..self.clone() }
+            }
+
+            fn add(&self, other: &Float) -> Float {
+                // An operand far below the precision of the other one does not matter
+                if self.is_zero() || other.exponent() > self.exponent() + 2 * P as i64 {
+                    return other.clone();
+                }
+                if other.is_zero() || self.exponent() > other.exponent() + 2 * P as i64 {
+                    return self.clone();
+                }
+
+                let e = self.e.min(other.e);
+                let a = self.m.shl((self.e - e) as u32);
+                let b = other.m.shl((other.e - e) as u32);
+                match (self.neg == other.neg, a.cmp(&b)) {
+                    (true, _) => Float::new(self.neg, a.add(&b), e),
+                    (false, Ordering::Less) => Float::new(other.neg, b.sub(&a), e),
+                    (false, _) => Float::new(self.neg, a.sub(&b), e),
+                }
+            }
+
+            fn sub(&self, other: &Float) -> Float {
+                self.add(&other.neg())
+            }
+
+            fn mul(&self, other: &Float) -> Float {
+                Float::new(self.neg != other.neg, self.m.mul(&other.m), self.e + other.e)
+            }
+
+            fn div_small(&self, d: u64) -> Float {
+                let shift = P + 64 - self.m.bits();
+                Float::new(self.neg, self.m.shl(shift).div_small(d), self.e - shift as i64)
+            }
+
+            fn recip(&self) -> Float {
+                // Newton iterations x (2 - a x), each doubling the 53 bits of the approximation
+                let two = Float::from_int(2);
+                (0..5).fold(Float::from_f64(1.0 / self.to_f64()), |x, _| x.mul(&two.sub(&self.mul(&x))))
+            }
+
+            // The nearest integer modulo 4, and the rest in [-1/2, 1/2], of a positive value
+            fn split_nearest(&self) -> (u64, Float) {
+                if self.e >= 0 {
+                    return (self.m.shl(self.e as u32).low_limb() & 3, Float::from_int(0));
+                }
+                let n = -self.e as u32;
+                let j = self.m.shr(n).low_limb() & 3;
+                let rest = self.m.low(n);
+                match rest.bits() == n {
+                    true => (j + 1, Float::new(true, Nat::from_u128(1).shl(n).sub(&rest), self.e)),
+                    false => (j, Float::new(false, rest, self.e)),
+                }
+            }
+        }
+
+        // atanh(Z), or atan(Z) with ALTERNATE, by their series
+        fn atanh_series(z: &Float, alternate: bool) -> Float {
+            let z2 = if alternate { z.mul(z).neg() } else { z.mul(z) };
+            let mut power = z.clone();
+            let mut sum = z.clone();
+            for k in 1.. {
+                power = power.mul(&z2);
+                let term = power.div_small(2 * k + 1);
+                if term.is_zero() || term.exponent() < sum.exponent() - P as i64 - 8 {
+                    break;
+                }
+                sum = sum.add(&term);
+            }
+            sum
+        }
+
+        fn ln2() -> &'static Float {
+            static LN2: OnceLock<Float> = OnceLock::new();
+            LN2.get_or_init(|| atanh_series(&Float::from_int(1).div_small(3), false).mul_pow2(1))
+        }
+
+        fn pi() -> &'static Float {
+            // Machin's formula, pi = 16 atan(1/5) - 4 atan(1/239)
+            static PI: OnceLock<Float> = OnceLock::new();
+            PI.get_or_init(|| {
+                let atan_5 = atanh_series(&Float::from_int(1).div_small(5), true);
+                let atan_239 = atanh_series(&Float::from_int(1).div_small(239), true);
+                atan_5.mul_pow2(4).sub(&atan_239.mul_pow2(2))
+            })
+        }
+
+        // e^T, for |T| below 2^10
+        fn exp_series(t: &Float) -> Float {
+            // e^T = 2^k e^r, with |r| about ln(2) / 2 at most
+            let k = (t.to_f64() / std::f64::consts::LN_2).round() as i64;
+            let r = t.sub(&ln2().mul(&Float::from_int(k)));
+            let mut term = Float::from_int(1);
+            let mut sum = term.clone();
+            for n in 1.. {
+                term = term.mul(&r).div_small(n);
+                if term.is_zero() || term.exponent() < -(P as i64) - 8 {
+                    break;
+                }
+                sum = sum.add(&term);
+            }
+            sum.mul_pow2(k)
+        }
+
+        // ln(X), for a positive X
+        fn ln_series(x: &Float) -> Float {
+            // X = 2^k f with f in [sqrt(1/2), sqrt(2)), and ln(f) = 2 atanh((f - 1) / (f + 1))
+            let mut k = x.exponent();
+            let mut f = x.mul_pow2(-k);
+            if f.to_f64() > std::f64::consts::SQRT_2 {
+                f = f.mul_pow2(-1);
+                k += 1;
+            }
+            let one = Float::from_int(1);
+            let z = f.sub(&one).mul(&f.add(&one).recip());
+            atanh_series(&z, false).mul_pow2(1).add(&ln2().mul(&Float::from_int(k)))
+        }
+
+        // (sin(X), cos(X)), for a positive X
+        fn sin_cos_series(x: &Float) -> (Float, Float) {
+            // X = (j + r) x pi / 2, where |r| <= 1/2
+            let pi_2 = pi().mul_pow2(-1);
+            let (j, r) = x.mul(&pi_2.recip()).split_nearest();
+            let t = r.mul(&pi_2);
+            let t2 = t.mul(&t).neg();
+
+            let series = |first: Float, k: u64| {
+                let mut term = first.clone();
+                let mut sum = first;
+                for n in (k..).step_by(2) {
+                    term = term.mul(&t2).div_small(n * (n + 1));
+                    if term.is_zero() || term.exponent() < sum.exponent() - P as i64 - 8 {
+                        break;
+                    }
+                    sum = sum.add(&term);
+                }
+                sum
+            };
+            let (sin, cos) = (series(t.clone(), 2), series(Float::from_int(1), 1));
+
+            match j {
+                0 => (sin, cos),
+                1 => (cos, sin.neg()),
+                2 => (sin.neg(), cos.neg()),
+                _ => (cos.neg(), sin),
+            }
+        }
+
+        // Where the bits below the precision are: zero, below half an ULP, half or above
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Rest {
+            Zero,
+            Below,
+            Half,
+            Above,
+        }
+
+        // The significand of V at the precision 2^Q and the rest. The rest is moved to a rounding
+        // boundary within TOL bits below V when SNAP, otherwise it gives None.
+        fn truncate(v: &Float, q: i64, tol: u32, snap: bool) -> Option<(u64, Rest)> {
+            let s = q - v.e;
+            if s <= 0 {
+                return Some((v.m.shl(-s as u32).low_limb(), Rest::Zero));
+            }
+            let s = s as u32;
+            let m = v.m.shr(s).low_limb();
+            let rest = v.m.low(s);
+            let ulp = Nat::from_u128(1).shl(s);
+            let half = ulp.shr(1);
+            let tol = v.m.shr(tol);
+
+            let (m, rest, is_snapped) = match () {
+                _ if rest.is_near(&Nat(vec![]), &tol) => (m, Rest::Zero, true),
+                _ if rest.is_near(&half, &tol) => (m, Rest::Half, true),
+                _ if rest.is_near(&ulp, &tol) => (m + 1, Rest::Zero, true),
+                _ if rest.cmp(&half) == Ordering::Less => (m, Rest::Below, false),
+                _ => (m, Rest::Above, false),
+            };
+            match is_snapped && !snap {
+                true => None,
+                false => Some((m, rest)),
+            }
+        }
+
+        fn round_significand(m: u64, rest: Rest, neg: bool, mode: RoundingMode) -> u64 {
+            let is_inexact = rest != Rest::Zero;
+            let is_up = match mode {
+                RoundingMode::NearEven => rest == Rest::Above || (rest == Rest::Half && m & 1 != 0),
+                RoundingMode::NearMaxMag => rest == Rest::Above || rest == Rest::Half,
+                RoundingMode::MinMag => false,
+                RoundingMode::Min => neg && is_inexact,
+                RoundingMode::Max => !neg && is_inexact,
+                RoundingMode::Odd => return m | is_inexact as u64,
+            };
+            m + is_up as u64
+        }
+
+        // V rounded to Float32 in MODE with its flags, tininess being detected after rounding, or
+        // None when it is within TOL bits below V of a rounding boundary and not SNAP
+        fn round(v: &Float, mode: RoundingMode, tol: u32, snap: bool) -> Option<(u32, ExceptionFlags)> {
+            let sign = (v.neg as u32) << 31;
+            if v.is_zero() {
+                return Some((sign, ExceptionFlags::empty()));
+            }
+
+            let e = v.exponent();
+            let mut q = (e - 23).max(-149);
+            let (m, rest) = truncate(v, q, tol, snap)?;
+            let mut m = round_significand(m, rest, v.neg, mode);
+            if m == 1 << 24 {
+                m >>= 1;
+                q += 1;
+            }
+
+            let mut flags = ExceptionFlags::empty();
+            if rest != Rest::Zero {
+                flags |= ExceptionFlags::INEXACT;
+                // Tiny when below 2^-126 rounded to 24 bits with an unbounded exponent
+                if e < -126 {
+                    let (m_24, rest_24) = truncate(v, e - 23, tol, snap)?;
+                    if e < -127 || round_significand(m_24, rest_24, v.neg, mode) < 1 << 24 {
+                        flags |= ExceptionFlags::UNDERFLOW;
+                    }
+                }
+            }
+
+            if q > 104 {
+                let (max, inf) = (sign | 0x7F7FFFFF, sign | 0x7F800000);
+                let r = match mode {
+                    RoundingMode::NearEven | RoundingMode::NearMaxMag => inf,
+                    RoundingMode::MinMag | RoundingMode::Odd => max,
+                    RoundingMode::Min => if v.neg { inf } else { max },
+                    RoundingMode::Max => if v.neg { max } else { inf },
+                };
+                return Some((r, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT));
+            }
+            match m < 1 << 23 {
+                // Subnormal or zero
+                true => Some((sign | m as u32, flags)),
+                false => Some((sign | (((q + 150) as u32) << 23 | (m as u32 & 0x007FFFFF)), flags)),
+            }
+        }
+
+        // The rounding of a real value in every mode, from its host f64 evaluation when it is far
+        // enough from every rounding boundary for the error of the host, otherwise from VALUE
+        fn round_value<F: FnOnce() -> Float>(host: f64, value: F) -> Vec<(u32, ExceptionFlags)> {
+            // Beyond the range of Float64, the value is also far beyond the range of Float32, and
+            // rounds like any huge or tiny value away from a rounding boundary
+            let host = match host.abs() {
+                v if v == f64::INFINITY => (f64::MAX * 0.6).copysign(host),
+                v if v < f64::MIN_POSITIVE => (f64::MIN_POSITIVE * 1.6).copysign(host),
+                _ => host,
+            };
+            let rounded: Option<Vec<_>> = MODES.iter().map(|&mode| round(&Float::from_f64(host), mode, 45, false)).collect();
+            rounded.unwrap_or_else(|| {
+                let v = value();
+                MODES.iter().map(|&mode| round(&v, mode, 400, true).unwrap()).collect()
+            })
+        }
+
+        // NaN results, which match any NaN
+        pub(super) const NAN: u32 = 0x7FC00000;
+
+        fn is_signaling_nan(a: u32) -> bool {
+            (a & 0x7FC00000) == 0x7F800000 && (a & 0x003FFFFF) != 0
+        }
+
+        // The special cases of a NaN argument
+        fn nan_arg(a: u32) -> (u32, ExceptionFlags) {
+            match is_signaling_nan(a) {
+                true => (NAN, ExceptionFlags::INVALID),
+                false => (NAN, ExceptionFlags::empty()),
+            }
+        }
+
+        // An exact special case, in every mode
+        fn special((r, flags): (u32, ExceptionFlags)) -> Vec<(u32, ExceptionFlags)> {
+            vec![(r, flags); MODES.len()]
+        }
+
+        pub(super) fn exp(a: u32) -> Vec<(u32, ExceptionFlags)> {
+            let x = f32::from_bits(a);
+            match x {
+                _ if x.is_nan() => special(nan_arg(a)),
+                _ if x == f32::INFINITY => special((0x7F800000, ExceptionFlags::empty())),
+                _ if x == f32::NEG_INFINITY => special((0x00000000, ExceptionFlags::empty())),
+                _ if x == 0.0 => special((0x3F800000, ExceptionFlags::empty())),
+                // Overflow or underflow all the same above 2^10
+                _ => round_value((x as f64).exp(), || exp_series(&Float::from_f64(x.clamp(-1024.0, 1024.0) as f64))),
+            }
+        }
+
+        pub(super) fn ln(a: u32) -> Vec<(u32, ExceptionFlags)> {
+            let x = f32::from_bits(a);
+            match x {
+                _ if x.is_nan() => special(nan_arg(a)),
+                _ if x == 0.0 => special((0xFF800000, ExceptionFlags::DIVIDE_BY_ZERO)),
+                _ if x < 0.0 => special((NAN, ExceptionFlags::INVALID)),
+                _ if x == f32::INFINITY => special((0x7F800000, ExceptionFlags::empty())),
+                _ => round_value((x as f64).ln(), || ln_series(&Float::from_f32(a))),
+            }
+        }
+
+        pub(super) fn sin(a: u32) -> Vec<(u32, ExceptionFlags)> {
+            let x = f32::from_bits(a);
+            match x {
+                _ if x.is_nan() => special(nan_arg(a)),
+                _ if x.is_infinite() => special((NAN, ExceptionFlags::INVALID)),
+                _ if x == 0.0 => special((a, ExceptionFlags::empty())),
+                _ => round_value((x as f64).sin(), || {
+                    let (sin, _) = sin_cos_series(&Float::from_f32(a & 0x7FFFFFFF));
+                    if x < 0.0 { sin.neg() } else { sin }
+                }),
+            }
+        }
+
+        pub(super) fn cos(a: u32) -> Vec<(u32, ExceptionFlags)> {
+            let x = f32::from_bits(a);
+            match x {
+                _ if x.is_nan() => special(nan_arg(a)),
+                _ if x.is_infinite() => special((NAN, ExceptionFlags::INVALID)),
+                _ if x == 0.0 => special((0x3F800000, ExceptionFlags::empty())),
+                _ => round_value((x as f64).cos(), || sin_cos_series(&Float::from_f32(a & 0x7FFFFFFF)).1),
+            }
+        }
+
+        // The special cases of C99 Annex F, where a signaling NaN is invalid even when the
+        // result would be 1
+        pub(super) fn powf(a: u32, b: u32) -> Vec<(u32, ExceptionFlags)> {
+            let (x, y) = (f32::from_bits(a), f32::from_bits(b));
+            let is_int = y.fract() == 0.0;
+            let is_odd = is_int && y % 2.0 != 0.0;
+            let odd_sign = if is_odd { a & 0x80000000 } else { 0 };
+            match () {
+                _ if is_signaling_nan(a) || is_signaling_nan(b) => special((NAN, ExceptionFlags::INVALID)),
+                _ if y == 0.0 || x == 1.0 => special((0x3F800000, ExceptionFlags::empty())),
+                _ if x.is_nan() || y.is_nan() => special((NAN, ExceptionFlags::empty())),
+                _ if y.is_infinite() && x.abs() == 1.0 => special((0x3F800000, ExceptionFlags::empty())),
+                _ if y.is_infinite() && (x.abs() > 1.0) == (y > 0.0) => special((0x7F800000, ExceptionFlags::empty())),
+                _ if y.is_infinite() => special((0x00000000, ExceptionFlags::empty())),
+                _ if x == 0.0 && y < 0.0 => special((odd_sign | 0x7F800000, ExceptionFlags::DIVIDE_BY_ZERO)),
+                _ if x == 0.0 => special((odd_sign, ExceptionFlags::empty())),
+                _ if x.is_infinite() && y < 0.0 => special((odd_sign, ExceptionFlags::empty())),
+                _ if x.is_infinite() => special((odd_sign | 0x7F800000, ExceptionFlags::empty())),
+                _ if x < 0.0 && !is_int => special((NAN, ExceptionFlags::INVALID)),
+                _ => {
+                    let neg = x < 0.0 && is_odd;
+                    let host = (x.abs() as f64).powf(y as f64);
+                    round_value(if neg { -host } else { host }, || {
+                        // Overflow or underflow all the same above 2^10
+                        let t = Float::from_f32(b).mul(&ln_series(&Float::from_f32(a & 0x7FFFFFFF)));
+                        let t = match t.to_f64() {
+                            v if v.abs() > 1024.0 => Float::from_f64(v.clamp(-1024.0, 1024.0)),
+                            _ => t,
+                        };
+                        let v = exp_series(&t);
+                        if neg { v.neg() } else { v }
+                    })
+                }
+            }
+        }
+    }
+
+    // Check a correctly rounded function in every rounding mode against the reference, where a
+    // NaN matches any NaN
+    fn check_cr<C: Fn(&mut Env) -> u32>(args: &[u32], cr: C, reference: Vec<(u32, ExceptionFlags)>) {
+        for (&mode, &(expected, flags)) in MODES.iter().zip(reference.iter()) {
+            let mut env = Env::new(mode);
+            let r = cr(&mut env);
+            let r = if crate::soft_f32::f32_is_nan(r) && expected == reference::NAN { reference::NAN } else { r };
+            assert_eq!((r, env.flags()), (expected, flags), "{:08X?} {:?}", args, mode);
+        }
+    }
+
+    // Check every step-th Float32, in as many threads as the machine runs
+    fn check_cr_all<T: Fn(u32) + Copy + Send + 'static>(step: u64, check: T) {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get()) as u64;
+        let handles: Vec<_> = (0..threads).map(|t| {
+            std::thread::spawn(move || {
+                let mut a = t * step;
+                while a < 1 << 32 {
+                    check(a as u32);
+                    a += threads * step;
+                }
+            })
+        }).collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    fn check_exp_cr(a: u32) {
+        check_cr(&[a], |env| f32_exp_cr_with_env(a, env), reference::exp(a));
+    }
+
+    fn check_ln_cr(a: u32) {
+        check_cr(&[a], |env| f32_ln_cr_with_env(a, env), reference::ln(a));
+    }
+
+    fn check_sin_cr(a: u32) {
+        check_cr(&[a], |env| f32_sin_cr_with_env(a, env), reference::sin(a));
+    }
+
+    fn check_cos_cr(a: u32) {
+        check_cr(&[a], |env| f32_cos_cr_with_env(a, env), reference::cos(a));
+    }
+
+    fn check_powf_cr(a: u32, b: u32) {
+        check_cr(&[a, b], |env| f32_powf_cr_with_env(a, b, env), reference::powf(a, b));
+    }
+
+    // The exponents of Y for X^Y: integers, halves, thirds, and a few with no exact results
+    const POWF_EXPONENTS: [u32; 17] = [
+        0x3F000000, 0xBF000000, 0x40000000, 0xC0000000, 0x40400000, 0x3FC00000, 0xBFC00000,
+        0x40200000, 0x40F00000, 0x42C90000, 0x3EAAAAAB, 0xBEAAAAAB, 0x40490FDB, 0x3DCCCCCD,
+        0xC2306666, 0x35800000, 0x49742408,
+    ];
+
+    // A xorshift generator, for the arguments of the tests
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    // The exponents of Y for X^Y which depend on X: a random one, a random small integer, and
+    // those of the overflow and underflow thresholds, with their neighbors and nearest integer
+    fn powf_exponents_of(a: u32) -> Vec<u32> {
+        let mut state = (a as u64).wrapping_mul(0x9E3779B97F4A7C15) | 1;
+        let r = xorshift(&mut state);
+        let mut exponents = vec![r as u32, ((((r >> 32) % 64) as i32 - 32) as f32).to_bits()];
+
+        let log2 = (f32::from_bits(a).abs() as f64).log2();
+        for &t in [128.0, -126.0, -149.0, -150.0].iter() {
+            let y = t / log2;
+            if y.is_finite() && y.abs() < f32::MAX as f64 {
+                let b = (y as f32).to_bits();
+                exponents.extend([b.wrapping_sub(1), b, b.wrapping_add(1), (y.round() as f32).to_bits()].iter());
+            }
+        }
+        exponents
+    }
+
+    fn check_powf_cr_of(a: u32) {
+        for &b in POWF_EXPONENTS.iter().chain(powf_exponents_of(a).iter()) {
+            check_powf_cr(a, b);
+        }
+    }
+
+    #[test]
+    fn test_f32_cr_values() {
+        // e^1 = 2.7182817
+        assert_eq!(crate::soft_f32::f32_exp_cr(0x3F800000), 0x402DF854);
+        // ln(58037908) = 17.876608, where f64 rounds twice to 17.876606
+        assert_eq!(crate::soft_f32::f32_ln_cr(0x4C5D65A5), 0x418F034B);
+        // sin(1e30) = -0.79116344
+        assert_eq!(crate::soft_f32::f32_sin_cr(0x7149F2CA), 0xBF4A89B0);
+        // cos(1) = 0.5403023
+        assert_eq!(crate::soft_f32::f32_cos_cr(0x3F800000), 0x3F0A5140);
+        // 3.1415927^3.1415927 = 36.462166
+        assert_eq!(crate::soft_f32::f32_powf_cr(0x40490FDB, 0x40490FDB), 0x4211D942);
+    }
+
+    #[test]
+    fn test_f32_cr_rounding_mode() {
+        // The results below are too close to a rounding boundary for the fixed-point evaluation
+        // ln(5.3e-38) = -85.43784
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x01D5ABC5, &mut Env::new(RoundingMode::NearEven)), 0xC2AAE02D);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x01D5ABC5, &mut Env::new(RoundingMode::MinMag)), 0xC2AAE02C);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x01D5ABC5, &mut Env::new(RoundingMode::Min)), 0xC2AAE02D);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x01D5ABC5, &mut Env::new(RoundingMode::Max)), 0xC2AAE02C);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x01D5ABC5, &mut Env::new(RoundingMode::NearMaxMag)), 0xC2AAE02D);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x01D5ABC5, &mut Env::new(RoundingMode::Odd)), 0xC2AAE02D);
+        // ln(3.5e-17) = -37.876774
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x2423C085, &mut Env::new(RoundingMode::NearEven)), 0xC21781D1);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x2423C085, &mut Env::new(RoundingMode::MinMag)), 0xC21781D0);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x2423C085, &mut Env::new(RoundingMode::Min)), 0xC21781D1);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x2423C085, &mut Env::new(RoundingMode::Max)), 0xC21781D0);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x2423C085, &mut Env::new(RoundingMode::NearMaxMag)), 0xC21781D1);
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x2423C085, &mut Env::new(RoundingMode::Odd)), 0xC21781D1);
+        // sin(2^-27) is just below 2^-27
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x32000000, &mut Env::new(RoundingMode::NearEven)), 0x32000000);
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x32000000, &mut Env::new(RoundingMode::MinMag)), 0x31FFFFFF);
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x32000000, &mut Env::new(RoundingMode::Min)), 0x31FFFFFF);
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x32000000, &mut Env::new(RoundingMode::Max)), 0x32000000);
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x32000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x32000000);
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x32000000, &mut Env::new(RoundingMode::Odd)), 0x31FFFFFF);
+        // cos(-2^-27) is just below 1
+        assert_eq!(crate::soft_f32::f32_cos_cr_with_env(0xB2000001, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_cos_cr_with_env(0xB2000001, &mut Env::new(RoundingMode::MinMag)), 0x3F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_cos_cr_with_env(0xB2000001, &mut Env::new(RoundingMode::Min)), 0x3F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_cos_cr_with_env(0xB2000001, &mut Env::new(RoundingMode::Max)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_cos_cr_with_env(0xB2000001, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_cos_cr_with_env(0xB2000001, &mut Env::new(RoundingMode::Odd)), 0x3F7FFFFF);
+        // 2^(2^-100) is just above 1
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40000000, 0x0D800000, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40000000, 0x0D800000, &mut Env::new(RoundingMode::MinMag)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40000000, 0x0D800000, &mut Env::new(RoundingMode::Min)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40000000, 0x0D800000, &mut Env::new(RoundingMode::Max)), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40000000, 0x0D800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40000000, 0x0D800000, &mut Env::new(RoundingMode::Odd)), 0x3F800001);
+    }
+
+    #[test]
+    fn test_f32_cr_exception_flags() {
+        // e^100 overflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp_cr_with_env(0x42C80000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // e^-104 underflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp_cr_with_env(0xC2D00000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // ln(1) = 0 is exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x3F800000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // ln(0) = -Inf
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_ln_cr_with_env(0x00000000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // sin(Inf) is invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_sin_cr_with_env(0x7F800000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // 3^-2 = 0.11111111
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powf_cr_with_env(0x40400000, 0xC0000000, &mut env), 0x3DE38E39);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // Huge exponents which are not integers, with bases above and below 1
+        let huge = [
+            // 5.19e17^3084170.3 and 3.7e-21^-633368.6 overflow
+            (0x5CE67B78, 0x4A3C3E29, 0x7F800000, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT),
+            (0x1D8BC834, 0xC91AA18A, 0x7F800000, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT),
+            // 2.9e-37^150010.28 and 1.5^-3084170.3 underflow
+            (0x02C3B54F, 0x48127E92, 0x00000000, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT),
+            (0x3FC00000, 0xCA3C3E29, 0x00000000, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT),
+        ];
+        for &(a, b, r, flags) in huge.iter() {
+            let mut env = Env::default();
+            assert_eq!(crate::soft_f32::f32_powf_cr_with_env(a, b, &mut env), r);
+            assert_eq!(env.flags(), flags);
+        }
+    }
+
+    #[test]
+    fn test_f32_cr_sampled() {
+        check_cr_all(0x400001, check_exp_cr);
+        check_cr_all(0x400001, check_ln_cr);
+        check_cr_all(0x400001, check_sin_cr);
+        check_cr_all(0x400001, check_cos_cr);
+        check_cr_all(0x100001, check_powf_cr_of);
+    }
+
+    #[test]
+    fn test_f32_powf_cr_random() {
+        // Any arguments, and then exponents of Y with neither overflow nor underflow for most X
+        let mut state = 0x9E3779B97F4A7C15;
+        for _ in 0..0x4000 {
+            let r = xorshift(&mut state);
+            check_powf_cr(r as u32, (r >> 32) as u32);
+        }
+        for _ in 0..0x4000 {
+            let r = xorshift(&mut state);
+            check_powf_cr(r as u32, ((r >> 32) as u32 & 0x81FFFFFF) | 0x3C000000);
+        }
+    }
+
+    // The exhaustive tests take hours, and run with
+    // cargo test --release --features correctly-rounded -- --ignored
+
+    #[test]
+    #[ignore]
+    fn test_f32_exp_cr_exhaustive() {
+        check_cr_all(1, check_exp_cr);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_f32_ln_cr_exhaustive() {
+        check_cr_all(1, check_ln_cr);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_f32_sin_cr_exhaustive() {
+        check_cr_all(1, check_sin_cr);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_f32_cos_cr_exhaustive() {
+        check_cr_all(1, check_cos_cr);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_f32_powf_cr_exhaustive() {
+        // Every X, with the fixed exponents and those which depend on X
+        check_cr_all(1, check_powf_cr_of);
+    }
+
+    #[test]
+    fn test_f32_cr_flush_to_zero() {
+        // exp(-100) is subnormal, so it is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        assert_eq!(f32_exp_cr_with_env(0xC2C80000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // sin(0) is 0 with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(f32_sin_cr_with_env(0x80000001, &mut env), 0x80000000);
+        assert_eq!(f32_ln_cr_with_env(0x00000001, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+    }
+}
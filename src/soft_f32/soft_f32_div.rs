@@ -1,21 +1,30 @@
 use super::util::{
-    f32_shift_right_jam,
-    f32_norm_round_and_pack,
     f32_round_and_pack,
-    f32_pack_raw, f32_pack,
+    f32_pack_raw,
     f32_propagate_nan,
     f32_sign, f32_exp, f32_frac,
     f32_norm_subnormal_frac,
-    f32_short_shift_right_jam64,
-    f32_approx_recip,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
 };
 
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
 
 pub fn f32_div(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_div_with_env(a, b, env))
+}
+
+pub fn f32_div_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| f32_div_unflushed(a, b, env), env)
+}
+
+fn f32_div_unflushed(a: u32, b: u32, env: &mut Env) -> u32 {
     // Sign
-    let mut a_sign = f32_sign(a);
-    let mut b_sign = f32_sign(b);
-    let mut r_sign = a_sign ^ b_sign;
+    let a_sign = f32_sign(a);
+    let b_sign = f32_sign(b);
+    let r_sign = a_sign ^ b_sign;
 
     // Exp
     let mut a_exp = f32_exp(a);
@@ -25,18 +34,18 @@ pub fn f32_div(a: u32, b: u32) -> u32 {
     // Frac
     let mut a_frac = f32_frac(a);
     let mut b_frac = f32_frac(b);
-    let mut r_frac;
 
     if a_exp == 0xFF {
         if a_frac != 0 {
-            return f32_propagate_nan(a, b);
+            return f32_propagate_nan(a, b, env);
         }
         if b_exp == 0xFF {
             if b_frac != 0 {
-                return f32_propagate_nan(a, b);
+                return f32_propagate_nan(a, b, env);
             } else {
-                // Invalid
-                return f32_pack_raw(r_sign, 0xFF, 0);
+                // Inf / Inf, invalid, return default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f32_default_nan(env);
             }
         }
         // INFINITY
@@ -44,7 +53,7 @@ pub fn f32_div(a: u32, b: u32) -> u32 {
     }
     if b_exp == 0xFF {
         if b_frac != 0 {
-            return f32_propagate_nan(a, b);
+            return f32_propagate_nan(a, b, env);
         }
         return f32_pack_raw(r_sign, 0, 0);
     }
@@ -52,10 +61,12 @@ pub fn f32_div(a: u32, b: u32) -> u32 {
     if b_exp == 0 {
         if b_frac == 0 {
             if (a_exp | a_frac) == 0 {
-                // Invalid, return default NaN
-                return f32_pack_raw(r_sign, 0xFF, 0);
+                // 0 / 0, invalid, return default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f32_default_nan(env);
             }
             // Infinity
+            env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
             return f32_pack_raw(r_sign, 0xFF, 0);
         }
 
@@ -88,19 +99,20 @@ pub fn f32_div(a: u32, b: u32) -> u32 {
     }
     let mut r_frac_u64 = a_frac_u64 / (b_frac as u64);
 
-    if (r_frac_u64 & 0x3F) == 0 {
-        if r_frac_u64 * (b_frac as u64) != a_frac_u64 {
-            r_frac_u64 = r_frac_u64 | 0x01;
-        }
+    if (r_frac_u64 & 0x3F) == 0 && r_frac_u64 * (b_frac as u64) != a_frac_u64 {
+        // Inexact, keep a sticky bit
+        r_frac_u64 |= 0x01;
     }
 
-    r_frac = (r_frac_u64 & 0xFFFFFFFF) as i32;
+    let r_frac = (r_frac_u64 & 0xFFFFFFFF) as i32;
 
-    f32_round_and_pack(r_sign, r_exp, r_frac)
+    f32_round_and_pack(r_sign, r_exp, r_frac, env)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, DetectTininess, FlushToZero };
+
     #[test]
     fn test_f32_div() {
         // 0.1 / 0.2 = 0.5
@@ -133,8 +145,8 @@ mod tests {
         // -Inf / 1 = -Inf
         assert_eq!(crate::soft_f32::f32_div(0xFF800000, 0x3F800000), 0xFF800000);
 
-        // -Inf / Inf = -Inf
-        assert_eq!(crate::soft_f32::f32_div(0xFF800000, 0x7F800000), 0xFF800000);
+        // -Inf / Inf = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_div(0xFF800000, 0x7F800000)), true);
 
         // Inf / -1 = -Inf
         assert_eq!(crate::soft_f32::f32_div(0x7F800000, 0xBF800000), 0xFF800000);
@@ -154,5 +166,124 @@ mod tests {
         // NaN / -Inf = NaN
         assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_div(0xFFFFFFFF, 0xFF800000)), true);
     }
-}
 
+    #[test]
+    fn test_f32_div_rounding_mode() {
+        // 1 / 3
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::new(RoundingMode::NearEven)), 0x3EAAAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::new(RoundingMode::MinMag)), 0x3EAAAAAA);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::new(RoundingMode::Min)), 0x3EAAAAAA);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::new(RoundingMode::Max)), 0x3EAAAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3EAAAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::new(RoundingMode::Odd)), 0x3EAAAAAB);
+
+        // -1 / 3
+        assert_eq!(crate::soft_f32::f32_div_with_env(0xBF800000, 0x40400000, &mut Env::new(RoundingMode::NearEven)), 0xBEAAAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0xBF800000, 0x40400000, &mut Env::new(RoundingMode::MinMag)), 0xBEAAAAAA);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0xBF800000, 0x40400000, &mut Env::new(RoundingMode::Min)), 0xBEAAAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0xBF800000, 0x40400000, &mut Env::new(RoundingMode::Max)), 0xBEAAAAAA);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0xBF800000, 0x40400000, &mut Env::new(RoundingMode::NearMaxMag)), 0xBEAAAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0xBF800000, 0x40400000, &mut Env::new(RoundingMode::Odd)), 0xBEAAAAAB);
+
+        // 2 / 3
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x40000000, 0x40400000, &mut Env::new(RoundingMode::NearEven)), 0x3F2AAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x40000000, 0x40400000, &mut Env::new(RoundingMode::MinMag)), 0x3F2AAAAA);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x40000000, 0x40400000, &mut Env::new(RoundingMode::Min)), 0x3F2AAAAA);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x40000000, 0x40400000, &mut Env::new(RoundingMode::Max)), 0x3F2AAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x40000000, 0x40400000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F2AAAAB);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x40000000, 0x40400000, &mut Env::new(RoundingMode::Odd)), 0x3F2AAAAB);
+
+        // 0.1 / 0.2
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3F000000);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3F000000);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Min)), 0x3F000000);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Max)), 0x3F000000);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F000000);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Odd)), 0x3F000000);
+    }
+
+    #[test]
+    fn test_f32_div_exception_flags() {
+        let mut env = Env::default();
+
+        // 0.1 / 0.2 = 0.5, exact
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut env), 0x3F000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 1 / 3, inexact
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut env), 0x3EAAAAAB);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // 1 / 0 = Inf, divide by zero
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x00000000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // Inf / 0 = Inf, exact
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x7F800000, 0x00000000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 0 / 0 = NaN, invalid
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_div_with_env(0x00000000, 0x80000000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Inf / -Inf = NaN, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_div_with_env(0x7F800000, 0xFF800000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // MAX / 0.5 = Inf, overflow
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x7F7FFFFF, 0x3F000000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_div_subnormal() {
+        let mut env = Env::default();
+
+        // 2^-126 / 2 = 2^-127, subnormal and exact
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x00800000, 0x40000000, &mut env), 0x00400000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 1 / MAX
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x7F7FFFFF, &mut env), 0x00200000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x7F7FFFFF, &mut Env::new(RoundingMode::MinMag)), 0x00200000);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x7F7FFFFF, &mut Env::new(RoundingMode::Max)), 0x00200001);
+
+        // (2^-125 - 1 ulp) / 2 rounds up to the smallest normal, but was tiny
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x00FFFFFF, 0x40000000, &mut env), 0x00800000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x00FFFFFF, 0x40000000, &mut Env::new(RoundingMode::MinMag)), 0x007FFFFF);
+
+        // 2^-126 / (1 + 2^-23) = largest subnormal
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::Before);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x00800000, 0x3F800001, &mut env), 0x007FFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_div_flush_to_zero() {
+        // 2^-126 / 3 is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x00800000, 0xC0400000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // 1 / 2^-149 = Inf, divide by zero with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x3F800000, 0x00000001, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // 2^-149 / 2^-149 = 0 / 0, invalid
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_div_with_env(0x00000001, 0x00000001, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
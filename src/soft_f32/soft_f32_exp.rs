@@ -0,0 +1,433 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_pack_raw,
+    f32_sign, f32_frac,
+    // The exponent field, not to be confused with the exponential below
+    f32_exp as f32_exp_field,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_exp,
+    fixed_mul,
+    fixed_square,
+    fixed_reduce_ln2,
+    fixed_from_f32,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_ONE,
+    FIXED_LN2,
+    FIXED_TINY_SQUARE,
+    LN10_Q100,
+};
+
+use crate::soft_float::{ Env, with_thread_env };
+
+// Split A into its sign, biased exponent and significand with the hidden bit. The result of
+// the special cases is returned as an error, and |A| is limited to 256 where any base overflows.
+fn f32_exp_reduce(a: u32, env: &mut Env) -> Result<(i32, i32, i32), u32> {
+    // Sign
+    let a_sign = f32_sign(a);
+
+    // Exp
+    let a_exp = f32_exp_field(a);
+
+    // Frac
+    let a_frac = f32_frac(a);
+
+    if a_exp == 0xFF {
+        if a_frac != 0 {
+            // Propagate NaN
+            return Err(f32_propagate_nan(a, 0, env));
+        }
+        // e^inf = inf and e^-inf = 0
+        return Err(if a_sign == 0 { a } else { f32_pack_raw(0, 0, 0) });
+    }
+
+    if a_exp | a_frac == 0 {
+        // Zero, the result is an exact 1
+        return Err(f32_pack_raw(0, 0x7F, 0));
+    }
+
+    if a_exp < 0x5C {
+        // |A| < 2^-35, the result is within 2^-33 of 1, on the side of A
+        let r = if a_sign == 0 { FIXED_ONE + 4 } else { FIXED_ONE - 4 };
+        return Err(f32_round_fixed(r, -FIXED_FRAC_BITS, false, env));
+    }
+
+    if a_exp >= 0x87 {
+        return Ok((a_sign, 0x87, 0x00800000));
+    }
+
+    Ok((a_sign, a_exp, a_frac | 0x00800000))
+}
+
+// Round 2^n x e^r to Float32, for the fixed-point r in [-0.35, 0.35]
+fn f32_round_exp(n: i32, r: i128, env: &mut Env) -> u32 {
+    f32_round_fixed(fixed_exp(r), n - FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_exp2(a: u32) -> u32 {
+    with_thread_env(|env| f32_exp2_with_env(a, env))
+}
+
+// Base 2 exponential. The result is exact for integers, and otherwise within 0.5 ULP plus the
+// 2^-56 relative error of the evaluation, that is correctly rounded but for hard cases.
+// It overflows to infinity from 128, and underflows to 0 below -149.
+pub fn f32_exp2_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_exp2_unflushed(a, env), env)
+}
+
+fn f32_exp2_unflushed(a: u32, env: &mut Env) -> u32 {
+    let (a_sign, a_exp, a_frac) = match f32_exp_reduce(a, env) {
+        Ok(reduced) => reduced,
+        Err(r) => return r,
+    };
+    let x = fixed_from_f32(a_exp, a_frac);
+    let x = if a_sign == 1 { -x } else { x };
+
+    // x = n + f with |f| <= 0.5, and 2^f = e^(f x ln(2))
+    let n = (x + (FIXED_ONE >> 1)) >> FIXED_FRAC_BITS;
+    let f = x - (n << FIXED_FRAC_BITS);
+    if f == 0 {
+        // Power of 2
+        return f32_round_fixed(FIXED_ONE, n as i32 - FIXED_FRAC_BITS, true, env);
+    }
+
+    f32_round_exp(n as i32, fixed_mul(f, FIXED_LN2), env)
+}
+
+pub fn f32_exp(a: u32) -> u32 {
+    with_thread_env(|env| f32_exp_with_env(a, env))
+}
+
+// Natural exponential. The result is exact only for 0, and otherwise within 0.5 ULP plus the
+// 2^-56 relative error of the evaluation. It overflows to infinity above 88.72.
+pub fn f32_exp_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_exp_unflushed(a, env), env)
+}
+
+fn f32_exp_unflushed(a: u32, env: &mut Env) -> u32 {
+    match f32_exp_fixed(a, env) {
+        Ok((v, scale)) => f32_round_fixed(v, scale, false, env),
+        Err(r) => r,
+    }
+}
+
+// e^A before rounding, as the fixed-point v x 2^scale. The result of the special cases, and of
+// a tiny A which rounds as the exact value, is returned as an error.
+pub(crate) fn f32_exp_fixed(a: u32, env: &mut Env) -> Result<(i128, i32), u32> {
+    let (a_sign, a_exp, a_frac) = f32_exp_reduce(a, env)?;
+    let x = fixed_from_f32(a_exp, a_frac);
+    let x = if a_sign == 1 { -x } else { x };
+
+    let (n, r) = fixed_reduce_ln2(x);
+    Ok((fixed_exp(r), n - FIXED_FRAC_BITS))
+}
+
+pub fn f32_exp10(a: u32) -> u32 {
+    with_thread_env(|env| f32_exp10_with_env(a, env))
+}
+
+// Base 10 exponential. The result is exact for the integers 0 to 10, and otherwise within
+// 0.5 ULP plus the 2^-56 relative error of the evaluation. It overflows to infinity above 38.53.
+pub fn f32_exp10_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_exp10_unflushed(a, env), env)
+}
+
+fn f32_exp10_unflushed(a: u32, env: &mut Env) -> u32 {
+    let (a_sign, a_exp, a_frac) = match f32_exp_reduce(a, env) {
+        Ok(reduced) => reduced,
+        Err(r) => return r,
+    };
+
+    // 10^k is exact up to 10^10
+    let x = fixed_from_f32(a_exp, a_frac);
+    if a_sign == 0 && x & (FIXED_ONE - 1) == 0 && x <= 10 << FIXED_FRAC_BITS {
+        let power = 10i128.pow((x >> FIXED_FRAC_BITS) as u32);
+        return f32_round_fixed(power, 0, true, env);
+    }
+
+    // y = x x ln(10), from the significand with a more accurate ln(10)
+    let y = ((a_frac as i128) * LN10_Q100) >> (150 + 100 - FIXED_FRAC_BITS - a_exp);
+    let y = if a_sign == 1 { -y } else { y };
+
+    let (n, r) = fixed_reduce_ln2(y);
+    f32_round_exp(n, r, env)
+}
+
+pub fn f32_expm1(a: u32) -> u32 {
+    with_thread_env(|env| f32_expm1_with_env(a, env))
+}
+
+// e^A - 1, without the cancellation of f32_exp(A) - 1 when A is close to 0. The result is exact
+// only for 0 and -infinity, and otherwise within 0.5 ULP plus the 2^-56 relative error of the
+// evaluation. It overflows to infinity above 88.72.
+pub fn f32_expm1_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_expm1_unflushed(a, env), env)
+}
+
+fn f32_expm1_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 || a == 0x7F800000 {
+        // Zero and infinity
+        return a;
+    }
+    if a == 0xFF800000 {
+        // e^-inf - 1 = -1
+        return f32_pack_raw(1, 0x7F, 0);
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    let x = if a_sign == 1 { -m } else { m };
+    if e < -2 {
+        // |A| < 1/4, x + x^2 / 2! + x^3 / 3! + ... with the scale of A
+        let k = -e;
+        if fixed_square(m, k) < FIXED_TINY_SQUARE {
+            // e^x - 1 is just above x
+            return f32_round_fixed(x + 1, -FIXED_FRAC_BITS - k, false, env);
+        }
+
+        let x_unscaled = x >> k;
+        let mut sum = x;
+        let mut term = x;
+        for n in 2..=20 {
+            term = fixed_mul(term, x_unscaled) / n;
+            if term == 0 {
+                break;
+            }
+            sum += term;
+        }
+        return f32_round_fixed(sum, -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    // |A| is limited to 256, where the result already overflows or rounds as -1
+    let y = match e {
+        8.. => 256 * FIXED_ONE,
+        0.. => m << e,
+        _ => m >> -e,
+    };
+    let y = if a_sign == 1 { -y } else { y };
+    let (n, r) = fixed_reduce_ln2(y);
+    let p = fixed_exp(r);
+
+    if n >= 0 {
+        let r = p - FIXED_ONE.checked_shr(n as u32).unwrap_or(0);
+        return f32_round_fixed(r, n - FIXED_FRAC_BITS, false, env);
+    }
+
+    // 2^n x e^r is rounded up, so that the result stays above -1
+    let r = p.checked_shr(-n as u32).unwrap_or(0) + 1 - FIXED_ONE;
+    f32_round_fixed(r, -FIXED_FRAC_BITS, false, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_exp2() {
+        // 2^3 = 8
+        assert_eq!(crate::soft_f32::f32_exp2(0x40400000), 0x41000000);
+        // 2^-2 = 0.25
+        assert_eq!(crate::soft_f32::f32_exp2(0xC0000000), 0x3E800000);
+        // 2^-149 = smallest subnormal
+        assert_eq!(crate::soft_f32::f32_exp2(0xC3150000), 0x00000001);
+        // 2^0.5 = 1.4142135
+        assert_eq!(crate::soft_f32::f32_exp2(0x3F000000), 0x3FB504F3);
+        // 2^10.5 = 1448.1547
+        assert_eq!(crate::soft_f32::f32_exp2(0x41280000), 0x44B504F3);
+
+        // 2^0 = 1
+        assert_eq!(crate::soft_f32::f32_exp2(0x00000000), 0x3F800000);
+        // 2^-0 = 1
+        assert_eq!(crate::soft_f32::f32_exp2(0x80000000), 0x3F800000);
+    }
+
+    #[test]
+    fn test_f32_exp() {
+        // e^1 = 2.7182817
+        assert_eq!(crate::soft_f32::f32_exp(0x3F800000), 0x402DF854);
+        // e^-1 = 0.36787945
+        assert_eq!(crate::soft_f32::f32_exp(0xBF800000), 0x3EBC5AB2);
+        // e^10 = 22026.465
+        assert_eq!(crate::soft_f32::f32_exp(0x41200000), 0x46AC14EE);
+        // e^88 = 1.6516363e38
+        assert_eq!(crate::soft_f32::f32_exp(0x42B00000), 0x7EF882B7);
+    }
+
+    #[test]
+    fn test_f32_exp10() {
+        // 10^2 = 100
+        assert_eq!(crate::soft_f32::f32_exp10(0x40000000), 0x42C80000);
+        // 10^10 = 10000000000
+        assert_eq!(crate::soft_f32::f32_exp10(0x41200000), 0x501502F9);
+        // 10^-1 = 0.1
+        assert_eq!(crate::soft_f32::f32_exp10(0xBF800000), 0x3DCCCCCD);
+        // 10^-2 = 0.01
+        assert_eq!(crate::soft_f32::f32_exp10(0xC0000000), 0x3C23D70A);
+        // 10^1.5 = 31.622776
+        assert_eq!(crate::soft_f32::f32_exp10(0x3FC00000), 0x41FCFB72);
+    }
+
+    #[test]
+    fn test_f32_exp_inf_nan() {
+        // e^Inf = Inf
+        assert_eq!(crate::soft_f32::f32_exp(0x7F800000), 0x7F800000);
+        // 2^-Inf = 0
+        assert_eq!(crate::soft_f32::f32_exp2(0xFF800000), 0x00000000);
+        // 10^-Inf = 0
+        assert_eq!(crate::soft_f32::f32_exp10(0xFF800000), 0x00000000);
+
+        // e^NaN = NaN
+        assert_eq!(crate::soft_f32::f32_exp(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_exp_exception_flags() {
+        // 2^128 = Inf, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0x43000000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // e^89 = Inf, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x42B20000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 10^39 = Inf, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0x421C0000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // e^1e30 rounds to the largest finite value toward zero
+        let mut env = Env::new(RoundingMode::MinMag);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x7149F2CA, &mut env), 0x7F7FFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 2^-150 = 0, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xC3160000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // e^-1e30 = 0, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0xF149F2CA, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // 2^-149, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xC3150000, &mut env), 0x00000001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 10^3 = 1000, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0x40400000, &mut env), 0x447A0000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // e^sNaN = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_exp_rounding_mode() {
+        // e^1
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x402DF854);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x402DF854);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x3F800000, &mut Env::new(RoundingMode::Min)), 0x402DF854);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x3F800000, &mut Env::new(RoundingMode::Max)), 0x402DF855);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x402DF854);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x402DF855);
+
+        // 2^-0.5
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xBF000000, &mut Env::new(RoundingMode::NearEven)), 0x3F3504F3);
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xBF000000, &mut Env::new(RoundingMode::MinMag)), 0x3F3504F3);
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xBF000000, &mut Env::new(RoundingMode::Min)), 0x3F3504F3);
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xBF000000, &mut Env::new(RoundingMode::Max)), 0x3F3504F4);
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xBF000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F3504F3);
+        assert_eq!(crate::soft_f32::f32_exp2_with_env(0xBF000000, &mut Env::new(RoundingMode::Odd)), 0x3F3504F3);
+
+        // 10^-1
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0xBF800000, &mut Env::new(RoundingMode::NearEven)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0xBF800000, &mut Env::new(RoundingMode::MinMag)), 0x3DCCCCCC);
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0xBF800000, &mut Env::new(RoundingMode::Min)), 0x3DCCCCCC);
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0xBF800000, &mut Env::new(RoundingMode::Max)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0xBF800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f32::f32_exp10_with_env(0xBF800000, &mut Env::new(RoundingMode::Odd)), 0x3DCCCCCD);
+
+        // e^1e-20 is just above 1
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x3F800001);
+
+        // e^-1e-20 is just below 1
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x9E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x9E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x3F7FFFFF);
+    }
+
+    #[test]
+    fn test_f32_expm1() {
+        // expm1(1) = 1.7182819
+        assert_eq!(crate::soft_f32::f32_expm1(0x3F800000), 0x3FDBF0A9);
+        // expm1(-0.5) = -0.39346933
+        assert_eq!(crate::soft_f32::f32_expm1(0xBF000000), 0xBEC974D0);
+        // expm1(1e-7) = 1.0000001e-7
+        assert_eq!(crate::soft_f32::f32_expm1(0x33D6BF95), 0x33D6BF96);
+        // expm1(88) = 1.6516363e38
+        assert_eq!(crate::soft_f32::f32_expm1(0x42B00000), 0x7EF882B7);
+        // expm1(-100) = -1
+        assert_eq!(crate::soft_f32::f32_expm1(0xC2C80000), 0xBF800000);
+
+        // expm1(-0) = -0
+        assert_eq!(crate::soft_f32::f32_expm1(0x80000000), 0x80000000);
+        // expm1(-Inf) = -1
+        assert_eq!(crate::soft_f32::f32_expm1(0xFF800000), 0xBF800000);
+        // expm1(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_expm1(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_expm1_rounding_mode() {
+        // expm1(1)
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x3FDBF0A9);
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x3FDBF0A8);
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x3F800000, &mut Env::new(RoundingMode::Min)), 0x3FDBF0A8);
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x3F800000, &mut Env::new(RoundingMode::Max)), 0x3FDBF0A9);
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FDBF0A9);
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x3FDBF0A9);
+
+        // expm1(-100) is just above -1
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0xC2C80000, &mut Env::new(RoundingMode::MinMag)), 0xBF7FFFFF);
+
+        // expm1(1e-20) is just above 1e-20
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x1E3CE509);
+        // expm1(-1e-20) is just above -1e-20
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x9E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x9E3CE507);
+    }
+
+    #[test]
+    fn test_f32_exp_flush_to_zero() {
+        // exp(-100) is subnormal, so it is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0xC2C80000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // exp(0) is exactly 1 with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_expm1_with_env(0x00000001, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_exp_with_env(0x80000001, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
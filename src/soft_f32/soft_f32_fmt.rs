@@ -0,0 +1,537 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::util::{
+    f32_sign, f32_exp, f32_frac,
+    f32_is_nan,
+};
+
+use super::big::Big;
+
+// Decimal formatting of Float32 values, byte-identical to the formatting of the primitive f32.
+//
+// The shortest digits which round-trip are generated with the Dragon4 algorithm as the standard
+// library does, so that the last digit is chosen the same way. Digits with a precision are
+// rounded to nearest with ties to even from the exact decimal expansion, which has at most
+// 105 significant digits. Both use big integers, without any floating-point arithmetic.
+
+// The finite and nonzero A is mant x 2^exp, and the values between (mant - minus) x 2^exp and
+// (mant + plus) x 2^exp round to A, the bounds included when inclusive. The standard library
+// considers the bounds of a subnormal as included, as its significand is scaled by 2.
+struct Decoded {
+    mant: u64,
+    minus: u64,
+    plus: u64,
+    exp: i32,
+    inclusive: bool,
+}
+
+fn f32_decode(a: u32) -> Decoded {
+    let exp = f32_exp(a);
+    let frac = f32_frac(a) as u64;
+
+    if exp == 0 {
+        return Decoded { mant: frac << 1, minus: 1, plus: 1, exp: -150, inclusive: true };
+    }
+
+    let mant = frac | 0x800000;
+    let inclusive = mant & 1 == 0;
+    if frac == 0 {
+        // The value below is closer than the value above
+        return Decoded { mant: mant << 2, minus: 1, plus: 2, exp: exp - 152, inclusive };
+    }
+    Decoded { mant: mant << 1, minus: 1, plus: 1, exp: exp - 151, inclusive }
+}
+
+// A lower bound of k with 10^(k - 1) < mant x 2^exp <= 10^k, less by at most 1
+fn f32_estimate_scaling_factor(mant: u64, exp: i32) -> i32 {
+    let nbits = 64 - (mant - 1).leading_zeros() as i64;
+    // 1292913986 = floor(2^32 x log10(2))
+    (((nbits + exp as i64) * 1292913986) >> 32) as i32
+}
+
+// Increment the digits, and return the digit to append when all of them are 9
+fn f32_round_up_digits(digits: &mut [u8]) -> Option<u8> {
+    match digits.iter().rposition(|&c| c != b'9') {
+        Some(i) => {
+            digits[i] += 1;
+            digits[i + 1..].iter_mut().for_each(|c| *c = b'0');
+            None
+        }
+        None if !digits.is_empty() => {
+            digits[0] = b'1';
+            digits[1..].iter_mut().for_each(|c| *c = b'0');
+            Some(b'0')
+        }
+        None => Some(b'1'),
+    }
+}
+
+// The shortest digits d which round to the finite and nonzero A, with A close to 0.d x 10^k.
+// Among the shortest, the closest to A is chosen, and a tie is rounded up.
+fn f32_shortest_digits(a: u32) -> (Vec<u8>, i32) {
+    let d = f32_decode(a);
+    // x <= y when the bounds are included, x < y otherwise
+    let below = |x: Big, y: Big| match x.cmp(y) {
+        Ordering::Equal => d.inclusive,
+        ordering => ordering == Ordering::Less,
+    };
+
+    // A = mant / scale x 10^k, and the bounds are minus and plus away
+    let mut k = f32_estimate_scaling_factor(d.mant + d.plus, d.exp);
+    let mut mant = Big::from_u64(d.mant);
+    let mut minus = Big::from_u64(d.minus);
+    let mut plus = Big::from_u64(d.plus);
+    let mut scale = Big::from_u64(1);
+    if d.exp < 0 {
+        scale = scale.mul_pow2(-d.exp);
+    } else {
+        mant = mant.mul_pow2(d.exp);
+        minus = minus.mul_pow2(d.exp);
+        plus = plus.mul_pow2(d.exp);
+    }
+    if k >= 0 {
+        scale = scale.mul_pow10(k);
+    } else {
+        mant = mant.mul_pow10(-k);
+        minus = minus.mul_pow10(-k);
+        plus = plus.mul_pow10(-k);
+    }
+
+    // Fix the estimate, so that scale < mant + plus <= 10 x scale
+    if below(scale, mant.add(plus)) {
+        k += 1;
+    } else {
+        mant = mant.mul_small(10);
+        minus = minus.mul_small(10);
+        plus = plus.mul_small(10);
+    }
+
+    let scale2 = scale.mul_pow2(1);
+    let scale4 = scale.mul_pow2(2);
+    let scale8 = scale.mul_pow2(3);
+
+    let mut digits = Vec::new();
+    let (down, up) = loop {
+        // The next digit, mant / scale < 10
+        let mut digit = 0;
+        for (s, n) in [(scale8, 8), (scale4, 4), (scale2, 2), (scale, 1)] {
+            if mant.cmp(s) != Ordering::Less {
+                mant = mant.sub(s);
+                digit += n;
+            }
+        }
+        digits.push(b'0' + digit);
+
+        // Stop when the digits, or the digits with the last one incremented, round to A
+        let down = below(mant, minus);
+        let up = below(scale, mant.add(plus));
+        if down || up {
+            break (down, up);
+        }
+
+        mant = mant.mul_small(10);
+        minus = minus.mul_small(10);
+        plus = plus.mul_small(10);
+    };
+
+    if up && (!down || mant.mul_pow2(1).cmp(scale) != Ordering::Less) {
+        if let Some(c) = f32_round_up_digits(&mut digits) {
+            digits.push(c);
+            k += 1;
+        }
+    }
+
+    (digits, k)
+}
+
+// The exact decimal digits d of the finite and nonzero A, with A = 0.d x 10^k, without
+// trailing zeros
+fn f32_exact_digits(a: u32) -> (Vec<u8>, i32) {
+    let exp = f32_exp(a);
+    let frac = f32_frac(a) as u64;
+    let (mant, exp) = match exp {
+        0 => (frac, -149),
+        _ => (frac | 0x800000, exp - 150),
+    };
+
+    // A = n / 10^point
+    let (n, point) = match exp >= 0 {
+        true => (Big::from_u64(mant).mul_pow2(exp), 0),
+        false => (Big::from_u64(mant).mul_pow5(-exp), -exp),
+    };
+
+    let mut digits = n.to_digits();
+    let k = digits.len() as i32 - point;
+    while digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    (digits, k)
+}
+
+// Round the digits d of A = 0.d x 10^k to their first len digits, to nearest with ties to even.
+// No digit is kept when len <= 0, unless the carry gives the digit 1 and increments k.
+fn f32_round_digits(mut digits: Vec<u8>, mut k: i32, len: i64) -> (Vec<u8>, i32) {
+    if len >= digits.len() as i64 {
+        return (digits, k);
+    }
+    if len < 0 {
+        return (Vec::new(), k);
+    }
+
+    let len = len as usize;
+    let is_odd = len > 0 && (digits[len - 1] - b'0') % 2 == 1;
+    let is_above_half = digits[len] > b'5' || (digits[len] == b'5' && digits.len() > len + 1);
+    let is_up = is_above_half || (digits[len] == b'5' && is_odd);
+    digits.truncate(len);
+
+    if is_up && f32_round_up_digits(&mut digits).is_some() {
+        // All the digits were 9, and are 10...0 once incremented
+        digits = vec![b'1'];
+        k += 1;
+    }
+    (digits, k)
+}
+
+// Append n zeros
+fn f32_push_zeros(s: &mut String, n: usize) {
+    s.push_str(&"0".repeat(n));
+}
+
+// Render 0.d x 10^k in fixed notation, with at least frac_digits fraction digits
+fn f32_digits_to_fixed(digits: &[u8], k: i32, frac_digits: usize) -> String {
+    let digits = std::str::from_utf8(digits).unwrap();
+    let mut s = String::new();
+
+    if k <= 0 {
+        // 0.[000][digits][000]
+        let zeros = -k as usize;
+        s.push_str("0.");
+        f32_push_zeros(&mut s, zeros);
+        s.push_str(digits);
+        f32_push_zeros(&mut s, frac_digits.saturating_sub(digits.len() + zeros));
+    } else if (k as usize) < digits.len() {
+        // [dig].[its][000]
+        let k = k as usize;
+        s.push_str(&digits[..k]);
+        s.push('.');
+        s.push_str(&digits[k..]);
+        f32_push_zeros(&mut s, frac_digits.saturating_sub(digits.len() - k));
+    } else {
+        // [digits][000].[000]
+        s.push_str(digits);
+        f32_push_zeros(&mut s, k as usize - digits.len());
+        if frac_digits > 0 {
+            s.push('.');
+            f32_push_zeros(&mut s, frac_digits);
+        }
+    }
+    s
+}
+
+// Render 0.d x 10^k in scientific notation, with at least min_digits significant digits
+fn f32_digits_to_exp(digits: &[u8], k: i32, min_digits: usize, upper: bool) -> String {
+    let digits = std::str::from_utf8(digits).unwrap();
+    let mut s = String::new();
+
+    s.push_str(&digits[..1]);
+    if digits.len() > 1 || min_digits > 1 {
+        s.push('.');
+        s.push_str(&digits[1..]);
+        f32_push_zeros(&mut s, min_digits.saturating_sub(digits.len()));
+    }
+    s.push(if upper { 'E' } else { 'e' });
+    s.push_str(&(k - 1).to_string());
+    s
+}
+
+// The body of NaN and infinities, which have no digits
+fn f32_special_body(a: u32) -> Option<String> {
+    if f32_is_nan(a) {
+        return Some("NaN".to_string());
+    }
+    if (a & 0x7FFFFFFF) == 0x7F800000 {
+        return Some("inf".to_string());
+    }
+    None
+}
+
+fn f32_zero_fixed(frac_digits: usize) -> String {
+    let mut s = String::from("0");
+    if frac_digits > 0 {
+        s.push('.');
+        f32_push_zeros(&mut s, frac_digits);
+    }
+    s
+}
+
+// The shortest digits in fixed notation, with at least frac_digits fraction digits
+fn f32_shortest_fixed(a: u32, frac_digits: usize) -> String {
+    if let Some(s) = f32_special_body(a) {
+        return s;
+    }
+    if (a << 1) == 0 {
+        return f32_zero_fixed(frac_digits);
+    }
+
+    let (digits, k) = f32_shortest_digits(a);
+    f32_digits_to_fixed(&digits, k, frac_digits)
+}
+
+// The shortest digits in scientific notation
+fn f32_shortest_exp(a: u32, upper: bool) -> String {
+    if let Some(s) = f32_special_body(a) {
+        return s;
+    }
+    if (a << 1) == 0 {
+        return if upper { "0E0" } else { "0e0" }.to_string();
+    }
+
+    let (digits, k) = f32_shortest_digits(a);
+    f32_digits_to_exp(&digits, k, 0, upper)
+}
+
+// Exactly frac_digits fraction digits in fixed notation
+fn f32_exact_fixed(a: u32, frac_digits: usize) -> String {
+    if let Some(s) = f32_special_body(a) {
+        return s;
+    }
+    if (a << 1) == 0 {
+        return f32_zero_fixed(frac_digits);
+    }
+
+    let (digits, k) = f32_exact_digits(a);
+    let (digits, k) = f32_round_digits(digits, k, k as i64 + frac_digits as i64);
+    if digits.is_empty() {
+        // Rounded to zero, with the sign of A
+        return f32_zero_fixed(frac_digits);
+    }
+    f32_digits_to_fixed(&digits, k, frac_digits)
+}
+
+// Exactly num_digits significant digits in scientific notation
+fn f32_exact_exp(a: u32, num_digits: usize, upper: bool) -> String {
+    if let Some(s) = f32_special_body(a) {
+        return s;
+    }
+    if (a << 1) == 0 {
+        let mut s = String::from("0");
+        if num_digits > 1 {
+            s.push('.');
+            f32_push_zeros(&mut s, num_digits - 1);
+        }
+        s.push(if upper { 'E' } else { 'e' });
+        s.push('0');
+        return s;
+    }
+
+    let (digits, k) = f32_exact_digits(a);
+    let (digits, k) = f32_round_digits(digits, k, num_digits as i64);
+    f32_digits_to_exp(&digits, k, num_digits, upper)
+}
+
+// Write the sign and the body of A with the width, fill and alignment of the formatter, which
+// default to the right. NaN has no sign, and the sign-aware zero padding goes after the sign.
+fn f32_pad(a: u32, body: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let sign = if f32_is_nan(a) {
+        ""
+    } else if f32_sign(a) != 0 {
+        "-"
+    } else if f.sign_plus() {
+        "+"
+    } else {
+        ""
+    };
+
+    let width = match f.width() {
+        Some(width) => width,
+        None => {
+            f.write_str(sign)?;
+            return f.write_str(body);
+        }
+    };
+
+    let (fill, align, len) = if f.sign_aware_zero_pad() {
+        f.write_str(sign)?;
+        ('0', fmt::Alignment::Right, sign.len() + body.len())
+    } else {
+        (f.fill(), f.align().unwrap_or(fmt::Alignment::Right), sign.len() + body.len())
+    };
+    let padding = width.saturating_sub(len);
+    let (pre, post) = match align {
+        fmt::Alignment::Left => (0, padding),
+        fmt::Alignment::Right => (padding, 0),
+        fmt::Alignment::Center => (padding / 2, padding - padding / 2),
+    };
+
+    for _ in 0..pre {
+        write!(f, "{}", fill)?;
+    }
+    if !f.sign_aware_zero_pad() {
+        f.write_str(sign)?;
+    }
+    f.write_str(body)?;
+    for _ in 0..post {
+        write!(f, "{}", fill)?;
+    }
+    Ok(())
+}
+
+// {}, the shortest digits in fixed notation, or {:.N} with N fraction digits
+pub(crate) fn f32_fmt_display(a: u32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let body = match f.precision() {
+        Some(precision) => f32_exact_fixed(a, precision),
+        None => f32_shortest_fixed(a, 0),
+    };
+    f32_pad(a, &body, f)
+}
+
+// {:e} and {:E}, the shortest digits in scientific notation, or {:.Ne} with N fraction digits
+pub(crate) fn f32_fmt_exp(a: u32, upper: bool, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let body = match f.precision() {
+        Some(precision) => f32_exact_exp(a, precision + 1, upper),
+        None => f32_shortest_exp(a, upper),
+    };
+    f32_pad(a, &body, f)
+}
+
+// {:?}, the shortest digits with at least one fraction digit, in scientific notation when
+// below 1e-4 or from 1e16, or {:.N?} as {:.N}. The bounds are the Float32 values of 1e-4 and 1e16.
+pub(crate) fn f32_fmt_debug(a: u32, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let abs = a & 0x7FFFFFFF;
+    let body = match f.precision() {
+        Some(precision) => f32_exact_fixed(a, precision),
+        None if abs != 0 && (abs < 0x38D1B717 || (0x5A0E1BCA..0x7F800000).contains(&abs)) => {
+            f32_shortest_exp(a, false)
+        }
+        None => f32_shortest_fixed(a, 1),
+    };
+    f32_pad(a, &body, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_f32::F32;
+
+    // The shortest digits of A are formatted as the primitive f32
+    fn check_fmt_shortest(bits: u32) {
+        let a = F32::from_bits(bits);
+        let h = f32::from_bits(bits);
+        assert_eq!(format!("{}", a), format!("{}", h), "{:08X}", bits);
+        assert_eq!(format!("{:?}", a), format!("{:?}", h), "{:08X}", bits);
+        assert_eq!(format!("{:e}", a), format!("{:e}", h), "{:08X}", bits);
+        assert_eq!(format!("{:E}", a), format!("{:E}", h), "{:08X}", bits);
+    }
+
+    // A is formatted as the primitive f32, with flags and precisions that cover every path
+    fn check_fmt(bits: u32) {
+        check_fmt_shortest(bits);
+        let a = F32::from_bits(bits);
+        let h = f32::from_bits(bits);
+        assert_eq!(format!("{:.0}", a), format!("{:.0}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.3}", a), format!("{:.3}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.45}", a), format!("{:.45}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.160}", a), format!("{:.160}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.0e}", a), format!("{:.0e}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.8e}", a), format!("{:.8e}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.120E}", a), format!("{:.120E}", h), "{:08X}", bits);
+        assert_eq!(format!("{:.2?}", a), format!("{:.2?}", h), "{:08X}", bits);
+        assert_eq!(format!("{:+015.4}", a), format!("{:+015.4}", h), "{:08X}", bits);
+    }
+
+    #[test]
+    fn test_f32_display() {
+        assert_eq!(format!("{}", F32::from_bits(0x3DCCCCCD)), "0.1");
+        assert_eq!(format!("{}", F32::from_bits(0x3F800000)), "1");
+        assert_eq!(format!("{}", F32::from_bits(0xC0490FDB)), "-3.1415927");
+        assert_eq!(format!("{}", F32::from_bits(0x80000000)), "-0");
+        assert_eq!(format!("{}", F32::from_bits(0x7149F2CA)), "1000000000000000000000000000000");
+        assert_eq!(format!("{}", F32::from_bits(0x00000001)), "0.000000000000000000000000000000000000000000001");
+        assert_eq!(format!("{}", F32::from_bits(0x7F7FFFFF)), "340282350000000000000000000000000000000");
+        assert_eq!(format!("{}", F32::from_bits(0xFF800000)), "-inf");
+        assert_eq!(format!("{}", F32::from_bits(0xFFC00000)), "NaN");
+    }
+
+    #[test]
+    fn test_f32_display_precision() {
+        // 0.1 is 0.100000001490116119384765625
+        assert_eq!(format!("{:.3}", F32::from_bits(0x3DCCCCCD)), "0.100");
+        assert_eq!(format!("{:.12}", F32::from_bits(0x3DCCCCCD)), "0.100000001490");
+        assert_eq!(format!("{:.30}", F32::from_bits(0x3DCCCCCD)), "0.100000001490116119384765625000");
+        // Ties to even
+        assert_eq!(format!("{:.0}", F32::from_bits(0x3F000000)), "0");
+        assert_eq!(format!("{:.0}", F32::from_bits(0x40200000)), "2");
+        assert_eq!(format!("{:.0}", F32::from_bits(0x3FC00000)), "2");
+        assert_eq!(format!("{:.1}", F32::from_bits(0x3E800000)), "0.2");
+        // 9.96 rounds to 10.0
+        assert_eq!(format!("{:.1}", F32::from_bits(0x411F5C29)), "10.0");
+        // -0.0001 rounds to -0.000
+        assert_eq!(format!("{:.3}", F32::from_bits(0xB8D1B717)), "-0.000");
+        assert_eq!(format!("{:.2}", F32::from_bits(0x7FC00000)), "NaN");
+    }
+
+    #[test]
+    fn test_f32_debug() {
+        assert_eq!(format!("{:?}", F32::from_bits(0x3F800000)), "1.0");
+        assert_eq!(format!("{:?}", F32::from_bits(0x80000000)), "-0.0");
+        assert_eq!(format!("{:?}", F32::from_bits(0x38D1B717)), "0.0001");
+        assert_eq!(format!("{:?}", F32::from_bits(0x38CF9E38)), "9.9e-5");
+        assert_eq!(format!("{:?}", F32::from_bits(0x5A0E1BCA)), "1e16");
+        assert_eq!(format!("{:?}", F32::from_bits(0x5A0E1BC9)), "9999999000000000.0");
+        assert_eq!(format!("{:?}", F32::from_bits(0x00800000)), "1.1754944e-38");
+        assert_eq!(format!("{:.2?}", F32::from_bits(0x3E000000)), "0.12");
+        assert_eq!(format!("{:?}", F32::from_bits(0x7F800000)), "inf");
+    }
+
+    #[test]
+    fn test_f32_exp() {
+        assert_eq!(format!("{:e}", F32::from_bits(0x3F800000)), "1e0");
+        assert_eq!(format!("{:E}", F32::from_bits(0x34210FB0)), "1.5E-7");
+        assert_eq!(format!("{:e}", F32::from_bits(0x00000000)), "0e0");
+        assert_eq!(format!("{:.3e}", F32::from_bits(0x00000000)), "0.000e0");
+        // Ties to even
+        assert_eq!(format!("{:.0e}", F32::from_bits(0x40200000)), "2e0");
+        assert_eq!(format!("{:.0e}", F32::from_bits(0x41C80000)), "2e1");
+        assert_eq!(format!("{:.1e}", F32::from_bits(0x3E000000)), "1.2e-1");
+        // 2^-149 has 105 significant digits
+        assert_eq!(
+            format!("{:.110e}", F32::from_bits(0x00000001)),
+            "1.40129846432481707092372958328991613128026194187651577175706828388979108268586060148663818836212158203125000000e-45"
+        );
+        assert_eq!(format!("{:e}", F32::from_bits(0xFFC00000)), "NaN");
+    }
+
+    #[test]
+    fn test_f32_fmt_padding() {
+        assert_eq!(format!("{:+}", F32::from_bits(0x3F800000)), "+1");
+        assert_eq!(format!("{:+}", F32::from_bits(0x7FC00000)), "NaN");
+        assert_eq!(format!("{:<6}|", F32::from_bits(0x7FC00000)), "NaN   |");
+        assert_eq!(format!("{:^7}", F32::from_bits(0x3FC00000)), "  1.5  ");
+        assert_eq!(format!("{:*^9}", F32::from_bits(0x80000000)), "***-0****");
+        assert_eq!(format!("{:+08.2}", F32::from_bits(0xBFC00000)), "-0001.50");
+        assert_eq!(format!("{:05}", F32::from_bits(0x7F800000)), "00inf");
+        assert_eq!(format!("{:010?}", F32::from_bits(0xE0AD78EC)), "-000001e20");
+    }
+
+    #[test]
+    fn test_f32_fmt_sampled() {
+        for bits in (0..=0xFFFFFFFFu32).step_by(0x100001) {
+            check_fmt(bits);
+        }
+        for &bits in [0x00000001, 0x007FFFFF, 0x00800000, 0x7F7FFFFF, 0x3F7FFFFF, 0x4B7FFFFF].iter() {
+            check_fmt(bits);
+            check_fmt(bits | 0x80000000);
+        }
+    }
+
+    // The exhaustive test of the shortest digits takes hours, and runs with
+    // cargo test --release -- --ignored
+
+    #[test]
+    #[ignore]
+    fn test_f32_fmt_shortest_exhaustive() {
+        for bits in 0..=0xFFFFFFFFu32 {
+            check_fmt_shortest(bits);
+        }
+    }
+}
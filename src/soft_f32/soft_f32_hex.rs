@@ -0,0 +1,396 @@
+use super::util::{
+    f32_round_and_pack,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+};
+
+use super::soft_f32_parse::{
+    Parsed,
+    ParseF32Error,
+    F32_PARSE_EMPTY, F32_PARSE_INVALID,
+    f32_parse_name,
+    f32_parsed_nan,
+    f32_round_and_pack_const,
+};
+
+use crate::soft_float::{ Env, NanPolicy, RoundingMode, with_thread_env };
+
+// Hexadecimal floating-point strings of Float32 values, as the C99 %a format and strtof.
+//
+// A finite value is printed with the hidden bit, the 23 bits of the fraction as 6 hexadecimal
+// digits without the trailing zeros, and the binary exponent. A subnormal keeps the exponent of
+// the smallest normal value, as 0x0.000002p-126. A NaN is printed as nan or snan, with its
+// payload when it is not zero, such as nan(0x1234), so that every value round-trips.
+
+// The value of a hexadecimal digit
+const fn f32_hex_digit(c: u8) -> Option<u32> {
+    match c {
+        b'0'..=b'9' => Some((c - b'0') as u32),
+        b'a'..=b'f' => Some((c - b'a' + 10) as u32),
+        b'A'..=b'F' => Some((c - b'A' + 10) as u32),
+        _ => None,
+    }
+}
+
+// Whether the bytes of S from START begin with the lowercase PREFIX, in any case
+const fn f32_hex_prefix(s: &[u8], start: usize, prefix: &[u8]) -> bool {
+    if s.len() - start < prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if s[start + i].to_ascii_lowercase() != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+// Parse nan, snan, nan(0xPAYLOAD) or snan(0xPAYLOAD) from START, None when it is not a NaN
+const fn f32_parse_hex_nan(s: &[u8], start: usize, sign: i32) -> Option<Result<Parsed, ParseF32Error>> {
+    let (is_quiet, mut i) = match (f32_hex_prefix(s, start, b"nan"), f32_hex_prefix(s, start, b"snan")) {
+        (true, _) => (true, start + 3),
+        (_, true) => (false, start + 4),
+        _ => return None,
+    };
+
+    let mut payload = 0;
+    if i < s.len() {
+        if !f32_hex_prefix(s, i, b"(0x") || s[s.len() - 1] != b')' || i + 4 >= s.len() {
+            return Some(Err(F32_PARSE_INVALID));
+        }
+        i += 3;
+        while i < s.len() - 1 {
+            match f32_hex_digit(s[i]) {
+                Some(digit) if payload <= 0x3FFFFF => payload = payload * 16 + digit,
+                _ => return Some(Err(F32_PARSE_INVALID)),
+            }
+            i += 1;
+        }
+    }
+
+    // The quiet bit is not part of the payload, and a signaling NaN needs a nonzero payload
+    if payload > 0x3FFFFF || (!is_quiet && payload == 0) {
+        return Some(Err(F32_PARSE_INVALID));
+    }
+    Some(Ok(Parsed::Nan(sign, is_quiet, payload)))
+}
+
+const fn f32_parse_hex(s: &str) -> Result<Parsed, ParseF32Error> {
+    let s = s.as_bytes();
+    if s.is_empty() {
+        return Err(F32_PARSE_EMPTY);
+    }
+
+    let mut i = 0;
+    let sign = match s[0] {
+        b'-' => 1,
+        _ => 0,
+    };
+    if s[0] == b'-' || s[0] == b'+' {
+        i += 1;
+    }
+
+    if f32_parse_name(s, i, b"inf") || f32_parse_name(s, i, b"infinity") {
+        return Ok(Parsed::Exact(f32_pack_raw(sign, 0xFF, 0)));
+    }
+    if let Some(r) = f32_parse_hex_nan(s, i, sign) {
+        return r;
+    }
+    if !f32_hex_prefix(s, i, b"0x") {
+        return Err(F32_PARSE_INVALID);
+    }
+    i += 2;
+
+    // The value is mant x 2^exp, the digits after the first 60 bits being only sticky
+    let mut mant: u64 = 0;
+    let mut exp: i64 = 0;
+    let mut sticky = false;
+    let mut any_digit = false;
+    let mut is_frac = false;
+    while i < s.len() {
+        let c = s[i];
+        if c == b'.' && !is_frac {
+            is_frac = true;
+        } else if let Some(digit) = f32_hex_digit(c) {
+            any_digit = true;
+            if mant < 1 << 56 {
+                mant = mant * 16 + digit as u64;
+                exp -= 4 * is_frac as i64;
+            } else {
+                sticky |= digit != 0;
+                exp += 4 * !is_frac as i64;
+            }
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if !any_digit {
+        return Err(F32_PARSE_INVALID);
+    }
+
+    // The binary exponent is optional, as for strtof
+    if i < s.len() && (s[i] == b'p' || s[i] == b'P') {
+        i += 1;
+        let exp_sign = match i < s.len() && s[i] == b'-' {
+            true => -1,
+            false => 1,
+        };
+        if i < s.len() && (s[i] == b'-' || s[i] == b'+') {
+            i += 1;
+        }
+        if i == s.len() {
+            return Err(F32_PARSE_INVALID);
+        }
+        // Saturate the exponent, any larger one overflows or underflows all the same
+        let mut p_exp: i64 = 0;
+        while i < s.len() && s[i].is_ascii_digit() {
+            if p_exp < 0x10000000 {
+                p_exp = p_exp * 10 + (s[i] - b'0') as i64;
+            }
+            i += 1;
+        }
+        exp += exp_sign * p_exp;
+    }
+    if i < s.len() {
+        return Err(F32_PARSE_INVALID);
+    }
+
+    if mant == 0 {
+        return Ok(Parsed::Exact(f32_pack_raw(sign, 0, 0)));
+    }
+
+    // Normalize the significand to bit 30, jamming the lost bits
+    let len = 64 - mant.leading_zeros() as i64;
+    let sig = match len > 31 {
+        true => (mant >> (len - 31)) as i32 | (mant << (95 - len) != 0 || sticky) as i32,
+        false => (mant << (31 - len)) as i32,
+    };
+    let exp = 156 + exp + len - 31;
+
+    // Beyond these exponents the result is rounded as an overflow or as a sticky bit
+    let exp = match exp {
+        exp if exp > 0x1FF => 0x1FF,
+        exp if exp < -0x100 => -0x100,
+        exp => exp as i32,
+    };
+    Ok(Parsed::Round(sign, exp, sig))
+}
+
+pub fn f32_from_hex_str(s: &str) -> Result<u32, ParseF32Error> {
+    with_thread_env(|env| f32_from_hex_str_with_env(s, env))
+}
+
+// Parse a hexadecimal string such as 0x1.99999ap-4, rounded in the rounding mode of the
+// environment when the mantissa has more than 24 significant bits. A NaN is in the encoding of
+// the NaN policy.
+pub fn f32_from_hex_str_with_env(s: &str, env: &mut Env) -> Result<u32, ParseF32Error> {
+    match f32_parse_hex(s)? {
+        Parsed::Exact(r) => Ok(r),
+        Parsed::Round(sign, exp, sig) => Ok(f32_round_and_pack(sign, exp, sig, env)),
+        Parsed::Nan(sign, is_quiet, payload) => {
+            Ok(f32_parsed_nan(sign, is_quiet, payload, env.nan_policy() == NanPolicy::MipsLegacy))
+        }
+    }
+}
+
+// Parse a hexadecimal string in a const context, rounded in ROUNDING_MODE, without flags.
+// A NaN has the IEEE 754 quiet bit.
+pub const fn f32_from_hex_str_const(s: &str, rounding_mode: RoundingMode) -> Result<u32, ParseF32Error> {
+    match f32_parse_hex(s) {
+        Ok(Parsed::Exact(r)) => Ok(r),
+        Ok(Parsed::Round(sign, exp, sig)) => Ok(f32_round_and_pack_const(sign, exp, sig, rounding_mode)),
+        Ok(Parsed::Nan(sign, is_quiet, payload)) => Ok(f32_parsed_nan(sign, is_quiet, payload, false)),
+        Err(e) => Err(e),
+    }
+}
+
+// The C99 %a form of A, such as 0x1.99999ap-4. The %A form is its uppercase.
+pub fn f32_to_hex_string(a: u32) -> String {
+    let sign = if f32_sign(a) == 1 { "-" } else { "" };
+    let exp = f32_exp(a);
+    let frac = f32_frac(a) as u32;
+
+    match (exp, frac) {
+        (0xFF, 0) => format!("{}inf", sign),
+        (0xFF, _) => {
+            let name = if (frac & 0x400000) != 0 { "nan" } else { "snan" };
+            match frac & 0x3FFFFF {
+                0 => format!("{}{}", sign, name),
+                payload => format!("{}{}(0x{:x})", sign, name, payload),
+            }
+        }
+        (0, 0) => format!("{}0x0p+0", sign),
+        _ => {
+            let (lead, exp) = match exp {
+                0 => (0, -126),
+                _ => (1, exp - 127),
+            };
+            let digits = format!("{:06x}", frac << 1);
+            let digits = digits.trim_end_matches('0');
+            let point = if digits.is_empty() { "" } else { "." };
+            format!("{}0x{}{}{}p{:+}", sign, lead, point, digits, exp)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    // Parse in a rounding mode, also checking the const parsing
+    fn parse(s: &str, rounding_mode: RoundingMode) -> (u32, ExceptionFlags) {
+        let mut env = Env::default();
+        env.set_rounding_mode(rounding_mode);
+        let r = crate::soft_f32::f32_from_hex_str_with_env(s, &mut env).unwrap();
+        assert_eq!(crate::soft_f32::f32_from_hex_str_const(s, rounding_mode), Ok(r));
+        (r, env.flags())
+    }
+
+    #[test]
+    fn test_f32_to_hex_string() {
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x3DCCCCCD), "0x1.99999ap-4");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x3F800000), "0x1p+0");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0xC0400000), "-0x1.8p+1");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x7F7FFFFF), "0x1.fffffep+127");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x00800000), "0x1p-126");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x00000000), "0x0p+0");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x80000000), "-0x0p+0");
+        // Subnormals
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x00000001), "0x0.000002p-126");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x807FFFFF), "-0x0.fffffep-126");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x00400000), "0x0.8p-126");
+        // Infinities and NaNs
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x7F800000), "inf");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0xFF800000), "-inf");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x7FC00000), "nan");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0xFFC00000), "-nan");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x7FC01234), "nan(0x1234)");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0x7F800001), "snan(0x1)");
+        assert_eq!(crate::soft_f32::f32_to_hex_string(0xFFBFFFFF), "-snan(0x3fffff)");
+    }
+
+    #[test]
+    fn test_f32_from_hex_str() {
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0x1.99999ap-4"), Ok(0x3DCCCCCD));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0X1.99999AP-4"), Ok(0x3DCCCCCD));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("-0x1.8p1"), Ok(0xC0400000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("+0x3p+0"), Ok(0x40400000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0x.8"), Ok(0x3F000000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0x10."), Ok(0x41800000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0x0.000002p-126"), Ok(0x00000001));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0x000.000p99999999999999999999"), Ok(0x00000000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("-0x0p+0"), Ok(0x80000000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("-Infinity"), Ok(0xFF800000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("NaN"), Ok(0x7FC00000));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("-nan(0x1234)"), Ok(0xFFC01234));
+        assert_eq!(crate::soft_f32::f32_from_hex_str("sNaN(0X3FFFFF)"), Ok(0x7FBFFFFF));
+    }
+
+    #[test]
+    fn test_f32_from_hex_str_nan_policy() {
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::RiscV);
+        assert_eq!(crate::soft_f32::f32_from_hex_str_with_env("nan(0x1234)", &mut env), Ok(0x7FC01234));
+        assert_eq!(crate::soft_f32::f32_from_hex_str_with_env("snan(0x1)", &mut env), Ok(0x7F800001));
+
+        // The quiet bit is inverted in MIPS legacy, so a quiet NaN without a payload is its default NaN
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_f32::f32_from_hex_str_with_env("nan", &mut env), Ok(0x7FBFFFFF));
+        assert_eq!(crate::soft_f32::f32_from_hex_str_with_env("-nan(0x1234)", &mut env), Ok(0xFF801234));
+        assert_eq!(crate::soft_f32::f32_from_hex_str_with_env("snan(0x1)", &mut env), Ok(0x7FC00001));
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_from_hex_str_error() {
+        let empty = crate::soft_f32::f32_from_hex_str("").unwrap_err();
+        let invalid = crate::soft_f32::f32_from_hex_str("x").unwrap_err();
+        assert_eq!(empty.to_string(), "cannot parse float from empty string");
+        assert_eq!(invalid.to_string(), "invalid float literal");
+
+        for s in ["1.5", "0x", "-0x.", "0xp1", "0x1p", "0x1p+", "0x1g", "0x1.2.3", " 0x1", "0x1 ", "x1",
+                  "snan", "nan(", "nan()", "nan(0x)", "nan(12)", "nan(0x400000)", "nan(0x1)x"] {
+            assert_eq!(crate::soft_f32::f32_from_hex_str(s), Err(invalid), "{:?}", s);
+            assert_eq!(crate::soft_f32::f32_from_hex_str_const(s, RoundingMode::NearEven), Err(invalid));
+        }
+    }
+
+    #[test]
+    fn test_f32_from_hex_str_rounding_mode() {
+        let modes = [
+            RoundingMode::NearEven,
+            RoundingMode::NearMaxMag,
+            RoundingMode::MinMag,
+            RoundingMode::Min,
+            RoundingMode::Max,
+            RoundingMode::Odd,
+        ];
+
+        // 1 + 2^-24 is the midpoint between 1 and the next value
+        let expected = [0x3F800000, 0x3F800001, 0x3F800000, 0x3F800000, 0x3F800001, 0x3F800001];
+        for (&rounding_mode, &r) in modes.iter().zip(expected.iter()) {
+            assert_eq!(parse("0x1.000001p0", rounding_mode), (r, ExceptionFlags::INEXACT));
+        }
+
+        // Just above the midpoint, with the nonzero bit far after it
+        let expected = [0xBF800001, 0xBF800001, 0xBF800000, 0xBF800001, 0xBF800000, 0xBF800001];
+        for (&rounding_mode, &r) in modes.iter().zip(expected.iter()) {
+            assert_eq!(parse("-0x1.0000010000000000000000000000001", rounding_mode), (r, ExceptionFlags::INEXACT));
+        }
+
+        // 24 significant bits are exact however they are written
+        for &rounding_mode in modes.iter() {
+            assert_eq!(parse("0x0.000001fffffe00000000p+47", rounding_mode), (0x4B7FFFFF, ExceptionFlags::empty()));
+            assert_eq!(parse("0xffffff", rounding_mode), (0x4B7FFFFF, ExceptionFlags::empty()));
+        }
+    }
+
+    #[test]
+    fn test_f32_from_hex_str_flags() {
+        // Overflow
+        assert_eq!(parse("0x1p128", RoundingMode::NearEven), (0x7F800000, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("0x1.ffffffp127", RoundingMode::NearEven), (0x7F800000, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("0x1.ffffffp127", RoundingMode::MinMag), (0x7F7FFFFF, ExceptionFlags::INEXACT));
+        assert_eq!(parse("-0x1p99999999999999999999", RoundingMode::Max), (0xFF7FFFFF, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT));
+
+        // Underflow
+        assert_eq!(parse("0x1p-150", RoundingMode::NearEven), (0x00000000, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("0x1.8p-149", RoundingMode::NearEven), (0x00000002, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("0x1p-99999999999999999999", RoundingMode::Max), (0x00000001, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("0x1.fffffcp-127", RoundingMode::NearEven), (0x007FFFFF, ExceptionFlags::empty()));
+
+        // Parsing raises the flags of the thread environment
+        crate::soft_float::clear_exception_flags();
+        assert_eq!(crate::soft_f32::f32_from_hex_str("0x1.0000001p0"), Ok(0x3F800000));
+        assert_eq!(crate::soft_float::get_exception_flags(), ExceptionFlags::INEXACT);
+        crate::soft_float::clear_exception_flags();
+    }
+
+    #[test]
+    fn test_f32_from_hex_str_const() {
+        const V0_1: u32 = match crate::soft_f32::f32_from_hex_str_const("0x1.99999ap-4", RoundingMode::NearEven) {
+            Ok(r) => r,
+            Err(_) => panic!(),
+        };
+        assert_eq!(V0_1, 0x3DCCCCCD);
+    }
+
+    #[test]
+    fn test_f32_hex_round_trip() {
+        // Every value, NaNs included, parses back from its hexadecimal string
+        let mut a = 0u32;
+        loop {
+            let s = crate::soft_f32::f32_to_hex_string(a);
+            assert_eq!(parse(&s, RoundingMode::Odd), (a, ExceptionFlags::empty()), "{}", s);
+            assert_eq!(parse(&s.to_uppercase(), RoundingMode::Odd).0, a);
+            a = match a.checked_add(0x10001) {
+                Some(a) => a,
+                None => break,
+            };
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_sign,
+    f32_pack_raw,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_exp,
+    fixed_mul,
+    fixed_square,
+    fixed_sqrt,
+    fixed_ln_1p,
+    fixed_ln_abs,
+    fixed_odd_series,
+    fixed_reduce_ln2,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_ONE,
+    FIXED_TINY_SQUARE,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Negate the fixed-point value when the sign is set
+fn fixed_with_sign(sign: i32, v: i128) -> i128 {
+    if sign == 1 { -v } else { v }
+}
+
+// e^|x| and e^-|x| for |x| = m x 2^e >= 1, as (n, p, q) where e^|x| = p x 2^n and
+// e^-|x| = q x 2^-n. |x| is clamped to 256, where e^|x| already overflows.
+fn fixed_exp_pair(m: i128, e: i32) -> (i32, i128, i128) {
+    let y = if e >= 8 { 256 * FIXED_ONE } else { m << e };
+    let (n, r) = fixed_reduce_ln2(y);
+    let p = fixed_exp(r);
+
+    (n, p, (FIXED_ONE << FIXED_FRAC_BITS) / p)
+}
+
+// sinh(t) = t + t^3 / 3! + t^5 / 5! + ... for t scaled by 2^k, with the scale of t. |t| < 1.
+fn fixed_sinh_series(t: i128, k: i32) -> i128 {
+    let t2 = fixed_square(t, k);
+    if t2 < FIXED_TINY_SQUARE {
+        return t + t.signum();
+    }
+
+    let mut sum = t;
+    let mut term = t;
+    for n in 1..=16 {
+        term = fixed_mul(term, t2) / (2 * n * (2 * n + 1));
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    sum
+}
+
+// cosh(t) = 1 + t^2 / 2! + t^4 / 4! + ... for |t| < 1, given t^2
+fn fixed_cosh_series(t2: i128) -> i128 {
+    let mut sum = FIXED_ONE;
+    let mut term = FIXED_ONE;
+    for n in 1..=16 {
+        term = fixed_mul(term, t2) / ((2 * n - 1) * (2 * n));
+        if term == 0 {
+            break;
+        }
+        sum += term;
+    }
+
+    sum
+}
+
+pub fn f32_sinh(a: u32) -> u32 {
+    with_thread_env(|env| f32_sinh_with_env(a, env))
+}
+
+// Hyperbolic sine. The result is within 0.5 ULP plus the 2^-56 relative error of the evaluation,
+// that is correctly rounded but for hard cases.
+pub fn f32_sinh_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_sinh_unflushed(a, env), env)
+}
+
+fn f32_sinh_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 || a & 0x7FFFFFFF == 0x7F800000 {
+        // sinh(0) = 0 and sinh(Inf) = Inf
+        return a;
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    if e < 0 {
+        let v = fixed_sinh_series(m, -e);
+        return f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS + e, false, env);
+    }
+
+    // sinh(x) = (e^x - e^-x) / 2
+    let (n, p, q) = fixed_exp_pair(m, e);
+    let v = p - q.checked_shr(2 * n as u32).unwrap_or(0);
+    f32_round_fixed(fixed_with_sign(a_sign, v), n - 1 - FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_cosh(a: u32) -> u32 {
+    with_thread_env(|env| f32_cosh_with_env(a, env))
+}
+
+// Hyperbolic cosine, with the accuracy of f32_sinh. It is exact for 0 only.
+pub fn f32_cosh_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_cosh_unflushed(a, env), env)
+}
+
+fn f32_cosh_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 {
+        // cosh(0) = 1
+        return f32_pack_raw(0, 0x7F, 0);
+    }
+    if a & 0x7FFFFFFF == 0x7F800000 {
+        // cosh(-Inf) = cosh(Inf) = Inf
+        return a & 0x7FFFFFFF;
+    }
+
+    let (_, m, e) = f32_to_fixed_parts(a);
+    if e < 0 {
+        let v = fixed_cosh_series(fixed_square(m, -e));
+        return f32_round_fixed(v, -FIXED_FRAC_BITS, false, env);
+    }
+
+    // cosh(x) = (e^x + e^-x) / 2
+    let (n, p, q) = fixed_exp_pair(m, e);
+    let v = p + q.checked_shr(2 * n as u32).unwrap_or(0);
+    f32_round_fixed(v, n - 1 - FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_tanh(a: u32) -> u32 {
+    with_thread_env(|env| f32_tanh_with_env(a, env))
+}
+
+// Hyperbolic tangent, in [-1, 1], with the accuracy of f32_sinh. It is exact for 0 and
+// infinities only.
+pub fn f32_tanh_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_tanh_unflushed(a, env), env)
+}
+
+fn f32_tanh_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 {
+        // Zero
+        return a;
+    }
+    if a & 0x7FFFFFFF == 0x7F800000 {
+        // tanh(Inf) = 1
+        return f32_pack_raw((a >> 31) as i32, 0x7F, 0);
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    if e < 0 {
+        let k = -e;
+        if fixed_square(m, k) < FIXED_TINY_SQUARE {
+            // tanh(x) is just below x
+            return f32_round_fixed(fixed_with_sign(a_sign, m - 1), -FIXED_FRAC_BITS - k, false, env);
+        }
+        let s = fixed_sinh_series(m, k);
+        let c = fixed_cosh_series(fixed_square(m, k));
+        let v = (s << FIXED_FRAC_BITS) / c;
+        return f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    // tanh(x) = (e^x - e^-x) / (e^x + e^-x), which is below 1
+    let (n, p, q) = fixed_exp_pair(m, e);
+    let q = q.checked_shr(2 * n as u32).unwrap_or(0);
+    let v = (((p - q) << FIXED_FRAC_BITS) / (p + q)).min(FIXED_ONE - 1);
+    f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_asinh(a: u32) -> u32 {
+    with_thread_env(|env| f32_asinh_with_env(a, env))
+}
+
+// Inverse hyperbolic sine, with the accuracy of f32_sinh
+pub fn f32_asinh_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_asinh_unflushed(a, env), env)
+}
+
+fn f32_asinh_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 || a & 0x7FFFFFFF == 0x7F800000 {
+        // asinh(0) = 0 and asinh(Inf) = Inf
+        return a;
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    if e < -1 {
+        // |A| < 1/2, asinh(x) = x - (1/2) x^3 / 3 + (1/2 x 3/4) x^5 / 5 - ...
+        let k = -e;
+        let t2 = fixed_square(m, k);
+        if t2 < FIXED_TINY_SQUARE {
+            // asinh(x) is just below x
+            return f32_round_fixed(fixed_with_sign(a_sign, m - 1), -FIXED_FRAC_BITS - k, false, env);
+        }
+
+        let mut sum = m;
+        let mut power = m;
+        for n in 1..=40 {
+            power = -fixed_mul(power, t2) * (2 * n - 1) / (2 * n);
+            if power == 0 {
+                break;
+            }
+            sum += power / (2 * n + 1);
+        }
+        return f32_round_fixed(fixed_with_sign(a_sign, sum), -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    // asinh(|x|) = ln(|x| + sqrt(x^2 + 1)), with x / 2 in [1/2, 1) and 1 / 4 scaled to match
+    let half = m >> 1;
+    let quarter = (FIXED_ONE << FIXED_FRAC_BITS).checked_shr((2 * e + 2) as u32).unwrap_or(0);
+    let (s, k) = fixed_sqrt(half * half + quarter);
+    let v = fixed_ln_abs(half + (s >> k), e + 1);
+    f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_acosh(a: u32) -> u32 {
+    with_thread_env(|env| f32_acosh_with_env(a, env))
+}
+
+// Inverse hyperbolic cosine, with the accuracy of f32_sinh. It is invalid below 1, and exact
+// for 1 only.
+pub fn f32_acosh_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_acosh_unflushed(a, env), env)
+}
+
+fn f32_acosh_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if f32_sign(a) == 1 || a < 0x3F800000 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
+    }
+    if a == 0x3F800000 {
+        // acosh(1) = 0
+        return f32_pack_raw(0, 0, 0);
+    }
+    if a == 0x7F800000 {
+        // acosh(Inf) = Inf
+        return a;
+    }
+
+    let (_, m, e) = f32_to_fixed_parts(a);
+    if e == 0 && m - FIXED_ONE <= FIXED_ONE / 20 {
+        // acosh(1 + d) = ln(1 + d + sqrt(d x (2 + d))), where d is exact
+        let d = m - FIXED_ONE;
+        let (s, k) = fixed_sqrt(d * (2 * FIXED_ONE + d));
+        let v = fixed_ln_1p(s + (d << k), k);
+        return f32_round_fixed(v, -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    // acosh(x) = ln(x + sqrt(x^2 - 1)), with x / 2 in [1/2, 1) and 1 / 4 scaled to match
+    let half = m >> 1;
+    let quarter = (FIXED_ONE << FIXED_FRAC_BITS).checked_shr((2 * e + 2) as u32).unwrap_or(0);
+    let (s, k) = fixed_sqrt(half * half - quarter);
+    let v = fixed_ln_abs(half + (s >> k), e + 1);
+    f32_round_fixed(v, -FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_atanh(a: u32) -> u32 {
+    with_thread_env(|env| f32_atanh_with_env(a, env))
+}
+
+// Inverse hyperbolic tangent, with the accuracy of f32_sinh. It is invalid above 1 in magnitude,
+// and raises DIVIDE_BY_ZERO for 1 in magnitude.
+pub fn f32_atanh_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_atanh_unflushed(a, env), env)
+}
+
+fn f32_atanh_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 {
+        // Zero
+        return a;
+    }
+    if a & 0x7FFFFFFF > 0x3F800000 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
+    }
+    if a & 0x7FFFFFFF == 0x3F800000 {
+        // atanh(1) = Inf
+        env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+        return f32_pack_raw((a >> 31) as i32, 0xFF, 0);
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    if e < -2 {
+        // |A| < 1/4, atanh(x) = x + x^3 / 3 + x^5 / 5 + ...
+        let v = fixed_odd_series(m, -e, false);
+        return f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS + e, false, env);
+    }
+
+    // atanh(|x|) = ln((1 + |x|) / (1 - |x|)) / 2, where 1 +- |x| are exact
+    let x = m >> -e;
+    let v = fixed_ln_abs(((FIXED_ONE + x) << FIXED_FRAC_BITS) / (FIXED_ONE - x), 0);
+    f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS - 1, false, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f32_sinh() {
+        // sinh(1) = 1.1752012
+        assert_eq!(crate::soft_f32::f32_sinh(0x3F800000), 0x3F966CFE);
+        // sinh(-0.5) = -0.5210953
+        assert_eq!(crate::soft_f32::f32_sinh(0xBF000000), 0xBF056680);
+        // sinh(89) = 2.2448064e38
+        assert_eq!(crate::soft_f32::f32_sinh(0x42B20000), 0x7F28E166);
+
+        // sinh(-0) = -0
+        assert_eq!(crate::soft_f32::f32_sinh(0x80000000), 0x80000000);
+        // sinh(-Inf) = -Inf
+        assert_eq!(crate::soft_f32::f32_sinh(0xFF800000), 0xFF800000);
+    }
+
+    #[test]
+    fn test_f32_cosh() {
+        // cosh(1) = 1.5430807
+        assert_eq!(crate::soft_f32::f32_cosh(0x3F800000), 0x3FC583AB);
+        // cosh(0.5) = 1.127626
+        assert_eq!(crate::soft_f32::f32_cosh(0x3F000000), 0x3F90560C);
+        // cosh(89) = 2.2448064e38
+        assert_eq!(crate::soft_f32::f32_cosh(0x42B20000), 0x7F28E166);
+
+        // cosh(-0) = 1
+        assert_eq!(crate::soft_f32::f32_cosh(0x80000000), 0x3F800000);
+        // cosh(-Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_cosh(0xFF800000), 0x7F800000);
+    }
+
+    #[test]
+    fn test_f32_tanh() {
+        // tanh(1) = 0.7615942
+        assert_eq!(crate::soft_f32::f32_tanh(0x3F800000), 0x3F42F7D6);
+        // tanh(-0.5) = -0.46211717
+        assert_eq!(crate::soft_f32::f32_tanh(0xBF000000), 0xBEEC9A9F);
+        // tanh(10) = 1
+        assert_eq!(crate::soft_f32::f32_tanh(0x41200000), 0x3F800000);
+
+        // tanh(-0) = -0
+        assert_eq!(crate::soft_f32::f32_tanh(0x80000000), 0x80000000);
+        // tanh(-Inf) = -1
+        assert_eq!(crate::soft_f32::f32_tanh(0xFF800000), 0xBF800000);
+    }
+
+    #[test]
+    fn test_f32_asinh() {
+        // asinh(1) = 0.8813736
+        assert_eq!(crate::soft_f32::f32_asinh(0x3F800000), 0x3F61A1B3);
+        // asinh(-0.5) = -0.4812118
+        assert_eq!(crate::soft_f32::f32_asinh(0xBF000000), 0xBEF66165);
+        // asinh(3.4028235e38) = 89.415985
+        assert_eq!(crate::soft_f32::f32_asinh(0x7F7FFFFF), 0x42B2D4FC);
+
+        // asinh(-0) = -0
+        assert_eq!(crate::soft_f32::f32_asinh(0x80000000), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_acosh() {
+        // acosh(2) = 1.316958
+        assert_eq!(crate::soft_f32::f32_acosh(0x40000000), 0x3FA89214);
+        // acosh(1.0000001) = 0.00048828125
+        assert_eq!(crate::soft_f32::f32_acosh(0x3F800001), 0x3A000000);
+        // acosh(3.4028235e38) = 89.415985
+        assert_eq!(crate::soft_f32::f32_acosh(0x7F7FFFFF), 0x42B2D4FC);
+
+        // acosh(1) = 0
+        assert_eq!(crate::soft_f32::f32_acosh(0x3F800000), 0x00000000);
+        // acosh(Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_acosh(0x7F800000), 0x7F800000);
+    }
+
+    #[test]
+    fn test_f32_atanh() {
+        // atanh(0.5) = 0.54930615
+        assert_eq!(crate::soft_f32::f32_atanh(0x3F000000), 0x3F0C9F54);
+        // atanh(-0.25) = -0.25541282
+        assert_eq!(crate::soft_f32::f32_atanh(0xBE800000), 0xBE82C578);
+        // atanh(0.99999994) = 8.66434
+        assert_eq!(crate::soft_f32::f32_atanh(0x3F7FFFFF), 0x410AA123);
+
+        // atanh(-0) = -0
+        assert_eq!(crate::soft_f32::f32_atanh(0x80000000), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_hyper_exception_flags() {
+        // sinh(100) = Inf, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x42C80000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // acosh(0.5) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_acosh_with_env(0x3F000000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // atanh(2) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_atanh_with_env(0x40000000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // atanh(-1) = -Inf, divide by zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_atanh_with_env(0xBF800000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // cosh(0) = 1, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_cosh_with_env(0x00000000, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // tanh(NaN) = NaN
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x7FC00001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // tanh(1), inexact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F800000, &mut env), 0x3F42F7D6);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_hyper_rounding_mode() {
+        // sinh(1)
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x3F966CFE);
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x3F966CFE);
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x3F800000, &mut Env::new(RoundingMode::Min)), 0x3F966CFE);
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x3F800000, &mut Env::new(RoundingMode::Max)), 0x3F966CFF);
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F966CFE);
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x3F966CFF);
+
+        // tanh(0.5)
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F000000, &mut Env::new(RoundingMode::NearEven)), 0x3EEC9A9F);
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F000000, &mut Env::new(RoundingMode::MinMag)), 0x3EEC9A9E);
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F000000, &mut Env::new(RoundingMode::Min)), 0x3EEC9A9E);
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F000000, &mut Env::new(RoundingMode::Max)), 0x3EEC9A9F);
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3EEC9A9F);
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x3F000000, &mut Env::new(RoundingMode::Odd)), 0x3EEC9A9F);
+
+        // sinh(1e-20) is just above 1e-20
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x1E3CE508);
+        assert_eq!(crate::soft_f32::f32_sinh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x1E3CE509);
+
+        // tanh(1e-20) and asinh(1e-20) are just below 1e-20
+        assert_eq!(crate::soft_f32::f32_tanh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x1E3CE507);
+        assert_eq!(crate::soft_f32::f32_asinh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x1E3CE507);
+
+        // atanh(1e-20) is just above 1e-20
+        assert_eq!(crate::soft_f32::f32_atanh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x1E3CE509);
+
+        // cosh(1e-20) is just above 1
+        assert_eq!(crate::soft_f32::f32_cosh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_cosh_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x3F800001);
+    }
+}
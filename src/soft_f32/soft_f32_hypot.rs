@@ -0,0 +1,137 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_is_signaling_nan_in,
+    f32_pack_raw,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_sqrt,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f32_hypot(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_hypot_with_env(a, b, env))
+}
+
+// sqrt(A^2 + B^2), correctly rounded. The sum of squares is exact, so that there is no
+// intermediate overflow or underflow. An infinity gives infinity, even with a quiet NaN.
+pub fn f32_hypot_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| f32_hypot_unflushed(a, b, env), env)
+}
+
+fn f32_hypot_unflushed(a: u32, b: u32, env: &mut Env) -> u32 {
+    let mut a_abs = a & 0x7FFFFFFF;
+    let mut b_abs = b & 0x7FFFFFFF;
+
+    if a_abs == 0x7F800000 || b_abs == 0x7F800000 {
+        if f32_is_signaling_nan_in(a, env) || f32_is_signaling_nan_in(b, env) {
+            // Invalid
+            env.raise(ExceptionFlags::INVALID);
+        }
+        return f32_pack_raw(0, 0xFF, 0);
+    }
+    if f32_is_nan(a) || f32_is_nan(b) {
+        // Propagate NaN
+        return f32_propagate_nan(a, b, env);
+    }
+
+    if a_abs < b_abs {
+        (a_abs, b_abs) = (b_abs, a_abs);
+    }
+    if b_abs == 0 {
+        // Zero, the result is an exact |A|
+        return a_abs;
+    }
+
+    let (_, a_m, a_e) = f32_to_fixed_parts(a_abs);
+    let (_, b_m, b_e) = f32_to_fixed_parts(b_abs);
+    let a_frac = a_m >> 39;
+    let b_frac = b_m >> 39;
+
+    let dist = a_e - b_e;
+    if dist > 36 {
+        // B^2 is below 2^-70 of A^2, the result is just above |A|
+        return f32_round_fixed(a_frac, a_e - 23, false, env);
+    }
+
+    // (A^2 + B^2) / 2^(2 b_e - 46), exact in 120 bits
+    let sum = ((a_frac * a_frac) << (2 * dist)) + b_frac * b_frac;
+    let (root, k) = fixed_sqrt(sum);
+    let is_exact = root * root == sum << (2 * k);
+
+    f32_round_fixed(root, b_e - 23 - k, is_exact, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f32_hypot() {
+        // hypot(3, 4) = 5
+        assert_eq!(crate::soft_f32::f32_hypot(0x40400000, 0x40800000), 0x40A00000);
+        // hypot(-3, 4) = 5
+        assert_eq!(crate::soft_f32::f32_hypot(0xC0400000, 0x40800000), 0x40A00000);
+        // hypot(1, 1) = 1.4142135
+        assert_eq!(crate::soft_f32::f32_hypot(0x3F800000, 0x3F800000), 0x3FB504F3);
+        // hypot(1.7014118e38, 1.7014118e38) = 2.4061597e38, without overflow
+        assert_eq!(crate::soft_f32::f32_hypot(0x7F000000, 0x7F000000), 0x7F3504F3);
+        // hypot(2^-149, 2^-149) = 2^-149
+        assert_eq!(crate::soft_f32::f32_hypot(0x00000001, 0x00000001), 0x00000001);
+
+        // hypot(-1, 0) = 1
+        assert_eq!(crate::soft_f32::f32_hypot(0xBF800000, 0x00000000), 0x3F800000);
+        // hypot(-0, -0) = 0
+        assert_eq!(crate::soft_f32::f32_hypot(0x80000000, 0x80000000), 0x00000000);
+    }
+
+    #[test]
+    fn test_f32_hypot_inf_nan() {
+        // hypot(-Inf, 1) = Inf
+        assert_eq!(crate::soft_f32::f32_hypot(0xFF800000, 0x3F800000), 0x7F800000);
+        // hypot(NaN, -Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_hypot(0x7FC00001, 0xFF800000), 0x7F800000);
+        // hypot(NaN, 1) = NaN
+        assert_eq!(crate::soft_f32::f32_hypot(0x7FC00001, 0x3F800000), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_hypot_exception_flags() {
+        // hypot(3, 4) = 5, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x40400000, 0x40800000, &mut env), 0x40A00000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // hypot(3.4028235e38, 3.4028235e38) = Inf, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x7F7FFFFF, 0x7F7FFFFF, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // hypot(sNaN, Inf) = Inf, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x7F800001, 0x7F800000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_hypot_rounding_mode() {
+        // hypot(1, 1)
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x3F800000, &mut Env::new(RoundingMode::Min)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x3F800000, &mut Env::new(RoundingMode::Max)), 0x3FB504F4);
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x3FB504F3);
+
+        // hypot(1, 2^-100) is just above 1
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x0D800000, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_hypot_with_env(0x3F800000, 0x0D800000, &mut Env::new(RoundingMode::Max)), 0x3F800001);
+    }
+}
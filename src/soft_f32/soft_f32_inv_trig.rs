@@ -0,0 +1,426 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_pack_raw,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_atan,
+    fixed_sqrt,
+    fixed_square,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_ONE,
+    FIXED_PI, FIXED_PI_2, FIXED_PI_4,
+    FIXED_TINY_SQUARE,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Negate the fixed-point value when the sign is set
+fn fixed_with_sign(sign: i32, v: i128) -> i128 {
+    if sign == 1 { -v } else { v }
+}
+
+// The value v scaled by 2^k, without scale
+fn fixed_unscale(v: i128, k: i32) -> i128 {
+    v.checked_shr(k as u32).unwrap_or(0)
+}
+
+// atan(p / q) for 0 < p <= q, where p = p_m x 2^p_exp and q = q_m x 2^q_exp with fixed-point
+// p_m and q_m in [1, 2). The result is returned as (v, k) where v is scaled by 2^k.
+fn fixed_atan_ratio(p_m: i128, p_exp: i32, q_m: i128, q_exp: i32) -> (i128, i32) {
+    // p_m / q_m is in (1/2, 2)
+    fixed_atan((p_m << FIXED_FRAC_BITS) / q_m, q_exp - p_exp)
+}
+
+// sqrt(1 - x^2) for |x| <= 1, where |x| = m x 2^e. The result is returned as (s, s_exp) with
+// sqrt(1 - x^2) = s x 2^s_exp and a fixed-point s in [1, 2), along with whether it is less than |x|.
+fn fixed_sqrt_one_minus_square(m: i128, e: i32) -> (i128, i32, bool) {
+    // 1 - x^2 with 124 fraction bits, exact when x^2 is not below the error of the evaluation
+    let x2 = (m * m).checked_shr((-2 * e) as u32).unwrap_or(0);
+    let one_minus = (FIXED_ONE << FIXED_FRAC_BITS) - x2;
+    let (s, k) = fixed_sqrt(one_minus);
+
+    // fixed_sqrt leaves s in [2^62, 2^63)
+    (s, -k, x2 > one_minus)
+}
+
+pub fn f32_atan(a: u32) -> u32 {
+    with_thread_env(|env| f32_atan_with_env(a, env))
+}
+
+// Arctangent, in [-pi / 2, pi / 2]. The result is within 0.5 ULP plus the 2^-56 relative
+// error of the evaluation, that is correctly rounded but for hard cases.
+pub fn f32_atan_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_atan_unflushed(a, env), env)
+}
+
+fn f32_atan_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 {
+        // Zero
+        return a;
+    }
+    let a_sign = (a >> 31) as i32;
+    if a & 0x7FFFFFFF == 0x7F800000 {
+        // atan(Inf) = pi / 2
+        return f32_round_fixed(fixed_with_sign(a_sign, FIXED_PI_2), -FIXED_FRAC_BITS, false, env);
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    if e < 0 || (m == FIXED_ONE && e == 0) {
+        // |A| <= 1
+        let (v, k) = fixed_atan(m, -e);
+        return f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    // atan(x) = pi / 2 - atan(1 / x)
+    let (v, k) = fixed_atan((FIXED_ONE << FIXED_FRAC_BITS) / m, e);
+    let r = FIXED_PI_2 - fixed_unscale(v, k);
+    f32_round_fixed(fixed_with_sign(a_sign, r), -FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_atan2(y: u32, x: u32) -> u32 {
+    with_thread_env(|env| f32_atan2_with_env(y, x, env))
+}
+
+// The angle of the point (X, Y), in [-pi, pi], with the special cases of C99 Annex F:
+// atan2(+-0, x) is +-0 for x = +0 or x > 0 and +-pi for x = -0 or x < 0, atan2(y, +-0) is
+// +-pi / 2 for y != 0, and infinities give multiples of pi / 4.
+pub fn f32_atan2_with_env(y: u32, x: u32, env: &mut Env) -> u32 {
+    let y = f32_flush_input(y, env);
+    let x = f32_flush_input(x, env);
+    f32_flush_result(|env| f32_atan2_unflushed(y, x, env), env)
+}
+
+fn f32_atan2_unflushed(y: u32, x: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(y) || f32_is_nan(x) {
+        // Propagate NaN
+        return f32_propagate_nan(y, x, env);
+    }
+
+    let y_sign = (y >> 31) as i32;
+    let x_sign = (x >> 31) as i32;
+    let y_abs = y & 0x7FFFFFFF;
+    let x_abs = x & 0x7FFFFFFF;
+
+    // Multiple of pi / 4 with the sign of y
+    let round_angle = |angle: i128, env: &mut Env| {
+        f32_round_fixed(fixed_with_sign(y_sign, angle), -FIXED_FRAC_BITS, false, env)
+    };
+
+    if y_abs == 0 {
+        // Zero
+        return match x_sign {
+            0 => f32_pack_raw(y_sign, 0, 0),
+            _ => round_angle(FIXED_PI, env),
+        };
+    }
+    if x_abs == 0 {
+        return round_angle(FIXED_PI_2, env);
+    }
+    if y_abs == 0x7F800000 {
+        return match (x_abs == 0x7F800000, x_sign) {
+            (true, 0) => round_angle(FIXED_PI_4, env),
+            (true, _) => round_angle(FIXED_PI_4 * 3, env),
+            _ => round_angle(FIXED_PI_2, env),
+        };
+    }
+    if x_abs == 0x7F800000 {
+        return match x_sign {
+            0 => f32_pack_raw(y_sign, 0, 0),
+            _ => round_angle(FIXED_PI, env),
+        };
+    }
+
+    let (_, y_m, y_e) = f32_to_fixed_parts(y);
+    let (_, x_m, x_e) = f32_to_fixed_parts(x);
+
+    if y_abs <= x_abs {
+        // atan(|y| / |x|), which is in [0, pi / 4]
+        let (v, k) = fixed_atan_ratio(y_m, y_e, x_m, x_e);
+        if x_sign == 0 {
+            return f32_round_fixed(fixed_with_sign(y_sign, v), -FIXED_FRAC_BITS - k, false, env);
+        }
+        return round_angle(FIXED_PI - fixed_unscale(v, k), env);
+    }
+
+    // pi / 2 -+ atan(|x| / |y|)
+    let (v, k) = fixed_atan_ratio(x_m, x_e, y_m, y_e);
+    match x_sign {
+        0 => round_angle(FIXED_PI_2 - fixed_unscale(v, k), env),
+        _ => round_angle(FIXED_PI_2 + fixed_unscale(v, k), env),
+    }
+}
+
+pub fn f32_asin(a: u32) -> u32 {
+    with_thread_env(|env| f32_asin_with_env(a, env))
+}
+
+// Arcsine, in [-pi / 2, pi / 2], with the accuracy of f32_atan. It is invalid above 1 in magnitude.
+pub fn f32_asin_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_asin_unflushed(a, env), env)
+}
+
+fn f32_asin_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 {
+        // Zero
+        return a;
+    }
+    if a & 0x7FFFFFFF > 0x3F800000 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    if fixed_square(m, -e) < FIXED_TINY_SQUARE {
+        // asin(x) is just above x
+        return f32_round_fixed(fixed_with_sign(a_sign, m + 1), -FIXED_FRAC_BITS + e, false, env);
+    }
+
+    // asin(x) = atan(x / sqrt(1 - x^2))
+    let (s, s_exp, is_s_less) = fixed_sqrt_one_minus_square(m, e);
+    if s == 0 {
+        // asin(1) = pi / 2
+        return f32_round_fixed(fixed_with_sign(a_sign, FIXED_PI_2), -FIXED_FRAC_BITS, false, env);
+    }
+    if !is_s_less {
+        let (v, k) = fixed_atan_ratio(m, e, s, s_exp);
+        return f32_round_fixed(fixed_with_sign(a_sign, v), -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    let (v, k) = fixed_atan_ratio(s, s_exp, m, e);
+    let r = FIXED_PI_2 - fixed_unscale(v, k);
+    f32_round_fixed(fixed_with_sign(a_sign, r), -FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_acos(a: u32) -> u32 {
+    with_thread_env(|env| f32_acos_with_env(a, env))
+}
+
+// Arccosine, in [0, pi], with the accuracy of f32_atan. It is invalid above 1 in magnitude,
+// and exact for 1 only.
+pub fn f32_acos_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_acos_unflushed(a, env), env)
+}
+
+fn f32_acos_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 {
+        // acos(0) = pi / 2
+        return f32_round_fixed(FIXED_PI_2, -FIXED_FRAC_BITS, false, env);
+    }
+    if a & 0x7FFFFFFF > 0x3F800000 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
+    }
+    if a == 0x3F800000 {
+        // acos(1) = 0
+        return f32_pack_raw(0, 0, 0);
+    }
+
+    // acos(|x|) = atan(sqrt(1 - x^2) / |x|), and acos(x) = pi - acos(|x|) for a negative x
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    let (s, s_exp, is_s_less) = fixed_sqrt_one_minus_square(m, e);
+    if s == 0 {
+        // acos(-1) = pi
+        return f32_round_fixed(FIXED_PI, -FIXED_FRAC_BITS, false, env);
+    }
+    if is_s_less {
+        let (v, k) = fixed_atan_ratio(s, s_exp, m, e);
+        if a_sign == 0 {
+            return f32_round_fixed(v, -FIXED_FRAC_BITS - k, false, env);
+        }
+        return f32_round_fixed(FIXED_PI - fixed_unscale(v, k), -FIXED_FRAC_BITS, false, env);
+    }
+
+    let (v, k) = fixed_atan_ratio(m, e, s, s_exp);
+    let r = FIXED_PI_2 - fixed_with_sign(a_sign, fixed_unscale(v, k));
+    f32_round_fixed(r, -FIXED_FRAC_BITS, false, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f32_asin() {
+        // asin(0.5) = 0.5235988
+        assert_eq!(crate::soft_f32::f32_asin(0x3F000000), 0x3F060A92);
+        // asin(-0.5) = -0.5235988
+        assert_eq!(crate::soft_f32::f32_asin(0xBF000000), 0xBF060A92);
+        // asin(0.99999994) = 1.570451
+        assert_eq!(crate::soft_f32::f32_asin(0x3F7FFFFF), 0x3FC9048A);
+        // asin(1) = 1.5707964
+        assert_eq!(crate::soft_f32::f32_asin(0x3F800000), 0x3FC90FDB);
+
+        // asin(-0) = -0
+        assert_eq!(crate::soft_f32::f32_asin(0x80000000), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_acos() {
+        // acos(0.5) = 1.0471976
+        assert_eq!(crate::soft_f32::f32_acos(0x3F000000), 0x3F860A92);
+        // acos(-0.5) = 2.0943952
+        assert_eq!(crate::soft_f32::f32_acos(0xBF000000), 0x40060A92);
+        // acos(0.99999994) = 0.00034526698
+        assert_eq!(crate::soft_f32::f32_acos(0x3F7FFFFF), 0x39B504F3);
+        // acos(-1) = 3.1415927
+        assert_eq!(crate::soft_f32::f32_acos(0xBF800000), 0x40490FDB);
+        // acos(0) = 1.5707964
+        assert_eq!(crate::soft_f32::f32_acos(0x00000000), 0x3FC90FDB);
+        // acos(1) = 0
+        assert_eq!(crate::soft_f32::f32_acos(0x3F800000), 0x00000000);
+    }
+
+    #[test]
+    fn test_f32_atan() {
+        // atan(1) = 0.7853982
+        assert_eq!(crate::soft_f32::f32_atan(0x3F800000), 0x3F490FDB);
+        // atan(0.5) = 0.4636476
+        assert_eq!(crate::soft_f32::f32_atan(0x3F000000), 0x3EED6338);
+        // atan(-2) = -1.1071488
+        assert_eq!(crate::soft_f32::f32_atan(0xC0000000), 0xBF8DB70D);
+        // atan(3.4028235e38) = 1.5707964
+        assert_eq!(crate::soft_f32::f32_atan(0x7F7FFFFF), 0x3FC90FDB);
+
+        // atan(Inf) = 1.5707964
+        assert_eq!(crate::soft_f32::f32_atan(0x7F800000), 0x3FC90FDB);
+        // atan(-Inf) = -1.5707964
+        assert_eq!(crate::soft_f32::f32_atan(0xFF800000), 0xBFC90FDB);
+        // atan(-0) = -0
+        assert_eq!(crate::soft_f32::f32_atan(0x80000000), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_atan2() {
+        // atan2(1, 1) = 0.7853982
+        assert_eq!(crate::soft_f32::f32_atan2(0x3F800000, 0x3F800000), 0x3F490FDB);
+        // atan2(1, -1) = 2.3561945
+        assert_eq!(crate::soft_f32::f32_atan2(0x3F800000, 0xBF800000), 0x4016CBE4);
+        // atan2(-1, -1) = -2.3561945
+        assert_eq!(crate::soft_f32::f32_atan2(0xBF800000, 0xBF800000), 0xC016CBE4);
+        // atan2(2, 1) = 1.1071488
+        assert_eq!(crate::soft_f32::f32_atan2(0x40000000, 0x3F800000), 0x3F8DB70D);
+        // atan2(1, 3) = 0.32175055
+        assert_eq!(crate::soft_f32::f32_atan2(0x3F800000, 0x40400000), 0x3EA4BC7D);
+    }
+
+    #[test]
+    fn test_f32_atan2_special() {
+        // atan2(+-0, +0) = +-0
+        assert_eq!(crate::soft_f32::f32_atan2(0x00000000, 0x00000000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_atan2(0x80000000, 0x00000000), 0x80000000);
+        // atan2(+-0, -0) = +-pi
+        assert_eq!(crate::soft_f32::f32_atan2(0x00000000, 0x80000000), 0x40490FDB);
+        assert_eq!(crate::soft_f32::f32_atan2(0x80000000, 0x80000000), 0xC0490FDB);
+        // atan2(+-0, 1) = +-0
+        assert_eq!(crate::soft_f32::f32_atan2(0x80000000, 0x3F800000), 0x80000000);
+        // atan2(+-0, -1) = +-pi
+        assert_eq!(crate::soft_f32::f32_atan2(0x80000000, 0xBF800000), 0xC0490FDB);
+        // atan2(+-1, +-0) = +-pi / 2
+        assert_eq!(crate::soft_f32::f32_atan2(0x3F800000, 0x80000000), 0x3FC90FDB);
+        assert_eq!(crate::soft_f32::f32_atan2(0xBF800000, 0x00000000), 0xBFC90FDB);
+
+        // atan2(+-Inf, Inf) = +-pi / 4
+        assert_eq!(crate::soft_f32::f32_atan2(0xFF800000, 0x7F800000), 0xBF490FDB);
+        // atan2(+-Inf, -Inf) = +-3pi / 4
+        assert_eq!(crate::soft_f32::f32_atan2(0x7F800000, 0xFF800000), 0x4016CBE4);
+        // atan2(+-Inf, -1) = +-pi / 2
+        assert_eq!(crate::soft_f32::f32_atan2(0xFF800000, 0xBF800000), 0xBFC90FDB);
+        // atan2(+-1, Inf) = +-0
+        assert_eq!(crate::soft_f32::f32_atan2(0xBF800000, 0x7F800000), 0x80000000);
+        // atan2(+-1, -Inf) = +-pi
+        assert_eq!(crate::soft_f32::f32_atan2(0x3F800000, 0xFF800000), 0x40490FDB);
+
+        // atan2(NaN, 1) = NaN
+        assert_eq!(crate::soft_f32::f32_atan2(0x7FC00001, 0x3F800000), 0x7FC00001);
+        // atan2(1, NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_atan2(0x3F800000, 0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_inv_trig_exception_flags() {
+        // asin(1.0000001) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F800001, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // acos(-Inf) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_acos_with_env(0xFF800000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // atan2(sNaN, 1) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_atan2_with_env(0x7F800001, 0x3F800000, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // acos(1) = 0, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_acos_with_env(0x3F800000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // atan2(1, Inf) = 0, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_atan2_with_env(0x3F800000, 0x7F800000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // asin(1) = pi / 2, inexact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F800000, &mut env), 0x3FC90FDB);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // atan(2^-149) rounds to 2^-149, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x00000001, &mut env), 0x00000001);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_inv_trig_rounding_mode() {
+        // asin(0.5)
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F000000, &mut Env::new(RoundingMode::NearEven)), 0x3F060A92);
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F000000, &mut Env::new(RoundingMode::MinMag)), 0x3F060A91);
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F000000, &mut Env::new(RoundingMode::Min)), 0x3F060A91);
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F000000, &mut Env::new(RoundingMode::Max)), 0x3F060A92);
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F060A92);
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x3F000000, &mut Env::new(RoundingMode::Odd)), 0x3F060A91);
+
+        // atan(1)
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x3F490FDB);
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x3F490FDA);
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x3F800000, &mut Env::new(RoundingMode::Min)), 0x3F490FDA);
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x3F800000, &mut Env::new(RoundingMode::Max)), 0x3F490FDB);
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F490FDB);
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x3F490FDB);
+
+        // atan(1e-20) is just below 1e-20
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x1E3CE508);
+        assert_eq!(crate::soft_f32::f32_atan_with_env(0x1E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x1E3CE507);
+
+        // asin(1e-20) is just above 1e-20
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x1E3CE508);
+        assert_eq!(crate::soft_f32::f32_asin_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x1E3CE509);
+    }
+}
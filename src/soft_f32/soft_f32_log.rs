@@ -0,0 +1,438 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_ln,
+    fixed_ln_1p,
+    fixed_ln_abs,
+    fixed_mul,
+    fixed_square,
+    fixed_from_f32,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_ONE,
+    FIXED_TINY_SQUARE,
+    FIXED_LN2, FIXED_LN2_LO,
+    FIXED_LOG2_E,
+    FIXED_LOG10_E,
+    FIXED_LOG10_2, FIXED_LOG10_2_LO,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Split A into e and m, where A = 2^e x m and m is in [1/sqrt(2), sqrt(2)] as a fixed-point value.
+// The result of the special cases is returned as an error.
+fn f32_log_reduce(a: u32, env: &mut Env) -> Result<(i32, i128), u32> {
+    // Sign
+    let a_sign = f32_sign(a);
+
+    // Exp
+    let mut a_exp = f32_exp(a);
+
+    // Frac
+    let mut a_frac = f32_frac(a);
+
+    if a_exp == 0xFF && a_frac != 0 {
+        // Propagate NaN
+        return Err(f32_propagate_nan(a, 0, env));
+    }
+
+    if a_exp | a_frac == 0 {
+        // Zero, the result is an exact -infinity
+        env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+        return Err(f32_pack_raw(1, 0xFF, 0));
+    }
+
+    if a_sign != 0 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return Err(f32_default_nan(env));
+    }
+
+    if a_exp == 0xFF {
+        // Infinity
+        return Err(a);
+    }
+
+    if a_exp == 0 {
+        let (exp, frac) = f32_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    a_frac |= 0x00800000;
+
+    // sqrt(2) x 2^23 is 0xB504F3.33
+    if a_frac > 0x00B504F3 {
+        Ok((a_exp - 0x7E, fixed_from_f32(0x7E, a_frac)))
+    } else {
+        Ok((a_exp - 0x7F, fixed_from_f32(0x7F, a_frac)))
+    }
+}
+
+pub fn f32_log2(a: u32) -> u32 {
+    with_thread_env(|env| f32_log2_with_env(a, env))
+}
+
+// Base 2 logarithm. The result is exact for powers of 2, and otherwise within 0.5 ULP plus the
+// 2^-56 relative error of the evaluation, that is correctly rounded but for hard cases.
+pub fn f32_log2_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_log2_unflushed(a, env), env)
+}
+
+fn f32_log2_unflushed(a: u32, env: &mut Env) -> u32 {
+    let (e, m) = match f32_log_reduce(a, env) {
+        Ok(reduced) => reduced,
+        Err(r) => return r,
+    };
+    let (ln_m, k) = fixed_ln(m);
+    let log2_m = fixed_mul(ln_m, FIXED_LOG2_E);
+
+    if e == 0 {
+        // Keep the scale of ln(m), as the result can be close to 0
+        return f32_round_fixed(log2_m, -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    let r = ((e as i128) << FIXED_FRAC_BITS) + (log2_m >> k);
+    f32_round_fixed(r, -FIXED_FRAC_BITS, ln_m == 0, env)
+}
+
+pub fn f32_ln(a: u32) -> u32 {
+    with_thread_env(|env| f32_ln_with_env(a, env))
+}
+
+// Natural logarithm. The result is exact only for 1, and otherwise within 0.5 ULP plus the
+// 2^-56 relative error of the evaluation.
+pub fn f32_ln_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_ln_unflushed(a, env), env)
+}
+
+fn f32_ln_unflushed(a: u32, env: &mut Env) -> u32 {
+    match f32_ln_fixed(a, env) {
+        Ok((v, scale)) => f32_round_fixed(v, scale, false, env),
+        Err(r) => r,
+    }
+}
+
+// ln(A) before rounding, as the fixed-point v x 2^scale, which is 0 only for an exact 0.
+// The result of the special cases is returned as an error.
+pub(crate) fn f32_ln_fixed(a: u32, env: &mut Env) -> Result<(i128, i32), u32> {
+    let (e, m) = f32_log_reduce(a, env)?;
+    let (ln_m, k) = fixed_ln(m);
+
+    if e == 0 {
+        return Ok((ln_m, -FIXED_FRAC_BITS - k));
+    }
+
+    let e = e as i128;
+    let r = e * FIXED_LN2 + ((e * FIXED_LN2_LO) >> FIXED_FRAC_BITS) + (ln_m >> k);
+    Ok((r, -FIXED_FRAC_BITS))
+}
+
+pub fn f32_log10(a: u32) -> u32 {
+    with_thread_env(|env| f32_log10_with_env(a, env))
+}
+
+// Base 10 logarithm. The result is exact for the powers of 10 which are exact in Float32,
+// and otherwise within 0.5 ULP plus the 2^-56 relative error of the evaluation.
+pub fn f32_log10_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_log10_unflushed(a, env), env)
+}
+
+fn f32_log10_unflushed(a: u32, env: &mut Env) -> u32 {
+    let (e, m) = match f32_log_reduce(a, env) {
+        Ok(reduced) => reduced,
+        Err(r) => return r,
+    };
+
+    // 10^k is exact up to 10^10, where e is at most 34
+    if (1..=34).contains(&e) {
+        let x = m << e;
+        let mut power = 1;
+        for k in 1..=10 {
+            power *= 10;
+            if x == power << FIXED_FRAC_BITS {
+                return f32_round_fixed(k, 0, true, env);
+            }
+        }
+    }
+
+    let (ln_m, k) = fixed_ln(m);
+    let log10_m = fixed_mul(ln_m, FIXED_LOG10_E);
+
+    if e == 0 {
+        return f32_round_fixed(log10_m, -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    let e = e as i128;
+    let r = e * FIXED_LOG10_2 + ((e * FIXED_LOG10_2_LO) >> FIXED_FRAC_BITS) + (log10_m >> k);
+    f32_round_fixed(r, -FIXED_FRAC_BITS, false, env)
+}
+
+pub fn f32_log1p(a: u32) -> u32 {
+    with_thread_env(|env| f32_log1p_with_env(a, env))
+}
+
+// ln(1 + A), without the cancellation of f32_ln(1 + A) when A is close to 0. The result is exact
+// only for 0, and otherwise within 0.5 ULP plus the 2^-56 relative error of the evaluation.
+pub fn f32_log1p_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_log1p_unflushed(a, env), env)
+}
+
+fn f32_log1p_unflushed(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a & 0x7FFFFFFF == 0 || a == 0x7F800000 {
+        // Zero and infinity
+        return a;
+    }
+    if a == 0xBF800000 {
+        // ln(0) = -infinity
+        env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+        return f32_pack_raw(1, 0xFF, 0);
+    }
+    if a > 0xBF800000 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
+    }
+
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    let x = if a_sign == 1 { -m } else { m };
+    if e < -2 {
+        // |A| < 1/4, keep the scale of A
+        let k = -e;
+        if fixed_square(m, k) < FIXED_TINY_SQUARE {
+            // ln(1 + x) is just below x
+            return f32_round_fixed(x - 1, -FIXED_FRAC_BITS - k, false, env);
+        }
+        return f32_round_fixed(fixed_ln_1p(x, k), -FIXED_FRAC_BITS - k, false, env);
+    }
+
+    // 1 + A with the scale of A, which is exact unless A is above 2^62
+    let v = match e >= 0 {
+        true => m + FIXED_ONE.checked_shr(e as u32).unwrap_or(0),
+        false => x + (FIXED_ONE << -e),
+    };
+    f32_round_fixed(fixed_ln_abs(v, e), -FIXED_FRAC_BITS, false, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_log2() {
+        // log2(8) = 3
+        assert_eq!(crate::soft_f32::f32_log2(0x41000000), 0x40400000);
+        // log2(0.25) = -2
+        assert_eq!(crate::soft_f32::f32_log2(0x3E800000), 0xC0000000);
+        // log2(1) = 0
+        assert_eq!(crate::soft_f32::f32_log2(0x3F800000), 0x00000000);
+        // log2(2^-149) = -149
+        assert_eq!(crate::soft_f32::f32_log2(0x00000001), 0xC3150000);
+        // log2(1.5) = 0.5849625
+        assert_eq!(crate::soft_f32::f32_log2(0x3FC00000), 0x3F15C01A);
+        // log2(1.0000001) = 1.7198264e-7
+        assert_eq!(crate::soft_f32::f32_log2(0x3F800001), 0x3438AA3A);
+    }
+
+    #[test]
+    fn test_f32_ln() {
+        // ln(1) = 0
+        assert_eq!(crate::soft_f32::f32_ln(0x3F800000), 0x00000000);
+        // ln(2) = 0.6931472
+        assert_eq!(crate::soft_f32::f32_ln(0x40000000), 0x3F317218);
+        // ln(10) = 2.3025851
+        assert_eq!(crate::soft_f32::f32_ln(0x41200000), 0x40135D8E);
+        // ln(0.5) = -0.6931472
+        assert_eq!(crate::soft_f32::f32_ln(0x3F000000), 0xBF317218);
+    }
+
+    #[test]
+    fn test_f32_log10() {
+        // log10(1000) = 3
+        assert_eq!(crate::soft_f32::f32_log10(0x447A0000), 0x40400000);
+        // log10(10^10) = 10
+        assert_eq!(crate::soft_f32::f32_log10(0x501502F9), 0x41200000);
+        // log10(1) = 0
+        assert_eq!(crate::soft_f32::f32_log10(0x3F800000), 0x00000000);
+        // log10(2) = 0.30103
+        assert_eq!(crate::soft_f32::f32_log10(0x40000000), 0x3E9A209B);
+        // log10(1.5) = 0.17609125
+        assert_eq!(crate::soft_f32::f32_log10(0x3FC00000), 0x3E345144);
+    }
+
+    #[test]
+    fn test_f32_log_inf_nan() {
+        // log2(Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_log2(0x7F800000), 0x7F800000);
+        // ln(Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_ln(0x7F800000), 0x7F800000);
+
+        // log2(-Inf) = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_log2(0xFF800000)), true);
+        // log10(-1) = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_log10(0xBF800000)), true);
+
+        // ln(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_ln(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_log_exception_flags() {
+        // log2(0) = -Inf, divide by zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x00000000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // ln(-0) = -Inf, divide by zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x80000000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // log10(-2) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_log10_with_env(0xC0000000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // log2(sNaN) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // log2(1024) = 10, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x44800000, &mut env), 0x41200000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // log10(100) = 2, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x42C80000, &mut env), 0x40000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // ln(2), inexact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x40000000, &mut env), 0x3F317218);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_log_rounding_mode() {
+        // log2(10)
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x41200000, &mut Env::new(RoundingMode::NearEven)), 0x40549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x41200000, &mut Env::new(RoundingMode::MinMag)), 0x40549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x41200000, &mut Env::new(RoundingMode::Min)), 0x40549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x41200000, &mut Env::new(RoundingMode::Max)), 0x40549A79);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x41200000, &mut Env::new(RoundingMode::NearMaxMag)), 0x40549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x41200000, &mut Env::new(RoundingMode::Odd)), 0x40549A79);
+
+        // log2(0.1)
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0xC0549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0xC0549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::Min)), 0xC0549A79);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::Max)), 0xC0549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0xC0549A78);
+        assert_eq!(crate::soft_f32::f32_log2_with_env(0x3DCCCCCD, &mut Env::new(RoundingMode::Odd)), 0xC0549A79);
+
+        // ln(0.5)
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x3F000000, &mut Env::new(RoundingMode::NearEven)), 0xBF317218);
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x3F000000, &mut Env::new(RoundingMode::MinMag)), 0xBF317217);
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x3F000000, &mut Env::new(RoundingMode::Min)), 0xBF317218);
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x3F000000, &mut Env::new(RoundingMode::Max)), 0xBF317217);
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x3F000000, &mut Env::new(RoundingMode::NearMaxMag)), 0xBF317218);
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x3F000000, &mut Env::new(RoundingMode::Odd)), 0xBF317217);
+
+        // log10(2)
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x40000000, &mut Env::new(RoundingMode::NearEven)), 0x3E9A209B);
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x40000000, &mut Env::new(RoundingMode::MinMag)), 0x3E9A209A);
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x40000000, &mut Env::new(RoundingMode::Min)), 0x3E9A209A);
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x40000000, &mut Env::new(RoundingMode::Max)), 0x3E9A209B);
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x40000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3E9A209B);
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x40000000, &mut Env::new(RoundingMode::Odd)), 0x3E9A209B);
+
+        // log10(1000) is exact in every rounding mode
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x447A0000, &mut Env::new(RoundingMode::Min)), 0x40400000);
+        assert_eq!(crate::soft_f32::f32_log10_with_env(0x447A0000, &mut Env::new(RoundingMode::Max)), 0x40400000);
+    }
+
+    #[test]
+    fn test_f32_log1p() {
+        // log1p(1) = 0.6931472
+        assert_eq!(crate::soft_f32::f32_log1p(0x3F800000), 0x3F317218);
+        // log1p(-0.5) = -0.6931472
+        assert_eq!(crate::soft_f32::f32_log1p(0xBF000000), 0xBF317218);
+        // log1p(0.25) = 0.22314355
+        assert_eq!(crate::soft_f32::f32_log1p(0x3E800000), 0x3E647FBE);
+        // log1p(1e-7) = 9.9999994e-8
+        assert_eq!(crate::soft_f32::f32_log1p(0x33D6BF95), 0x33D6BF94);
+        // log1p(3.4028235e38) = 88.72284
+        assert_eq!(crate::soft_f32::f32_log1p(0x7F7FFFFF), 0x42B17218);
+
+        // log1p(-0) = -0
+        assert_eq!(crate::soft_f32::f32_log1p(0x80000000), 0x80000000);
+        // log1p(Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_log1p(0x7F800000), 0x7F800000);
+    }
+
+    #[test]
+    fn test_f32_log1p_exception_flags() {
+        // log1p(-1) = -Inf, divide by zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0xBF800000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // log1p(-2) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0xC0000000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // log1p(0) = 0, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x00000000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_log1p_rounding_mode() {
+        // log1p(0.25)
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x3E800000, &mut Env::new(RoundingMode::NearEven)), 0x3E647FBE);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x3E800000, &mut Env::new(RoundingMode::MinMag)), 0x3E647FBE);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x3E800000, &mut Env::new(RoundingMode::Min)), 0x3E647FBE);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x3E800000, &mut Env::new(RoundingMode::Max)), 0x3E647FBF);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x3E800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3E647FBE);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x3E800000, &mut Env::new(RoundingMode::Odd)), 0x3E647FBF);
+
+        // log1p(1e-20) is just below 1e-20
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x1E3CE508);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x1E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x1E3CE507);
+    }
+
+    #[test]
+    fn test_f32_log_flush_to_zero() {
+        // ln(0) is -infinity with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_ln_with_env(0x00000001, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // log1p(2^-149) is subnormal, so it is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_log1p_with_env(0x00000001, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+    }
+}
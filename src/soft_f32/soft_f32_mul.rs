@@ -1,21 +1,31 @@
 use super::util::{
-    f32_shift_right_jam,
-    f32_norm_round_and_pack,
     f32_round_and_pack,
-    f32_pack_raw, f32_pack,
+    f32_pack_raw,
     f32_propagate_nan,
     f32_sign, f32_exp, f32_frac,
     f32_norm_subnormal_frac,
     f32_short_shift_right_jam64,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
 };
 
-use crate::soft_f32::f32_sub;
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
 
 pub fn f32_mul(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_mul_with_env(a, b, env))
+}
+
+pub fn f32_mul_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| f32_mul_unflushed(a, b, env), env)
+}
+
+fn f32_mul_unflushed(a: u32, b: u32, env: &mut Env) -> u32 {
     // Sign
-    let mut a_sign = f32_sign(a);
-    let mut b_sign = f32_sign(b);
-    let mut r_sign = a_sign ^ b_sign;
+    let a_sign = f32_sign(a);
+    let b_sign = f32_sign(b);
+    let r_sign = a_sign ^ b_sign;
 
     // Exp
     let mut a_exp = f32_exp(a);
@@ -30,13 +40,14 @@ pub fn f32_mul(a: u32, b: u32) -> u32 {
     if a_exp == 0xFF {
         // NaN
         if a_frac != 0 || (b_exp == 0xFF && b_frac != 0) {
-            return f32_propagate_nan(a, b);
+            return f32_propagate_nan(a, b, env);
         }
 
         // Inf
         if b_exp | b_frac == 0 {
-            // Default NaN
-            return f32_pack_raw(r_sign, 0xFF, 0);
+            // Inf x 0, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f32_default_nan(env);
         }
 
         return f32_pack_raw(r_sign, 0xFF, 0);
@@ -44,20 +55,21 @@ pub fn f32_mul(a: u32, b: u32) -> u32 {
     if b_exp == 0xFF {
         // Nan
         if b_frac != 0 {
-            return f32_propagate_nan(a, b);
+            return f32_propagate_nan(a, b, env);
         }
 
         // Inf
         if a_exp | a_frac == 0 {
-            // Default NaN
-            return f32_pack_raw(r_sign, 0xFF, 0);
+            // 0 x Inf, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f32_default_nan(env);
         }
 
         return f32_pack_raw(r_sign, 0xFF, 0);
     }
 
     if a_exp == 0 {
-        if a_sign == 0 {
+        if a_frac == 0 {
             // Zero
             return f32_pack_raw(r_sign, 0, 0);
         }
@@ -67,7 +79,7 @@ pub fn f32_mul(a: u32, b: u32) -> u32 {
         a_frac = frac;
     }
     if b_exp == 0 {
-        if b_sign == 0 {
+        if b_frac == 0 {
             // Zero
             return f32_pack_raw(r_sign, 0, 0);
         }
@@ -94,11 +106,13 @@ pub fn f32_mul(a: u32, b: u32) -> u32 {
         r_frac <<= 1;
     }
 
-    f32_round_and_pack(r_sign, r_exp, r_frac)
+    f32_round_and_pack(r_sign, r_exp, r_frac, env)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, DetectTininess, FlushToZero };
+
     #[test]
     fn test_f32_mul() {
         // 0.1 x 0.2 = 0.02
@@ -146,4 +160,173 @@ mod tests {
         // NaN x -Inf = NaN
         assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_mul(0xFFFFFFFF, 0xFF800000)), true);
     }
+
+    #[test]
+    fn test_f32_mul_rounding_mode() {
+        // 0.1 x 0.2
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3CA3D70B);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3CA3D70A);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Min)), 0x3CA3D70A);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Max)), 0x3CA3D70B);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0x3CA3D70B);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Odd)), 0x3CA3D70B);
+
+        // -0.1 x 0.2
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0xBDCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0xBCA3D70B);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0xBDCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0xBCA3D70A);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0xBDCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Min)), 0xBCA3D70B);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0xBDCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Max)), 0xBCA3D70A);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0xBDCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0xBCA3D70B);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0xBDCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Odd)), 0xBCA3D70B);
+
+        // (1 + 2^-23) x (1 + 2^-23)
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F800001, 0x3F800001, &mut Env::new(RoundingMode::NearEven)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F800001, 0x3F800001, &mut Env::new(RoundingMode::MinMag)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F800001, 0x3F800001, &mut Env::new(RoundingMode::Min)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F800001, 0x3F800001, &mut Env::new(RoundingMode::Max)), 0x3F800003);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F800001, 0x3F800001, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F800002);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F800001, 0x3F800001, &mut Env::new(RoundingMode::Odd)), 0x3F800003);
+
+        // 2^127 x 2, overflow
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut Env::new(RoundingMode::NearEven)), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut Env::new(RoundingMode::MinMag)), 0x7F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut Env::new(RoundingMode::Min)), 0x7F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut Env::new(RoundingMode::Max)), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut Env::new(RoundingMode::Odd)), 0x7F7FFFFF);
+
+        // 2^127 x -2, overflow
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0xC0000000, &mut Env::new(RoundingMode::NearEven)), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0xC0000000, &mut Env::new(RoundingMode::MinMag)), 0xFF7FFFFF);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0xC0000000, &mut Env::new(RoundingMode::Min)), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0xC0000000, &mut Env::new(RoundingMode::Max)), 0xFF7FFFFF);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0xC0000000, &mut Env::new(RoundingMode::NearMaxMag)), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0xC0000000, &mut Env::new(RoundingMode::Odd)), 0xFF7FFFFF);
+    }
+
+    #[test]
+    fn test_f32_mul_exception_flags() {
+        let mut env = Env::default();
+
+        // 2 x 3 = 6, exact
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x40000000, 0x40400000, &mut env), 0x40C00000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 0.1 x 0.2 = 0.02, inexact
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut env), 0x3CA3D70B);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // 2^127 x 2 = Inf, overflow
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x7F000000, 0x40000000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // Inf x 0 = NaN, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_mul_with_env(0x7F800000, 0x00000000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // -0 x Inf = NaN, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_mul_with_env(0x80000000, 0x7F800000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_mul_subnormal() {
+        let mut env = Env::default();
+
+        // 2^-126 x 0.5 = 2^-127, subnormal and exact
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00800000, 0x3F000000, &mut env), 0x00400000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // (2^-126 + 1 ulp) x 0.5, a tie rounded to even
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00800001, 0x3F000000, &mut env), 0x00400000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // (2^-126 + 3 ulps) x 0.5, a tie rounded to even
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00800003, 0x3F000000, &mut env), 0x00400002);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // 2^-149 x 0.5, half of the smallest subnormal
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x3F000000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x3F000000, &mut Env::new(RoundingMode::MinMag)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x3F000000, &mut Env::new(RoundingMode::Min)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x3F000000, &mut Env::new(RoundingMode::Max)), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x3F000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x3F000000, &mut Env::new(RoundingMode::Odd)), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x80000001, 0x3F000000, &mut Env::new(RoundingMode::Min)), 0x80000001);
+
+        // 1e-30 x 1e-30 = 0
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x0DA24260, 0x0DA24260, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_mul_detect_tininess() {
+        // (1 - 2^-23) x (2^-126 + 1 ulp) = 2^-126 x (1 - 2^-46), which rounds to 2^-126
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::After);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F7FFFFE, 0x00800001, &mut env), 0x00800000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::Before);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F7FFFFE, 0x00800001, &mut env), 0x00800000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // Exact tiny results never underflow
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::Before);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00800000, 0x3F000000, &mut env), 0x00400000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_mul_flush_to_zero() {
+        // (1 - 2^-23) x (2^-126 + 1 ulp) = 2^-126 x (1 - 2^-46), tiny before rounding only (ARMv8)
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::Before);
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F7FFFFE, 0x00800001, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        // (x86)
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::After);
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x3F7FFFFE, 0x00800001, &mut env), 0x00800000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // -2^-126 x 0.5 = -2^-127
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x80800000, 0x3F000000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // A result rounded to zero is flushed to the same zero
+        let mut env = Env::new(RoundingMode::Max);
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x0DA24260, 0x0DA24260, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+    }
+
+    #[test]
+    fn test_f32_mul_denormals_are_zero() {
+        // 2^-149 x Inf is 0 x Inf, invalid
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x00000001, 0x7F800000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // -2^-149 x 2^127 = -0
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_mul_with_env(0x80000001, 0x7F000000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
 }
@@ -0,0 +1,375 @@
+use super::util::{
+    f32_round_and_pack,
+    f32_pack_raw,
+    f32_propagate_nan,
+    f32_propagate_nan3,
+    f32_sign, f32_exp, f32_frac,
+    f32_is_nan,
+    f32_norm_subnormal_frac,
+    f32_short_shift_right_jam64,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use crate::soft_f64::util::f64_count_leading_zero;
+
+use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode, with_thread_env };
+
+// Shift a u64 right, the lost bits are jammed into the least significant bit
+fn f32_shift_right_jam64(a: u64, dist: i32) -> u64 {
+    if dist == 0 {
+        a
+    } else if dist < 64 {
+        (a >> dist) | ((a << (64 - dist)) != 0) as u64
+    } else {
+        (a != 0) as u64
+    }
+}
+
+// Negate an operand of the fused variants, a NaN is kept as is so that it propagates unchanged
+fn f32_negate_operand(a: u32) -> u32 {
+    if f32_is_nan(a) {
+        return a;
+    }
+    a ^ 0x80000000
+}
+
+pub fn f32_mul_add(a: u32, b: u32, c: u32) -> u32 {
+    with_thread_env(|env| f32_mul_add_with_env(a, b, c, env))
+}
+
+// Compute A x B + C with a single rounding
+pub fn f32_mul_add_with_env(a: u32, b: u32, c: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    let c = f32_flush_input(c, env);
+    f32_flush_result(|env| f32_mul_add_unflushed(a, b, c, env), env)
+}
+
+fn f32_mul_add_unflushed(a: u32, b: u32, c: u32, env: &mut Env) -> u32 {
+    // Sign
+    let a_sign = f32_sign(a);
+    let b_sign = f32_sign(b);
+    let c_sign = f32_sign(c);
+    let prod_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f32_exp(a);
+    let mut b_exp = f32_exp(b);
+    let mut c_exp = f32_exp(c);
+
+    // Frac
+    let mut a_frac = f32_frac(a);
+    let mut b_frac = f32_frac(b);
+    let mut c_frac = f32_frac(c);
+
+    if a_exp == 0xFF || b_exp == 0xFF {
+        if (a_exp == 0xFF && a_frac != 0) || (b_exp == 0xFF && b_frac != 0) {
+            // Propagate NaN
+            return f32_propagate_nan3(a, b, c, env);
+        }
+
+        // Inf x 0 is invalid
+        let is_prod_valid = match a_exp == 0xFF {
+            true => b_exp | b_frac != 0,
+            false => a_exp | a_frac != 0,
+        };
+        if is_prod_valid {
+            let r = f32_pack_raw(prod_sign, 0xFF, 0);
+            if c_exp != 0xFF {
+                return r;
+            }
+            if c_frac != 0 {
+                // Propagate NaN
+                return f32_propagate_nan(r, c, env);
+            }
+            if c_sign == prod_sign {
+                return r;
+            }
+        }
+
+        // Inf x 0 or Inf - Inf, invalid, return default NaN unless C is a NaN
+        env.raise(ExceptionFlags::INVALID);
+        if c_exp == 0xFF && c_frac != 0 {
+            return match env.nan_policy() {
+                // The default NaN of the invalid product competes with C
+                NanPolicy::X87 | NanPolicy::Armv8 => f32_propagate_nan(f32_default_nan(env), c, env),
+                _ => f32_propagate_nan(0, c, env),
+            };
+        }
+        return f32_default_nan(env);
+    }
+    if c_exp == 0xFF {
+        if c_frac != 0 {
+            // Propagate NaN
+            return f32_propagate_nan(0, c, env);
+        }
+        // Infinity
+        return c;
+    }
+
+    if (a_exp | a_frac) == 0 || (b_exp | b_frac) == 0 {
+        // Zero product, C is exact unless it is a zero of the other sign
+        if (c_exp | c_frac) == 0 && c_sign != prod_sign {
+            let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+            return f32_pack_raw(zero_sign, 0, 0);
+        }
+        return c;
+    }
+
+    if a_exp == 0 {
+        let (exp, frac) = f32_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    if b_exp == 0 {
+        let (exp, frac) = f32_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+
+    // The exact product has its most significant bit at bit 61
+    let mut prod_exp = a_exp + b_exp - 0x7E;
+    let a_frac_u64 = (((a_frac | 0x00800000) as u32) << 7) as u64;
+    let b_frac_u64 = (((b_frac | 0x00800000) as u32) << 7) as u64;
+    let mut prod_frac = a_frac_u64 * b_frac_u64;
+    if prod_frac < 0x2000000000000000 {
+        prod_exp -= 1;
+        prod_frac <<= 1;
+    }
+
+    if (c_exp | c_frac) == 0 {
+        // Zero addend, round the product
+        let r_frac = f32_short_shift_right_jam64(prod_frac, 31);
+        return f32_round_and_pack(prod_sign, prod_exp - 1, r_frac, env);
+    }
+    if c_exp == 0 {
+        let (exp, frac) = f32_norm_subnormal_frac(c_frac);
+        c_exp = exp;
+        c_frac = frac;
+    }
+
+    // Move the fraction of C to have its most significant bit at bit 61 as well
+    let c_frac_u64 = (((c_frac | 0x00800000) as u32) << 6) as u64;
+    let c_frac_u64 = c_frac_u64 << 32;
+
+    let diff_exp = prod_exp - c_exp;
+    let mut r_sign = prod_sign;
+    let mut r_exp;
+    let mut r_frac;
+
+    if c_sign == prod_sign {
+        let r_frac_u64 = if diff_exp <= 0 {
+            r_exp = c_exp;
+            c_frac_u64 + f32_shift_right_jam64(prod_frac, -diff_exp)
+        } else {
+            r_exp = prod_exp;
+            prod_frac + f32_shift_right_jam64(c_frac_u64, diff_exp)
+        };
+        r_frac = f32_short_shift_right_jam64(r_frac_u64, 32);
+        if r_frac < 0x40000000 {
+            r_exp -= 1;
+            r_frac <<= 1;
+        }
+    } else {
+        let r_frac_u64 = if diff_exp < 0 {
+            // The magnitude of C is greater
+            r_sign = c_sign;
+            r_exp = c_exp;
+            c_frac_u64 - f32_shift_right_jam64(prod_frac, -diff_exp)
+        } else if diff_exp == 0 {
+            r_exp = prod_exp;
+            if prod_frac == c_frac_u64 {
+                // Exact cancellation, the zero is negative only when rounding toward negative
+                let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+                return f32_pack_raw(zero_sign, 0, 0);
+            }
+            if prod_frac < c_frac_u64 {
+                r_sign ^= 1;
+                c_frac_u64 - prod_frac
+            } else {
+                prod_frac - c_frac_u64
+            }
+        } else {
+            r_exp = prod_exp;
+            prod_frac - f32_shift_right_jam64(c_frac_u64, diff_exp)
+        };
+
+        // Normalize, the cancellation may have cleared many leading bits
+        let shift_count = f64_count_leading_zero(r_frac_u64 as i64) - 1;
+        r_exp -= shift_count;
+        let shift_count = shift_count - 32;
+        r_frac = if shift_count < 0 {
+            f32_short_shift_right_jam64(r_frac_u64, -shift_count)
+        } else {
+            ((r_frac_u64 as u32) << shift_count) as i32
+        };
+    }
+
+    f32_round_and_pack(r_sign, r_exp, r_frac, env)
+}
+
+pub fn f32_mul_sub(a: u32, b: u32, c: u32) -> u32 {
+    with_thread_env(|env| f32_mul_sub_with_env(a, b, c, env))
+}
+
+// Compute A x B - C with a single rounding
+pub fn f32_mul_sub_with_env(a: u32, b: u32, c: u32, env: &mut Env) -> u32 {
+    f32_mul_add_with_env(a, b, f32_negate_operand(c), env)
+}
+
+pub fn f32_neg_mul_add(a: u32, b: u32, c: u32) -> u32 {
+    with_thread_env(|env| f32_neg_mul_add_with_env(a, b, c, env))
+}
+
+// Compute -(A x B) + C with a single rounding
+pub fn f32_neg_mul_add_with_env(a: u32, b: u32, c: u32, env: &mut Env) -> u32 {
+    f32_mul_add_with_env(f32_negate_operand(a), b, c, env)
+}
+
+pub fn f32_neg_mul_sub(a: u32, b: u32, c: u32) -> u32 {
+    with_thread_env(|env| f32_neg_mul_sub_with_env(a, b, c, env))
+}
+
+// Compute -(A x B) - C with a single rounding
+pub fn f32_neg_mul_sub_with_env(a: u32, b: u32, c: u32, env: &mut Env) -> u32 {
+    f32_mul_add_with_env(f32_negate_operand(a), b, f32_negate_operand(c), env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_mul_add() {
+        // 0.1 * 10 - 1 = 2^-26, the exact error of 0.1, while 0.1 * 10 rounds to 1
+        assert_eq!(crate::soft_f32::f32_mul(0x3DCCCCCD, 0x41200000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_mul_add(0x3DCCCCCD, 0x41200000, 0xBF800000), 0x32800000);
+
+        // 0.1 * 0.2 + 0.2 = 0.22
+        assert_eq!(crate::soft_f32::f32_mul_add(0x3DCCCCCD, 0x3E4CCCCD, 0x3E4CCCCD), 0x3E6147AE);
+
+        // MAX * 2 - MAX = MAX, without overflow in the product
+        assert_eq!(crate::soft_f32::f32_mul_add(0x7F7FFFFF, 0x40000000, 0xFF7FFFFF), 0x7F7FFFFF);
+
+        // MIN_POSITIVE * 0.5 - the smallest subnormal, exact in the subnormal range
+        assert_eq!(crate::soft_f32::f32_mul_add(0x00800000, 0x3F000000, 0x80000001), 0x003FFFFF);
+
+        // 0 * 1 + -0 = 0
+        assert_eq!(crate::soft_f32::f32_mul_add(0x00000000, 0x3F800000, 0x80000000), 0x00000000);
+    }
+
+    #[test]
+    fn test_f32_mul_add_negated() {
+        // 0.1 * 10 - 1
+        assert_eq!(crate::soft_f32::f32_mul_sub(0x3DCCCCCD, 0x41200000, 0x3F800000), 0x32800000);
+        // -(0.1 * 10) + 1
+        assert_eq!(crate::soft_f32::f32_neg_mul_add(0x3DCCCCCD, 0x41200000, 0x3F800000), 0xB2800000);
+        // -(0.1 * 10) - -1
+        assert_eq!(crate::soft_f32::f32_neg_mul_sub(0x3DCCCCCD, 0x41200000, 0xBF800000), 0xB2800000);
+
+        // A NaN operand is not negated
+        assert_eq!(crate::soft_f32::f32_neg_mul_add(0x7FC00001, 0x3F800000, 0x3F800000), 0x7FC00001);
+        assert_eq!(crate::soft_f32::f32_mul_sub(0x3F800000, 0x3F800000, 0x7FC00001), 0x7FC00001);
+
+        // -(1 * 1) - -1 is -0 only when rounding toward negative infinity
+        assert_eq!(crate::soft_f32::f32_neg_mul_sub_with_env(0x3F800000, 0x3F800000, 0xBF800000, &mut Env::new(RoundingMode::NearEven)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_neg_mul_sub_with_env(0x3F800000, 0x3F800000, 0xBF800000, &mut Env::new(RoundingMode::Min)), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_mul_add_inf_nan() {
+        // Inf * 1 + 1 = Inf
+        assert_eq!(crate::soft_f32::f32_mul_add(0x7F800000, 0x3F800000, 0x3F800000), 0x7F800000);
+        // 1 * 1 + -Inf = -Inf
+        assert_eq!(crate::soft_f32::f32_mul_add(0x3F800000, 0x3F800000, 0xFF800000), 0xFF800000);
+        // Inf * 1 + -Inf = NaN
+        assert_eq!(crate::soft_f32::f32_mul_add(0x7F800000, 0x3F800000, 0xFF800000), 0xFFC00000);
+        // 1 * 1 + NaN = NaN
+        assert_eq!(crate::soft_f32::f32_mul_add(0x3F800000, 0x3F800000, 0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_mul_add_rounding_mode() {
+        // 0.1 * 0.2 + 0.2
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3E6147AE);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3E6147AE);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x3DCCCCCD, 0x3E4CCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Max)), 0x3E6147AF);
+
+        // 1 * 1 - 1 is -0 when rounding toward negative infinity
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x3F800000, 0x3F800000, 0xBF800000, &mut Env::new(RoundingMode::NearEven)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x3F800000, 0x3F800000, 0xBF800000, &mut Env::new(RoundingMode::Min)), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_mul_add_exception_flags() {
+        // 0.1 * 10 - 1 is exact
+        let mut env = Env::default();
+        crate::soft_f32::f32_mul_add_with_env(0x3DCCCCCD, 0x41200000, 0xBF800000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Inf * 0 + 1 is invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x7F800000, 0x00000000, 0x3F800000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Inf * 0 + qNaN is invalid as well, and propagates the NaN
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x7F800000, 0x00000000, 0x7FC00001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Inf * 1 - Inf is invalid
+        let mut env = Env::default();
+        crate::soft_f32::f32_mul_add_with_env(0x7F800000, 0x3F800000, 0xFF800000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_mul_add_nan_policy() {
+        let nan_env = |nan_policy| {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            env
+        };
+
+        // qNaN x 1 + sNaN
+        let (a, b, c) = (0x7FC00001, 0x3F800000, 0x7F800002);
+        let cases = [
+            (NanPolicy::X87, 0x7FC00001),
+            (NanPolicy::X86Sse, 0x7FC00001),
+            // The addend first, and a signaling NaN first
+            (NanPolicy::Armv8, 0x7FC00002),
+            (NanPolicy::Armv8DefaultNan, 0x7FC00000),
+            (NanPolicy::RiscV, 0x7FC00000),
+            (NanPolicy::Wasm, 0x7FC00000),
+        ];
+        for &(nan_policy, r) in cases.iter() {
+            let mut env = nan_env(nan_policy);
+            assert_eq!(crate::soft_f32::f32_mul_add_with_env(a, b, c, &mut env), r);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+
+        // Inf x 0 + qNaN is invalid, ARM gives the default NaN and x86 the addend
+        let mut env = nan_env(NanPolicy::Armv8);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x7F800000, 0x00000000, 0x7FC00001, &mut env), 0x7FC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        let mut env = nan_env(NanPolicy::X86Sse);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x7F800000, 0x00000000, 0x7FC00001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_mul_add_flush_to_zero() {
+        // 2^-126 x 0.5 + -0 = 2^-127
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x00800000, 0x3F000000, 0x80000000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        // 1 x 1 + 2^-149 = 1, exact with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_mul_add_with_env(0x3F800000, 0x3F800000, 0x00000001, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
@@ -0,0 +1,234 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_pack_raw,
+    f32_exp,
+    f32_is_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// The position of A in the order of the Float32 values, with -0 and +0 at 0
+fn f32_ordinal(a: u32) -> i64 {
+    let mag = (a & 0x7FFFFFFF) as i64;
+    if (a >> 31) != 0 { -mag } else { mag }
+}
+
+pub fn f32_next_up(a: u32) -> u32 {
+    with_thread_env(|env| f32_next_up_with_env(a, env))
+}
+
+// IEEE 754 nextUp, the least value above A. The next value of -2^-149 is -0,
+// and the next value of a zero is 2^-149. Only a signaling NaN raises a flag.
+pub fn f32_next_up_with_env(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        return f32_propagate_nan(a, 0, env);
+    }
+    if a == f32_pack_raw(0, 0xFF, 0) {
+        return a;
+    }
+    if (a << 1) == 0 {
+        return 0x00000001;
+    }
+
+    match a >> 31 {
+        0 => a + 1,
+        _ => a - 1,
+    }
+}
+
+pub fn f32_next_down(a: u32) -> u32 {
+    with_thread_env(|env| f32_next_down_with_env(a, env))
+}
+
+// IEEE 754 nextDown, the greatest value below A, that is -nextUp(-A)
+pub fn f32_next_down_with_env(a: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) {
+        return f32_propagate_nan(a, 0, env);
+    }
+
+    f32_next_up_with_env(a ^ 0x80000000, env) ^ 0x80000000
+}
+
+pub fn f32_next_after(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_next_after_with_env(a, b, env))
+}
+
+// C nextafter, the next value after A in the direction of B, or B when A equals B.
+// A finite A stepping to infinity overflows, and a subnormal or zero result underflows.
+pub fn f32_next_after_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    if f32_is_nan(a) || f32_is_nan(b) {
+        return f32_propagate_nan(a, b, env);
+    }
+    if f32_ordinal(a) == f32_ordinal(b) {
+        return b;
+    }
+
+    let r = match f32_ordinal(a) < f32_ordinal(b) {
+        true => f32_next_up_with_env(a, env),
+        false => f32_next_down_with_env(a, env),
+    };
+    match f32_exp(r) {
+        0xFF if f32_exp(a) != 0xFF => env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT),
+        0 => env.raise(ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT),
+        _ => {}
+    }
+    r
+}
+
+pub fn f32_ulp(a: u32) -> u32 {
+    with_thread_env(|env| f32_ulp_with_env(a, env))
+}
+
+// The unit in the last place of A, the positive distance from |A| to the next value away from
+// zero, as Java Math.ulp. It is 2^-149 for zero and the subnormals, 2^104 for the largest finite
+// value, and infinity for infinity.
+pub fn f32_ulp_with_env(a: u32, env: &mut Env) -> u32 {
+    let a_exp = f32_exp(a);
+    if a_exp == 0xFF {
+        if f32_is_nan(a) {
+            return f32_propagate_nan(a, 0, env);
+        }
+        return f32_pack_raw(0, 0xFF, 0);
+    }
+
+    // 2^(exp - 150), subnormal below 2^-126
+    match a_exp {
+        0 => 0x00000001,
+        exp if exp > 23 => f32_pack_raw(0, exp - 23, 0),
+        exp => 1 << (exp - 1),
+    }
+}
+
+// The number of Float32 values from A to B, counting B but not A, so that it is 1 for adjacent
+// values and 0 for equal ones, -0 and +0 included. None when A or B is NaN.
+pub fn f32_ulp_distance(a: u32, b: u32) -> Option<u32> {
+    if f32_is_nan(a) || f32_is_nan(b) {
+        return None;
+    }
+
+    Some((f32_ordinal(a) - f32_ordinal(b)).unsigned_abs() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f32_next_up() {
+        // next_up(1) = 1.0000001 and next_up(-1) = -0.99999994
+        assert_eq!(crate::soft_f32::f32_next_up(0x3F800000), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_next_up(0xBF800000), 0xBF7FFFFF);
+        // next_up(0) = next_up(-0) = 2^-149 and next_up(-2^-149) = -0
+        assert_eq!(crate::soft_f32::f32_next_up(0x00000000), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_next_up(0x80000000), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_next_up(0x80000001), 0x80000000);
+        // next_up(1.1754942e-38) = 2^-126, across the subnormal boundary
+        assert_eq!(crate::soft_f32::f32_next_up(0x007FFFFF), 0x00800000);
+        // next_up(3.4028235e38) = inf, next_up(inf) = inf and next_up(-inf) = -3.4028235e38
+        assert_eq!(crate::soft_f32::f32_next_up(0x7F7FFFFF), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_next_up(0x7F800000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_next_up(0xFF800000), 0xFF7FFFFF);
+        // NaN
+        assert_eq!(crate::soft_f32::f32_next_up(0xFFC00001), 0xFFC00001);
+    }
+
+    #[test]
+    fn test_f32_next_down() {
+        // next_down(1) = 0.99999994 and next_down(-1) = -1.0000001
+        assert_eq!(crate::soft_f32::f32_next_down(0x3F800000), 0x3F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_next_down(0xBF800000), 0xBF800001);
+        // next_down(0) = next_down(-0) = -2^-149 and next_down(2^-149) = 0
+        assert_eq!(crate::soft_f32::f32_next_down(0x00000000), 0x80000001);
+        assert_eq!(crate::soft_f32::f32_next_down(0x80000000), 0x80000001);
+        assert_eq!(crate::soft_f32::f32_next_down(0x00000001), 0x00000000);
+        // next_down(-inf) = -inf and next_down(inf) = 3.4028235e38
+        assert_eq!(crate::soft_f32::f32_next_down(0xFF800000), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_next_down(0x7F800000), 0x7F7FFFFF);
+        // NaN keeps its sign
+        assert_eq!(crate::soft_f32::f32_next_down(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_next_after() {
+        // next_after(1, 2) = 1.0000001 and next_after(1, -inf) = 0.99999994
+        assert_eq!(crate::soft_f32::f32_next_after(0x3F800000, 0x40000000), 0x3F800001);
+        assert_eq!(crate::soft_f32::f32_next_after(0x3F800000, 0xFF800000), 0x3F7FFFFF);
+        // next_after(1, 1) = 1, and next_after(0, -0) = -0
+        assert_eq!(crate::soft_f32::f32_next_after(0x3F800000, 0x3F800000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_next_after(0x00000000, 0x80000000), 0x80000000);
+        // next_after(-0, 1) = 2^-149 and next_after(0, -1) = -2^-149
+        assert_eq!(crate::soft_f32::f32_next_after(0x80000000, 0x3F800000), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_next_after(0x00000000, 0xBF800000), 0x80000001);
+        // next_after(inf, 0) = 3.4028235e38
+        assert_eq!(crate::soft_f32::f32_next_after(0x7F800000, 0x00000000), 0x7F7FFFFF);
+        // NaN
+        assert_eq!(crate::soft_f32::f32_next_after(0x3F800000, 0x7FC00001), 0x7FC00001);
+        assert_eq!(crate::soft_f32::f32_next_after(0x7FC00002, 0x3F800000), 0x7FC00002);
+    }
+
+    #[test]
+    fn test_f32_ulp() {
+        // ulp(1) = 2^-23 and ulp(-1.5) = 2^-23
+        assert_eq!(crate::soft_f32::f32_ulp(0x3F800000), 0x34000000);
+        assert_eq!(crate::soft_f32::f32_ulp(0xBFC00000), 0x34000000);
+        // ulp(0.99999994) = 2^-24
+        assert_eq!(crate::soft_f32::f32_ulp(0x3F7FFFFF), 0x33800000);
+        // ulp(3.4028235e38) = 2^104
+        assert_eq!(crate::soft_f32::f32_ulp(0x7F7FFFFF), 0x73800000);
+        // ulp(0) = ulp(2^-126) = ulp(1.1754942e-38) = 2^-149
+        assert_eq!(crate::soft_f32::f32_ulp(0x80000000), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_ulp(0x00800000), 0x00000001);
+        assert_eq!(crate::soft_f32::f32_ulp(0x007FFFFF), 0x00000001);
+        // ulp(2^-125) = 2^-148 and ulp(2^-103) = 2^-126
+        assert_eq!(crate::soft_f32::f32_ulp(0x01000000), 0x00000002);
+        assert_eq!(crate::soft_f32::f32_ulp(0x0C000000), 0x00800000);
+        // ulp(-inf) = inf and ulp(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_ulp(0xFF800000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_ulp(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_ulp_distance() {
+        // Adjacent values, in both orders
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x3F800000, 0x3F800001), Some(1));
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x3F800001, 0x3F800000), Some(1));
+        // Across the subnormal boundary and across zero
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x007FFFFF, 0x00800001), Some(2));
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x80000001, 0x00000001), Some(2));
+        // -0 and +0 are equal
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x80000000, 0x00000000), Some(0));
+        // 3.4028235e38 and inf are adjacent, and -inf and inf are the farthest
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x7F7FFFFF, 0x7F800000), Some(1));
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0xFF800000, 0x7F800000), Some(0xFF000000));
+        // NaN
+        assert_eq!(crate::soft_f32::f32_ulp_distance(0x7FC00000, 0x3F800000), None);
+    }
+
+    #[test]
+    fn test_f32_next_exception_flags() {
+        // next_up and next_down raise no flag
+        let mut env = Env::default();
+        crate::soft_f32::f32_next_up_with_env(0x7F7FFFFF, &mut env);
+        crate::soft_f32::f32_next_down_with_env(0x00000001, &mut env);
+        crate::soft_f32::f32_next_after_with_env(0x3F800000, 0x40000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Stepping to infinity overflows
+        assert_eq!(crate::soft_f32::f32_next_after_with_env(0xFF7FFFFF, 0xFF800000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // Stepping to a subnormal or to zero underflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_next_after_with_env(0x00800000, 0x00000000, &mut env), 0x007FFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_next_after_with_env(0x80000001, 0x3F800000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // A signaling NaN is invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_next_up_with_env(0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
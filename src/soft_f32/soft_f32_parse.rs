@@ -0,0 +1,453 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::util::{
+    f32_round_and_pack,
+    f32_shift_right_jam,
+    f32_pack_raw,
+};
+
+use super::big::Big;
+
+use crate::soft_float::{ Env, NanPolicy, RoundingMode, with_thread_env };
+
+// Correctly rounded parsing of decimal strings to Float32 values, with the grammar of the
+// primitive f32: an optional sign, then "inf", "infinity" or "nan" in any case, or digits with
+// an optional point and an optional exponent.
+//
+// The first significant digits are kept in a big integer and the others are only remembered as
+// nonzero, which is exact enough as every midpoint between two Float32 values has at most
+// 112 significant digits. The digits are then divided by the power of 10 with big integers to
+// get the significand and the sticky bit, which are rounded as any other operation.
+
+// The number of significant digits which are kept
+const F32_PARSE_DIGITS: usize = 120;
+
+/// Error of the parsing of a decimal string to `F32`, when the string is empty or is not a
+/// valid float literal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseF32Error {
+    kind: ParseF32ErrorKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParseF32ErrorKind {
+    Empty,
+    Invalid,
+}
+
+impl fmt::Display for ParseF32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseF32ErrorKind::Empty => f.write_str("cannot parse float from empty string"),
+            ParseF32ErrorKind::Invalid => f.write_str("invalid float literal"),
+        }
+    }
+}
+
+impl std::error::Error for ParseF32Error {}
+
+pub(super) const F32_PARSE_EMPTY: ParseF32Error = ParseF32Error { kind: ParseF32ErrorKind::Empty };
+pub(super) const F32_PARSE_INVALID: ParseF32Error = ParseF32Error { kind: ParseF32ErrorKind::Invalid };
+
+// A parsed string, either an exact value, sig x 2^(exp - 156) to round as f32_round_and_pack
+// does, or a NaN with its sign, whether it is quiet and its payload
+pub(super) enum Parsed {
+    Exact(u32),
+    Round(i32, i32, i32),
+    Nan(i32, bool, u32),
+}
+
+// The parsed NaN in the encoding of the NaN policy, where MIPS legacy inverts the quiet bit and
+// gives its default NaN for a quiet NaN without a payload
+pub(super) const fn f32_parsed_nan(sign: i32, is_quiet: bool, payload: u32, is_mips_legacy: bool) -> u32 {
+    match (is_quiet != is_mips_legacy, payload) {
+        (true, _) => f32_pack_raw(sign, 0xFF, 0x400000 | payload as i32),
+        (false, 0) => f32_pack_raw(sign, 0xFF, 0x3FFFFF),
+        (false, _) => f32_pack_raw(sign, 0xFF, payload as i32),
+    }
+}
+
+// Whether the bytes of S from START are NAME, in any case
+pub(super) const fn f32_parse_name(s: &[u8], start: usize, name: &[u8]) -> bool {
+    if s.len() - start != name.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < name.len() {
+        if s[start + i].to_ascii_lowercase() != name[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn f32_parse(s: &str) -> Result<Parsed, ParseF32Error> {
+    let s = s.as_bytes();
+    if s.is_empty() {
+        return Err(F32_PARSE_EMPTY);
+    }
+
+    let mut i = 0;
+    let sign = match s[0] {
+        b'-' => 1,
+        _ => 0,
+    };
+    if s[0] == b'-' || s[0] == b'+' {
+        i += 1;
+    }
+
+    if f32_parse_name(s, i, b"inf") || f32_parse_name(s, i, b"infinity") {
+        return Ok(Parsed::Exact(f32_pack_raw(sign, 0xFF, 0)));
+    }
+    if f32_parse_name(s, i, b"nan") {
+        return Ok(Parsed::Nan(sign, true, 0));
+    }
+
+    // The value is 0.d1d2d3... x 10^point, of which the first digits are kept in sig
+    let mut sig = Big::from_u64(0);
+    let mut digits = 0;
+    let mut point: i64 = 0;
+    let mut sticky = false;
+    let mut any_digit = false;
+    let mut is_frac = false;
+    while i < s.len() {
+        let c = s[i];
+        if c == b'.' && !is_frac {
+            is_frac = true;
+        } else if c.is_ascii_digit() {
+            any_digit = true;
+            if digits == 0 && c == b'0' {
+                // Leading zero
+                point -= is_frac as i64;
+            } else {
+                point += !is_frac as i64;
+                if digits < F32_PARSE_DIGITS {
+                    sig = sig.mul_add_small(10, (c - b'0') as u32);
+                    digits += 1;
+                } else if c != b'0' {
+                    sticky = true;
+                }
+            }
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if !any_digit {
+        return Err(F32_PARSE_INVALID);
+    }
+
+    if i < s.len() && (s[i] == b'e' || s[i] == b'E') {
+        i += 1;
+        let exp_sign = match i < s.len() && s[i] == b'-' {
+            true => -1,
+            false => 1,
+        };
+        if i < s.len() && (s[i] == b'-' || s[i] == b'+') {
+            i += 1;
+        }
+        if i == s.len() {
+            return Err(F32_PARSE_INVALID);
+        }
+        // Saturate the exponent, any larger one overflows or underflows all the same
+        let mut exp: i64 = 0;
+        while i < s.len() && s[i].is_ascii_digit() {
+            if exp < 0x10000000 {
+                exp = exp * 10 + (s[i] - b'0') as i64;
+            }
+            i += 1;
+        }
+        point += exp_sign * exp;
+    }
+    if i < s.len() {
+        return Err(F32_PARSE_INVALID);
+    }
+
+    if digits == 0 {
+        return Ok(Parsed::Exact(f32_pack_raw(sign, 0, 0)));
+    }
+    if point > 39 {
+        // At least 10^39, overflow
+        return Ok(Parsed::Round(sign, 0x1FF, 0x40000000));
+    }
+    if point < -45 {
+        // Below 10^-46, less than a quarter of the smallest subnormal
+        return Ok(Parsed::Round(sign, -0x100, 1));
+    }
+
+    // The kept digits are n / m
+    let dec_exp = point as i32 - digits as i32;
+    let one = Big::from_u64(1);
+    let (mut n, mut m) = match dec_exp >= 0 {
+        true => (sig.mul_pow10(dec_exp), one),
+        false => (sig, one.mul_pow10(-dec_exp)),
+    };
+
+    // Scale n / m to [2^30, 2^31) by 2^shift
+    let mut shift = 30 - (n.bit_len() - m.bit_len());
+    if shift >= 0 {
+        n = n.mul_pow2(shift);
+    } else {
+        m = m.mul_pow2(-shift);
+    }
+    if let Ordering::Less = n.cmp(m.mul_pow2(30)) {
+        n = n.mul_pow2(1);
+        shift += 1;
+    }
+
+    // Long division, one bit of the quotient at a time
+    let mut q = 0;
+    let mut bit = 31;
+    while bit > 0 {
+        bit -= 1;
+        let t = m.mul_pow2(bit);
+        if let Ordering::Less = n.cmp(t) {
+            continue;
+        }
+        n = n.sub(t);
+        q |= 1 << bit;
+    }
+    sticky |= !n.is_zero();
+
+    Ok(Parsed::Round(sign, 156 - shift, q | sticky as i32))
+}
+
+// f32_round_and_pack for the value only, without the environment
+pub(super) const fn f32_round_and_pack_const(sign: i32, exp: i32, sig: i32, rounding_mode: RoundingMode) -> u32 {
+    let round_increment = match rounding_mode {
+        RoundingMode::NearEven | RoundingMode::NearMaxMag => 0x40,
+        RoundingMode::Min if sign == 1 => 0x7F,
+        RoundingMode::Max if sign == 0 => 0x7F,
+        _ => 0,
+    };
+
+    let (exp, sig) = match exp < 0 {
+        true => (0, f32_shift_right_jam(sig, -exp)),
+        false => (exp, sig),
+    };
+    if exp > 0xFD || (exp == 0xFD && (sig as u32) + round_increment >= 0x80000000) {
+        // Overflow, round to infinity or to the largest finite value
+        return f32_pack_raw(sign, 0xFF, 0) - (round_increment == 0) as u32;
+    }
+
+    let round_bits = sig & 0x7F;
+    let mut r_frac = ((sig as u32) + round_increment) >> 7;
+    match rounding_mode {
+        RoundingMode::Odd if round_bits != 0 => r_frac |= 1,
+        RoundingMode::NearEven if round_bits == 0x40 => r_frac &= !1,
+        _ => {}
+    }
+    let exp = if r_frac == 0 { 0 } else { exp };
+
+    f32_pack_raw(sign, exp, r_frac as i32)
+}
+
+pub fn f32_from_str(s: &str) -> Result<u32, ParseF32Error> {
+    with_thread_env(|env| f32_from_str_with_env(s, env))
+}
+
+// Parse a decimal string, correctly rounded in the rounding mode of the environment.
+// An inexact value raises inexact, and underflow or overflow as any other operation.
+// A NaN is quiet in the encoding of the NaN policy.
+pub fn f32_from_str_with_env(s: &str, env: &mut Env) -> Result<u32, ParseF32Error> {
+    match f32_parse(s)? {
+        Parsed::Exact(r) => Ok(r),
+        Parsed::Round(sign, exp, sig) => Ok(f32_round_and_pack(sign, exp, sig, env)),
+        Parsed::Nan(sign, is_quiet, payload) => {
+            Ok(f32_parsed_nan(sign, is_quiet, payload, env.nan_policy() == NanPolicy::MipsLegacy))
+        }
+    }
+}
+
+// Parse a decimal string in a const context, correctly rounded in ROUNDING_MODE, without flags.
+// A NaN has the IEEE 754 quiet bit.
+pub const fn f32_from_str_const(s: &str, rounding_mode: RoundingMode) -> Result<u32, ParseF32Error> {
+    match f32_parse(s) {
+        Ok(Parsed::Exact(r)) => Ok(r),
+        Ok(Parsed::Round(sign, exp, sig)) => Ok(f32_round_and_pack_const(sign, exp, sig, rounding_mode)),
+        Ok(Parsed::Nan(sign, is_quiet, payload)) => Ok(f32_parsed_nan(sign, is_quiet, payload, false)),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    const ROUNDING_MODES: [RoundingMode; 6] = [
+        RoundingMode::NearEven,
+        RoundingMode::NearMaxMag,
+        RoundingMode::MinMag,
+        RoundingMode::Min,
+        RoundingMode::Max,
+        RoundingMode::Odd,
+    ];
+
+    // Parse in a rounding mode, also checking the const parsing
+    fn parse(s: &str, rounding_mode: RoundingMode) -> (u32, ExceptionFlags) {
+        let mut env = Env::default();
+        env.set_rounding_mode(rounding_mode);
+        let r = crate::soft_f32::f32_from_str_with_env(s, &mut env).unwrap();
+        assert_eq!(crate::soft_f32::f32_from_str_const(s, rounding_mode), Ok(r));
+        (r, env.flags())
+    }
+
+    #[test]
+    fn test_f32_from_str() {
+        assert_eq!(crate::soft_f32::f32_from_str("1"), Ok(0x3F800000));
+        assert_eq!(crate::soft_f32::f32_from_str("-2.5"), Ok(0xC0200000));
+        assert_eq!(crate::soft_f32::f32_from_str("+.5"), Ok(0x3F000000));
+        assert_eq!(crate::soft_f32::f32_from_str("3."), Ok(0x40400000));
+        assert_eq!(crate::soft_f32::f32_from_str("0.1"), Ok(0x3DCCCCCD));
+        assert_eq!(crate::soft_f32::f32_from_str("1.5E-2"), Ok(0x3C75C28F));
+        assert_eq!(crate::soft_f32::f32_from_str("12e+3"), Ok(0x463B8000));
+        assert_eq!(crate::soft_f32::f32_from_str("000123.4500"), Ok(0x42F6E666));
+        assert_eq!(crate::soft_f32::f32_from_str("0"), Ok(0x00000000));
+        assert_eq!(crate::soft_f32::f32_from_str("-0.000e10"), Ok(0x80000000));
+        // The largest finite value and the smallest subnormal
+        assert_eq!(crate::soft_f32::f32_from_str("3.4028235e38"), Ok(0x7F7FFFFF));
+        assert_eq!(crate::soft_f32::f32_from_str("1e-45"), Ok(0x00000001));
+    }
+
+    #[test]
+    fn test_f32_from_str_special() {
+        assert_eq!(crate::soft_f32::f32_from_str("inf"), Ok(0x7F800000));
+        assert_eq!(crate::soft_f32::f32_from_str("-Infinity"), Ok(0xFF800000));
+        assert_eq!(crate::soft_f32::f32_from_str("+INF"), Ok(0x7F800000));
+        assert_eq!(crate::soft_f32::f32_from_str("NaN"), Ok(0x7FC00000));
+        assert_eq!(crate::soft_f32::f32_from_str("-nan"), Ok(0xFFC00000));
+        // Saturated exponents
+        assert_eq!(crate::soft_f32::f32_from_str("1e99999999999999999999"), Ok(0x7F800000));
+        assert_eq!(crate::soft_f32::f32_from_str("-1e-99999999999999999999"), Ok(0x80000000));
+        assert_eq!(crate::soft_f32::f32_from_str("0e99999999999999999999"), Ok(0x00000000));
+    }
+
+    #[test]
+    fn test_f32_from_str_error() {
+        let empty = crate::soft_f32::f32_from_str("").unwrap_err();
+        let invalid = crate::soft_f32::f32_from_str("x").unwrap_err();
+        assert_eq!(empty.to_string(), "cannot parse float from empty string");
+        assert_eq!(invalid.to_string(), "invalid float literal");
+
+        for s in ["+", "-", ".", "+.", "e5", ".e1", "1e", "1e+", " 1", "1 ", "1..2", "1.2.3", "1_0",
+                  "0x10", "infin", "nan(1)", "--1", "1e5.0", "١"] {
+            assert_eq!(crate::soft_f32::f32_from_str(s), Err(invalid), "{:?}", s);
+            assert_eq!(crate::soft_f32::f32_from_str_const(s, RoundingMode::NearEven), Err(invalid));
+        }
+    }
+
+    #[test]
+    fn test_f32_from_str_rounding_mode() {
+        // 0.1 is between 0x3DCCCCCC and 0x3DCCCCCD, closer to the latter
+        let expected = [0x3DCCCCCD, 0x3DCCCCCD, 0x3DCCCCCC, 0x3DCCCCCC, 0x3DCCCCCD, 0x3DCCCCCD];
+        for (&rounding_mode, &r) in ROUNDING_MODES.iter().zip(expected.iter()) {
+            assert_eq!(parse("0.1", rounding_mode), (r, ExceptionFlags::INEXACT));
+            assert_eq!(parse("-0.1", rounding_mode).0, r ^ 0x80000000 ^ match rounding_mode {
+                // The directed modes round the other way for a negative value
+                RoundingMode::Min => 1,
+                RoundingMode::Max => 1,
+                _ => 0,
+            });
+        }
+
+        // 2^24 + 1 is the midpoint between 2^24 and 2^24 + 2
+        let expected = [0x4B800000, 0x4B800001, 0x4B800000, 0x4B800000, 0x4B800001, 0x4B800001];
+        for (&rounding_mode, &r) in ROUNDING_MODES.iter().zip(expected.iter()) {
+            assert_eq!(parse("16777217", rounding_mode), (r, ExceptionFlags::INEXACT));
+        }
+
+        // Exact values are not rounded
+        for &rounding_mode in ROUNDING_MODES.iter() {
+            assert_eq!(parse("16777218", rounding_mode), (0x4B800001, ExceptionFlags::empty()));
+            assert_eq!(parse("-0.375", rounding_mode), (0xBEC00000, ExceptionFlags::empty()));
+        }
+    }
+
+    #[test]
+    fn test_f32_from_str_long_mantissa() {
+        // The midpoint between 1 and the next value, with a nonzero digit far after it
+        let mid = "1.00000005960464477539062500000000000000000000000000000000000000000000000\
+                   000000000000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(parse(mid, RoundingMode::NearEven), (0x3F800000, ExceptionFlags::INEXACT));
+        assert_eq!(parse(&(mid.to_owned() + "1"), RoundingMode::NearEven), (0x3F800001, ExceptionFlags::INEXACT));
+        assert_eq!(parse(&(mid.to_owned() + "1e0"), RoundingMode::MinMag), (0x3F800000, ExceptionFlags::INEXACT));
+
+        // Exactly 2^-149 with its 105 significant digits and many zeros
+        let min = "0.00000000000000000000000000000000000000000000140129846432481707092372958328991613\
+                   128026194187651577175706828388979108268586060148663818836212158203125";
+        let zeros = "0".repeat(1000);
+        assert_eq!(parse(min, RoundingMode::NearEven), (0x00000001, ExceptionFlags::empty()));
+        assert_eq!(parse(&(min.to_owned() + &zeros), RoundingMode::Odd), (0x00000001, ExceptionFlags::empty()));
+        assert_eq!(parse(&(zeros.clone() + "1" + &zeros + "e-1000"), RoundingMode::NearEven), (0x3F800000, ExceptionFlags::empty()));
+    }
+
+    #[test]
+    fn test_f32_from_str_flags() {
+        // The midpoint between the largest finite value and 2^128 overflows
+        let (r, flags) = parse("3.40282356779733661637539395458142568448e38", RoundingMode::NearEven);
+        assert_eq!(r, 0x7F800000);
+        assert_eq!(flags, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+        assert_eq!(parse("1e39", RoundingMode::MinMag), (0x7F7FFFFF, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("-1e39", RoundingMode::Min).0, 0xFF800000);
+
+        // Tiny values underflow
+        assert_eq!(parse("1e-40", RoundingMode::NearEven), (0x000116C2, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("1e-50", RoundingMode::NearEven), (0x00000000, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("1e-50", RoundingMode::Max), (0x00000001, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        assert_eq!(parse("-1e-50", RoundingMode::Odd), (0x80000001, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT));
+        // An exact subnormal does not underflow
+        assert_eq!(parse("1.1754942e-38", RoundingMode::NearEven).0, 0x007FFFFF);
+        assert_eq!(parse("2.8025969286496341418474591665798322625605238837530315435141365677795821653717212029732763767242431640625e-45", RoundingMode::NearEven),
+                   (0x00000002, ExceptionFlags::empty()));
+
+        // Parsing raises the flags of the thread environment
+        crate::soft_float::clear_exception_flags();
+        assert_eq!("0.2".parse::<crate::soft_f32::F32>().unwrap().value(), 0x3E4CCCCD);
+        assert_eq!(crate::soft_float::get_exception_flags(), ExceptionFlags::INEXACT);
+        crate::soft_float::clear_exception_flags();
+        assert!("0.2x".parse::<crate::soft_f32::F32>().is_err());
+    }
+
+    #[test]
+    fn test_f32_from_str_const() {
+        const PI: u32 = match crate::soft_f32::f32_from_str_const("3.14159265358979", RoundingMode::NearEven) {
+            Ok(r) => r,
+            Err(_) => panic!(),
+        };
+        assert_eq!(PI, 0x40490FDB);
+    }
+
+    #[test]
+    fn test_f32_from_str_nan_policy() {
+        for &nan_policy in [NanPolicy::RiscV, NanPolicy::Armv8DefaultNan, NanPolicy::X87].iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            assert_eq!(crate::soft_f32::f32_from_str_with_env("nan", &mut env), Ok(0x7FC00000));
+            assert_eq!(crate::soft_f32::f32_from_str_with_env("-NaN", &mut env), Ok(0xFFC00000));
+        }
+
+        // The quiet bit is inverted in MIPS legacy, so a quiet NaN is its default NaN
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_f32::f32_from_str_with_env("nan", &mut env), Ok(0x7FBFFFFF));
+        assert_eq!(crate::soft_f32::f32_from_str_with_env("-nan", &mut env), Ok(0xFFBFFFFF));
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        // The const parsing has the IEEE 754 quiet bit
+        assert_eq!(crate::soft_f32::f32_from_str_const("nan", RoundingMode::NearEven), Ok(0x7FC00000));
+    }
+
+    #[test]
+    fn test_f32_from_str_round_trip() {
+        // The shortest and the exact outputs of the formatting parse back to the same value
+        let mut a = 0u32;
+        while a < 0x7F800000 {
+            let v = crate::soft_f32::F32::from_bits(a);
+            assert_eq!(crate::soft_f32::f32_from_str(&format!("{}", v)), Ok(a));
+            assert_eq!(crate::soft_f32::f32_from_str(&format!("{:e}", v)), Ok(a));
+            assert_eq!(parse(&format!("{:.150}", v), RoundingMode::Odd), (a, ExceptionFlags::empty()));
+            a += 0x10001;
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_is_nan,
+    f32_is_signaling_nan_in,
+    f32_pack_raw,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_exp,
+    fixed_ln,
+    fixed_isqrt,
+    fixed_reduce_ln2,
+    f32_to_fixed_parts,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_LN2, FIXED_LN2_LO,
+    FIXED_SQRT_2,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// |Y x ln(X)| is limited to 512, where any result overflows or underflows
+pub(crate) const POW_LIMIT: i128 = 512 << FIXED_FRAC_BITS;
+
+// Split the finite and nonzero A into its sign, and an odd m and e where |A| = m x 2^e
+pub(crate) fn f32_odd_parts(a: u32) -> (i32, i128, i32) {
+    let (a_sign, m, e) = f32_to_fixed_parts(a);
+    let frac = m >> 39;
+    let zeros = frac.trailing_zeros() as i32;
+
+    (a_sign, frac >> zeros, e - 23 + zeros)
+}
+
+// X^Y exactly, where |X| = x_m x 2^x_e and |Y| = y_m x 2^y_e with odd x_m and y_m, when the result
+// is representable. As y_m is odd, X has to be a perfect 2^-y_e-th power for a negative y_e, and
+// the integer power of a larger x_m is representable only when it fits in 24 bits.
+fn f32_pow_exact(r_sign: i32, x_m: i128, x_e: i32, y_sign: i32, y_m: i128, y_e: i32, env: &mut Env) -> Option<u32> {
+    let mut x_m = x_m;
+    let mut x_e = x_e;
+    for _ in y_e..0 {
+        if x_e & 1 != 0 {
+            return None;
+        }
+        let root = fixed_isqrt(x_m as u128) as i128;
+        if root * root != x_m {
+            return None;
+        }
+        x_m = root;
+        x_e /= 2;
+    }
+
+    // The integer power, large enough to overflow or underflow above 2^64
+    let power = y_m << y_e.clamp(0, 64);
+    let power = if y_sign == 1 { -power } else { power };
+    let r_exp = (x_e as i128 * power).clamp(-1024, 1024) as i32;
+
+    if x_m == 1 {
+        return Some(f32_round_fixed(if r_sign == 1 { -1 } else { 1 }, r_exp, true, env));
+    }
+    if power < 0 || (128 - x_m.leading_zeros() as i128) * power > 126 {
+        return None;
+    }
+
+    let r = x_m.pow(power as u32);
+    Some(f32_round_fixed(if r_sign == 1 { -r } else { r }, r_exp, true, env))
+}
+
+// X^Y for a finite and nonzero Y with |Y| = y_m x 2^y_e and an odd y_m, and X other than NaN and 1,
+// before rounding as the fixed-point v x 2^scale. The result of the special cases, and the
+// exact results, are returned as an error.
+fn f32_pow_parts(a: u32, y_sign: i32, y_m: i128, y_e: i32, env: &mut Env) -> Result<(i128, i32), u32> {
+    let a_sign = (a >> 31) as i32;
+    let a_abs = a & 0x7FFFFFFF;
+
+    // The result is negative only for a negative X and an odd Y
+    let r_sign = if y_e == 0 { a_sign } else { 0 };
+
+    if a_abs == 0 {
+        if y_sign == 1 {
+            // Zero to a negative power is infinity
+            env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+            return Err(f32_pack_raw(r_sign, 0xFF, 0));
+        }
+        return Err(f32_pack_raw(r_sign, 0, 0));
+    }
+    if a_abs == 0x7F800000 {
+        return match y_sign {
+            0 => Err(f32_pack_raw(r_sign, 0xFF, 0)),
+            _ => Err(f32_pack_raw(r_sign, 0, 0)),
+        };
+    }
+    if a_sign == 1 && y_e < 0 {
+        // Invalid, a negative X to a power which is not an integer
+        env.raise(ExceptionFlags::INVALID);
+        return Err(f32_default_nan(env));
+    }
+
+    let (_, x_m, x_e) = f32_odd_parts(a);
+    if let Some(r) = f32_pow_exact(r_sign, x_m, x_e, y_sign, y_m, y_e, env) {
+        return Err(r);
+    }
+
+    // ln|X| = e x ln(2) + ln(m), where m is in [1/sqrt(2), sqrt(2)] and ln(m) = ln_m x 2^-k
+    let (_, mut m, mut e) = f32_to_fixed_parts(a);
+    if m > FIXED_SQRT_2 {
+        m >>= 1;
+        e += 1;
+    }
+    let (ln_m, k) = fixed_ln(m);
+
+    // y_m x ln|X|, where the products are exact and ln(m) keeps its precision when e = 0
+    let e = e as i128;
+    let s = y_m * (e * FIXED_LN2 + ((e * FIXED_LN2_LO) >> FIXED_FRAC_BITS)) + ((y_m * ln_m) >> k);
+
+    // Y x ln|X|
+    let t = match y_e >= 0 {
+        true if y_e > 40 || s.abs() > POW_LIMIT >> y_e => POW_LIMIT * s.signum(),
+        true => s << y_e,
+        false => match s.checked_shr(-y_e as u32).unwrap_or(if s < 0 { -1 } else { 0 }) {
+            // A huge Y which is not an integer overflows or underflows all the same
+            t if t.abs() > POW_LIMIT => POW_LIMIT * t.signum(),
+            t => t,
+        },
+    };
+    let t = if y_sign == 1 { -t } else { t };
+
+    let (n, r) = fixed_reduce_ln2(t);
+    let r = fixed_exp(r);
+    Ok((if r_sign == 1 { -r } else { r }, n - FIXED_FRAC_BITS))
+}
+
+pub fn f32_powf(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_powf_with_env(a, b, env))
+}
+
+// A to the power of B, with the special cases of C99 Annex F. The result is exact whenever it is
+// representable, and otherwise within 0.5 ULP plus the 2^-56 relative error of the evaluation
+// times |B x ln(A)|, below 2^-47 as the result overflows or underflows above 104.
+pub fn f32_powf_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| f32_powf_unflushed(a, b, env), env)
+}
+
+fn f32_powf_unflushed(a: u32, b: u32, env: &mut Env) -> u32 {
+    match f32_powf_fixed(a, b, env) {
+        Ok((v, scale)) => f32_round_fixed(v, scale, false, env),
+        Err(r) => r,
+    }
+}
+
+// A^B before rounding, as the fixed-point v x 2^scale. The result of the special cases, and the
+// exact results, are returned as an error.
+pub(crate) fn f32_powf_fixed(a: u32, b: u32, env: &mut Env) -> Result<(i128, i32), u32> {
+    let b_abs = b & 0x7FFFFFFF;
+
+    if b_abs == 0 || a == 0x3F800000 {
+        if f32_is_signaling_nan_in(a, env) || f32_is_signaling_nan_in(b, env) {
+            // Propagate NaN
+            return Err(f32_propagate_nan(a, b, env));
+        }
+        // X^0 = 1 and 1^Y = 1, even for a quiet NaN
+        return Err(f32_pack_raw(0, 0x7F, 0));
+    }
+    if f32_is_nan(a) || f32_is_nan(b) {
+        // Propagate NaN
+        return Err(f32_propagate_nan(a, b, env));
+    }
+
+    if b_abs == 0x7F800000 {
+        let a_abs = a & 0x7FFFFFFF;
+        let b_sign = (b >> 31) as i32;
+        if a_abs == 0x3F800000 {
+            // (-1)^(+-inf) = 1
+            return Err(f32_pack_raw(0, 0x7F, 0));
+        }
+        if a_abs == 0 && b_sign == 1 {
+            // Zero to the power of -inf is infinity
+            env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+            return Err(f32_pack_raw(0, 0xFF, 0));
+        }
+        // |X|^+inf is +inf above 1 and +0 below, and the other way around for -inf
+        return match (a_abs > 0x3F800000) == (b_sign == 0) {
+            true => Err(f32_pack_raw(0, 0xFF, 0)),
+            false => Err(f32_pack_raw(0, 0, 0)),
+        };
+    }
+
+    let (b_sign, b_m, b_e) = f32_odd_parts(b);
+    f32_pow_parts(a, b_sign, b_m, b_e, env)
+}
+
+pub fn f32_powi(a: u32, n: i32) -> u32 {
+    with_thread_env(|env| f32_powi_with_env(a, n, env))
+}
+
+// A to the power of the integer N, which needs not be representable in Float32. The special
+// cases and the accuracy are those of f32_powf.
+pub fn f32_powi_with_env(a: u32, n: i32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_powi_unflushed(a, n, env), env)
+}
+
+fn f32_powi_unflushed(a: u32, n: i32, env: &mut Env) -> u32 {
+    if n == 0 || a == 0x3F800000 {
+        if f32_is_signaling_nan_in(a, env) {
+            // Propagate NaN
+            return f32_propagate_nan(a, 0, env);
+        }
+        // X^0 = 1 and 1^N = 1, even for a quiet NaN
+        return f32_pack_raw(0, 0x7F, 0);
+    }
+    if f32_is_nan(a) {
+        // Propagate NaN
+        return f32_propagate_nan(a, 0, env);
+    }
+
+    let zeros = n.trailing_zeros();
+    match f32_pow_parts(a, (n < 0) as i32, (n.unsigned_abs() >> zeros) as i128, zeros as i32, env) {
+        Ok((v, scale)) => f32_round_fixed(v, scale, false, env),
+        Err(r) => r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_powf() {
+        // 2^10 = 1024
+        assert_eq!(crate::soft_f32::f32_powf(0x40000000, 0x41200000), 0x44800000);
+        // 9^0.5 = 3
+        assert_eq!(crate::soft_f32::f32_powf(0x41100000, 0x3F000000), 0x40400000);
+        // 0.5^-3 = 8
+        assert_eq!(crate::soft_f32::f32_powf(0x3F000000, 0xC0400000), 0x41000000);
+        // (-2)^3 = -8
+        assert_eq!(crate::soft_f32::f32_powf(0xC0000000, 0x40400000), 0xC1000000);
+        // 3^0.5 = 1.7320508
+        assert_eq!(crate::soft_f32::f32_powf(0x40400000, 0x3F000000), 0x3FDDB3D7);
+        // 3.1415927^3.1415927 = 36.462166
+        assert_eq!(crate::soft_f32::f32_powf(0x40490FDB, 0x40490FDB), 0x4211D942);
+        // 1.0000001^8388608 = 2.7182817
+        assert_eq!(crate::soft_f32::f32_powf(0x3F800001, 0x4B000000), 0x402DF854);
+        // 10^-3 = 0.001
+        assert_eq!(crate::soft_f32::f32_powf(0x41200000, 0xC0400000), 0x3A83126F);
+    }
+
+    #[test]
+    fn test_f32_powi() {
+        // 3^5 = 243
+        assert_eq!(crate::soft_f32::f32_powi(0x40400000, 5), 0x43730000);
+        // (-2)^3 = -8
+        assert_eq!(crate::soft_f32::f32_powi(0xC0000000, 3), 0xC1000000);
+        // 1.5^-3 = 0.2962963
+        assert_eq!(crate::soft_f32::f32_powi(0x3FC00000, -3), 0x3E97B426);
+        // 1.0000001^2147483647 = Inf
+        assert_eq!(crate::soft_f32::f32_powi(0x3F800001, 2147483647), 0x7F800000);
+
+        // NaN^0 = 1 for a quiet NaN, and NaN for a signaling one
+        assert_eq!(crate::soft_f32::f32_powi(0x7FC00001, 0), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powi(0x7F800001, 0), 0x7FC00001);
+        // (-0)^-1 = -Inf
+        assert_eq!(crate::soft_f32::f32_powi(0x80000000, -1), 0xFF800000);
+        // (-Inf)^2 = Inf
+        assert_eq!(crate::soft_f32::f32_powi(0xFF800000, 2), 0x7F800000);
+    }
+
+    #[test]
+    fn test_f32_powf_special() {
+        // x^+-0 = 1, even for a quiet NaN, infinity and zero
+        assert_eq!(crate::soft_f32::f32_powf(0x7FC00001, 0x00000000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf(0xFF800000, 0x80000000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf(0x00000000, 0x00000000), 0x3F800000);
+        // 1^y = 1, even for a quiet NaN and infinity, but not for a signaling NaN
+        assert_eq!(crate::soft_f32::f32_powf(0x3F800000, 0x7FC00001), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_powf(0x3F800000, 0x7F800001), 0x7FC00001);
+        assert_eq!(crate::soft_f32::f32_powf(0x3F800000, 0xFF800000), 0x3F800000);
+        // (-1)^+-Inf = 1
+        assert_eq!(crate::soft_f32::f32_powf(0xBF800000, 0x7F800000), 0x3F800000);
+
+        // (+-0)^y = +-Inf for an odd integer y < 0
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0xC0400000), 0xFF800000);
+        // (+-0)^y = +Inf for any other y < 0
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0xC0000000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0xBF000000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0xFF800000), 0x7F800000);
+        // (+-0)^y = +-0 for an odd integer y > 0
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0x40400000), 0x80000000);
+        // (+-0)^y = +0 for any other y > 0
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0x40000000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_powf(0x80000000, 0x3F000000), 0x00000000);
+
+        // x^-Inf = +Inf for |x| < 1, and +0 for |x| > 1
+        assert_eq!(crate::soft_f32::f32_powf(0xBF000000, 0xFF800000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_powf(0x40000000, 0xFF800000), 0x00000000);
+        // x^+Inf = +0 for |x| < 1, and +Inf for |x| > 1
+        assert_eq!(crate::soft_f32::f32_powf(0x3F000000, 0x7F800000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_powf(0xC0000000, 0x7F800000), 0x7F800000);
+
+        // (-Inf)^y = -0 for an odd integer y < 0, and +0 for any other y < 0
+        assert_eq!(crate::soft_f32::f32_powf(0xFF800000, 0xC0400000), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_powf(0xFF800000, 0xC0000000), 0x00000000);
+        // (-Inf)^y = -Inf for an odd integer y > 0, and +Inf for any other y > 0
+        assert_eq!(crate::soft_f32::f32_powf(0xFF800000, 0x40400000), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_powf(0xFF800000, 0x3F000000), 0x7F800000);
+        // (+Inf)^y = +0 for y < 0, and +Inf for y > 0
+        assert_eq!(crate::soft_f32::f32_powf(0x7F800000, 0xC0400000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_powf(0x7F800000, 0x3F000000), 0x7F800000);
+
+        // NaN^1 = NaN
+        assert_eq!(crate::soft_f32::f32_powf(0x7FC00001, 0x3F800000), 0x7FC00001);
+        // 2^NaN = NaN
+        assert_eq!(crate::soft_f32::f32_powf(0x40000000, 0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_powf_exception_flags() {
+        // (-2)^0.5 = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0xC0000000, 0x3F000000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // 0^-1 = Inf, divide by zero
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x00000000, 0xBF800000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // sNaN^0 = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x7F800001, 0x00000000, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // 9^0.5 = 3, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x41100000, 0x3F000000, &mut env), 0x40400000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 10^39 = Inf, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x41200000, 0x421C0000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 2^-150 = 0, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_powi_with_env(0x40000000, -150, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // Huge exponents which are not integers, with bases above and below 1
+        let huge = [
+            // 5.19e17^3084170.3 and 3.7e-21^-633368.6 overflow
+            (0x5CE67B78, 0x4A3C3E29, 0x7F800000, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT),
+            (0x1D8BC834, 0xC91AA18A, 0x7F800000, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT),
+            // 2.9e-37^150010.28 and 1.5^-3084170.3 underflow
+            (0x02C3B54F, 0x48127E92, 0x00000000, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT),
+            (0x3FC00000, 0xCA3C3E29, 0x00000000, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT),
+        ];
+        for &(a, b, r, flags) in huge.iter() {
+            let mut env = Env::default();
+            assert_eq!(crate::soft_f32::f32_powf_with_env(a, b, &mut env), r);
+            assert_eq!(env.flags(), flags);
+        }
+    }
+
+    #[test]
+    fn test_f32_powf_rounding_mode() {
+        // 3.1415927^3.1415927
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x40490FDB, 0x40490FDB, &mut Env::new(RoundingMode::NearEven)), 0x4211D942);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x40490FDB, 0x40490FDB, &mut Env::new(RoundingMode::MinMag)), 0x4211D942);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x40490FDB, 0x40490FDB, &mut Env::new(RoundingMode::Min)), 0x4211D942);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x40490FDB, 0x40490FDB, &mut Env::new(RoundingMode::Max)), 0x4211D943);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x40490FDB, 0x40490FDB, &mut Env::new(RoundingMode::NearMaxMag)), 0x4211D942);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x40490FDB, 0x40490FDB, &mut Env::new(RoundingMode::Odd)), 0x4211D943);
+
+        // 29/64^5 is a tie, exactly between two Float32
+        assert_eq!(crate::soft_f32::f32_powi_with_env(0x3EE80000, 5, &mut Env::new(RoundingMode::NearEven)), 0x3C9C7CD6);
+        assert_eq!(crate::soft_f32::f32_powi_with_env(0x3EE80000, 5, &mut Env::new(RoundingMode::NearMaxMag)), 0x3C9C7CD7);
+    }
+
+    #[test]
+    fn test_f32_pow_flush_to_zero() {
+        // (2^-64)^2 = 2^-128 is subnormal, so it is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_powi_with_env(0x1F800000, 2, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x1F800000, 0x40000000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        // pow(0, 1) is 0 with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_powf_with_env(0x00000001, 0x3F800000, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_powi_with_env(0x80000001, 3, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
@@ -0,0 +1,268 @@
+use super::util::{
+    f32_norm_round_and_pack,
+    f32_propagate_nan,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Special cases of the remainder of A / B, which are returned as an error
+fn f32_rem_special(a: u32, b: u32, env: &mut Env) -> Result<(), u32> {
+    let a_exp = f32_exp(a);
+    let a_frac = f32_frac(a);
+    let b_exp = f32_exp(b);
+    let b_frac = f32_frac(b);
+
+    if (a_exp == 0xFF && a_frac != 0) || (b_exp == 0xFF && b_frac != 0) {
+        // Propagate NaN
+        return Err(f32_propagate_nan(a, b, env));
+    }
+    if a_exp == 0xFF || (b_exp | b_frac) == 0 {
+        // Inf rem B and A rem 0, invalid
+        env.raise(ExceptionFlags::INVALID);
+        return Err(f32_default_nan(env));
+    }
+    if b_exp == 0xFF || (a_exp | a_frac) == 0 {
+        // A rem Inf and 0 rem B are A
+        return Err(a);
+    }
+
+    Ok(())
+}
+
+// The biased exponent and the significand with the hidden bit of the finite and nonzero A,
+// that is |A| = frac x 2^(exp - 150)
+fn f32_rem_unpack(a: u32) -> (i32, u64) {
+    let exp = f32_exp(a);
+    let frac = f32_frac(a);
+    if exp == 0 {
+        let (norm_exp, norm_frac) = f32_norm_subnormal_frac(frac);
+        return (norm_exp, norm_frac as u64);
+    }
+
+    (exp, (frac | 0x00800000) as u64)
+}
+
+// The remainder of |A| / |B| for finite and nonzero A and B, as (q, r, exp). The quotient q is
+// truncated, or rounded to nearest with ties to even when to_nearest is set, and holds its low
+// 31 bits. The remainder |A| - q x |B| is r x 2^(exp - 150), which is exact in 24 bits and
+// negative only when the quotient is rounded up.
+fn f32_rem_parts(a: u32, b: u32, to_nearest: bool) -> (u32, i64, i32) {
+    let (a_exp, a_frac) = f32_rem_unpack(a);
+    let (b_exp, b_frac) = f32_rem_unpack(b);
+    let dist = a_exp - b_exp;
+
+    let (mut q, r, y, exp) = if dist < 0 {
+        if !to_nearest || dist < -1 {
+            // |A| < |B| / 2, the quotient is 0
+            return (0, a_frac as i64, a_exp);
+        }
+        // |A| < |B|, compare |A| with |B| / 2 in the unit of A
+        (0, a_frac, b_frac << 1, a_exp)
+    } else {
+        // Long division of a_frac x 2^dist by b_frac, 32 bits at a time
+        let mut q = a_frac / b_frac;
+        let mut r = a_frac % b_frac;
+        let mut left = dist;
+        while left > 0 {
+            let shift = left.min(32);
+            r <<= shift;
+            q = ((q << shift) + r / b_frac) & 0x7FFFFFFF;
+            r %= b_frac;
+            left -= shift;
+        }
+        (q, r, b_frac, b_exp)
+    };
+
+    if to_nearest && (2 * r > y || (2 * r == y && q & 1 == 1)) {
+        // Round the quotient up
+        q = (q + 1) & 0x7FFFFFFF;
+        return (q as u32, r as i64 - y as i64, exp);
+    }
+    (q as u32, r as i64, exp)
+}
+
+// Pack the remainder r x 2^(exp - 150) of A, exactly. A zero remainder has the sign of A.
+fn f32_pack_rem(a_sign: i32, r: i64, exp: i32, env: &mut Env) -> u32 {
+    if r == 0 {
+        return f32_pack_raw(a_sign, 0, 0);
+    }
+    f32_norm_round_and_pack(a_sign ^ (r < 0) as i32, exp + 6, r.abs() as i32, env)
+}
+
+pub fn f32_remainder(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_remainder_with_env(a, b, env))
+}
+
+// IEEE 754 remainder, A - n x B where n is A / B rounded to nearest with ties to even.
+// The result is exact, and |result| <= |B| / 2.
+pub fn f32_remainder_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_remquo_with_env(a, b, env).0
+}
+
+pub fn f32_fmod(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_fmod_with_env(a, b, env))
+}
+
+// C fmod, A - n x B where n is A / B truncated. The result is exact, has the sign of A,
+// and |result| < |B|.
+pub fn f32_fmod_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| f32_fmod_unflushed(a, b, env), env)
+}
+
+fn f32_fmod_unflushed(a: u32, b: u32, env: &mut Env) -> u32 {
+    if let Err(r) = f32_rem_special(a, b, env) {
+        return r;
+    }
+
+    let (_, r, exp) = f32_rem_parts(a, b, false);
+    f32_pack_rem(f32_sign(a), r, exp, env)
+}
+
+pub fn f32_remquo(a: u32, b: u32) -> (u32, i32) {
+    with_thread_env(|env| f32_remquo_with_env(a, b, env))
+}
+
+// C remquo, the IEEE 754 remainder along with the low 31 bits of the rounded quotient n, with
+// the sign of A / B. The quotient is 0 for the special cases.
+pub fn f32_remquo_with_env(a: u32, b: u32, env: &mut Env) -> (u32, i32) {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    let mut quo = 0;
+    let r = f32_flush_result(|env| {
+        let (r, q) = f32_remquo_unflushed(a, b, env);
+        quo = q;
+        r
+    }, env);
+    (r, quo)
+}
+
+fn f32_remquo_unflushed(a: u32, b: u32, env: &mut Env) -> (u32, i32) {
+    if let Err(r) = f32_rem_special(a, b, env) {
+        return (r, 0);
+    }
+
+    let (q, r, exp) = f32_rem_parts(a, b, true);
+    let q = q as i32;
+    let q = if f32_sign(a) != f32_sign(b) { -q } else { q };
+    (f32_pack_rem(f32_sign(a), r, exp, env), q)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_remainder() {
+        // remainder(5, 3) = -1
+        assert_eq!(crate::soft_f32::f32_remainder(0x40A00000, 0x40400000), 0xBF800000);
+        // remainder(7, 2) = -1, as 3.5 rounds to 4
+        assert_eq!(crate::soft_f32::f32_remainder(0x40E00000, 0x40000000), 0xBF800000);
+        // remainder(5, 2) = 1, as 2.5 rounds to 2
+        assert_eq!(crate::soft_f32::f32_remainder(0x40A00000, 0x40000000), 0x3F800000);
+        // remainder(-4, 2) = -0
+        assert_eq!(crate::soft_f32::f32_remainder(0xC0800000, 0x40000000), 0x80000000);
+        // remainder(1, 3) = 1
+        assert_eq!(crate::soft_f32::f32_remainder(0x3F800000, 0x40400000), 0x3F800000);
+        // remainder(2, 3) = -1
+        assert_eq!(crate::soft_f32::f32_remainder(0x40000000, 0x40400000), 0xBF800000);
+        // remainder(1e30, 3.1415927) = 0.31446218
+        assert_eq!(crate::soft_f32::f32_remainder(0x7149F2CA, 0x40490FDB), 0x3EA10130);
+        // remainder(1e-45, 3e-45) = 1e-45
+        assert_eq!(crate::soft_f32::f32_remainder(0x00000001, 0x00000002), 0x00000001);
+    }
+
+    #[test]
+    fn test_f32_fmod() {
+        // fmod(5, 3) = 2
+        assert_eq!(crate::soft_f32::f32_fmod(0x40A00000, 0x40400000), 0x40000000);
+        // fmod(-5, 3) = -2
+        assert_eq!(crate::soft_f32::f32_fmod(0xC0A00000, 0x40400000), 0xC0000000);
+        // fmod(5, -3) = 2
+        assert_eq!(crate::soft_f32::f32_fmod(0x40A00000, 0xC0400000), 0x40000000);
+        // fmod(5.5, 1) = 0.5
+        assert_eq!(crate::soft_f32::f32_fmod(0x40B00000, 0x3F800000), 0x3F000000);
+        // fmod(-6, 3) = -0
+        assert_eq!(crate::soft_f32::f32_fmod(0xC0C00000, 0x40400000), 0x80000000);
+        // fmod(1, 3) = 1
+        assert_eq!(crate::soft_f32::f32_fmod(0x3F800000, 0x40400000), 0x3F800000);
+        // fmod(3.4028235e38, 1.1754944e-38) = 0
+        assert_eq!(crate::soft_f32::f32_fmod(0x7F7FFFFF, 0x00800000), 0x00000000);
+        // fmod(3.4028235e38, 11) = 9
+        assert_eq!(crate::soft_f32::f32_fmod(0x7F7FFFFF, 0x41300000), 0x41100000);
+    }
+
+    #[test]
+    fn test_f32_remquo() {
+        // remquo(5, 3) = (-1, 2)
+        assert_eq!(crate::soft_f32::f32_remquo(0x40A00000, 0x40400000), (0xBF800000, 2));
+        // remquo(-7, 2) = (1, -4)
+        assert_eq!(crate::soft_f32::f32_remquo(0xC0E00000, 0x40000000), (0x3F800000, -4));
+        // remquo(10, -3) = (1, -3)
+        assert_eq!(crate::soft_f32::f32_remquo(0x41200000, 0xC0400000), (0x3F800000, -3));
+        // remquo(1e30, 1.5707964) gives the low bits of the quotient
+        let (r, q) = crate::soft_f32::f32_remquo(0x7149F2CA, 0x3FC90FDB);
+        assert_eq!((r, q & 7), (0x3EA10130, 4));
+        // remquo(0.5, 1) = (0.5, 0)
+        assert_eq!(crate::soft_f32::f32_remquo(0x3F000000, 0x3F800000), (0x3F000000, 0));
+    }
+
+    #[test]
+    fn test_f32_rem_special() {
+        // NaN
+        assert_eq!(crate::soft_f32::f32_fmod(0x7FC00001, 0x3F800000), 0x7FC00001);
+        assert_eq!(crate::soft_f32::f32_remainder(0x3F800000, 0x7FC00002), 0x7FC00002);
+        // Inf rem 1 and 1 rem 0 are invalid
+        assert_eq!(crate::soft_f32::f32_fmod(0x7F800000, 0x3F800000), 0xFFC00000);
+        assert_eq!(crate::soft_f32::f32_remainder(0x3F800000, 0x80000000), 0xFFC00000);
+        // 1 rem Inf = 1
+        assert_eq!(crate::soft_f32::f32_fmod(0x3F800000, 0xFF800000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_remainder(0xBF800000, 0x7F800000), 0xBF800000);
+        // -0 rem 1 = -0
+        assert_eq!(crate::soft_f32::f32_fmod(0x80000000, 0x3F800000), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_remquo(0x80000000, 0x3F800000), (0x80000000, 0));
+    }
+
+    #[test]
+    fn test_f32_rem_exception_flags() {
+        // The result is exact, even when subnormal
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_fmod_with_env(0x00800001, 0x00400000, &mut env), 0x00000001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        let mut env = Env::default();
+        crate::soft_f32::f32_remainder_with_env(0x7149F2CA, 0x40490FDB, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Invalid
+        let mut env = Env::default();
+        crate::soft_f32::f32_fmod_with_env(0x3F800000, 0x00000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // The rounding mode does not matter
+        assert_eq!(crate::soft_f32::f32_remainder_with_env(0x40A00000, 0x40000000, &mut Env::new(RoundingMode::Max)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_fmod_with_env(0x40A00000, 0x40400000, &mut Env::new(RoundingMode::Min)), 0x40000000);
+    }
+
+    #[test]
+    fn test_f32_rem_flush_to_zero() {
+        // remainder(3 x 2^-149, 2^-148) is -2^-149 with a quotient of 2, which is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_remquo_with_env(0x00000003, 0x00000002, &mut env), (0x80000000, 2));
+        assert_eq!(crate::soft_f32::f32_fmod_with_env(0x00000003, 0x00000002, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        // The remainder of zero by zero is invalid with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_fmod_with_env(0x00000003, 0x00000002, &mut env)));
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
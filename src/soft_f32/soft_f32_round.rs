@@ -1,131 +1,393 @@
 use super::util::{
-    f32_shift_right_jam,
+    f32_pack_raw,
     f32_norm_round_and_pack,
-    f32_round_and_pack,
-    f32_pack_raw, f32_pack,
     f32_propagate_nan,
     f32_sign, f32_exp, f32_frac,
     f32_is_nan,
-    f32_count_leading_zero,
+    f32_flush_input,
 };
 
-pub fn f32_round(a: u32) -> u32 {
+use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, with_thread_env };
+
+// Round A to an integer in the given rounding mode, raising inexact only when `exact` is set
+pub fn f32_round_to_int(a: u32, rounding_mode: RoundingMode, exact: bool) -> u32 {
+    with_thread_env(|env| f32_round_to_int_with_env(a, rounding_mode, exact, env))
+}
+
+pub fn f32_round_to_int_with_env(a: u32, rounding_mode: RoundingMode, exact: bool, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+
     // Sign
-    let mut a_sign = f32_sign(a);
+    let a_sign = f32_sign(a);
 
     // Exp
-    let mut a_exp = f32_exp(a);
+    let a_exp = f32_exp(a);
 
     // Frac
-    let mut a_frac = f32_frac(a);
+    let a_frac = f32_frac(a);
 
-    if (a_exp < 0x7E) {
-        if ((a << 1) == 0) {
+    if a_exp <= 0x7E {
+        // |A| < 1, the result is 0 or 1 with the sign of A
+        if (a << 1) == 0 {
             return a;
         }
-        let mut z = a & f32_pack_raw(1, 0, 0);
-        if (a_frac != 0) {
-            if (a_exp == 0x7E) {
-                z |= f32_pack_raw(0, 0x7F, 0);
-            }
+        if exact {
+            env.raise(ExceptionFlags::INEXACT);
         }
-        return z;
+        let is_one = match rounding_mode {
+            // Above 1/2, or 1/2 which rounds to the even 0
+            RoundingMode::NearEven => a_exp == 0x7E && a_frac != 0,
+            RoundingMode::NearMaxMag => a_exp == 0x7E,
+            RoundingMode::MinMag => false,
+            RoundingMode::Min => a_sign == 1,
+            RoundingMode::Max => a_sign == 0,
+            RoundingMode::Odd => true,
+        };
+        return match is_one {
+            true => f32_pack_raw(a_sign, 0x7F, 0),
+            false => f32_pack_raw(a_sign, 0, 0),
+        };
     }
-    if (0x96 <= a_exp) {
-        if (a_exp == 0xFF && a_frac != 0) {
-            return f32_propagate_nan(a, 0);
+    if 0x96 <= a_exp {
+        // Integer, infinity or NaN
+        if a_exp == 0xFF && a_frac != 0 {
+            return f32_propagate_nan(a, 0, env);
         }
         return a;
     }
-    let last_bit_mask = (1 << (0x96 - a_exp));
+
+    // The bits below the last integer bit are rounded away
+    let last_bit_mask = 1 << (0x96 - a_exp);
     let round_bits_mask = last_bit_mask - 1;
-    // By default use near even round mode
     let mut r = a;
-    r += (last_bit_mask >> 1);
-    if ((r & round_bits_mask) == 0) {
-        r &= (!last_bit_mask);
+    match rounding_mode {
+        RoundingMode::NearEven => {
+            r += last_bit_mask >> 1;
+            if (r & round_bits_mask) == 0 {
+                // Tie, round to even
+                r &= !last_bit_mask;
+            }
+        }
+        RoundingMode::NearMaxMag => r += last_bit_mask >> 1,
+        RoundingMode::Min if a_sign == 1 => r += round_bits_mask,
+        RoundingMode::Max if a_sign == 0 => r += round_bits_mask,
+        _ => {}
     }
-    r &= (!round_bits_mask);
-    return r;
+    r &= !round_bits_mask;
+
+    if r != a {
+        if rounding_mode == RoundingMode::Odd {
+            r |= last_bit_mask;
+        }
+        if exact {
+            env.raise(ExceptionFlags::INEXACT);
+        }
+    }
+    r
+}
+
+pub fn f32_floor(a: u32) -> u32 {
+    with_thread_env(|env| f32_floor_with_env(a, env))
+}
+
+// Round toward negative infinity, without raising inexact
+pub fn f32_floor_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, RoundingMode::Min, false, env)
+}
+
+pub fn f32_ceil(a: u32) -> u32 {
+    with_thread_env(|env| f32_ceil_with_env(a, env))
+}
+
+// Round toward positive infinity, without raising inexact
+pub fn f32_ceil_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, RoundingMode::Max, false, env)
+}
+
+pub fn f32_trunc(a: u32) -> u32 {
+    with_thread_env(|env| f32_trunc_with_env(a, env))
+}
+
+// Round toward zero, without raising inexact
+pub fn f32_trunc_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, RoundingMode::MinMag, false, env)
+}
+
+pub fn f32_round(a: u32) -> u32 {
+    with_thread_env(|env| f32_round_with_env(a, env))
+}
+
+// Round to nearest with ties to even, without raising inexact, the same as f32_round_ties_even.
+// Unlike C round and F32::round, which are f32_round_ties_away.
+pub fn f32_round_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_ties_even_with_env(a, env)
+}
+
+pub fn f32_round_ties_away(a: u32) -> u32 {
+    with_thread_env(|env| f32_round_ties_away_with_env(a, env))
+}
+
+// Round to nearest with ties away from zero, as C round, without raising inexact
+pub fn f32_round_ties_away_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, RoundingMode::NearMaxMag, false, env)
+}
+
+pub fn f32_round_ties_even(a: u32) -> u32 {
+    with_thread_env(|env| f32_round_ties_even_with_env(a, env))
+}
+
+// Round to nearest with ties to even, as C roundeven, without raising inexact
+pub fn f32_round_ties_even_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, RoundingMode::NearEven, false, env)
+}
+
+pub fn f32_rint(a: u32) -> u32 {
+    with_thread_env(|env| f32_rint_with_env(a, env))
+}
+
+// Round in the rounding mode of the environment, raising inexact when the result differs from A
+pub fn f32_rint_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, env.rounding_mode(), true, env)
+}
+
+pub fn f32_nearbyint(a: u32) -> u32 {
+    with_thread_env(|env| f32_nearbyint_with_env(a, env))
+}
+
+// Round in the rounding mode of the environment, without raising inexact
+pub fn f32_nearbyint_with_env(a: u32, env: &mut Env) -> u32 {
+    f32_round_to_int_with_env(a, env.rounding_mode(), false, env)
 }
 
 pub fn from_int32(a: i32) -> u32 {
+    with_thread_env(|env| from_int32_with_env(a, env))
+}
+
+pub fn from_int32_with_env(a: i32, env: &mut Env) -> u32 {
     /*
     1. Convert the int representation into a sign and a positive binary number
-    2. Convert the positive binary number to a fixed point representation
-        where the integral part = 1.xxxxx
-        (This step uses shift operations - you shift the decimal point to the left
-        until you find the most significant 1 bit in the binary number)
-        Let M be the mantissa with the leading 1 bit omitted
-        Let E be the exponent of the fixed point representation
-    3. Express the exponent E in  excess 127  code
-    4. Assemble:
-          Sign Exponent Mantissa     into  a IEEE 754 respresentation 
+    2. Normalize the positive binary number, so that the most significant 1 bit
+        becomes the implicit bit of the fraction
+    3. Round the bits which do not fit in the fraction, and pack it
     */
-    if (a == 0) {
-        return 0;
+    let sign = if a < 0 { 1 } else { 0 };
+    if a & 0x7FFFFFFF == 0 {
+        // 0 or -2^31, which is exact
+        return if sign != 0 { f32_pack_raw(1, 0x9E, 0) } else { 0 };
     }
 
-    let frac: i32;
-    if (a < 0) {
-        frac = (!a) + 1;
-    } else {
-        frac = a;
-    }
-    let leading_zero = f32_count_leading_zero(frac);
-    let shift = (32 - leading_zero - 1);
-    let exp = shift + 0x7F;
-    let sign = if (a < 0) { 1 } else { 0 };
-    f32_pack(sign, exp, (frac << (24 - shift - 1)) & 0x7fffff)
+    let frac = if a < 0 { -a } else { a };
+    f32_norm_round_and_pack(sign, 0x9C, frac, env)
 }
 
 // TODO: Add more convertors
 pub fn to_int32(a: u32) -> i32 {
-    let p = f32_round(a);
-    let mut r: i32 = 0;
-
-    if (f32_is_nan(p)) {
-        return std::i32::MAX;
-    } else if (p == 0x7F800000 || p == 0xFF800000) {
-        // Infinity
-        if (f32_sign(p) != 0) {
-            return std::i32::MIN;
-        }
-        return std::i32::MAX;
-    } else {
-        if (p == 0 || p == 0x80000000) {
-            // +- 0
-            return 0;
-        }
+    with_thread_env(|env| to_int32_with_env(a, env))
+}
 
-        let sign = f32_sign(a);
-        let exp = f32_exp(a);
-        let mut frac = f32_frac(a);
+pub fn to_int32_with_env(a: u32, env: &mut Env) -> i32 {
+    let p = f32_round_ties_even_with_env(a, env);
 
-        frac |= 0x800000;
+    if f32_is_nan(p) {
+        env.raise(ExceptionFlags::INVALID);
+        return i32::MAX;
+    }
+
+    let sign = f32_sign(p);
+    let exp = f32_exp(p);
+    let mut frac = f32_frac(p);
 
-        if (exp < 0x7F) {
-            // It must be a zero, because it is too tiny
-            return 0;
+    if exp < 0x7F {
+        // +- 0, because it is too tiny
+        if p != a {
+            env.raise(ExceptionFlags::INEXACT);
+        }
+        return 0;
+    }
+
+    let shift = exp - 0x7F;
+    if shift >= 31 {
+        if p == f32_pack_raw(1, 0x9E, 0) {
+            // -2^31
+            if p != a {
+                env.raise(ExceptionFlags::INEXACT);
+            }
+            return i32::MIN;
         }
 
-        let shift = exp - 0x7F;
-        if (sign == 0) {
-            return frac >> (23 - shift);
-        } else {
-            return -!((frac >> (23 - shift)) - 1);
+        // Infinity or out of range
+        env.raise(ExceptionFlags::INVALID);
+        if sign != 0 {
+            return i32::MIN;
         }
+        return i32::MAX;
+    }
+
+    if p != a {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    frac |= 0x800000;
+    let r = if shift > 23 {
+        frac << (shift - 23)
+    } else {
+        frac >> (23 - shift)
+    };
+
+    if sign != 0 {
+        -r
+    } else {
+        r
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
     #[test]
     fn test_f32_round() {
         // round(0.01) = 0
         assert_eq!(crate::soft_f32::f32_round(0x3C23D70A), 0x00000000);
         // round(4) = 4
         assert_eq!(crate::soft_f32::f32_round(0x40800000), 0x40800000);
+        // round(0.5) = 0 and round(-2.5) = -2, ties to even
+        assert_eq!(crate::soft_f32::f32_round(0x3F000000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_round(0xC0200000), 0xC0000000);
+    }
+
+    #[test]
+    fn test_f32_round_ties_away() {
+        // round_ties_away(0.01) = 0
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0x3C23D70A), 0x00000000);
+        // round_ties_away(4) = 4
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0x40800000), 0x40800000);
+        // round_ties_away(0.5) = 1 and round_ties_away(-2.5) = -3, ties away from zero
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0x3F000000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0xC0200000), 0xC0400000);
+        // round_ties_away(0.49999997) = 0
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0x3EFFFFFF), 0x00000000);
+        // round_ties_away(-0.75) = -1
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0xBF400000), 0xBF800000);
+        // round_ties_away(8388607.5) = 8388608, the largest exponent with a fraction
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0x4AFFFFFF), 0x4B000000);
+        // round_ties_away(16777215) = 16777215, an integer
+        assert_eq!(crate::soft_f32::f32_round_ties_away(0x4B7FFFFF), 0x4B7FFFFF);
+    }
+
+    #[test]
+    fn test_f32_round_ties_even() {
+        // round_ties_even(0.5) = 0, round_ties_even(1.5) = 2 and round_ties_even(2.5) = 2
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0x3F000000), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0x3FC00000), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0x40200000), 0x40000000);
+        // round_ties_even(0.50000006) = 1
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0x3F000001), 0x3F800000);
+        // round_ties_even(-0.75) = -1
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0xBF400000), 0xBF800000);
+        // round_ties_even(8388606.5) = 8388606 and round_ties_even(8388607.5) = 8388608
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0x4AFFFFFD), 0x4AFFFFFC);
+        assert_eq!(crate::soft_f32::f32_round_ties_even(0x4AFFFFFF), 0x4B000000);
+    }
+
+    #[test]
+    fn test_f32_floor_ceil_trunc() {
+        // floor(1.5) = 1, ceil(1.5) = 2, trunc(1.5) = 1
+        assert_eq!(crate::soft_f32::f32_floor(0x3FC00000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_ceil(0x3FC00000), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_trunc(0x3FC00000), 0x3F800000);
+        // floor(-1.5) = -2, ceil(-1.5) = -1, trunc(-1.5) = -1
+        assert_eq!(crate::soft_f32::f32_floor(0xBFC00000), 0xC0000000);
+        assert_eq!(crate::soft_f32::f32_ceil(0xBFC00000), 0xBF800000);
+        assert_eq!(crate::soft_f32::f32_trunc(0xBFC00000), 0xBF800000);
+        // floor(0.99999994) = 0, ceil(0.99999994) = 1
+        assert_eq!(crate::soft_f32::f32_floor(0x3F7FFFFF), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_ceil(0x3F7FFFFF), 0x3F800000);
+        // floor(-0.1) = -1, ceil(-0.1) = -0, trunc(-0.1) = -0
+        assert_eq!(crate::soft_f32::f32_floor(0xBDCCCCCD), 0xBF800000);
+        assert_eq!(crate::soft_f32::f32_ceil(0xBDCCCCCD), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_trunc(0xBDCCCCCD), 0x80000000);
+        // floor(1e-45) = 0, ceil(1e-45) = 1, ceil(-1e-45) = -0, floor(-1e-45) = -1
+        assert_eq!(crate::soft_f32::f32_floor(0x00000001), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_ceil(0x00000001), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_ceil(0x80000001), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_floor(0x80000001), 0xBF800000);
+        // floor(1) = 1, ceil(-2) = -2
+        assert_eq!(crate::soft_f32::f32_floor(0x3F800000), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_ceil(0xC0000000), 0xC0000000);
+        // floor(-8388607.5) = -8388608, ceil(8388607.5) = 8388608, trunc(8388607.5) = 8388607
+        assert_eq!(crate::soft_f32::f32_floor(0xCAFFFFFF), 0xCB000000);
+        assert_eq!(crate::soft_f32::f32_ceil(0x4AFFFFFF), 0x4B000000);
+        assert_eq!(crate::soft_f32::f32_trunc(0x4AFFFFFF), 0x4AFFFFFE);
+        // floor(3.4028235e38) = 3.4028235e38
+        assert_eq!(crate::soft_f32::f32_floor(0x7F7FFFFF), 0x7F7FFFFF);
+    }
+
+    #[test]
+    fn test_f32_round_to_int_special() {
+        // -0, infinity and NaN are returned
+        assert_eq!(crate::soft_f32::f32_floor(0x80000000), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_ceil(0xFF800000), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_trunc(0x7F800000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_round(0x7FC00001), 0x7FC00001);
+
+        // A signaling NaN is quieted, and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_rint_with_env(0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_rint_nearbyint() {
+        // 2.5 and -0.5 in every rounding mode
+        let cases = [
+            (RoundingMode::NearEven, 0x40000000, 0x80000000),
+            (RoundingMode::NearMaxMag, 0x40400000, 0xBF800000),
+            (RoundingMode::MinMag, 0x40000000, 0x80000000),
+            (RoundingMode::Min, 0x40000000, 0xBF800000),
+            (RoundingMode::Max, 0x40400000, 0x80000000),
+            (RoundingMode::Odd, 0x40400000, 0xBF800000),
+        ];
+        for &(rounding_mode, r2_5, r_0_5) in cases.iter() {
+            let mut env = Env::new(rounding_mode);
+            assert_eq!(crate::soft_f32::f32_nearbyint_with_env(0x40200000, &mut env), r2_5);
+            assert_eq!(crate::soft_f32::f32_nearbyint_with_env(0xBF000000, &mut env), r_0_5);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+
+            assert_eq!(crate::soft_f32::f32_rint_with_env(0x40200000, &mut env), r2_5);
+            assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f32::f32_rint_with_env(0xBF000000, &mut env), r_0_5);
+            assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+        }
+
+        // An integer is exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_rint_with_env(0x4B000001, &mut env), 0x4B000001);
+        assert_eq!(crate::soft_f32::f32_rint_with_env(0x00000000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Rounding to odd, 4.25 gives 5 and 5.75 gives 5
+        let mut env = Env::new(RoundingMode::Odd);
+        assert_eq!(crate::soft_f32::f32_rint_with_env(0x40880000, &mut env), 0x40A00000);
+        assert_eq!(crate::soft_f32::f32_rint_with_env(0x40B80000, &mut env), 0x40A00000);
+    }
+
+    #[test]
+    fn test_f32_round_to_int_exception_flags() {
+        // floor, ceil, trunc, round and round_ties_even never raise inexact
+        let mut env = Env::default();
+        crate::soft_f32::f32_floor_with_env(0x3FC00000, &mut env);
+        crate::soft_f32::f32_ceil_with_env(0x00000001, &mut env);
+        crate::soft_f32::f32_trunc_with_env(0xBF400000, &mut env);
+        crate::soft_f32::f32_round_with_env(0x40200000, &mut env);
+        crate::soft_f32::f32_round_ties_even_with_env(0x4AFFFFFF, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Rounding to an integer with `exact` is inexact
+        assert_eq!(crate::soft_f32::f32_round_to_int_with_env(0x3F7FFFFF, RoundingMode::MinMag, true, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
     }
 
     #[test]
@@ -139,10 +401,20 @@ mod tests {
         // round(128.2) = 128
         assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0x43003333), 128);
 
-        // FIXME: round(0x4f000000) = 2147483647)
-        // assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0x4F000000), std::i32::MAX);
-        // FIXME: round(0xcf000000) = -2147483648)
-        // assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0xCF000000), std::i32::MIN);
+        // round(-128.2) = -128
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0xC3003333), -128);
+        // round(1.5) = 2
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0x3FC00000), 2);
+        // round(0.75) = 1 and round(-0.5) = 0
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0x3F400000), 1);
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0xBF000000), 0);
+        // round(2^30) = 1073741824
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0x4E800000), 0x40000000);
+
+        // round(0x4f000000) = 2147483647, out of range
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0x4F000000), i32::MAX);
+        // round(0xcf000000) = -2147483648
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32(0xCF000000), i32::MIN);
     }
 
     #[test]
@@ -162,10 +434,58 @@ mod tests {
         // from_int32(80235) = 0x479CB580
         assert_eq!(crate::soft_f32::soft_f32_round::from_int32(-80235), 0xC79CB580);
 
-        // FIXME: from_int32(2147483647) =  0x4f000000
-        // assert_eq!(crate::soft_f32::soft_f32_round::from_int32(std::i32::MAX), 0x4F000000);
+        // from_int32(2147483647) =  0x4f000000
+        assert_eq!(crate::soft_f32::soft_f32_round::from_int32(i32::MAX), 0x4F000000);
+
+        // from_int32(-2147483648) = 0xcf000000
+        assert_eq!(crate::soft_f32::soft_f32_round::from_int32(i32::MIN), 0xCF000000);
+    }
+
+    #[test]
+    fn test_f32_int_exception_flags() {
+        let mut env = Env::default();
+
+        // to_int32(4) = 4, exact
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32_with_env(0x40800000, &mut env), 4);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // to_int32(128.2) = 128, inexact
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32_with_env(0x43003333, &mut env), 128);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // to_int32(NaN), invalid
+        env.clear_flags();
+        crate::soft_f32::soft_f32_round::to_int32_with_env(0x7FC00000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // to_int32(-Inf) = -2147483648, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::soft_f32_round::to_int32_with_env(0xFF800000, &mut env), i32::MIN);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // from_int32(16777216) = 2^24, exact
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::soft_f32_round::from_int32_with_env(16777216, &mut env), 0x4B800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // from_int32(16777217) = 2^24, inexact
+        assert_eq!(crate::soft_f32::soft_f32_round::from_int32_with_env(16777217, &mut env), 0x4B800000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_round_to_int_denormals_are_zero() {
+        // A subnormal operand is zero, which is exact
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_ceil_with_env(0x00000001, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_floor_with_env(0x80000001, &mut env), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_rint_with_env(0x00000001, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
 
-        // FIXME: from_int32(-2147483648) = 0xcf000000
-        // assert_eq!(crate::soft_f32::soft_f32_round::from_int32(std::i32::MIN), 0xCF000000);
+        // An integer is never tiny
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_ceil_with_env(0x00000001, &mut env), 0x3F800000);
     }
 }
@@ -0,0 +1,348 @@
+use super::util::{
+    f32_norm_round_and_pack,
+    f32_propagate_nan,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_flush_input, f32_flush_result,
+};
+use super::soft_f32_conv::i32_to_f32_with_env;
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// The result of ilogb for zero and for NaN, as the C FP_ILOGB0 and FP_ILOGBNAN.
+// Infinity gives i32::MAX.
+pub const FP_ILOGB0: i32 = -i32::MAX;
+pub const FP_ILOGBNAN: i32 = i32::MAX;
+
+// The biased exponent and the significand with the hidden bit of the finite and nonzero A,
+// that is |A| = frac x 2^(exp - 150), with a subnormal normalized
+fn f32_unpack_finite(a: u32) -> (i32, i32) {
+    let exp = f32_exp(a);
+    let frac = f32_frac(a);
+    if exp == 0 {
+        return f32_norm_subnormal_frac(frac);
+    }
+
+    (exp, frac | 0x00800000)
+}
+
+fn f32_is_zero(a: u32) -> bool {
+    (a << 1) == 0
+}
+
+pub fn f32_scalbn(a: u32, n: i32) -> u32 {
+    with_thread_env(|env| f32_scalbn_with_env(a, n, env))
+}
+
+// A x 2^n, rounded once, which is also ldexp. It is exact unless the result overflows
+// or is a subnormal.
+pub fn f32_scalbn_with_env(a: u32, n: i32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_scalbn_unflushed(a, n, env), env)
+}
+
+fn f32_scalbn_unflushed(a: u32, n: i32, env: &mut Env) -> u32 {
+    let a_exp = f32_exp(a);
+    if a_exp == 0xFF {
+        if f32_frac(a) != 0 {
+            // Propagate NaN
+            return f32_propagate_nan(a, 0, env);
+        }
+        return a;
+    }
+    if f32_is_zero(a) {
+        return a;
+    }
+
+    // Beyond 2^9 the result overflows or underflows to zero anyway,
+    // and the exponent stays in range
+    let n = n.clamp(-0x200, 0x200);
+    let (exp, frac) = f32_unpack_finite(a);
+    f32_norm_round_and_pack(f32_sign(a), exp + n + 6, frac, env)
+}
+
+pub fn f32_frexp(a: u32) -> (u32, i32) {
+    with_thread_env(|env| f32_frexp_with_env(a, env))
+}
+
+// Split A into a significand in [0.5, 1) with the sign of A, and an exponent, so that
+// A = significand x 2^exponent. Zero, infinity and NaN are returned with the exponent 0.
+pub fn f32_frexp_with_env(a: u32, env: &mut Env) -> (u32, i32) {
+    let a = f32_flush_input(a, env);
+    let a_exp = f32_exp(a);
+    if a_exp == 0xFF {
+        if f32_frac(a) != 0 {
+            // Propagate NaN
+            return (f32_propagate_nan(a, 0, env), 0);
+        }
+        return (a, 0);
+    }
+    if f32_is_zero(a) {
+        return (a, 0);
+    }
+
+    let (exp, frac) = f32_unpack_finite(a);
+    (f32_pack_raw(f32_sign(a), 0x7E, frac & 0x007FFFFF), exp - 0x7E)
+}
+
+pub fn f32_logb(a: u32) -> u32 {
+    with_thread_env(|env| f32_logb_with_env(a, env))
+}
+
+// The unbiased exponent of A as a Float32, floor(log2(|A|)), exactly.
+// logb(0) is -infinity and divide-by-zero, and logb(infinity) is infinity.
+pub fn f32_logb_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let a_exp = f32_exp(a);
+    if a_exp == 0xFF {
+        if f32_frac(a) != 0 {
+            // Propagate NaN
+            return f32_propagate_nan(a, 0, env);
+        }
+        return f32_pack_raw(0, 0xFF, 0);
+    }
+    if f32_is_zero(a) {
+        env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+        return f32_pack_raw(1, 0xFF, 0);
+    }
+
+    let (exp, _) = f32_unpack_finite(a);
+    i32_to_f32_with_env(exp - 0x7F, env)
+}
+
+pub fn f32_ilogb(a: u32) -> i32 {
+    with_thread_env(|env| f32_ilogb_with_env(a, env))
+}
+
+// The unbiased exponent of A, floor(log2(|A|)). Zero gives FP_ILOGB0, NaN gives FP_ILOGBNAN
+// and infinity gives i32::MAX, all of them invalid.
+pub fn f32_ilogb_with_env(a: u32, env: &mut Env) -> i32 {
+    let a = f32_flush_input(a, env);
+    let a_exp = f32_exp(a);
+    if a_exp == 0xFF || f32_is_zero(a) {
+        env.raise(ExceptionFlags::INVALID);
+        return match a_exp {
+            0xFF if f32_frac(a) != 0 => FP_ILOGBNAN,
+            0xFF => i32::MAX,
+            _ => FP_ILOGB0,
+        };
+    }
+
+    let (exp, _) = f32_unpack_finite(a);
+    exp - 0x7F
+}
+
+pub fn f32_modf(a: u32) -> (u32, u32) {
+    with_thread_env(|env| f32_modf_with_env(a, env))
+}
+
+// Split A into its fractional and integral parts, both with the sign of A, as C modf.
+// Both are exact, and the fractional part of infinity is zero.
+pub fn f32_modf_with_env(a: u32, env: &mut Env) -> (u32, u32) {
+    let a = f32_flush_input(a, env);
+    // The integral part is never tiny
+    let mut int_part = 0;
+    let frac_part = f32_flush_result(|env| {
+        let (frac_part, r) = f32_modf_unflushed(a, env);
+        int_part = r;
+        frac_part
+    }, env);
+    (frac_part, int_part)
+}
+
+fn f32_modf_unflushed(a: u32, env: &mut Env) -> (u32, u32) {
+    let a_sign = f32_sign(a);
+    let a_exp = f32_exp(a);
+    let a_frac = f32_frac(a);
+
+    if a_exp == 0xFF && a_frac != 0 {
+        // Propagate NaN
+        let r = f32_propagate_nan(a, 0, env);
+        return (r, r);
+    }
+    if a_exp >= 0x96 {
+        // Integer or infinity
+        return (f32_pack_raw(a_sign, 0, 0), a);
+    }
+    if a_exp < 0x7F {
+        // |A| < 1
+        return (a, f32_pack_raw(a_sign, 0, 0));
+    }
+
+    // The bits below the last integer bit are the fractional part
+    let round_bits_mask = (1 << (0x96 - a_exp)) - 1;
+    let int_part = a & !round_bits_mask;
+    let frac = (a_frac | 0x00800000) & round_bits_mask as i32;
+    if frac == 0 {
+        return (f32_pack_raw(a_sign, 0, 0), a);
+    }
+    (f32_norm_round_and_pack(a_sign, a_exp + 6, frac, env), int_part)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_scalbn() {
+        // scalbn(1, 3) = 8
+        assert_eq!(crate::soft_f32::f32_scalbn(0x3F800000, 3), 0x41000000);
+        // scalbn(-1.5, -2) = -0.375
+        assert_eq!(crate::soft_f32::f32_scalbn(0xBFC00000, -2), 0xBEC00000);
+        // scalbn(1, -126) = 2^-126 and scalbn(1, -149) = 2^-149
+        assert_eq!(crate::soft_f32::f32_scalbn(0x3F800000, -126), 0x00800000);
+        assert_eq!(crate::soft_f32::f32_scalbn(0x3F800000, -149), 0x00000001);
+        // scalbn(2^-149, 149) = 1 and scalbn(2^-149, 276) = 2^127
+        assert_eq!(crate::soft_f32::f32_scalbn(0x00000001, 149), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_scalbn(0x00000001, 276), 0x7F000000);
+        // scalbn(3.4028235e38, -277) = 2^-149, rounded up from 0.99999994 x 2^-149
+        assert_eq!(crate::soft_f32::f32_scalbn(0x7F7FFFFF, -277), 0x00000001);
+        // scalbn(1.5 x 2^-127, -1) = 1.5 x 2^-128, exactly
+        assert_eq!(crate::soft_f32::f32_scalbn(0x00600000, -1), 0x00300000);
+        // scalbn(1 + 2^-23, -127) = 2^-127, rounded to even from the tie
+        assert_eq!(crate::soft_f32::f32_scalbn(0x3F800001, -127), 0x00400000);
+        // scalbn(1, 128) = inf and scalbn(1, i32::MAX) = inf
+        assert_eq!(crate::soft_f32::f32_scalbn(0x3F800000, 128), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_scalbn(0x3F800000, i32::MAX), 0x7F800000);
+        // scalbn(-1, i32::MIN) = -0
+        assert_eq!(crate::soft_f32::f32_scalbn(0xBF800000, i32::MIN), 0x80000000);
+        // -0, infinity and NaN are returned
+        assert_eq!(crate::soft_f32::f32_scalbn(0x80000000, 10), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_scalbn(0xFF800000, -10), 0xFF800000);
+        assert_eq!(crate::soft_f32::f32_scalbn(0x7FC00001, 1), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_frexp() {
+        // frexp(8) = (0.5, 4)
+        assert_eq!(crate::soft_f32::f32_frexp(0x41000000), (0x3F000000, 4));
+        // frexp(-0.75) = (-0.75, 0)
+        assert_eq!(crate::soft_f32::f32_frexp(0xBF400000), (0xBF400000, 0));
+        // frexp(3.4028235e38) = (0.99999994, 128)
+        assert_eq!(crate::soft_f32::f32_frexp(0x7F7FFFFF), (0x3F7FFFFF, 128));
+        // frexp(2^-149) = (0.5, -148) and frexp(1.5 x 2^-127) = (0.75, -126)
+        assert_eq!(crate::soft_f32::f32_frexp(0x00000001), (0x3F000000, -148));
+        assert_eq!(crate::soft_f32::f32_frexp(0x00600000), (0x3F400000, -126));
+        // -0, infinity and NaN are returned with 0
+        assert_eq!(crate::soft_f32::f32_frexp(0x80000000), (0x80000000, 0));
+        assert_eq!(crate::soft_f32::f32_frexp(0x7F800000), (0x7F800000, 0));
+        assert_eq!(crate::soft_f32::f32_frexp(0xFFC00000), (0xFFC00000, 0));
+    }
+
+    #[test]
+    fn test_f32_logb() {
+        // logb(8) = 3, logb(-0.75) = -1 and logb(1) = 0
+        assert_eq!(crate::soft_f32::f32_logb(0x41000000), 0x40400000);
+        assert_eq!(crate::soft_f32::f32_logb(0xBF400000), 0xBF800000);
+        assert_eq!(crate::soft_f32::f32_logb(0x3F800000), 0x00000000);
+        // logb(2^-149) = -149 and logb(3.4028235e38) = 127
+        assert_eq!(crate::soft_f32::f32_logb(0x00000001), 0xC3150000);
+        assert_eq!(crate::soft_f32::f32_logb(0x7F7FFFFF), 0x42FE0000);
+        // logb(-inf) = inf and logb(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_logb(0xFF800000), 0x7F800000);
+        assert_eq!(crate::soft_f32::f32_logb(0x7FC00001), 0x7FC00001);
+    }
+
+    #[test]
+    fn test_f32_ilogb() {
+        // ilogb(8) = 3, ilogb(-0.75) = -1
+        assert_eq!(crate::soft_f32::f32_ilogb(0x41000000), 3);
+        assert_eq!(crate::soft_f32::f32_ilogb(0xBF400000), -1);
+        // ilogb(2^-126) = -126, ilogb(1.1754942e-38) = -127 and ilogb(2^-149) = -149
+        assert_eq!(crate::soft_f32::f32_ilogb(0x00800000), -126);
+        assert_eq!(crate::soft_f32::f32_ilogb(0x007FFFFF), -127);
+        assert_eq!(crate::soft_f32::f32_ilogb(0x00000001), -149);
+        // Zero, NaN and infinity
+        assert_eq!(crate::soft_f32::f32_ilogb(0x80000000), crate::soft_f32::FP_ILOGB0);
+        assert_eq!(crate::soft_f32::f32_ilogb(0x7FC00000), crate::soft_f32::FP_ILOGBNAN);
+        assert_eq!(crate::soft_f32::f32_ilogb(0xFF800000), i32::MAX);
+    }
+
+    #[test]
+    fn test_f32_modf() {
+        // modf(3.75) = (0.75, 3) and modf(-3.75) = (-0.75, -3)
+        assert_eq!(crate::soft_f32::f32_modf(0x40700000), (0x3F400000, 0x40400000));
+        assert_eq!(crate::soft_f32::f32_modf(0xC0700000), (0xBF400000, 0xC0400000));
+        // modf(-0.1) = (-0.1, -0)
+        assert_eq!(crate::soft_f32::f32_modf(0xBDCCCCCD), (0xBDCCCCCD, 0x80000000));
+        // modf(-4) = (-0, -4)
+        assert_eq!(crate::soft_f32::f32_modf(0xC0800000), (0x80000000, 0xC0800000));
+        // modf(8388607.5) = (0.5, 8388607)
+        assert_eq!(crate::soft_f32::f32_modf(0x4AFFFFFF), (0x3F000000, 0x4AFFFFFE));
+        // modf(1.0000001) = (1.1920929e-7, 1)
+        assert_eq!(crate::soft_f32::f32_modf(0x3F800001), (0x34000000, 0x3F800000));
+        // modf(-inf) = (-0, -inf) and modf(NaN) = (NaN, NaN)
+        assert_eq!(crate::soft_f32::f32_modf(0xFF800000), (0x80000000, 0xFF800000));
+        assert_eq!(crate::soft_f32::f32_modf(0x7FC00001), (0x7FC00001, 0x7FC00001));
+    }
+
+    #[test]
+    fn test_f32_scale_exception_flags() {
+        // scalbn is exact when the result is normal
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0x3F800001, -126, &mut env), 0x00800001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // An exact subnormal result does not underflow
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0x3F800000, -140, &mut env), 0x00000200);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // An inexact subnormal result underflows, and is rounded in the rounding mode
+        let mut env = Env::new(RoundingMode::Max);
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0x3F800000, -150, &mut env), 0x00000001);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // Overflow
+        let mut env = Env::new(RoundingMode::MinMag);
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0xBF800000, 200, &mut env), 0xFF7FFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // frexp, logb and modf are exact
+        let mut env = Env::default();
+        crate::soft_f32::f32_frexp_with_env(0x00000001, &mut env);
+        crate::soft_f32::f32_logb_with_env(0x00600000, &mut env);
+        crate::soft_f32::f32_modf_with_env(0x3F800001, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // logb(0) divides by zero
+        assert_eq!(crate::soft_f32::f32_logb_with_env(0x00000000, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // ilogb(0) is invalid
+        let mut env = Env::default();
+        crate::soft_f32::f32_ilogb_with_env(0x00000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_scale_flush_to_zero() {
+        // scalbn(1, -130) is subnormal, so it is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0x3F800000, -130, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::UnderflowInexact);
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0xBF800000, -130, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+        // The fractional part of a subnormal is itself
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_modf_with_env(0x80000001, &mut env), (0x80000000, 0x80000000));
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        // A subnormal operand is zero with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_scalbn_with_env(0x00000001, 200, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_frexp_with_env(0x00400000, &mut env), (0x00000000, 0));
+        assert_eq!(crate::soft_f32::f32_modf_with_env(0x00000001, &mut env), (0x00000000, 0x00000000));
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f32::f32_logb_with_env(0x00000001, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_ilogb_with_env(0x00000001, &mut env), crate::soft_f32::FP_ILOGB0);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
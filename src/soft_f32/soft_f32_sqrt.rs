@@ -1,24 +1,31 @@
 use super::util::{
-    f32_shift_right_jam,
-    f32_norm_round_and_pack,
     f32_round_and_pack,
-    f32_pack_raw, f32_pack,
     f32_propagate_nan,
     f32_sign, f32_exp, f32_frac,
     f32_norm_subnormal_frac,
-    f32_short_shift_right_jam64,
     f32_approx_recip_sqrt,
-    f32_is_nan,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
 };
 
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
 
 pub fn f32_sqrt(a: u32) -> u32 {
+    with_thread_env(|env| f32_sqrt_with_env(a, env))
+}
+
+pub fn f32_sqrt_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_sqrt_unflushed(a, env), env)
+}
+
+fn f32_sqrt_unflushed(a: u32, env: &mut Env) -> u32 {
     // Sign
-    let mut a_sign = f32_sign(a);
+    let a_sign = f32_sign(a);
 
     // Exp
     let mut a_exp = f32_exp(a);
-    let mut r_exp;
+    
 
     // Frac
     let mut a_frac = f32_frac(a);
@@ -26,14 +33,15 @@ pub fn f32_sqrt(a: u32) -> u32 {
 
     if a_exp == 0xFF {
         if a_frac != 0 {
-            return f32_propagate_nan(a, 0);
+            return f32_propagate_nan(a, 0, env);
         }
         if a_sign == 0 {
             return a;
         }
 
         // Invalid
-        return f32_pack_raw(1, 0xFF, 0);
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
     }
 
     if a_sign != 0 {
@@ -43,7 +51,8 @@ pub fn f32_sqrt(a: u32) -> u32 {
         }
 
         // Invalid
-        return f32_pack_raw(1, 0xFF, 0);
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
     }
 
     if a_exp == 0 {
@@ -55,25 +64,25 @@ pub fn f32_sqrt(a: u32) -> u32 {
         a_frac = frac;
     }
 
-    r_exp = ((a_exp - 0x7F) >> 1) + 0x7E;
+    let r_exp = ((a_exp - 0x7F) >> 1) + 0x7E;
     a_exp &= 1;
 
     let a_frac_u32: u32 = ((a_frac | 0x00800000) as u32) << 8;
-    let mut result = f32_approx_recip_sqrt(a_exp as u32, a_frac_u32);
+    let result = f32_approx_recip_sqrt(a_exp as u32, a_frac_u32);
 
     let r_frac_u64: u64 = (a_frac_u32 as u64) * (result as u64);
-    let r_frac_u32 = (r_frac_u64>> 32) as u32;
-    r_frac = r_frac_u32 as i32;
+    let mut r_frac_u32 = (r_frac_u64 >> 32) as u32;
 
     if a_exp != 0 {
-        r_frac >>= 1;
+        r_frac_u32 >>= 1;
     }
-    r_frac += 2;
+    r_frac = r_frac_u32 as i32 + 2;
 
     if (r_frac & 0x3F) < 2 {
+        // Close to a rounding boundary, compute the exact remainder
         let r_shifted_frac = (r_frac >> 2) as u32;
-        let neg_rem = (r_shifted_frac as u64) * (r_shifted_frac as u64);
-        r_frac &= (!0x03);
+        let neg_rem = r_shifted_frac.wrapping_mul(r_shifted_frac);
+        r_frac &= !0x03;
 
         if neg_rem & 0x80000000 != 0 {
             r_frac |= 0x01;
@@ -84,11 +93,13 @@ pub fn f32_sqrt(a: u32) -> u32 {
         }
     }
 
-    f32_round_and_pack(0, r_exp, r_frac)
+    f32_round_and_pack(0, r_exp, r_frac, env)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
     #[test]
     fn test_f32_sqrt() {
         // sqrt(0.01) = 0.1
@@ -104,7 +115,112 @@ mod tests {
 
     #[test]
     fn test_f32_sqrt_inf_nan() {
-        // TODO: add some tests
+        // sqrt(Inf) = Inf
+        assert_eq!(crate::soft_f32::f32_sqrt(0x7F800000), 0x7F800000);
+
+        // sqrt(-Inf) = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_sqrt(0xFF800000)), true);
+
+        // sqrt(-1) = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_sqrt(0xBF800000)), true);
+
+        // sqrt(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_sqrt(0x7FC00001), 0x7FC00001);
     }
-}
 
+    #[test]
+    fn test_f32_sqrt_rounding_mode() {
+        // sqrt(2)
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut Env::new(RoundingMode::NearEven)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut Env::new(RoundingMode::MinMag)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut Env::new(RoundingMode::Min)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut Env::new(RoundingMode::Max)), 0x3FB504F4);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FB504F3);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut Env::new(RoundingMode::Odd)), 0x3FB504F3);
+
+        // sqrt(3)
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40400000, &mut Env::new(RoundingMode::NearEven)), 0x3FDDB3D7);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40400000, &mut Env::new(RoundingMode::MinMag)), 0x3FDDB3D7);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40400000, &mut Env::new(RoundingMode::Min)), 0x3FDDB3D7);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40400000, &mut Env::new(RoundingMode::Max)), 0x3FDDB3D8);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40400000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FDDB3D7);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40400000, &mut Env::new(RoundingMode::Odd)), 0x3FDDB3D7);
+
+        // sqrt(4)
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut Env::new(RoundingMode::NearEven)), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut Env::new(RoundingMode::MinMag)), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut Env::new(RoundingMode::Min)), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut Env::new(RoundingMode::Max)), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x40000000);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut Env::new(RoundingMode::Odd)), 0x40000000);
+
+        // sqrt(0.01)
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x3C23D70A, &mut Env::new(RoundingMode::NearEven)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x3C23D70A, &mut Env::new(RoundingMode::MinMag)), 0x3DCCCCCC);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x3C23D70A, &mut Env::new(RoundingMode::Min)), 0x3DCCCCCC);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x3C23D70A, &mut Env::new(RoundingMode::Max)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x3C23D70A, &mut Env::new(RoundingMode::NearMaxMag)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x3C23D70A, &mut Env::new(RoundingMode::Odd)), 0x3DCCCCCD);
+    }
+
+    #[test]
+    fn test_f32_sqrt_exception_flags() {
+        let mut env = Env::default();
+
+        // sqrt(4) = 2, exact
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40800000, &mut env), 0x40000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // sqrt(2), inexact
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x40000000, &mut env), 0x3FB504F3);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // sqrt(-1) = NaN, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_sqrt_with_env(0xBF800000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // sqrt(-0) = -0, exact
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x80000000, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // sqrt(sNaN) = qNaN, invalid
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_sqrt_subnormal() {
+        let mut env = Env::default();
+
+        // sqrt(2^-149) = 2^-74.5
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x00000001, &mut env), 0x1A3504F3);
+        // sqrt(2^-127) = 2^-63.5
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x00400000, &mut env), 0x1FB504F3);
+        // sqrt(2^-126 - 2^-149)
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x007FFFFF, &mut env), 0x1FFFFFFF);
+        // Results of sqrt are never tiny
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // sqrt(2^-148) = 2^-74, exact
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x00000002, &mut env), 0x1A800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+
+    #[test]
+    fn test_f32_sqrt_flush_to_zero() {
+        // sqrt(-2^-149) = -0 with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x80000001, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // The square root of a subnormal is normal, so it is never flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_sqrt_with_env(0x00000001, &mut env), 0x1A3504F3);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+}
@@ -1,76 +1,103 @@
 use super::util::{
     f32_shift_right_jam,
     f32_norm_round_and_pack,
-    f32_round_and_pack,
     f32_pack_raw, f32_pack,
     f32_propagate_nan,
     f32_sign, f32_exp, f32_frac,
+    f32_count_leading_zero,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
 };
 
+use super::soft_f32_add::f32_add_mags;
+
+use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, with_thread_env };
+
 pub fn f32_sub(a: u32, b: u32) -> u32 {
+    with_thread_env(|env| f32_sub_with_env(a, b, env))
+}
+
+pub fn f32_sub_with_env(a: u32, b: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    let b = f32_flush_input(b, env);
+    f32_flush_result(|env| {
+        if f32_sign(a) != f32_sign(b) {
+            // Consider as addition
+            return f32_add_mags(a, b, env);
+        }
+
+        f32_sub_mags(a, b, env)
+    }, env)
+}
+
+// Subtract the magnitude of B from the magnitude of A, the result has the sign of A
+pub(crate) fn f32_sub_mags(a: u32, b: u32, env: &mut Env) -> u32 {
     // Sign
-    let mut a_sign = f32_sign(a);
-    let mut b_sign = f32_sign(b);
+    let a_sign = f32_sign(a);
     let mut r_sign = a_sign;
 
     // Exp
     let mut a_exp = f32_exp(a);
-    let mut b_exp = f32_exp(b);
-    let mut r_exp = 0;
+    let b_exp = f32_exp(b);
+    let r_exp;
 
     // Frac
     let mut a_frac = f32_frac(a);
     let mut b_frac = f32_frac(b);
-    let mut r_frac = 0;
+    let mut r_frac;
 
     let diff_exp = a_exp - b_exp;
 
-    a_frac <<= 7;
-    b_frac <<= 7;
-
     if diff_exp == 0 {
         if a_exp == 0xFF {
-            if (a_sign | b_sign) != 0 {
+            if (a_frac | b_frac) != 0 {
                 // Propagate NaN
-                return f32_propagate_nan(a, b);
+                return f32_propagate_nan(a, b, env);
             } else {
-                // Return a NaN
-                // FIXME: 0x7FC00000 is used in IBM IEEE, while 0xFFC00000 is used otherwise
-                return f32_pack_raw(0, 0xFF, 0);
+                // Inf - Inf, invalid, return the default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f32_default_nan(env);
             }
         }
 
-        if a_exp == 0 {
-            a_exp = 1;
-            b_exp = 1;
+        r_frac = a_frac - b_frac;
+        if r_frac == 0 {
+            // Same, will cause a 0, which is negative only when rounding toward negative
+            let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+            return f32_pack(zero_sign, 0, 0);
         }
 
-        if a_frac > b_frac {
-            // Fraction of A is greater
-            r_exp = a_exp;
-            r_frac = a_frac - b_frac;
-        } else if b_frac > a_frac {
+        if a_exp != 0 {
+            a_exp -= 1;
+        }
+        if r_frac < 0 {
             // Fraction of B is greater
             r_sign ^= 1;
-            r_exp = b_exp;
-            r_frac = b_frac - a_frac;
-        } else {
-            // Same, will cause a 0
-            return f32_pack(0, 0, 0);
+            r_frac = -r_frac;
         }
-        return f32_norm_round_and_pack(r_sign, r_exp - 1, r_frac);
-    } else if diff_exp > 0 {
+
+        // The result is exact, normalize it as far as the exponent allows
+        let mut shift_count = f32_count_leading_zero(r_frac) - 8;
+        let mut exp = a_exp - shift_count;
+        if exp < 0 {
+            shift_count = a_exp;
+            exp = 0;
+        }
+        return f32_pack_raw(r_sign, exp, r_frac << shift_count);
+    }
+
+    a_frac <<= 7;
+    b_frac <<= 7;
+
+    if diff_exp > 0 {
         // Exp of A is greater
         if a_exp == 0xFF {
             if a_frac != 0 {
                 // Propagate NaN
-                return f32_propagate_nan(a, b);
-            } else {
-                r_sign = a_sign;
-                r_exp = a_exp;
-                r_frac = a_frac;
+                return f32_propagate_nan(a, b, env);
             }
-            return f32_pack_raw(r_sign, r_exp, r_frac);
+            // Infinity
+            return a;
         }
 
         if b_exp != 0 {
@@ -89,11 +116,10 @@ pub fn f32_sub(a: u32, b: u32) -> u32 {
         if b_exp == 0xFF {
             if b_frac != 0 {
                 // Propagate NaN
-                return f32_propagate_nan(a, b);
-            } else {
-                // Return a NaN
-                return f32_pack_raw(r_sign ^ 1, 0xFF, 0);
+                return f32_propagate_nan(a, b, env);
             }
+            // Infinity of the opposite sign
+            return f32_pack_raw(r_sign ^ 1, 0xFF, 0);
         }
 
         if a_exp != 0 {
@@ -109,11 +135,13 @@ pub fn f32_sub(a: u32, b: u32) -> u32 {
         r_exp = b_exp;
         r_frac = b_frac - a_frac;
     }
-    return f32_norm_round_and_pack(r_sign, r_exp - 1, r_frac);
+    f32_norm_round_and_pack(r_sign, r_exp - 1, r_frac, env)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
     #[test]
     fn test_f32_sub() {
         // 0.3 - 0.2 = 0.1
@@ -122,17 +150,14 @@ mod tests {
         // 0.2 - 0.3 = -0.1
         assert_eq!(crate::soft_f32::f32_sub(0x3E4CCCCD, 0x3E99999A), 0xBDCCCCCE);
 
-        // FIXME: 0.2 - -0.1 = 0.3
-        // 0x3DCCCCCC
-        // assert_eq!(crate::soft_f32::f32_sub(0x3E4CCCCD, 0xBDCCCCCE), 0x3E99999A);
+        // 0.2 - -0.1 = 0.3
+        assert_eq!(crate::soft_f32::f32_sub(0x3E4CCCCD, 0xBDCCCCCE), 0x3E99999A);
 
-        // FIXME: -0.2 - 0.1 = -0.3
-        // 0xBDCCCCCC
-        // assert_eq!(crate::soft_f32::f32_sub(0xBE4CCCCD, 0x3DCCCCCE), 0xBE99999A);
+        // -0.2 - 0.1 = -0.3
+        assert_eq!(crate::soft_f32::f32_sub(0xBE4CCCCD, 0x3DCCCCCE), 0xBE99999A);
 
-        // FIXME: -0.2 - -0.1 = -0.1
-        // 0xBDCCCCCC, precision issue
-        // assert_eq!(crate::soft_f32::f32_sub(0xBE4CCCCD, 0xBDCCCCCE), 0xBDCCCCCE);
+        // -0.2 - -0.1 = -0.1, the exact difference is 0xBDCCCCCC
+        assert_eq!(crate::soft_f32::f32_sub(0xBE4CCCCD, 0xBDCCCCCE), 0xBDCCCCCC);
 
         // 80235 - 67890 = 12345
         assert_eq!(crate::soft_f32::f32_sub(0x479CB580, 0x47849900), 0x4640E400);
@@ -140,10 +165,84 @@ mod tests {
         // 0.004 - 0.004 = 0
         assert_eq!(crate::soft_f32::f32_sub(0x3B83126F, 0x3B83126F), 0x00000000);
 
-        // FIXME
-        // assert_eq!(crate::soft_f32::f32_sub(0x0, 0xBDCCCCCE), 0x3DCCCCCE);
+        assert_eq!(crate::soft_f32::f32_sub(0x0, 0xBDCCCCCE), 0x3DCCCCCE);
         assert_eq!(crate::soft_f32::f32_sub(0x0, 0x3DCCCCCE), 0xBDCCCCCE);
         assert_eq!(crate::soft_f32::f32_sub(0x0, 0x0), 0x0);
         assert_eq!(crate::soft_f32::f32_sub(0x0, 0x80000000), 0x0);
     }
+
+    #[test]
+    fn test_f32_sub_rounding_mode() {
+        // 0.2 - -0.1
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3E4CCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3E99999A);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3E4CCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3E999999);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3E4CCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::Min)), 0x3E999999);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3E4CCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::Max)), 0x3E99999A);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3E4CCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0x3E99999A);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3E4CCCCD, 0xBDCCCCCD, &mut Env::new(RoundingMode::Odd)), 0x3E999999);
+
+        // 0.1 - 0.2
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearEven)), 0xBDCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::MinMag)), 0xBDCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Min)), 0xBDCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Max)), 0xBDCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0xBDCCCCCD);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3E4CCCCD, &mut Env::new(RoundingMode::Odd)), 0xBDCCCCCD);
+
+        // 1 - 2^-25
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut Env::new(RoundingMode::MinMag)), 0x3F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut Env::new(RoundingMode::Min)), 0x3F7FFFFF);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut Env::new(RoundingMode::Max)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut Env::new(RoundingMode::Odd)), 0x3F7FFFFF);
+
+        // 0.1 - 0.1
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3DCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3DCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3DCCCCCD, &mut Env::new(RoundingMode::Min)), 0x80000000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3DCCCCCD, &mut Env::new(RoundingMode::Max)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3DCCCCCD, &mut Env::new(RoundingMode::NearMaxMag)), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3DCCCCCD, 0x3DCCCCCD, &mut Env::new(RoundingMode::Odd)), 0x00000000);
+    }
+
+    #[test]
+    fn test_f32_sub_exception_flags() {
+        let mut env = Env::default();
+
+        // 0.004 - 0.004 = 0, exact
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3B83126F, 0x3B83126F, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 1 - 2^-25, inexact
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x3F800000, 0x33000000, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // -MAX - MAX = -Inf, overflow
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0xFF7FFFFF, 0x7F7FFFFF, &mut env), 0xFF800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // Inf - Inf = NaN, invalid
+        env.clear_flags();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_sub_with_env(0x7F800000, 0x7F800000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f32_sub_subnormal() {
+        let mut env = Env::default();
+
+        // Smallest normal - smallest subnormal = largest subnormal, exact
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x00800000, 0x00000001, &mut env), 0x007FFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Smallest subnormal - largest subnormal = -(smallest normal - 2 ulps)
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x00000001, 0x007FFFFF, &mut env), 0x807FFFFE);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Smallest normal - -smallest subnormal = smallest normal + 1 ulp
+        assert_eq!(crate::soft_f32::f32_sub_with_env(0x00800000, 0x80000001, &mut env), 0x00800001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
 }
@@ -0,0 +1,485 @@
+use super::util::{
+    f32_propagate_nan,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_default_nan,
+    f32_flush_input, f32_flush_result,
+};
+
+use super::fixed::{
+    fixed_mul,
+    fixed_square,
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+    FIXED_ONE,
+    FIXED_PI_2,
+    FIXED_TINY_SQUARE,
+};
+
+use crate::soft_f128::util::f128_count_leading_zero;
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// The fraction bits of 2 / pi, enough for the largest Float32
+const TWO_OVER_PI: [u64; 6] = [
+    0xA2F9836E4E441529, 0xFC2757D1F534DDC0, 0xDB6295993C439041,
+    0xFE5163ABDEBBC561, 0xB7246E3A424DD2E0, 0x06492EEA09D1921C,
+];
+
+// The 64 bits of 2 / pi after the first skip bits
+pub(crate) fn two_over_pi_bits(skip: i32) -> u64 {
+    let word = (skip / 64) as usize;
+    let offset = skip % 64;
+    if offset == 0 {
+        TWO_OVER_PI[word]
+    } else {
+        (TWO_OVER_PI[word] << offset) | (TWO_OVER_PI[word + 1] >> (64 - offset))
+    }
+}
+
+// Reduce |A| = frac x 2^(exp - 150) to j x pi / 2 + r with |r| <= pi / 4, with the bits of 2 / pi
+// which matter for the result only (Payne-Hanek). The result is (j mod 4, r, k) where r is
+// a fixed-point value scaled by 2^k, so that it keeps its precision when |A| is close to a
+// multiple of pi / 2.
+fn f32_reduce_pi_2(exp: i32, frac: i32) -> (i32, i128, i32) {
+    let shift = exp - 150;
+    if exp < 0x7E || (exp == 0x7E && frac <= 0x00C90FDA) {
+        // |A| < pi / 4
+        return (0, (frac as i128) << (FIXED_FRAC_BITS - 23), 127 - exp);
+    }
+
+    // The bits of 2 / pi before the first skip ones are multiples of 4 in the product
+    let skip = (shift - 2).max(0);
+    let p0 = (frac as u128) * (two_over_pi_bits(skip) as u128);
+    let p1 = (frac as u128) * (two_over_pi_bits(skip + 64) as u128);
+    let p2 = (frac as u128) * (two_over_pi_bits(skip + 128) as u128);
+
+    // 256 bits product, with 192 + skip - shift fraction bits
+    let (lo, carry) = p2.overflowing_add(p1 << 64);
+    let hi = p0 + (p1 >> 64) + carry as u128;
+
+    // 2 integer bits and 126 fraction bits of |A| x 2 / pi
+    let dist = 192 + skip - shift - 126;
+    let v = (hi << (128 - dist)) | (lo >> dist);
+
+    // Round to the nearest j, leaving f in [-0.5, 0.5]
+    let j = (v.wrapping_add(1 << 125) >> 126) as i32;
+    let f = v.wrapping_sub((j as u128) << 126) as i128;
+    if f == 0 {
+        return (j & 3, 0, 0);
+    }
+
+    // Scale |f| to [2^125, 2^126), and drop to 62 fraction bits
+    let k = f128_count_leading_zero(f.unsigned_abs()) - 2;
+    let f = (f << k) >> 64;
+
+    (j & 3, fixed_mul(f, FIXED_PI_2), k)
+}
+
+// sin(r) and cos(r) for r scaled by 2^k, with |r| <= pi / 4. sin(r) has the scale of r.
+// A tiny r gives sin(r) just below r and cos(r) just below 1.
+fn fixed_sin_cos(r: i128, k: i32) -> (i128, i128) {
+    let r2 = fixed_square(r, k);
+    if r2 < FIXED_TINY_SQUARE {
+        return (r - r.signum(), FIXED_ONE - 1);
+    }
+
+    // sin(r) = r x (1 - r^2 / 3! + r^4 / 5! - ...)
+    // cos(r) = 1 - r^2 / 2! + r^4 / 4! - ...
+    let mut sin_sum = FIXED_ONE;
+    let mut cos_sum = FIXED_ONE;
+    let mut term = FIXED_ONE;
+    for n in 1..=12 {
+        term = fixed_mul(term, r2) / (2 * n - 1);
+        cos_sum -= term / (2 * n);
+        term = -term / (2 * n);
+        sin_sum += term / (2 * n + 1);
+        if term == 0 {
+            break;
+        }
+    }
+
+    (fixed_mul(r, sin_sum), cos_sum)
+}
+
+// Special cases of A, which is either returned as an error or split into its sign and reduced
+// argument (j, r, k)
+fn f32_trig_reduce(a: u32, env: &mut Env) -> Result<(i32, i32, i128, i32), u32> {
+    // Sign
+    let a_sign = f32_sign(a);
+
+    // Exp
+    let mut a_exp = f32_exp(a);
+
+    // Frac
+    let mut a_frac = f32_frac(a);
+
+    if a_exp == 0xFF {
+        if a_frac != 0 {
+            // Propagate NaN
+            return Err(f32_propagate_nan(a, 0, env));
+        }
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return Err(f32_default_nan(env));
+    }
+
+    if a_exp == 0 {
+        if a_frac == 0 {
+            // Zero
+            return Err(a);
+        }
+        let (exp, frac) = f32_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+
+    let (j, r, k) = f32_reduce_pi_2(a_exp, a_frac | 0x00800000);
+    Ok((a_sign, j, r, k))
+}
+
+// cos(A) from the result of sin(A) for the special cases, which is 1 for a zero and the NaN otherwise
+fn f32_cos_of_special(r: u32) -> u32 {
+    if r & 0x7FFFFFFF == 0 {
+        return f32_pack_raw(0, 0x7F, 0);
+    }
+    r
+}
+
+// Negate the fixed-point value when the sign is set
+fn fixed_with_sign(sign: i32, v: i128) -> i128 {
+    if sign == 1 { -v } else { v }
+}
+
+// sin(A) from the reduced argument of |A| = j x pi / 2 + r, as the fixed-point v x 2^scale
+fn fixed_sin_of_reduced(a_sign: i32, j: i32, sin_r: i128, cos_r: i128, k: i32) -> (i128, i32) {
+    match j {
+        0 => (fixed_with_sign(a_sign, sin_r), -FIXED_FRAC_BITS - k),
+        1 => (fixed_with_sign(a_sign, cos_r), -FIXED_FRAC_BITS),
+        2 => (fixed_with_sign(a_sign ^ 1, sin_r), -FIXED_FRAC_BITS - k),
+        _ => (fixed_with_sign(a_sign ^ 1, cos_r), -FIXED_FRAC_BITS),
+    }
+}
+
+// cos(A) from the reduced argument of |A| = j x pi / 2 + r, as the fixed-point v x 2^scale
+fn fixed_cos_of_reduced(j: i32, sin_r: i128, cos_r: i128, k: i32) -> (i128, i32) {
+    match j {
+        0 => (cos_r, -FIXED_FRAC_BITS),
+        1 => (-sin_r, -FIXED_FRAC_BITS - k),
+        2 => (-cos_r, -FIXED_FRAC_BITS),
+        _ => (sin_r, -FIXED_FRAC_BITS - k),
+    }
+}
+
+// Round sin(A) from the reduced argument of |A| = j x pi / 2 + r
+fn f32_round_sin(a_sign: i32, j: i32, sin_r: i128, cos_r: i128, k: i32, env: &mut Env) -> u32 {
+    let (v, scale) = fixed_sin_of_reduced(a_sign, j, sin_r, cos_r, k);
+    f32_round_fixed(v, scale, false, env)
+}
+
+// Round cos(A) from the reduced argument of |A| = j x pi / 2 + r
+fn f32_round_cos(j: i32, sin_r: i128, cos_r: i128, k: i32, env: &mut Env) -> u32 {
+    let (v, scale) = fixed_cos_of_reduced(j, sin_r, cos_r, k);
+    f32_round_fixed(v, scale, false, env)
+}
+
+// Whether |A| < 2^-28, where r = A is tiny and the result rounds as the exact value
+fn f32_is_tiny_angle(a: u32) -> bool {
+    a & 0x7FFFFFFF < 0x31800000
+}
+
+pub fn f32_sin(a: u32) -> u32 {
+    with_thread_env(|env| f32_sin_with_env(a, env))
+}
+
+// Sine. The argument is reduced exactly, and the result is within 0.5 ULP plus the 2^-56
+// relative error of the evaluation, that is correctly rounded but for hard cases.
+pub fn f32_sin_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_sin_unflushed(a, env), env)
+}
+
+fn f32_sin_unflushed(a: u32, env: &mut Env) -> u32 {
+    match f32_sin_fixed(a, env) {
+        Ok((v, scale)) => f32_round_fixed(v, scale, false, env),
+        Err(r) => r,
+    }
+}
+
+// sin(A) before rounding, as the fixed-point v x 2^scale. The result of the special cases, and of
+// a tiny A, is returned as an error.
+pub(crate) fn f32_sin_fixed(a: u32, env: &mut Env) -> Result<(i128, i32), u32> {
+    let (a_sign, j, r, k) = f32_trig_reduce(a, env)?;
+    let (sin_r, cos_r) = fixed_sin_cos(r, k);
+
+    if f32_is_tiny_angle(a) {
+        return Err(f32_round_sin(a_sign, j, sin_r, cos_r, k, env));
+    }
+    Ok(fixed_sin_of_reduced(a_sign, j, sin_r, cos_r, k))
+}
+
+pub fn f32_cos(a: u32) -> u32 {
+    with_thread_env(|env| f32_cos_with_env(a, env))
+}
+
+// Cosine, with the accuracy of f32_sin
+pub fn f32_cos_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_cos_unflushed(a, env), env)
+}
+
+fn f32_cos_unflushed(a: u32, env: &mut Env) -> u32 {
+    match f32_cos_fixed(a, env) {
+        Ok((v, scale)) => f32_round_fixed(v, scale, false, env),
+        Err(r) => r,
+    }
+}
+
+// cos(A) before rounding, as the fixed-point v x 2^scale. The result of the special cases, and of
+// a tiny A, is returned as an error.
+pub(crate) fn f32_cos_fixed(a: u32, env: &mut Env) -> Result<(i128, i32), u32> {
+    let (_, j, r, k) = f32_trig_reduce(a, env).map_err(f32_cos_of_special)?;
+    let (sin_r, cos_r) = fixed_sin_cos(r, k);
+
+    if f32_is_tiny_angle(a) {
+        return Err(f32_round_cos(j, sin_r, cos_r, k, env));
+    }
+    Ok(fixed_cos_of_reduced(j, sin_r, cos_r, k))
+}
+
+pub fn f32_tan(a: u32) -> u32 {
+    with_thread_env(|env| f32_tan_with_env(a, env))
+}
+
+// Tangent, with the accuracy of f32_sin. The result is finite for every Float32,
+// as none is close enough to an odd multiple of pi / 2.
+pub fn f32_tan_with_env(a: u32, env: &mut Env) -> u32 {
+    let a = f32_flush_input(a, env);
+    f32_flush_result(|env| f32_tan_unflushed(a, env), env)
+}
+
+fn f32_tan_unflushed(a: u32, env: &mut Env) -> u32 {
+    let (a_sign, j, r, k) = match f32_trig_reduce(a, env) {
+        Ok(reduced) => reduced,
+        Err(r) => return r,
+    };
+    let (sin_r, cos_r) = fixed_sin_cos(r, k);
+
+    if j & 1 == 0 {
+        // tan(r), moved just above r along with sin(r) below it
+        let tan_r = match fixed_square(r, k) < FIXED_TINY_SQUARE {
+            true => r + r.signum(),
+            false => (sin_r << FIXED_FRAC_BITS) / cos_r,
+        };
+        f32_round_fixed(fixed_with_sign(a_sign, tan_r), -FIXED_FRAC_BITS - k, false, env)
+    } else {
+        // -1 / tan(r) = -cos(r) / sin(r), which has the inverse scale of r
+        let cot_r = (cos_r << FIXED_FRAC_BITS) / sin_r;
+        f32_round_fixed(fixed_with_sign(a_sign ^ 1, cot_r), k - FIXED_FRAC_BITS, false, env)
+    }
+}
+
+pub fn f32_sincos(a: u32) -> (u32, u32) {
+    with_thread_env(|env| f32_sincos_with_env(a, env))
+}
+
+// Sine and cosine of A, with a single argument reduction
+pub fn f32_sincos_with_env(a: u32, env: &mut Env) -> (u32, u32) {
+    let a = f32_flush_input(a, env);
+    // The cosine is never tiny
+    let mut cos = 0;
+    let sin = f32_flush_result(|env| {
+        let (sin, r) = f32_sincos_unflushed(a, env);
+        cos = r;
+        sin
+    }, env);
+    (sin, cos)
+}
+
+fn f32_sincos_unflushed(a: u32, env: &mut Env) -> (u32, u32) {
+    let (a_sign, j, r, k) = match f32_trig_reduce(a, env) {
+        Ok(reduced) => reduced,
+        Err(r) => return (r, f32_cos_of_special(r)),
+    };
+    let (sin_r, cos_r) = fixed_sin_cos(r, k);
+
+    (f32_round_sin(a_sign, j, sin_r, cos_r, k, env), f32_round_cos(j, sin_r, cos_r, k, env))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, FlushToZero };
+
+    #[test]
+    fn test_f32_sin() {
+        // sin(1) = 0.84147096
+        assert_eq!(crate::soft_f32::f32_sin(0x3F800000), 0x3F576AA4);
+        // sin(0.5) = 0.47942555
+        assert_eq!(crate::soft_f32::f32_sin(0x3F000000), 0x3EF57744);
+        // sin(-2) = -0.9092974
+        assert_eq!(crate::soft_f32::f32_sin(0xC0000000), 0xBF68C7B7);
+        // sin(3.1415927) = -8.742278e-8
+        assert_eq!(crate::soft_f32::f32_sin(0x40490FDB), 0xB3BBBD2E);
+        // sin(100) = -0.50636566
+        assert_eq!(crate::soft_f32::f32_sin(0x42C80000), 0xBF01A12E);
+
+        // sin(0) = 0
+        assert_eq!(crate::soft_f32::f32_sin(0x00000000), 0x00000000);
+        // sin(-0) = -0
+        assert_eq!(crate::soft_f32::f32_sin(0x80000000), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_cos() {
+        // cos(1) = 0.5403023
+        assert_eq!(crate::soft_f32::f32_cos(0x3F800000), 0x3F0A5140);
+        // cos(0.5) = 0.87758255
+        assert_eq!(crate::soft_f32::f32_cos(0x3F000000), 0x3F60A940);
+        // cos(3.1415927) = -1
+        assert_eq!(crate::soft_f32::f32_cos(0x40490FDB), 0xBF800000);
+        // cos(100) = 0.8623189
+        assert_eq!(crate::soft_f32::f32_cos(0x42C80000), 0x3F5CC0EE);
+
+        // cos(0) = 1
+        assert_eq!(crate::soft_f32::f32_cos(0x00000000), 0x3F800000);
+        // cos(-0) = 1
+        assert_eq!(crate::soft_f32::f32_cos(0x80000000), 0x3F800000);
+    }
+
+    #[test]
+    fn test_f32_tan() {
+        // tan(1) = 1.5574077
+        assert_eq!(crate::soft_f32::f32_tan(0x3F800000), 0x3FC75923);
+        // tan(-2) = 2.1850398
+        assert_eq!(crate::soft_f32::f32_tan(0xC0000000), 0x400BD7B1);
+        // tan(1.5707964) = -22877332
+        assert_eq!(crate::soft_f32::f32_tan(0x3FC90FDB), 0xCBAE8A4A);
+
+        // tan(-0) = -0
+        assert_eq!(crate::soft_f32::f32_tan(0x80000000), 0x80000000);
+    }
+
+    #[test]
+    fn test_f32_sincos() {
+        // sincos(1) = (0.84147096, 0.5403023)
+        assert_eq!(crate::soft_f32::f32_sincos(0x3F800000), (0x3F576AA4, 0x3F0A5140));
+        // sincos(1e30) = (-0.79116344, -0.6116048)
+        assert_eq!(crate::soft_f32::f32_sincos(0x7149F2CA), (0xBF4A89B0, 0xBF1C9222));
+        // sincos(-0) = (-0, 1)
+        assert_eq!(crate::soft_f32::f32_sincos(0x80000000), (0x80000000, 0x3F800000));
+    }
+
+    #[test]
+    fn test_f32_trig_large() {
+        // sin(1e22) = -0.7340815
+        assert_eq!(crate::soft_f32::f32_sin(0x64078678), 0xBF3BECC4);
+        // sin(1e30) = -0.79116344
+        assert_eq!(crate::soft_f32::f32_sin(0x7149F2CA), 0xBF4A89B0);
+        // cos(1e30) = -0.6116048
+        assert_eq!(crate::soft_f32::f32_cos(0x7149F2CA), 0xBF1C9222);
+        // tan(1e30) = 1.2935861
+        assert_eq!(crate::soft_f32::f32_tan(0x7149F2CA), 0x3FA5943B);
+        // sin(3.4028235e38) = -0.5218765
+        assert_eq!(crate::soft_f32::f32_sin(0x7F7FFFFF), 0xBF0599B3);
+        // cos(3.4028235e38) = 0.853021
+        assert_eq!(crate::soft_f32::f32_cos(0x7F7FFFFF), 0x3F5A5F96);
+    }
+
+    #[test]
+    fn test_f32_trig_inf_nan() {
+        // sin(Inf) = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_sin(0x7F800000)), true);
+        // cos(-Inf) = NaN
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_cos(0xFF800000)), true);
+
+        // tan(NaN) = NaN
+        assert_eq!(crate::soft_f32::f32_tan(0x7FC00001), 0x7FC00001);
+        // sincos(NaN) = (NaN, NaN)
+        assert_eq!(crate::soft_f32::f32_sincos(0x7FC00001), (0x7FC00001, 0x7FC00001));
+    }
+
+    #[test]
+    fn test_f32_trig_exception_flags() {
+        // sin(Inf) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_is_nan(crate::soft_f32::f32_sin_with_env(0x7F800000, &mut env)), true);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // cos(sNaN) = NaN, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x7F800001, &mut env), 0x7FC00001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // cos(0) = 1, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x00000000, &mut env), 0x3F800000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // sin(1), inexact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x3F800000, &mut env), 0x3F576AA4);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // sin(2^-149) rounds to 2^-149, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x00000001, &mut env), 0x00000001);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_f32_trig_rounding_mode() {
+        // sin(1e30)
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x7149F2CA, &mut Env::new(RoundingMode::NearEven)), 0xBF4A89B0);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x7149F2CA, &mut Env::new(RoundingMode::MinMag)), 0xBF4A89AF);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x7149F2CA, &mut Env::new(RoundingMode::Min)), 0xBF4A89B0);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x7149F2CA, &mut Env::new(RoundingMode::Max)), 0xBF4A89AF);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x7149F2CA, &mut Env::new(RoundingMode::NearMaxMag)), 0xBF4A89B0);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x7149F2CA, &mut Env::new(RoundingMode::Odd)), 0xBF4A89AF);
+
+        // cos(1)
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x3F0A5140);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x3F0A5140);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x3F800000, &mut Env::new(RoundingMode::Min)), 0x3F0A5140);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x3F800000, &mut Env::new(RoundingMode::Max)), 0x3F0A5141);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F0A5140);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x3F0A5141);
+
+        // tan(1)
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x3F800000, &mut Env::new(RoundingMode::NearEven)), 0x3FC75923);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x3F800000, &mut Env::new(RoundingMode::MinMag)), 0x3FC75922);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x3F800000, &mut Env::new(RoundingMode::Min)), 0x3FC75922);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x3F800000, &mut Env::new(RoundingMode::Max)), 0x3FC75923);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x3F800000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FC75923);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x3F800000, &mut Env::new(RoundingMode::Odd)), 0x3FC75923);
+
+        // sin(1e-20) is just below 1e-20
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x1E3CE508);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x1E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x1E3CE507);
+
+        // cos(1e-20) is just below 1
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x1E3CE508, &mut Env::new(RoundingMode::MinMag)), 0x3F7FFFFF);
+
+        // tan(1e-20) is just above 1e-20
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x1E3CE508, &mut Env::new(RoundingMode::NearEven)), 0x1E3CE508);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x1E3CE508, &mut Env::new(RoundingMode::Max)), 0x1E3CE509);
+    }
+
+    #[test]
+    fn test_f32_trig_flush_to_zero() {
+        // sin(2^-149) is subnormal, so it is flushed
+        let mut env = Env::default();
+        env.set_flush_to_zero(FlushToZero::Underflow);
+        assert_eq!(crate::soft_f32::f32_sin_with_env(0x00000001, &mut env), 0x00000000);
+        assert_eq!(crate::soft_f32::f32_sincos_with_env(0x80000001, &mut env), (0x80000000, 0x3F800000));
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW);
+
+        // cos(0) is exactly 1 with denormals are zero
+        let mut env = Env::default();
+        env.set_denormals_are_zero(true);
+        assert_eq!(crate::soft_f32::f32_cos_with_env(0x00000001, &mut env), 0x3F800000);
+        assert_eq!(crate::soft_f32::f32_tan_with_env(0x80000001, &mut env), 0x80000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
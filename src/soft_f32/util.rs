@@ -1,10 +1,10 @@
 
-pub(crate) fn f32_shift_right_jam(a: i32, dist: i32) -> i32 {
+pub(crate) const fn f32_shift_right_jam(a: i32, dist: i32) -> i32 {
     if dist < 31 {
         if (a << (-dist & 31)) != 0 {
             (a >> dist) | 1
         } else {
-            (a >> dist) | 0
+            a >> dist
         }
     } else {
         if a != 0 {
@@ -15,7 +15,7 @@ pub(crate) fn f32_shift_right_jam(a: i32, dist: i32) -> i32 {
     }
 }
 
-use crate::soft_float::{ RoundingMode, DetectTininess };
+use crate::soft_float::{ RoundingMode, DetectTininess, Env, ExceptionFlags, NanPolicy, FlushToZero };
 
 pub(crate) fn f32_sign(a: u32) -> i32 {
     ((a >> 31) & 0x01) as i32
@@ -29,37 +29,114 @@ pub(crate) fn f32_frac(a: u32) -> i32 {
     (a & 0x7FFFFF) as i32
 }
 
-pub(crate) fn f32_frac_old(a: u32) -> i32 {
-    ((a & 0x7FFFFF) | (0x01 << 23)) as i32
+pub(crate) const fn f32_pack_raw(in_sign: i32, in_exp: i32, in_frac: i32) -> u32 {
+    // Use + rather than |, so that a carry out of the fraction increments the exponent
+    ((in_sign << 31) | ((in_exp << 23) + in_frac)) as u32
 }
 
-pub(crate) fn f32_pack_raw(in_sign: i32, in_exp: i32, in_frac: i32) -> u32 {
-    // FIXME: why + is not equivalent to |
-    ((in_sign << 31) | (in_exp << 23) + in_frac) as u32
+// The NaN of an invalid operation without a NaN operand
+pub(crate) fn f32_default_nan(env: &Env) -> u32 {
+    match env.nan_policy() {
+        NanPolicy::X87 | NanPolicy::X86Sse => 0xFFC00000,
+        NanPolicy::MipsLegacy => 0x7FBFFFFF,
+        _ => 0x7FC00000,
+    }
 }
 
 pub fn f32_is_nan(a: u32) -> bool {
-    let is_exp_nan = ((a & 0x7F800000) == 0x7F800000);
-    let is_frac_nan = ((a & 0x007FFFFF) != 0);
+    let is_exp_nan = (a & 0x7F800000) == 0x7F800000;
+    let is_frac_nan = (a & 0x007FFFFF) != 0;
 
     is_exp_nan && is_frac_nan
 }
 
-pub(crate) fn f32_is_frac_nan(a: u32) -> bool {
-    let is_exp_nan = ((a & 0x7FC00000) == 0x7F800000);
+// A signaling NaN has the most significant bit of its fraction cleared
+pub fn f32_is_signaling_nan(a: u32) -> bool {
+    let is_exp_nan = (a & 0x7FC00000) == 0x7F800000;
     let is_frac_nan = (a & 0x003FFFFF) != 0;
 
     is_exp_nan && is_frac_nan
 }
 
-pub(crate) fn f32_propagate_nan(in_a: u32, in_b: u32) -> u32 {
-    let mut a = in_a | 0x00400000;
-    let mut b = in_b | 0x00400000;
+// Whether A is a signaling NaN in the encoding of the NaN policy, where MIPS legacy
+// inverts the quiet bit
+pub(crate) fn f32_is_signaling_nan_in(a: u32, env: &Env) -> bool {
+    match env.nan_policy() {
+        NanPolicy::MipsLegacy => (a & 0x7FC00000) == 0x7FC00000,
+        _ => f32_is_signaling_nan(a),
+    }
+}
+
+// The NaN result of an operation on A and B, at least one of which is a NaN, chosen by the
+// NaN policy of the environment. A signaling NaN is invalid.
+pub(crate) fn f32_propagate_nan(a: u32, b: u32, env: &mut Env) -> u32 {
+    f32_propagate_nans(&[a, b], env)
+}
+
+// The Float32 NaN result of the conversion of a NaN, of another format or of Float32, given
+// its sign, whether its quiet bit is set, and the high 22 bits of its payload. The payload is
+// kept as the NaN policy of the environment propagates an operand. A signaling NaN is invalid.
+pub(crate) fn f32_convert_nan(sign: i32, is_quiet_bit: bool, payload: u32, env: &mut Env) -> u32 {
+    let is_signaling = match env.nan_policy() {
+        NanPolicy::MipsLegacy => is_quiet_bit,
+        _ => !is_quiet_bit,
+    };
+    if is_signaling {
+        env.raise(ExceptionFlags::INVALID);
+    }
+
+    match env.nan_policy() {
+        NanPolicy::X87 | NanPolicy::X86Sse | NanPolicy::Armv8 => f32_pack_raw(sign, 0xFF, 0x00400000 | payload as i32),
+        // A quiet NaN without the quiet bit needs a payload
+        NanPolicy::MipsLegacy if !is_signaling && payload != 0 => f32_pack_raw(sign, 0xFF, payload as i32),
+        _ => f32_default_nan(env),
+    }
+}
+
+// The NaN result of the fused multiply-add A x B + C
+pub(crate) fn f32_propagate_nan3(a: u32, b: u32, c: u32, env: &mut Env) -> u32 {
+    match env.nan_policy() {
+        // The addend comes first
+        NanPolicy::Armv8 => f32_propagate_nans(&[c, a, b], env),
+        _ => f32_propagate_nans(&[a, b, c], env),
+    }
+}
+
+// The NaN result of the operands, in the order of the instruction
+fn f32_propagate_nans(ops: &[u32], env: &mut Env) -> u32 {
+    if env.nan_policy() == NanPolicy::X87 {
+        // Pairwise, in order
+        return ops[1..].iter().fold(ops[0], |r, &op| f32_propagate_nan_x87(r, op, env));
+    }
+
+    let default_nan = f32_default_nan(env);
+    let first_signaling = ops.iter().copied().find(|&op| f32_is_signaling_nan_in(op, env));
+    let first_nan = ops.iter().copied().find(|&op| f32_is_nan(op)).unwrap_or(default_nan);
+    if first_signaling.is_some() {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+    }
+
+    match env.nan_policy() {
+        NanPolicy::X86Sse => first_nan | 0x00400000,
+        NanPolicy::Armv8 => first_signaling.unwrap_or(first_nan) | 0x00400000,
+        // Quieting a NaN could give infinity
+        NanPolicy::MipsLegacy if first_signaling.is_some() => default_nan,
+        NanPolicy::MipsLegacy => first_nan,
+        _ => default_nan,
+    }
+}
+
+fn f32_propagate_nan_x87(in_a: u32, in_b: u32, env: &mut Env) -> u32 {
+    let a = in_a | 0x00400000;
+    let b = in_b | 0x00400000;
 
-    let is_a_frac_nan = f32_is_frac_nan(in_a);
-    let is_b_frac_nan = f32_is_frac_nan(in_b);
+    let is_a_frac_nan = f32_is_signaling_nan(in_a);
+    let is_b_frac_nan = f32_is_signaling_nan(in_b);
 
     if is_a_frac_nan | is_b_frac_nan {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
         if is_a_frac_nan {
             if !is_b_frac_nan {
                 return match f32_is_nan(b) {
@@ -75,18 +152,16 @@ pub(crate) fn f32_propagate_nan(in_a: u32, in_b: u32) -> u32 {
         }
     }
 
-    let a_frac = f32_frac(a);
-    let b_frac = f32_frac(b);
+    // Return the NaN with the larger magnitude
+    let a_mag = a & 0x7FFFFFFF;
+    let b_mag = b & 0x7FFFFFFF;
 
-    if a_frac < b_frac {
-        return b;
-    } else if b_frac < a_frac {
-        return a;
+    if a_mag < b_mag {
+        b
+    } else if b_mag < a_mag || a < b {
+        a
     } else {
-        if a < b {
-            return a;
-        }
-        return b;
+        b
     }
 }
 
@@ -95,71 +170,131 @@ pub fn f32_pack(in_sign: i32, in_exp: i32, in_frac: i32) -> u32 {
     ((in_sign << 31) | ((in_exp & 0x0FF) << 23) | (in_frac & 0x007fffff)) as u32
 }
 
-pub(crate) fn f32_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i32) -> u32 {
-    let rounding_mode = RoundingMode::NearEven;
-    let detect_tininess = DetectTininess::After;
+pub(crate) fn f32_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i32, env: &mut Env) -> u32 {
+    let rounding_mode = env.rounding_mode();
+    let detect_tininess = env.detect_tininess();
 
+    let round_near_even = rounding_mode == RoundingMode::NearEven;
     let mut round_increment = 0x40;
 
-    let mut sign = in_sign;
+    let sign = in_sign;
     let mut exp = in_exp;
     let mut frac = in_frac;
 
-    match &rounding_mode {
-        NearEven => { /* Do nothing */ }
-        NearMaxMag => { /* Do nothing */ }
+    match rounding_mode {
+        RoundingMode::NearEven | RoundingMode::NearMaxMag => { /* Do nothing */ }
         _ => {
-            if sign == 1 {
-                match &rounding_mode {
-                    Min => round_increment = 0x7F,
-                    _ => round_increment = 0,
-                }
-            } else {
-                match &rounding_mode {
-                    Max => round_increment = 0x7F,
-                    _ => round_increment = 0,
-                }
-            }
+            // Round away from zero only when rounding toward the infinity of the same sign
+            let round_away = match rounding_mode {
+                RoundingMode::Min => sign == 1,
+                RoundingMode::Max => sign == 0,
+                _ => false,
+            };
+            round_increment = if round_away { 0x7F } else { 0 };
         }
     }
     let mut round_bits = frac & 0x7F;
 
-    if exp >= 0xFD {    // FIXME: exponential value is wrongly detected here
+    // A negative exponent is seen as a large unsigned one, so that tiny results are caught here as well
+    if (exp as u32) >= 0xFD {
         if exp < 0 {
-            let is_tiny = (
-                match detect_tininess {
-                    Before => true,
-                    _ => false,
-                } || exp < -1 || (frac as u32) + (round_increment as u32) < 0x80000000
-            );
+            // Tiny result, denormalize it before rounding
+            let is_tiny = detect_tininess == DetectTininess::Before
+                || exp < -1
+                || (frac as u32) + (round_increment as u32) < 0x80000000;
             frac = f32_shift_right_jam(frac, -exp);
             exp = 0;
             round_bits = frac & 0x7F;
             if is_tiny && round_bits != 0 {
                 // Underflow
+                env.raise(ExceptionFlags::UNDERFLOW);
             }
         } else if exp > 0xFD || (frac as u32) + (round_increment as u32) >= 0x80000000 {
-            // Overflow and inexact
-            return (((sign << 31) | ((exp & 0x0FF) << 23) | (frac & 0x7fffff)) - ! round_increment) as u32
+            // Overflow and inexact, round to infinity or to the largest finite value
+            env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+            return f32_pack_raw(sign, 0xFF, 0) - (round_increment == 0) as u32;
         }
     }
 
-    frac = (frac + round_increment) >> 7;
+    let mut r_frac = ((frac as u32) + (round_increment as u32)) >> 7;
 
-    // if ( roundBits ) { softfloat_exceptionFlags |= softfloat_flag_inexact; }
+    if round_bits != 0 {
+        env.raise(ExceptionFlags::INEXACT);
+    }
 
-    match &rounding_mode {
-        NearEven => {
-            if round_bits ^ 0x40 == 0 {
-                frac &= 0x7FFFFFFE;
-            } else {
-                frac &= 0x7FFFFFFF;
-            }
-        }
+    if round_bits != 0 && rounding_mode == RoundingMode::Odd {
+        // Inexact, jam the lost bits into the least significant bit
+        r_frac |= 1;
+    } else if round_near_even && round_bits == 0x40 {
+        // Tie, round to even
+        r_frac &= !1;
+    }
+    if r_frac == 0 { exp = 0; }
+
+    f32_pack_raw(sign, exp, r_frac as i32)
+}
+
+// A subnormal operand is read as a zero of the same sign when denormals are zero
+pub(crate) fn f32_flush_input(a: u32, env: &Env) -> u32 {
+    if env.denormals_are_zero() && f32_exp(a) == 0 {
+        return a & 0x80000000;
+    }
+    a
+}
+
+// Compute an operation and flush its result to a zero of the same sign when it is tiny and
+// flush to zero is set. The result is tiny when it raised underflow or is exactly subnormal.
+pub(crate) fn f32_flush_result<F>(op: F, env: &mut Env) -> u32
+where
+    F: FnOnce(&mut Env) -> u32,
+{
+    let flush_flags = match env.flush_to_zero() {
+        FlushToZero::Off => return op(env),
+        FlushToZero::Underflow => ExceptionFlags::UNDERFLOW,
+        FlushToZero::UnderflowInexact => ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+    };
+
+    let flags = env.flags();
+    env.clear_flags();
+    let r = op(env);
+
+    let is_subnormal = f32_exp(r) == 0 && f32_frac(r) != 0;
+    if is_subnormal || env.flags().contains(ExceptionFlags::UNDERFLOW) {
+        // The flush replaces the underflow and inexact of the operation
+        env.set_flags(flags | flush_flags);
+        return f32_pack_raw(f32_sign(r), 0, 0);
+    }
+
+    env.raise(flags);
+    r
+}
+
+// Compute an operation on Float32 values and round its result to a narrower format.
+// The operation is rounded to odd, and Float32 has at least two more bits than the
+// narrower formats, so that the final rounding gives the correctly rounded result.
+pub(crate) fn f32_round_to_narrow<T, F, N>(op: F, narrow: N, env: &mut Env) -> T
+where
+    F: Fn(&mut Env) -> u32,
+    N: FnOnce(u32, &mut Env) -> T,
+{
+    // Only a Float32 result is flushed to zero, not the intermediate one
+    let mut op_env = *env;
+    op_env.set_flush_to_zero(FlushToZero::Off);
+    op_env.set_rounding_mode(RoundingMode::Odd);
+    op_env.clear_flags();
+    let mut r = op(&mut op_env);
+
+    if (r << 1) == 0 {
+        // A zero is exact, but its sign may depend on the rounding mode
+        op_env = *env;
+        op_env.clear_flags();
+        r = op(&mut op_env);
     }
-    if frac == 0 { exp = 0; }
 
-    f32_pack_raw(sign, exp, frac)
+    // Inexact and underflow are raised by the final rounding. An overflow rounded
+    // to odd gives the largest Float32, which may not overflow the narrower format.
+    env.raise(op_env.flags() & (ExceptionFlags::INVALID | ExceptionFlags::DIVIDE_BY_ZERO | ExceptionFlags::OVERFLOW));
+    narrow(r, env)
 }
 
 pub(crate) fn f32_count_leading_zero(in_frac: i32) -> i32 {
@@ -183,7 +318,7 @@ pub(crate) fn f32_count_leading_zero(in_frac: i32) -> i32 {
     ];
 
     let mut count = 0;
-    let mut frac = in_frac;
+    let mut frac = in_frac as u32;
     if frac < 0x10000 {
         count = 16;
         frac <<= 16;
@@ -195,19 +330,20 @@ pub(crate) fn f32_count_leading_zero(in_frac: i32) -> i32 {
     count + f32_count_leading_zeros_8[((frac >> 24) & 0xFF) as usize]
 }
 
-pub(crate) fn f32_norm_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i32) -> u32 {
+pub(crate) fn f32_norm_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i32, env: &mut Env) -> u32 {
     let shift_count = f32_count_leading_zero(in_frac) - 1;
-    let mut sign = in_sign;
+    let sign = in_sign;
     let mut exp = in_exp - shift_count;
-    let mut frac = in_frac;
+    let frac = in_frac;
 
-    if exp < 0xFD && shift_count >= 7 {
+    if (exp as u32) < 0xFD && shift_count >= 7 {
+        // Exact, no rounding needed
         if frac == 0 {
             exp = 0;
         }
-        return f32_pack_raw(sign, exp, frac << shift_count);
+        f32_pack_raw(sign, exp, frac << (shift_count - 7))
     } else {
-        return f32_round_and_pack(sign, exp, frac << shift_count);
+        f32_round_and_pack(sign, exp, frac << shift_count, env)
     }
 }
 
@@ -227,34 +363,30 @@ pub(crate) fn f32_short_shift_right_jam64(a: u64, count: i32) -> i32 {
     b
 }
 
+// Approximate 2^64 / a with a table lookup and a Newton-Raphson iteration,
+// where the most significant bit of a is set. The result is a little too small.
 pub(crate) fn f32_approx_recip(a: u32) -> u32 {
-    let k0s: &[u64] = &[
+    let k0s: &[u16] = &[
         0xFFC4, 0xF0BE, 0xE363, 0xD76F, 0xCCAD, 0xC2F0, 0xBA16, 0xB201,
         0xAA97, 0xA3C6, 0x9D7A, 0x97A6, 0x923C, 0x8D32, 0x887E, 0x8417,
     ];
 
-    let k1s: &[u64] = &[
+    let k1s: &[u16] = &[
         0xF0F1, 0xD62C, 0xBFA1, 0xAC77, 0x9C0A, 0x8DDB, 0x8185, 0x76BA,
         0x6D3B, 0x64D4, 0x5D5C, 0x56B1, 0x50B6, 0x4B55, 0x4679, 0x4211,
     ];
 
-    let a_u64 = a as u64;
-
     let index = ((a >> 27) & 0x0F) as usize;
+    let eps = (a >> 11) & 0x0000FFFF;   // Only use the low 16 bits
+    let r0: u16 = k0s[index] - ((k1s[index] as u32 * eps) >> 20) as u16;
+    let delta0 = !(((r0 as u64 * a as u64) >> 7) as u32);
 
-    let eps = (a >> 11) as u64;
-
-    let r0: u64 = k0s[index] - ((k1s[index] * eps) >> 20);
-    
-    let delta0: u32 = ((r0 * a_u64) >> 7) as u32;
+    let mut r: u32 = ((r0 as u32) << 16).wrapping_add(((r0 as u64 * delta0 as u64) >> 24) as u32);
 
-    let r: u64  = (r0 << 16) + ((r0 * delta0 as u64) >> 24);
+    let sqr_delta0 = ((delta0 as u64 * delta0 as u64) >> 32) & 0xFFFFFFFF;
+    r = r.wrapping_add(((r as u64 * sqr_delta0) >> 48) as u32);
 
-    let sqr_delta0 = (delta0 as u64 * delta0 as u64) >> 32;
-
-    let result = (r + (r * sqr_delta0) >> 48) as u32;
-
-    result
+    r
 }
 
 pub(crate) fn f32_approx_recip_sqrt(odd_exp: u32, a: u32) -> u32 {
@@ -270,7 +402,7 @@ pub(crate) fn f32_approx_recip_sqrt(odd_exp: u32, a: u32) -> u32 {
 
     let index = (((a >> 27) & 0x0E) + odd_exp) as usize;
     let eps = (a >> 12) & 0x0000FFFF;   // Only use the low 16 bits
-    let r0: u16 = (k0s[index] - (((k1s[index] as u64 * eps as u64) >> 20) & 0xFFFF) as u16);
+    let r0: u16 = k0s[index] - (((k1s[index] as u64 * eps as u64) >> 20) & 0xFFFF) as u16;
     let mut e_sqr_r0: u32 = r0 as u32 * r0 as u32;
 
     if odd_exp == 0 {
@@ -283,9 +415,8 @@ pub(crate) fn f32_approx_recip_sqrt(odd_exp: u32, a: u32) -> u32 {
 
     let sqr_delta0 = (((delta0 as u64) * (delta0 as u64)) >> 32) & 0xFFFFFFFF;
 
-    let r_temp_left_u64 = ((r >> 1) as u64) + ((r >> 3) as u64) - ((r0 as u64) << 14);
-    let r_temp: u64 = (r_temp_left_u64 * (sqr_delta0 as u64));
-    r += ((r_temp >> 48) & 0xFFFFFFFF) as u32;
+    let r_temp_left = (r >> 1).wrapping_add(r >> 3).wrapping_sub((r0 as u32) << 14);
+    r += ((r_temp_left as u64 * sqr_delta0) >> 48) as u32;
 
     if r & 0x80000000 == 0 {
         r = 0x80000000;
@@ -0,0 +1,327 @@
+use std::cmp::Ordering;
+use std::ops::{ Add, Sub, Neg };
+
+use super::fixed::{
+    f32_round_fixed,
+    FIXED_FRAC_BITS,
+};
+
+use crate::soft_float::Env;
+
+// Wide fixed-point arithmetic for the correctly rounded functions.
+//
+// A wide value is a 256 bits two's complement integer with 192 fraction bits, in little-endian
+// 64 bits limbs, so that it holds values below 2^63 in magnitude. Each operation truncates, with
+// an error of at most 2^-192, and the evaluations below stay within WIDE_ERROR of the exact value.
+// It is only used when the fixed-point result is too close to a rounding boundary, and the
+// Float32 arguments never give an exact value that close to a boundary without being exact.
+
+pub(crate) const WIDE_FRAC_BITS: i32 = 192;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Wide([u64; 4]);
+
+// The bound on the error of the evaluations, as a wide value: 2^-176
+pub(crate) const WIDE_ERROR: Wide = Wide([0x10000, 0, 0, 0]);
+
+// Constants are truncated
+pub(crate) const WIDE_ONE: Wide = Wide([0, 0, 0, 1]);
+pub(crate) const WIDE_LN2: Wide = Wide([
+    0x40F343267298B62D, 0xC9E3B39803F2F6AF, 0xB17217F7D1CF79AB, 0x0000000000000000,
+]);
+pub(crate) const WIDE_PI_2: Wide = Wide([
+    0x52049C1114CF98E8, 0x898CC51701B839A2, 0x921FB54442D18469, 0x0000000000000001,
+]);
+
+impl Wide {
+    // The integer n
+    pub(crate) fn from_int(n: i64) -> Wide {
+        Wide([0, 0, 0, n as u64])
+    }
+
+    // The two's complement integer of the limbs, times 2^-192
+    pub(crate) fn from_limbs(limbs: [u64; 4]) -> Wide {
+        Wide(limbs)
+    }
+
+    // The integer below
+    pub(crate) fn floor(self) -> i64 {
+        self.0[3] as i64
+    }
+
+    // The fixed-point value of fixed.rs, exactly
+    pub(crate) fn from_fixed(v: i128) -> Wide {
+        let fill = (v >> 127) as u64;
+        Wide([v as u64, (v >> 64) as u64, fill, fill]).shl((WIDE_FRAC_BITS - FIXED_FRAC_BITS) as u32)
+    }
+
+    // The fixed-point value of fixed.rs, truncated
+    pub(crate) fn to_fixed(self) -> i128 {
+        let v = self.shr((WIDE_FRAC_BITS - FIXED_FRAC_BITS) as u32);
+        (((v.0[1] as u128) << 64) | v.0[0] as u128) as i128
+    }
+
+    pub(crate) fn is_negative(self) -> bool {
+        (self.0[3] as i64) < 0
+    }
+
+    pub(crate) fn is_zero(self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    // The sign, and the magnitude as an unsigned value
+    fn sign_abs(self) -> (bool, Wide) {
+        match self.is_negative() {
+            true => (true, -self),
+            false => (false, self),
+        }
+    }
+
+    fn with_sign(self, negative: bool) -> Wide {
+        if negative { -self } else { self }
+    }
+
+    // Position of the most significant bit of an unsigned value, -1 for zero
+    fn lead_pos(self) -> i32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return 64 * i as i32 + 63 - self.0[i].leading_zeros() as i32;
+            }
+        }
+        -1
+    }
+
+    // The low n bits of an unsigned value
+    fn low_bits(self, n: u32) -> Wide {
+        let mut r = self;
+        for i in 0..4 {
+            let start = 64 * i as u32;
+            if n <= start {
+                r.0[i] = 0;
+            } else if n < start + 64 {
+                r.0[i] &= (1 << (n - start)) - 1;
+            }
+        }
+        r
+    }
+
+    // Order of two unsigned values
+    fn cmp_abs(self, other: Wide) -> Ordering {
+        for i in (0..4).rev() {
+            match self.0[i].cmp(&other.0[i]) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            }
+        }
+        Ordering::Equal
+    }
+
+    // Arithmetic shift right, truncating toward -infinity
+    pub(crate) fn shr(self, n: u32) -> Wide {
+        let fill = if self.is_negative() { u64::MAX } else { 0 };
+        let mut r = Wide([fill; 4]);
+        let words = (n / 64) as usize;
+        let bits = n % 64;
+        for i in 0..4 {
+            let lo = if i + words < 4 { self.0[i + words] } else { fill };
+            let hi = if i + words + 1 < 4 { self.0[i + words + 1] } else { fill };
+            r.0[i] = match bits {
+                0 => lo,
+                _ => (lo >> bits) | (hi << (64 - bits)),
+            };
+        }
+        r
+    }
+
+    pub(crate) fn shl(self, n: u32) -> Wide {
+        let mut r = Wide([0; 4]);
+        let words = (n / 64) as usize;
+        let bits = n % 64;
+        for i in words..4 {
+            let lo = if i > words { self.0[i - words - 1] } else { 0 };
+            r.0[i] = match bits {
+                0 => self.0[i - words],
+                _ => (self.0[i - words] << bits) | (lo >> (64 - bits)),
+            };
+        }
+        r
+    }
+
+    pub(crate) fn mul(self, other: Wide) -> Wide {
+        let (a_sign, a) = self.sign_abs();
+        let (b_sign, b) = other.sign_abs();
+
+        // 512 bits product, of which the limbs 3 to 6 hold the result
+        let mut p = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = (a.0[i] as u128) * (b.0[j] as u128) + p[i + j] as u128 + carry;
+                p[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            p[i + 4] = carry as u64;
+        }
+
+        Wide([p[3], p[4], p[5], p[6]]).with_sign(a_sign != b_sign)
+    }
+
+    pub(crate) fn mul_int(self, n: u64) -> Wide {
+        let (sign, a) = self.sign_abs();
+        let mut r = Wide([0; 4]);
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let t = (a.0[i] as u128) * (n as u128) + carry;
+            r.0[i] = t as u64;
+            carry = t >> 64;
+        }
+        r.with_sign(sign)
+    }
+
+    pub(crate) fn div_int(self, n: u64) -> Wide {
+        let (sign, a) = self.sign_abs();
+        let mut r = Wide([0; 4]);
+        let mut rem = 0u128;
+        for i in (0..4).rev() {
+            let t = (rem << 64) | a.0[i] as u128;
+            r.0[i] = (t / n as u128) as u64;
+            rem = t % n as u128;
+        }
+        r.with_sign(sign)
+    }
+
+    // Long division of the magnitudes, one bit at a time, for a quotient below 2^63
+    pub(crate) fn div(self, other: Wide) -> Wide {
+        let (a_sign, a) = self.sign_abs();
+        let (b_sign, b) = other.sign_abs();
+
+        let mut q = Wide([0; 4]);
+        let mut rem = Wide([0; 4]);
+        for pos in (0..256 + WIDE_FRAC_BITS as u32).rev() {
+            let bit = match pos >= WIDE_FRAC_BITS as u32 {
+                true => (a.0[((pos - WIDE_FRAC_BITS as u32) / 64) as usize] >> (pos % 64)) & 1,
+                false => 0,
+            };
+            rem = rem.shl(1);
+            rem.0[0] |= bit;
+            if rem.cmp_abs(b) != Ordering::Less {
+                rem = rem - b;
+                if pos < 256 {
+                    q.0[(pos / 64) as usize] |= 1 << (pos % 64);
+                }
+            }
+        }
+
+        q.with_sign(a_sign != b_sign)
+    }
+}
+
+impl Add for Wide {
+    type Output = Wide;
+
+    fn add(self, other: Wide) -> Wide {
+        let mut r = Wide([0; 4]);
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let t = self.0[i] as u128 + other.0[i] as u128 + carry;
+            r.0[i] = t as u64;
+            carry = t >> 64;
+        }
+        r
+    }
+}
+
+impl Neg for Wide {
+    type Output = Wide;
+
+    fn neg(self) -> Wide {
+        Wide([!self.0[0], !self.0[1], !self.0[2], !self.0[3]]) + Wide([1, 0, 0, 0])
+    }
+}
+
+impl Sub for Wide {
+    type Output = Wide;
+
+    fn sub(self, other: Wide) -> Wide {
+        self + -other
+    }
+}
+
+// e^r for |r| <= 0.35, with the Taylor series. The terms fall below 2^-192 after the 45th.
+pub(crate) fn wide_exp(r: Wide) -> Wide {
+    let mut sum = WIDE_ONE;
+    let mut term = WIDE_ONE;
+    for k in 1..=50 {
+        term = term.mul(r).div_int(k);
+        if term.is_zero() {
+            break;
+        }
+        sum = sum + term;
+    }
+    sum
+}
+
+// ln(m) for m in [1/sqrt(2), sqrt(2)], with ln(m) = 2 x (t + t^3 / 3 + t^5 / 5 + ...) where
+// t = (m - 1) / (m + 1). |t| <= 0.172, so that the terms fall below 2^-192 after the 38th.
+pub(crate) fn wide_ln(m: Wide) -> Wide {
+    let t = (m - WIDE_ONE).div(m + WIDE_ONE);
+    let t2 = t.mul(t);
+
+    let mut sum = t;
+    let mut power = t;
+    for n in (3..=91).step_by(2) {
+        power = power.mul(t2);
+        if power.is_zero() {
+            break;
+        }
+        sum = sum + power.div_int(n);
+    }
+    sum.shl(1)
+}
+
+// sin(r) and cos(r) for |r| <= pi / 4, with the Taylor series
+pub(crate) fn wide_sin_cos(r: Wide) -> (Wide, Wide) {
+    let r2 = r.mul(r);
+
+    let mut sin_sum = r;
+    let mut cos_sum = WIDE_ONE;
+    let mut term = WIDE_ONE;
+    for n in 1..=40 {
+        term = term.mul(r2).div_int(2 * n - 1);
+        cos_sum = cos_sum - term.div_int(2 * n);
+        term = -term.div_int(2 * n);
+        sin_sum = sin_sum + term.mul(r).div_int(2 * n + 1);
+        if term.is_zero() {
+            break;
+        }
+    }
+    (sin_sum, cos_sum)
+}
+
+// Whether the value v, within WIDE_ERROR of the exact value, is too close to a rounding
+// boundary to decide the rounding of the exact value to a 24 bits significand
+pub(crate) fn wide_is_ambiguous(v: Wide) -> bool {
+    let (_, mag) = v.sign_abs();
+    let lead_pos = mag.lead_pos();
+    if lead_pos - 24 <= WIDE_ERROR.lead_pos() + 1 {
+        return true;
+    }
+
+    // The boundaries are the multiples of the half ULP of the significand
+    let grid = (lead_pos - 24) as u32;
+    let low = (mag + WIDE_ERROR).low_bits(grid);
+    low.cmp_abs(WIDE_ERROR.shl(1)) != Ordering::Greater
+}
+
+// Round the value v x 2^scale to Float32, keeping the leading 126 bits of v and a sticky bit.
+// The value is never exact.
+pub(crate) fn wide_round(v: Wide, scale: i32, env: &mut Env) -> u32 {
+    let (sign, mag) = v.sign_abs();
+    let shift = (mag.lead_pos() - 125).max(0) as u32;
+    let top = mag.shr(shift);
+    let top = ((top.0[1] as u128) << 64) | top.0[0] as u128;
+    let sticky = !mag.low_bits(shift).is_zero() as u128;
+    let top = (top | sticky) as i128;
+
+    f32_round_fixed(if sign { -top } else { top }, scale + shift as i32 - WIDE_FRAC_BITS, false, env)
+}
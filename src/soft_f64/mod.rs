@@ -0,0 +1,251 @@
+mod soft_f64_add;
+mod soft_f64_sub;
+mod soft_f64_mul;
+mod soft_f64_div;
+mod soft_f64_comp;
+mod soft_f64_sqrt;
+mod soft_f64_round;
+pub(crate) mod util;
+
+// The procedural API works on raw u64 bit patterns. Each function uses the
+// environment of the current thread, and has a `_with_env` variant which
+// takes an explicit environment.
+
+// Operations
+pub use soft_f64_add::{ f64_add, f64_add_with_env };
+pub use soft_f64_sub::{ f64_sub, f64_sub_with_env };
+pub use soft_f64_mul::{ f64_mul, f64_mul_with_env };
+pub use soft_f64_div::{ f64_div, f64_div_with_env };
+
+// Comparisons
+pub use soft_f64_comp::{ f64_eq, f64_eq_with_env };
+pub use soft_f64_comp::{ f64_ne, f64_ne_with_env };
+pub use soft_f64_comp::{ f64_lt, f64_lt_with_env };
+pub use soft_f64_comp::{ f64_gt, f64_gt_with_env };
+pub use soft_f64_comp::{ f64_le, f64_le_with_env };
+pub use soft_f64_comp::{ f64_ge, f64_ge_with_env };
+
+// Others
+pub use soft_f64_sqrt::{ f64_sqrt, f64_sqrt_with_env };
+pub use soft_f64_round::{ f64_round, f64_round_with_env };
+
+// Conversions
+pub use soft_f64_round::{ from_int32, from_int32_with_env };
+pub use soft_f64_round::{ to_int32, to_int32_with_env };
+
+// Utilities
+pub use util::{
+    f64_is_nan,
+    f64_is_signaling_nan,
+};
+
+// F64 struct
+#[derive(Clone, Copy, Debug)]
+pub struct F64 {
+    value: u64,
+}
+
+impl F64 {
+    pub const fn from_bits(bits: u64) -> F64 {
+        F64 {
+            value: bits
+        }
+    }
+
+    pub fn to_bits(self) -> u64 {
+        self.value
+    }
+
+    #[deprecated(note = "use from_bits, as F32::from_bits")]
+    pub const fn from_u64(value: u64) -> F64 {
+        F64::from_bits(value)
+    }
+
+    // The bit pattern, as to_bits
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn sqrt(self) -> Self {
+        F64 {
+            value: f64_sqrt(self.value)
+        }
+    }
+}
+
+use std::ops;
+
+impl ops::Add<F64> for F64 {
+    type Output = F64;
+
+    fn add(self, other: F64) -> F64 {
+        F64 {
+            value: f64_add(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Sub<F64> for F64 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        F64 {
+            value: f64_sub(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Mul<F64> for F64 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        F64 {
+            value: f64_mul(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Div<F64> for F64 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        F64 {
+            value: f64_div(self.value, other.value)
+        }
+    }
+}
+
+use std::cmp;
+
+impl cmp::PartialEq for F64 {
+    // Implement equal with only symmetric and transitive for F64.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
+    // For example, in floating point numbers NaN != NaN,
+    // so floating point types implement PartialEq but not Eq.
+    fn eq(&self, other: &Self) -> bool {
+        f64_eq(self.value, other.value)
+    }
+
+    #[allow(clippy::partialeq_ne_impl)]
+    fn ne(&self, other: &Self) -> bool {
+        f64_ne(self.value, other.value)
+    }
+}
+
+impl cmp::PartialOrd for F64 {
+    // Implement compare with only symmetric and transitive for F64.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if f64_eq(self.value, other.value) { return Some(cmp::Ordering::Equal); }
+        if f64_lt(self.value, other.value) { return Some(cmp::Ordering::Less); }
+        if f64_gt(self.value, other.value) { return Some(cmp::Ordering::Greater); }
+
+        None
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        f64_gt(self.value, other.value)
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        f64_lt(self.value, other.value)
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        f64_ge(self.value, other.value)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        f64_le(self.value, other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_f64_add_with_struct() {
+        let v0_1 = crate::soft_f64::F64::from_bits(0x3FB999999999999A);
+        let v0_2 = crate::soft_f64::F64::from_bits(0x3FC999999999999A);
+
+        let v0_3 = v0_1 + v0_2;
+
+        assert_eq!(v0_3.value(), 0x3FD3333333333334);
+    }
+
+    #[test]
+    fn test_f64_sub_with_struct() {
+        let v0_1 = crate::soft_f64::F64::from_bits(0x3FB999999999999A);
+        let v0_2 = crate::soft_f64::F64::from_bits(0x3FC999999999999A);
+
+        let v0_1_result = v0_2 - v0_1;
+
+        assert_eq!(v0_1_result.value(), 0x3FB999999999999A);
+    }
+
+    #[test]
+    fn test_f64_mul_with_struct() {
+        let v0_1 = crate::soft_f64::F64::from_bits(0x3FB999999999999A);
+        let v0_2 = crate::soft_f64::F64::from_bits(0x3FC999999999999A);
+
+        let v0_02 = v0_2 * v0_1;
+
+        assert_eq!(v0_02.value(), 0x3F947AE147AE147C);
+    }
+
+    #[test]
+    fn test_f64_div_with_struct() {
+        let v0_1 = crate::soft_f64::F64::from_bits(0x3FB999999999999A);
+        let v0_2 = crate::soft_f64::F64::from_bits(0x3FC999999999999A);
+
+        let v0_5 = v0_1 / v0_2;
+
+        assert_eq!(v0_5.value(), 0x3FE0000000000000);
+    }
+
+    #[test]
+    fn test_f64_compare_with_struct() {
+        let v0_1 = crate::soft_f64::F64::from_bits(0x3FB999999999999A);
+        let v0_2 = crate::soft_f64::F64::from_bits(0x3FC999999999999A);
+
+        assert_eq!(v0_1 == v0_2, false);
+        assert_eq!(v0_1 != v0_2, true);
+
+        assert_eq!(v0_1 < v0_2, true);
+        assert_eq!(v0_1 <= v0_1, true);
+        assert_eq!(v0_1 > v0_2, false);
+        assert_eq!(v0_1 >= v0_1, true);
+    }
+
+    #[test]
+    fn test_f64_rounding_mode_with_struct() {
+        let v0_1 = crate::soft_f64::F64::from_bits(0x3FB999999999999A);
+        let v0_2 = crate::soft_f64::F64::from_bits(0x3FC999999999999A);
+
+        // Operators use the rounding mode of the current thread
+        crate::soft_float::set_rounding_mode(crate::soft_float::RoundingMode::MinMag);
+        let v0_3 = v0_1 + v0_2;
+        crate::soft_float::set_rounding_mode(crate::soft_float::RoundingMode::NearEven);
+
+        assert_eq!(v0_3.value(), 0x3FD3333333333333);
+    }
+
+    #[test]
+    fn test_f64_sqrt_with_struct() {
+        // sqrt(0.01) = 0.1
+        let v0_01 = crate::soft_f64::F64::from_bits(0x3F847AE147AE147B);
+
+        let v0_1 = v0_01.sqrt();
+
+        assert_eq!(v0_1.value(), 0x3FB999999999999A);
+    }
+
+    #[test]
+    fn test_f64_bits_with_struct() {
+        assert_eq!(crate::soft_f64::F64::from_bits(0x3FB999999999999A).to_bits(), 0x3FB999999999999A);
+        #[allow(deprecated)]
+        let v = crate::soft_f64::F64::from_u64(0x3FB999999999999A);
+        assert_eq!(v.to_bits(), 0x3FB999999999999A);
+    }
+}
@@ -0,0 +1,273 @@
+use super::util::{
+    f64_shift_right_jam,
+    f64_round_and_pack,
+    f64_pack_raw,
+    f64_propagate_nan,
+    f64_sign, f64_exp, f64_frac,
+};
+
+use super::soft_f64_sub::f64_sub_mags;
+
+use crate::soft_float::{ Env, with_thread_env };
+
+pub fn f64_add(a: u64, b: u64) -> u64 {
+    with_thread_env(|env| f64_add_with_env(a, b, env))
+}
+
+pub fn f64_add_with_env(a: u64, b: u64, env: &mut Env) -> u64 {
+    if f64_sign(a) != f64_sign(b) {
+        // Consider as substraction
+        return f64_sub_mags(a, b, env);
+    }
+
+    f64_add_mags(a, b, env)
+}
+
+// Add the magnitudes of A and B, which have the same sign
+pub(crate) fn f64_add_mags(a: u64, b: u64, env: &mut Env) -> u64 {
+    // Sign
+    let a_sign = f64_sign(a);
+    let r_sign = a_sign;
+
+    // Exp
+    let a_exp = f64_exp(a);
+    let b_exp = f64_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f64_frac(a);
+    let mut b_frac = f64_frac(b);
+    let mut r_frac;
+
+    let diff_exp = a_exp - b_exp;
+
+    if diff_exp == 0 {
+        if a_exp == 0 {
+            // Subnormal, a carry makes a normal number
+            return f64_pack_raw(r_sign, 0, a_frac + b_frac);
+        }
+
+        if a_exp == 0x7FF {
+            if (a_frac | b_frac) != 0 {
+                // Propagate NaN
+                return f64_propagate_nan(a, b, env);
+            } else {
+                // Infinity
+                return a;
+            }
+        }
+
+        r_exp = a_exp;
+        r_frac = (0x0020000000000000 + a_frac + b_frac) << 9;
+    } else {
+        // Prepare fractions to do calculation
+        a_frac <<= 9;
+        b_frac <<= 9;
+
+        if diff_exp < 0 {
+            // a_exp < b_exp
+            if b_exp == 0x7FF {
+                if b_frac != 0 {
+                    // Propagate NaN
+                    return f64_propagate_nan(a, b, env);
+                } else {
+                    // Infinity
+                    return f64_pack_raw(r_sign, 0x7FF, 0);
+                }
+            }
+
+            r_exp = b_exp;
+
+            // Prepare the Fraction of A to do an addition with B
+            if a_exp != 0 {
+                a_frac += 0x2000000000000000;
+            } else {
+                a_frac += a_frac;
+            }
+            a_frac = f64_shift_right_jam(a_frac, -diff_exp);
+        } else {
+            // a_exp > b_exp, a_exp == b_exp is considered in the other case
+            if a_exp == 0x7FF {
+                if a_frac != 0 {
+                    // Propagate NaN
+                    return f64_propagate_nan(a, b, env);
+                } else {
+                    // Infinity
+                    return a;
+                }
+            }
+
+            r_exp = a_exp;
+
+            // Prepare the Fraction of B to do an addition with A
+            if b_exp != 0 {
+                b_frac += 0x2000000000000000;
+            } else {
+                b_frac += b_frac;
+            }
+            b_frac = f64_shift_right_jam(b_frac, diff_exp);
+        }
+
+        r_frac = 0x2000000000000000 + a_frac + b_frac;
+
+        if r_frac < 0x4000000000000000 {
+            r_exp -= 1;
+            r_frac <<= 1;
+        }
+    }
+
+    f64_round_and_pack(r_sign, r_exp, r_frac, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f64_add() {
+        // 0.1 + 0.2 = 0.30000000000000004
+        assert_eq!(crate::soft_f64::f64_add(0x3FB999999999999A, 0x3FC999999999999A), 0x3FD3333333333334);
+        // -0.1 + -0.2 = -0.30000000000000004
+        assert_eq!(crate::soft_f64::f64_add(0xBFB999999999999A, 0xBFC999999999999A), 0xBFD3333333333334);
+
+        // 12345 + 67890 = 80235
+        assert_eq!(crate::soft_f64::f64_add(0x40C81C8000000000, 0x40F0932000000000), 0x40F396B000000000);
+
+        // -0.1 + 0.2 = 0.1
+        assert_eq!(crate::soft_f64::f64_add(0xBFB999999999999A, 0x3FC999999999999A), 0x3FB999999999999A);
+
+        // 1.98 + -2 = -0.02
+        assert_eq!(crate::soft_f64::f64_add(0x3FFFAE147AE147AE, 0xC000000000000000), 0xBF947AE147AE1480);
+        // 2.02 + -2 = 0.02
+        assert_eq!(crate::soft_f64::f64_add(0x400028F5C28F5C29, 0xC000000000000000), 0x3F947AE147AE1480);
+    }
+
+    #[test]
+    fn test_f64_add_inf_nan() {
+        // Inf + 1 = Inf
+        assert_eq!(crate::soft_f64::f64_add(0x7FF0000000000000, 0x3FF0000000000000), 0x7FF0000000000000);
+        // -Inf + 1 = -Inf
+        assert_eq!(crate::soft_f64::f64_add(0xFFF0000000000000, 0x3FF0000000000000), 0xFFF0000000000000);
+        // -Inf + Inf = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_add(0xFFF0000000000000, 0x7FF0000000000000)), true);
+        // NaN + 1 = NaN
+        assert_eq!(crate::soft_f64::f64_add(0xFFFFFFFFFFFFFFFF, 0x3FF0000000000000), 0xFFFFFFFFFFFFFFFF);
+        // NaN + Inf = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_add(0x7FF8000000000000, 0x7FF0000000000000)), true);
+
+        assert_eq!(crate::soft_f64::f64_add(0x0, 0xBFB999999999999A), 0xBFB999999999999A);
+        assert_eq!(crate::soft_f64::f64_add(0x0, 0x8000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f64_add_rounding_mode() {
+        // 0.1 + 0.2
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x3FD3333333333334);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::MinMag)), 0x3FD3333333333333);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Min)), 0x3FD3333333333333);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Max)), 0x3FD3333333333334);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FD3333333333334);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Odd)), 0x3FD3333333333333);
+
+        // 1 + 2^-53, a tie
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x3CA0000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x3CA0000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x3CA0000000000000, &mut Env::new(RoundingMode::Min)), 0x3FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x3CA0000000000000, &mut Env::new(RoundingMode::Max)), 0x3FF0000000000001);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x3CA0000000000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FF0000000000001);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x3CA0000000000000, &mut Env::new(RoundingMode::Odd)), 0x3FF0000000000001);
+
+        // MAX + MAX, overflow
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x7FEFFFFFFFFFFFFF, 0x7FEFFFFFFFFFFFFF, &mut Env::new(RoundingMode::NearEven)), 0x7FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x7FEFFFFFFFFFFFFF, 0x7FEFFFFFFFFFFFFF, &mut Env::new(RoundingMode::MinMag)), 0x7FEFFFFFFFFFFFFF);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0xFFEFFFFFFFFFFFFF, 0xFFEFFFFFFFFFFFFF, &mut Env::new(RoundingMode::Min)), 0xFFF0000000000000);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0xFFEFFFFFFFFFFFFF, 0xFFEFFFFFFFFFFFFF, &mut Env::new(RoundingMode::Max)), 0xFFEFFFFFFFFFFFFF);
+
+        // 0.1 + -0.1
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0xBFB999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x0000000000000000);
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0xBFB999999999999A, &mut Env::new(RoundingMode::Min)), 0x8000000000000000);
+    }
+
+    #[test]
+    fn test_f64_add_exception_flags() {
+        // 0.1 + 0.2, inexact
+        let mut env = Env::default();
+        crate::soft_f64::f64_add_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // 1 + 2 = 3, exact
+        let mut env = Env::default();
+        crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x4000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // MAX + MAX, overflow
+        let mut env = Env::default();
+        crate::soft_f64::f64_add_with_env(0x7FEFFFFFFFFFFFFF, 0x7FEFFFFFFFFFFFFF, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // Inf + -Inf, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x7FF0000000000000, 0xFFF0000000000000, &mut env), 0xFFF8000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // sNaN + 1, invalid, the NaN is quieted
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x7FF0000000000001, 0x3FF0000000000000, &mut env), 0x7FF8000000000001);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f64_add_subnormal() {
+        // MAX_SUBNORMAL + MAX_SUBNORMAL, a carry makes a normal number
+        assert_eq!(crate::soft_f64::f64_add(0x000FFFFFFFFFFFFF, 0x000FFFFFFFFFFFFF), 0x001FFFFFFFFFFFFE);
+        // MIN_SUBNORMAL + MIN_SUBNORMAL = 2 * MIN_SUBNORMAL
+        assert_eq!(crate::soft_f64::f64_add(0x0000000000000001, 0x0000000000000001), 0x0000000000000002);
+        // MIN_POSITIVE + -MAX_SUBNORMAL = MIN_SUBNORMAL
+        assert_eq!(crate::soft_f64::f64_add(0x0010000000000000, 0x800FFFFFFFFFFFFF), 0x0000000000000001);
+    }
+
+    #[test]
+    fn test_f64_add_nan_policy() {
+        // (qNaN A, qNaN B with a larger payload, sNaN, 1 + sNaN, sNaN + qNaN B, inf - inf) in each policy
+        let cases = [
+            (NanPolicy::X87, 0xFFF8000000000002, 0x7FF8000000000003, 0xFFF8000000000000, 0x7FF8000000000002),
+            (NanPolicy::X86Sse, 0x7FF8000000000001, 0x7FF8000000000003, 0xFFF8000000000000, 0x7FF8000000000003),
+            (NanPolicy::Armv8, 0x7FF8000000000001, 0x7FF8000000000003, 0x7FF8000000000000, 0x7FF8000000000003),
+            (NanPolicy::Armv8DefaultNan, 0x7FF8000000000000, 0x7FF8000000000000, 0x7FF8000000000000, 0x7FF8000000000000),
+            (NanPolicy::RiscV, 0x7FF8000000000000, 0x7FF8000000000000, 0x7FF8000000000000, 0x7FF8000000000000),
+            (NanPolicy::Wasm, 0x7FF8000000000000, 0x7FF8000000000000, 0x7FF8000000000000, 0x7FF8000000000000),
+        ];
+        for &(nan_policy, qnan_qnan, one_snan, inf_inf, snan_qnan) in cases.iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+
+            assert_eq!(crate::soft_f64::f64_add_with_env(0x7FF8000000000001, 0xFFF8000000000002, &mut env), qnan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+
+            assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0x7FF0000000000003, &mut env), one_snan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f64::f64_add_with_env(0x7FF0000000000000, 0xFFF0000000000000, &mut env), inf_inf);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f64::f64_add_with_env(0x7FF0000000000003, 0x7FF8000000000002, &mut env), snan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+    }
+
+    #[test]
+    fn test_f64_add_nan_policy_mips_legacy() {
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+
+        // The quiet bit is clear in a quiet NaN, which propagates unchanged
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x3FF0000000000000, 0xFFF0000000000002, &mut env), 0xFFF0000000000002);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // A signaling NaN gives the default NaN
+        assert_eq!(crate::soft_f64::f64_add_with_env(0x7FF0000000000001, 0x7FF8000000000000, &mut env), 0x7FF7FFFFFFFFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
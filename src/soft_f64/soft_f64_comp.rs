@@ -0,0 +1,206 @@
+use super::util::{
+    f64_sign,
+    f64_is_nan,
+    f64_is_signaling_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Equality comparisons are quiet, only a signaling NaN is invalid
+fn f64_is_unordered_quiet(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_nan(a) || f64_is_nan(b) {
+        if f64_is_signaling_nan(a) || f64_is_signaling_nan(b) {
+            env.raise(ExceptionFlags::INVALID);
+        }
+        return true;
+    }
+    false
+}
+
+// Relational comparisons signal, any NaN is invalid
+fn f64_is_unordered_signaling(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_nan(a) || f64_is_nan(b) {
+        env.raise(ExceptionFlags::INVALID);
+        return true;
+    }
+    false
+}
+
+pub fn f64_ne(a: u64, b: u64) -> bool {
+    with_thread_env(|env| f64_ne_with_env(a, b, env))
+}
+
+pub fn f64_ne_with_env(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_unordered_quiet(a, b, env) {
+        // Unable to compare, so they are not equal
+        return true;
+    }
+
+    !f64_eq_ordered(a, b)
+}
+
+pub fn f64_eq(a: u64, b: u64) -> bool {
+    with_thread_env(|env| f64_eq_with_env(a, b, env))
+}
+
+pub fn f64_eq_with_env(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_unordered_quiet(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f64_eq_ordered(a, b)
+}
+
+fn f64_eq_ordered(a: u64, b: u64) -> bool {
+    a == b || ((a | b) << 1) == 0
+}
+
+pub fn f64_ge(a: u64, b: u64) -> bool {
+    with_thread_env(|env| f64_ge_with_env(a, b, env))
+}
+
+pub fn f64_ge_with_env(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    !f64_lt_ordered(a, b)
+}
+
+pub fn f64_lt(a: u64, b: u64) -> bool {
+    with_thread_env(|env| f64_lt_with_env(a, b, env))
+}
+
+pub fn f64_lt_with_env(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f64_lt_ordered(a, b)
+}
+
+fn f64_lt_ordered(a: u64, b: u64) -> bool {
+    // Sign
+    let a_sign = f64_sign(a);
+    let b_sign = f64_sign(b);
+
+    if a_sign != b_sign {
+        // Different sign, but +0 and -0 are equal
+        if (a | b) << 1 != 0 {
+            return a_sign == 1;
+        }
+    } else {
+        // Same sign
+        if a != b {
+            if a_sign != 0 {
+                return a > b;
+            } else {
+                return a < b;
+            }
+        }
+    }
+    false
+}
+
+pub fn f64_gt(a: u64, b: u64) -> bool {
+    with_thread_env(|env| f64_gt_with_env(a, b, env))
+}
+
+pub fn f64_gt_with_env(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    !f64_le_ordered(a, b)
+}
+
+pub fn f64_le(a: u64, b: u64) -> bool {
+    with_thread_env(|env| f64_le_with_env(a, b, env))
+}
+
+pub fn f64_le_with_env(a: u64, b: u64, env: &mut Env) -> bool {
+    if f64_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f64_le_ordered(a, b)
+}
+
+fn f64_le_ordered(a: u64, b: u64) -> bool {
+    // Sign
+    let a_sign = f64_sign(a);
+    let b_sign = f64_sign(b);
+
+    if a_sign != b_sign {
+        // Different sign, +0 and -0 are equal
+        if (a | b) << 1 == 0 || a_sign == 1 {
+            return true;
+        }
+    } else {
+        // Same sign
+        if a == b {
+            return true;
+        }
+
+        if a_sign != 0 {
+            return a > b;
+        } else {
+            return a < b;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f64_compare() {
+        // 0.1 and 0.2
+        assert_eq!(crate::soft_f64::f64_eq(0x3FB999999999999A, 0x3FC999999999999A), false);
+        assert_eq!(crate::soft_f64::f64_ne(0x3FB999999999999A, 0x3FC999999999999A), true);
+        assert_eq!(crate::soft_f64::f64_lt(0x3FB999999999999A, 0x3FC999999999999A), true);
+        assert_eq!(crate::soft_f64::f64_le(0x3FB999999999999A, 0x3FC999999999999A), true);
+        assert_eq!(crate::soft_f64::f64_gt(0x3FB999999999999A, 0x3FC999999999999A), false);
+        assert_eq!(crate::soft_f64::f64_ge(0x3FB999999999999A, 0x3FC999999999999A), false);
+
+        // -0.1 and -0.2
+        assert_eq!(crate::soft_f64::f64_lt(0xBFB999999999999A, 0xBFC999999999999A), false);
+        assert_eq!(crate::soft_f64::f64_gt(0xBFB999999999999A, 0xBFC999999999999A), true);
+
+        // 0 and -0
+        assert_eq!(crate::soft_f64::f64_eq(0x0000000000000000, 0x8000000000000000), true);
+        assert_eq!(crate::soft_f64::f64_lt(0x8000000000000000, 0x0000000000000000), false);
+        assert_eq!(crate::soft_f64::f64_le(0x8000000000000000, 0x0000000000000000), true);
+
+        // NaN is unordered
+        assert_eq!(crate::soft_f64::f64_eq(0x7FF8000000000000, 0x7FF8000000000000), false);
+        assert_eq!(crate::soft_f64::f64_ne(0x7FF8000000000000, 0x7FF8000000000000), true);
+        assert_eq!(crate::soft_f64::f64_lt(0x7FF8000000000000, 0x3FF0000000000000), false);
+        assert_eq!(crate::soft_f64::f64_ge(0x7FF8000000000000, 0x3FF0000000000000), false);
+    }
+
+    #[test]
+    fn test_f64_compare_exception_flags() {
+        // Equality with a quiet NaN is quiet
+        let mut env = Env::default();
+        crate::soft_f64::f64_eq_with_env(0x7FF8000000000000, 0x3FF0000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Equality with a signaling NaN is invalid
+        let mut env = Env::default();
+        crate::soft_f64::f64_ne_with_env(0x7FF0000000000001, 0x3FF0000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Relations with any NaN are invalid
+        let mut env = Env::default();
+        crate::soft_f64::f64_lt_with_env(0x7FF8000000000000, 0x3FF0000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,215 @@
+use super::util::{
+    f64_round_and_pack,
+    f64_pack_raw,
+    f64_propagate_nan,
+    f64_sign, f64_exp, f64_frac,
+    f64_norm_subnormal_frac,
+    f64_approx_recip,
+    f64_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f64_div(a: u64, b: u64) -> u64 {
+    with_thread_env(|env| f64_div_with_env(a, b, env))
+}
+
+pub fn f64_div_with_env(a: u64, b: u64, env: &mut Env) -> u64 {
+    // Sign
+    let a_sign = f64_sign(a);
+    let b_sign = f64_sign(b);
+    let r_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f64_exp(a);
+    let mut b_exp = f64_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f64_frac(a);
+    let mut b_frac = f64_frac(b);
+
+    if a_exp == 0x7FF {
+        if a_frac != 0 {
+            return f64_propagate_nan(a, b, env);
+        }
+        if b_exp == 0x7FF {
+            if b_frac != 0 {
+                return f64_propagate_nan(a, b, env);
+            } else {
+                // Inf / Inf, invalid, return default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f64_default_nan(env);
+            }
+        }
+        // INFINITY
+        return f64_pack_raw(r_sign, 0x7FF, 0);
+    }
+    if b_exp == 0x7FF {
+        if b_frac != 0 {
+            return f64_propagate_nan(a, b, env);
+        }
+        return f64_pack_raw(r_sign, 0, 0);
+    }
+
+    if b_exp == 0 {
+        if b_frac == 0 {
+            if (a_exp as i64 | a_frac) == 0 {
+                // 0 / 0, invalid, return default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f64_default_nan(env);
+            }
+            // Infinity
+            env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+            return f64_pack_raw(r_sign, 0x7FF, 0);
+        }
+
+        let (exp, frac) = f64_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+    if a_exp == 0 {
+        if a_frac == 0 {
+            // Zero
+            return f64_pack_raw(r_sign, 0, 0);
+        }
+
+        let (exp, frac) = f64_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+
+    r_exp = a_exp - b_exp + 0x3FE;
+    a_frac |= 0x0010000000000000;
+    b_frac |= 0x0010000000000000;
+
+    let mut a_frac_u64 = a_frac as u64;
+    if a_frac < b_frac {
+        r_exp -= 1;
+        a_frac_u64 <<= 11;
+    } else {
+        a_frac_u64 <<= 10;
+    }
+    let b_frac_u64 = (b_frac as u64) << 11;
+    let b_frac_hi = b_frac_u64 >> 32;
+    let b_frac_lo = (b_frac_u64 & 0xFFFFFFFF) >> 4;
+
+    // Estimate the high 32 bits of the quotient with the reciprocal of B,
+    // which is a little too small, so the quotient never gets too large
+    let recip = f64_approx_recip(b_frac_u64) - 2;
+    let r_frac_hi = ((a_frac_u64 >> 32) * recip as u64) >> 32;
+
+    // Estimate the low bits with the remainder
+    let mut double_term = r_frac_hi << 1;
+    let mut rem = (a_frac_u64.wrapping_sub(double_term * b_frac_hi) << 28)
+        .wrapping_sub(double_term * b_frac_lo);
+    let mut q = (((rem >> 32) * recip as u64) >> 32) + 4;
+    let mut r_frac = (r_frac_hi << 32) + (q << 4);
+
+    if (r_frac & 0x1FF) < (4 << 4) {
+        // Close to a rounding boundary, compute the exact remainder
+        q &= !7;
+        r_frac &= !0x7F;
+        double_term = q << 1;
+        rem = (rem.wrapping_sub(double_term * b_frac_hi) << 28)
+            .wrapping_sub(double_term * b_frac_lo);
+
+        if rem & 0x8000000000000000 != 0 {
+            r_frac -= 1 << 7;
+        } else {
+            if rem != 0 {
+                r_frac |= 0x01;
+            }
+        }
+    }
+
+    f64_round_and_pack(r_sign, r_exp, r_frac as i64, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f64_div() {
+        // 0.1 / 0.2 = 0.5
+        assert_eq!(crate::soft_f64::f64_div(0x3FB999999999999A, 0x3FC999999999999A), 0x3FE0000000000000);
+        // 1 / 3 = 0.3333333333333333
+        assert_eq!(crate::soft_f64::f64_div(0x3FF0000000000000, 0x4008000000000000), 0x3FD5555555555555);
+        // 2 / -3 = -0.6666666666666666
+        assert_eq!(crate::soft_f64::f64_div(0x4000000000000000, 0xC008000000000000), 0xBFE5555555555555);
+        // 80235 / 16.25 = 4937.538461538462
+        assert_eq!(crate::soft_f64::f64_div(0x40F396B000000000, 0x4030400000000000), 0x40B34989D89D89D9);
+    }
+
+    #[test]
+    fn test_f64_div_inf_nan() {
+        // Inf / Inf = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_div(0x7FF0000000000000, 0xFFF0000000000000)), true);
+        // 0 / 0 = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_div(0x0000000000000000, 0x0000000000000000)), true);
+        // -1 / 0 = -Inf
+        assert_eq!(crate::soft_f64::f64_div(0xBFF0000000000000, 0x0000000000000000), 0xFFF0000000000000);
+        // 1 / -Inf = -0
+        assert_eq!(crate::soft_f64::f64_div(0x3FF0000000000000, 0xFFF0000000000000), 0x8000000000000000);
+    }
+
+    #[test]
+    fn test_f64_div_rounding_mode() {
+        // 1 / 3
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FD5555555555555);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FD5555555555555);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::Min)), 0x3FD5555555555555);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::Max)), 0x3FD5555555555556);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FD5555555555555);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::Odd)), 0x3FD5555555555555);
+
+        // -1 / 3
+        assert_eq!(crate::soft_f64::f64_div_with_env(0xBFF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::Min)), 0xBFD5555555555556);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0xBFF0000000000000, 0x4008000000000000, &mut Env::new(RoundingMode::Max)), 0xBFD5555555555555);
+    }
+
+    #[test]
+    fn test_f64_div_exception_flags() {
+        // 1 / 3, inexact
+        let mut env = Env::default();
+        crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4008000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // 1 / 4, exact
+        let mut env = Env::default();
+        crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x4010000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // 1 / 0, divide by zero
+        let mut env = Env::default();
+        crate::soft_f64::f64_div_with_env(0x3FF0000000000000, 0x0000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // 0 / 0, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x0000000000000000, 0x0000000000000000, &mut env), 0xFFF8000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f64_div_subnormal() {
+        // MIN_POSITIVE / 3, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x0010000000000000, 0x4008000000000000, &mut env), 0x0005555555555555);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // MIN_SUBNORMAL / 2^-52 = MIN_POSITIVE
+        assert_eq!(crate::soft_f64::f64_div(0x0000000000000001, 0x3CB0000000000000), 0x0010000000000000);
+    }
+
+    #[test]
+    fn test_f64_div_nan_policy() {
+        // 0 / 0 gives the default NaN of the policy
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x0000000000000000, 0x0000000000000000, &mut env), 0xFFF8000000000000);
+        env.set_nan_policy(NanPolicy::RiscV);
+        assert_eq!(crate::soft_f64::f64_div_with_env(0x0000000000000000, 0x0000000000000000, &mut env), 0x7FF8000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
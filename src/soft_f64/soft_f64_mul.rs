@@ -0,0 +1,198 @@
+use super::util::{
+    f64_round_and_pack,
+    f64_pack_raw,
+    f64_propagate_nan,
+    f64_sign, f64_exp, f64_frac,
+    f64_norm_subnormal_frac,
+    f64_short_shift_right_jam128,
+    f64_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f64_mul(a: u64, b: u64) -> u64 {
+    with_thread_env(|env| f64_mul_with_env(a, b, env))
+}
+
+pub fn f64_mul_with_env(a: u64, b: u64, env: &mut Env) -> u64 {
+    // Sign
+    let a_sign = f64_sign(a);
+    let b_sign = f64_sign(b);
+    let r_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f64_exp(a);
+    let mut b_exp = f64_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f64_frac(a);
+    let mut b_frac = f64_frac(b);
+    let mut r_frac;
+
+    if a_exp == 0x7FF {
+        // NaN
+        if a_frac != 0 || (b_exp == 0x7FF && b_frac != 0) {
+            return f64_propagate_nan(a, b, env);
+        }
+
+        // Inf
+        if b_exp as i64 | b_frac == 0 {
+            // Inf x 0, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f64_default_nan(env);
+        }
+
+        return f64_pack_raw(r_sign, 0x7FF, 0);
+    }
+    if b_exp == 0x7FF {
+        // Nan
+        if b_frac != 0 {
+            return f64_propagate_nan(a, b, env);
+        }
+
+        // Inf
+        if a_exp as i64 | a_frac == 0 {
+            // 0 x Inf, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f64_default_nan(env);
+        }
+
+        return f64_pack_raw(r_sign, 0x7FF, 0);
+    }
+
+    if a_exp == 0 {
+        if a_frac == 0 {
+            // Zero
+            return f64_pack_raw(r_sign, 0, 0);
+        }
+
+        let (exp, frac) = f64_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    if b_exp == 0 {
+        if b_frac == 0 {
+            // Zero
+            return f64_pack_raw(r_sign, 0, 0);
+        }
+
+        let (exp, frac) = f64_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+
+    r_exp = a_exp + b_exp - 0x3FF;
+
+    a_frac = (a_frac | 0x0010000000000000) << 10;
+    b_frac = (b_frac | 0x0010000000000000) << 11;
+
+    // Use u128 to multiply u64
+    let frac_prod: u128 = (a_frac as u64 as u128) * (b_frac as u64 as u128);
+    r_frac = f64_short_shift_right_jam128(frac_prod, 64);
+
+    if r_frac < 0x4000000000000000 {
+        r_exp -= 1;
+        r_frac <<= 1;
+    }
+
+    f64_round_and_pack(r_sign, r_exp, r_frac, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ DetectTininess, Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f64_mul() {
+        // 0.1 * 0.2 = 0.020000000000000004
+        assert_eq!(crate::soft_f64::f64_mul(0x3FB999999999999A, 0x3FC999999999999A), 0x3F947AE147AE147C);
+        // -0.1 * 0.2 = -0.020000000000000004
+        assert_eq!(crate::soft_f64::f64_mul(0xBFB999999999999A, 0x3FC999999999999A), 0xBF947AE147AE147C);
+        // 2 * 2 = 4
+        assert_eq!(crate::soft_f64::f64_mul(0x4000000000000000, 0x4000000000000000), 0x4010000000000000);
+        // 1.5 * -0 = -0
+        assert_eq!(crate::soft_f64::f64_mul(0x3FF8000000000000, 0x8000000000000000), 0x8000000000000000);
+    }
+
+    #[test]
+    fn test_f64_mul_inf_nan() {
+        // Inf * -2 = -Inf
+        assert_eq!(crate::soft_f64::f64_mul(0x7FF0000000000000, 0xC000000000000000), 0xFFF0000000000000);
+        // Inf * 0 = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_mul(0x7FF0000000000000, 0x0000000000000000)), true);
+        // 0 * -Inf = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_mul(0x0000000000000000, 0xFFF0000000000000)), true);
+        // NaN * 1 = NaN
+        assert_eq!(crate::soft_f64::f64_mul(0x7FF8000000000000, 0x3FF0000000000000), 0x7FF8000000000000);
+    }
+
+    #[test]
+    fn test_f64_mul_rounding_mode() {
+        // 0.1 * 0.2
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x3F947AE147AE147C);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::MinMag)), 0x3F947AE147AE147B);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Min)), 0x3F947AE147AE147B);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Max)), 0x3F947AE147AE147C);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::NearMaxMag)), 0x3F947AE147AE147C);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Odd)), 0x3F947AE147AE147B);
+
+        // -0.1 * 0.2
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0xBFB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Min)), 0xBF947AE147AE147C);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0xBFB999999999999A, 0x3FC999999999999A, &mut Env::new(RoundingMode::Max)), 0xBF947AE147AE147B);
+    }
+
+    #[test]
+    fn test_f64_mul_exception_flags() {
+        // 0.1 * 0.2, inexact
+        let mut env = Env::default();
+        crate::soft_f64::f64_mul_with_env(0x3FB999999999999A, 0x3FC999999999999A, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // MAX * 2, overflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x7FEFFFFFFFFFFFFF, 0x4000000000000000, &mut env), 0x7FF0000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 1e-300 * 1e-300, underflow
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x01A56E1FC2F8F359, 0x01A56E1FC2F8F359, &mut env), 0x0000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // Inf * 0, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x7FF0000000000000, 0x0000000000000000, &mut env), 0xFFF8000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f64_mul_subnormal() {
+        // 2^-537 * 2^-537 = MIN_SUBNORMAL, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x1E60000000000000, 0x1E60000000000000, &mut env), 0x0000000000000001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // MAX_SUBNORMAL * 2, a normal number
+        assert_eq!(crate::soft_f64::f64_mul(0x000FFFFFFFFFFFFF, 0x4000000000000000), 0x001FFFFFFFFFFFFE);
+        // MIN_SUBNORMAL * 2^52 = MIN_POSITIVE
+        assert_eq!(crate::soft_f64::f64_mul(0x0000000000000001, 0x4330000000000000), 0x0010000000000000);
+    }
+
+    #[test]
+    fn test_f64_mul_detect_tininess() {
+        // (1 + 2^-52) * MIN_POSITIVE / 2 rounds to MIN_POSITIVE / 2, tiny in both cases
+        let mut env = Env::default();
+        crate::soft_f64::f64_mul_with_env(0x3FF0000000000001, 0x0008000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // (1 - 2^-52) * (MIN_POSITIVE + MIN_SUBNORMAL) rounds to MIN_POSITIVE, tiny only before rounding
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FEFFFFFFFFFFFFE, 0x0010000000000001, &mut env), 0x0010000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        let mut env = Env::default();
+        env.set_detect_tininess(DetectTininess::Before);
+        assert_eq!(crate::soft_f64::f64_mul_with_env(0x3FEFFFFFFFFFFFFE, 0x0010000000000001, &mut env), 0x0010000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+    }
+}
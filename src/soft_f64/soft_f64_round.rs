@@ -0,0 +1,197 @@
+use super::util::{
+    f64_pack_raw,
+    f64_propagate_nan,
+    f64_sign, f64_exp, f64_frac,
+    f64_count_leading_zero,
+    f64_is_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f64_round(a: u64) -> u64 {
+    with_thread_env(|env| f64_round_with_env(a, env))
+}
+
+pub fn f64_round_with_env(a: u64, env: &mut Env) -> u64 {
+    // Exp
+    let a_exp = f64_exp(a);
+
+    // Frac
+    let a_frac = f64_frac(a);
+
+    if a_exp <= 0x3FE {
+        // |a| < 1, round to 0 or 1
+        if (a << 1) == 0 {
+            return a;
+        }
+        let mut z = a & f64_pack_raw(1, 0, 0);
+        if a_frac != 0 && a_exp == 0x3FE {
+            z |= f64_pack_raw(0, 0x3FF, 0);
+        }
+        return z;
+    }
+    if 0x433 <= a_exp {
+        if a_exp == 0x7FF && a_frac != 0 {
+            return f64_propagate_nan(a, 0, env);
+        }
+        return a;
+    }
+    let last_bit_mask = 1 << (0x433 - a_exp);
+    let round_bits_mask = last_bit_mask - 1;
+    // By default use near even round mode
+    let mut r = a;
+    r += last_bit_mask >> 1;
+    if (r & round_bits_mask) == 0 {
+        r &= !last_bit_mask;
+    }
+    r &= !round_bits_mask;
+    r
+}
+
+pub fn from_int32(a: i32) -> u64 {
+    with_thread_env(|env| from_int32_with_env(a, env))
+}
+
+pub fn from_int32_with_env(a: i32, _env: &mut Env) -> u64 {
+    // Every i32 fits in the fraction, so the conversion is always exact
+    let sign = if a < 0 { 1 } else { 0 };
+    if a == 0 {
+        return 0;
+    }
+
+    let frac = (a as i64).abs();
+    let shift_count = f64_count_leading_zero(frac) - 11;
+    f64_pack_raw(sign, 0x432 - shift_count, frac << shift_count)
+}
+
+pub fn to_int32(a: u64) -> i32 {
+    with_thread_env(|env| to_int32_with_env(a, env))
+}
+
+pub fn to_int32_with_env(a: u64, env: &mut Env) -> i32 {
+    let p = f64_round_with_env(a, env);
+
+    if f64_is_nan(p) {
+        env.raise(ExceptionFlags::INVALID);
+        return i32::MAX;
+    }
+
+    let sign = f64_sign(p);
+    let exp = f64_exp(p);
+    let mut frac = f64_frac(p);
+
+    if exp < 0x3FF {
+        // +- 0, because it is too tiny
+        if p != a {
+            env.raise(ExceptionFlags::INEXACT);
+        }
+        return 0;
+    }
+
+    let shift = exp - 0x3FF;
+    if shift >= 31 {
+        if p == f64_pack_raw(1, 0x41E, 0) {
+            // -2^31
+            if p != a {
+                env.raise(ExceptionFlags::INEXACT);
+            }
+            return i32::MIN;
+        }
+
+        // Infinity or out of range
+        env.raise(ExceptionFlags::INVALID);
+        if sign != 0 {
+            return i32::MIN;
+        }
+        return i32::MAX;
+    }
+
+    if p != a {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    frac |= 0x0010000000000000;
+    let r = (frac >> (52 - shift)) as i32;
+
+    if sign != 0 {
+        -r
+    } else {
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f64_round() {
+        // round(0.01) = 0
+        assert_eq!(crate::soft_f64::f64_round(0x3F847AE147AE147B), 0x0000000000000000);
+        // round(0.5) = 0
+        assert_eq!(crate::soft_f64::f64_round(0x3FE0000000000000), 0x0000000000000000);
+        // round(-0.6) = -1
+        assert_eq!(crate::soft_f64::f64_round(0xBFE3333333333333), 0xBFF0000000000000);
+        // round(2.5) = 2
+        assert_eq!(crate::soft_f64::f64_round(0x4004000000000000), 0x4000000000000000);
+        // round(3.5) = 4
+        assert_eq!(crate::soft_f64::f64_round(0x400C000000000000), 0x4010000000000000);
+        // round(2^53) = 2^53
+        assert_eq!(crate::soft_f64::f64_round(0x4340000000000000), 0x4340000000000000);
+    }
+
+    #[test]
+    fn test_f64_to_i32() {
+        // round(4) = 4
+        assert_eq!(crate::soft_f64::to_int32(0x4010000000000000), 4);
+        // round(244.5) = 244
+        assert_eq!(crate::soft_f64::to_int32(0x406E900000000000), 244);
+        // round(-128.2) = -128
+        assert_eq!(crate::soft_f64::to_int32(0xC060066666666666), -128);
+        // round(2^30) = 1073741824
+        assert_eq!(crate::soft_f64::to_int32(0x41D0000000000000), 0x40000000);
+
+        // round(2^31) = 2147483647, out of range
+        assert_eq!(crate::soft_f64::to_int32(0x41E0000000000000), i32::MAX);
+        // round(-2^31) = -2147483648
+        assert_eq!(crate::soft_f64::to_int32(0xC1E0000000000000), i32::MIN);
+    }
+
+    #[test]
+    fn test_i32_to_f64() {
+        // from_int32(0) = 0.0
+        assert_eq!(crate::soft_f64::from_int32(0), 0x0);
+        // from_int32(-4) = -4.0
+        assert_eq!(crate::soft_f64::from_int32(-4), 0xC010000000000000);
+        // from_int32(80235) = 80235.0
+        assert_eq!(crate::soft_f64::from_int32(80235), 0x40F396B000000000);
+        // from_int32(2147483647) = 2147483647.0
+        assert_eq!(crate::soft_f64::from_int32(i32::MAX), 0x41DFFFFFFFC00000);
+        // from_int32(-2147483648) = -2147483648.0
+        assert_eq!(crate::soft_f64::from_int32(i32::MIN), 0xC1E0000000000000);
+    }
+
+    #[test]
+    fn test_f64_int_exception_flags() {
+        let mut env = Env::default();
+
+        // to_int32(128.2) = 128, inexact
+        assert_eq!(crate::soft_f64::to_int32_with_env(0x4060066666666666, &mut env), 128);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // to_int32(-2^31 - 0.5) = -2147483648, inexact
+        env.clear_flags();
+        assert_eq!(crate::soft_f64::to_int32_with_env(0xC1E0000000100000, &mut env), i32::MIN);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // to_int32(NaN), invalid
+        env.clear_flags();
+        crate::soft_f64::to_int32_with_env(0x7FF8000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // from_int32(16777217) = 16777217.0, exact
+        env.clear_flags();
+        assert_eq!(crate::soft_f64::from_int32_with_env(16777217, &mut env), 0x4170000010000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+    }
+}
@@ -0,0 +1,164 @@
+use super::util::{
+    f64_round_and_pack,
+    f64_propagate_nan,
+    f64_sign, f64_exp, f64_frac,
+    f64_norm_subnormal_frac,
+    f64_approx_recip_sqrt,
+    f64_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f64_sqrt(a: u64) -> u64 {
+    with_thread_env(|env| f64_sqrt_with_env(a, env))
+}
+
+pub fn f64_sqrt_with_env(a: u64, env: &mut Env) -> u64 {
+    // Sign
+    let a_sign = f64_sign(a);
+
+    // Exp
+    let mut a_exp = f64_exp(a);
+
+    // Frac
+    let mut a_frac = f64_frac(a);
+
+    if a_exp == 0x7FF {
+        if a_frac != 0 {
+            return f64_propagate_nan(a, 0, env);
+        }
+        if a_sign == 0 {
+            return a;
+        }
+
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f64_default_nan(env);
+    }
+
+    if a_sign != 0 {
+        if a_exp as i64 | a_frac == 0 {
+            // 0 or -0
+            return a;
+        }
+
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f64_default_nan(env);
+    }
+
+    if a_exp == 0 {
+        if a_frac == 0 {
+            return a;
+        }
+        let (exp, frac) = f64_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+
+    let r_exp = ((a_exp - 0x3FF) >> 1) + 0x3FE;
+    a_exp &= 1;
+
+    let mut a_frac_u64 = (a_frac | 0x0010000000000000) as u64;
+    let a_frac_hi = a_frac_u64 >> 21;
+    let recip_sqrt = f64_approx_recip_sqrt(a_exp as u32, a_frac_u64 << 11) as u64;
+
+    // Estimate the high 32 bits of the root
+    let mut r_frac_hi = (a_frac_hi * recip_sqrt) >> 32;
+    if a_exp != 0 {
+        a_frac_u64 <<= 8;
+        r_frac_hi >>= 1;
+    } else {
+        a_frac_u64 <<= 9;
+    }
+
+    // Estimate the low bits with the remainder
+    let rem = a_frac_u64 - r_frac_hi * r_frac_hi;
+    let q = (((rem >> 2) & 0xFFFFFFFF) * recip_sqrt) >> 32;
+    let mut r_frac = ((r_frac_hi << 32) | (1 << 5)) + (q << 3);
+
+    if (r_frac & 0x1FF) < 0x22 {
+        // Close to a rounding boundary, compute the exact remainder
+        r_frac &= !0x3F;
+        let r_shifted_frac = r_frac >> 6;
+        let neg_rem = (a_frac_u64 << 52).wrapping_sub(r_shifted_frac.wrapping_mul(r_shifted_frac));
+
+        if neg_rem & 0x8000000000000000 != 0 {
+            r_frac -= 1;
+        } else {
+            if neg_rem != 0 {
+                r_frac |= 0x01;
+            }
+        }
+    }
+
+    f64_round_and_pack(0, r_exp, r_frac as i64, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f64_sqrt() {
+        // sqrt(0.01) = 0.1
+        assert_eq!(crate::soft_f64::f64_sqrt(0x3F847AE147AE147B), 0x3FB999999999999A);
+        // sqrt(2) = 1.4142135623730951
+        assert_eq!(crate::soft_f64::f64_sqrt(0x4000000000000000), 0x3FF6A09E667F3BCD);
+        // sqrt(4) = 2
+        assert_eq!(crate::soft_f64::f64_sqrt(0x4010000000000000), 0x4000000000000000);
+        // sqrt(80235) = 283.2578330988416
+        assert_eq!(crate::soft_f64::f64_sqrt(0x40F396B000000000), 0x4071B4201593D80B);
+    }
+
+    #[test]
+    fn test_f64_sqrt_inf_nan() {
+        // sqrt(Inf) = Inf
+        assert_eq!(crate::soft_f64::f64_sqrt(0x7FF0000000000000), 0x7FF0000000000000);
+        // sqrt(-Inf) = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_sqrt(0xFFF0000000000000)), true);
+        // sqrt(-1) = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_sqrt(0xBFF0000000000000)), true);
+        // sqrt(-0) = -0
+        assert_eq!(crate::soft_f64::f64_sqrt(0x8000000000000000), 0x8000000000000000);
+        // sqrt(NaN) = NaN
+        assert_eq!(crate::soft_f64::f64_sqrt(0x7FF8000000000000), 0x7FF8000000000000);
+    }
+
+    #[test]
+    fn test_f64_sqrt_rounding_mode() {
+        // sqrt(2)
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FF6A09E667F3BCD);
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FF6A09E667F3BCC);
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut Env::new(RoundingMode::Min)), 0x3FF6A09E667F3BCC);
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FF6A09E667F3BCD);
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FF6A09E667F3BCD);
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut Env::new(RoundingMode::Odd)), 0x3FF6A09E667F3BCD);
+    }
+
+    #[test]
+    fn test_f64_sqrt_exception_flags() {
+        // sqrt(2), inexact
+        let mut env = Env::default();
+        crate::soft_f64::f64_sqrt_with_env(0x4000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // sqrt(4), exact
+        let mut env = Env::default();
+        crate::soft_f64::f64_sqrt_with_env(0x4010000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // sqrt(-1), invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_sqrt_with_env(0xBFF0000000000000, &mut env), 0xFFF8000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f64_sqrt_subnormal() {
+        // sqrt(MIN_SUBNORMAL) = 2^-537
+        assert_eq!(crate::soft_f64::f64_sqrt(0x0000000000000001), 0x1E60000000000000);
+        // sqrt(MIN_POSITIVE) = 2^-511
+        assert_eq!(crate::soft_f64::f64_sqrt(0x0010000000000000), 0x2000000000000000);
+    }
+}
@@ -0,0 +1,212 @@
+use super::util::{
+    f64_shift_right_jam,
+    f64_norm_round_and_pack,
+    f64_pack_raw, f64_pack,
+    f64_propagate_nan,
+    f64_sign, f64_exp, f64_frac,
+    f64_count_leading_zero,
+    f64_default_nan,
+};
+
+use super::soft_f64_add::f64_add_mags;
+
+use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, with_thread_env };
+
+pub fn f64_sub(a: u64, b: u64) -> u64 {
+    with_thread_env(|env| f64_sub_with_env(a, b, env))
+}
+
+pub fn f64_sub_with_env(a: u64, b: u64, env: &mut Env) -> u64 {
+    if f64_sign(a) != f64_sign(b) {
+        // Consider as addition
+        return f64_add_mags(a, b, env);
+    }
+
+    f64_sub_mags(a, b, env)
+}
+
+// Subtract the magnitude of B from the magnitude of A, the result has the sign of A
+pub(crate) fn f64_sub_mags(a: u64, b: u64, env: &mut Env) -> u64 {
+    // Sign
+    let a_sign = f64_sign(a);
+    let mut r_sign = a_sign;
+
+    // Exp
+    let mut a_exp = f64_exp(a);
+    let b_exp = f64_exp(b);
+    let r_exp;
+
+    // Frac
+    let mut a_frac = f64_frac(a);
+    let mut b_frac = f64_frac(b);
+    let mut r_frac;
+
+    let diff_exp = a_exp - b_exp;
+
+    if diff_exp == 0 {
+        if a_exp == 0x7FF {
+            if (a_frac | b_frac) != 0 {
+                // Propagate NaN
+                return f64_propagate_nan(a, b, env);
+            } else {
+                // Inf - Inf, invalid, return the default NaN
+                env.raise(ExceptionFlags::INVALID);
+                return f64_default_nan(env);
+            }
+        }
+
+        r_frac = a_frac - b_frac;
+        if r_frac == 0 {
+            // Same, will cause a 0, which is negative only when rounding toward negative
+            let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+            return f64_pack(zero_sign, 0, 0);
+        }
+
+        if a_exp != 0 {
+            a_exp -= 1;
+        }
+        if r_frac < 0 {
+            // Fraction of B is greater
+            r_sign ^= 1;
+            r_frac = -r_frac;
+        }
+
+        // The result is exact, normalize it as far as the exponent allows
+        let mut shift_count = f64_count_leading_zero(r_frac) - 11;
+        let mut exp = a_exp - shift_count;
+        if exp < 0 {
+            shift_count = a_exp;
+            exp = 0;
+        }
+        return f64_pack_raw(r_sign, exp, r_frac << shift_count);
+    }
+
+    a_frac <<= 10;
+    b_frac <<= 10;
+
+    if diff_exp > 0 {
+        // Exp of A is greater
+        if a_exp == 0x7FF {
+            if a_frac != 0 {
+                // Propagate NaN
+                return f64_propagate_nan(a, b, env);
+            }
+            // Infinity
+            return a;
+        }
+
+        if b_exp != 0 {
+            b_frac += 0x4000000000000000;
+        } else {
+            b_frac += b_frac;
+        }
+
+        b_frac = f64_shift_right_jam(b_frac, diff_exp);
+        a_frac |= 0x4000000000000000;   // Add the implicit 1
+
+        r_exp = a_exp;
+        r_frac = a_frac - b_frac;
+    } else {
+        // Exp of B is greater
+        if b_exp == 0x7FF {
+            if b_frac != 0 {
+                // Propagate NaN
+                return f64_propagate_nan(a, b, env);
+            }
+            // Infinity of the opposite sign
+            return f64_pack_raw(r_sign ^ 1, 0x7FF, 0);
+        }
+
+        if a_exp != 0 {
+            a_frac += 0x4000000000000000;
+        } else {
+            a_frac += a_frac;
+        }
+
+        a_frac = f64_shift_right_jam(a_frac, -diff_exp);
+        b_frac |= 0x4000000000000000;
+
+        r_sign ^= 1;
+        r_exp = b_exp;
+        r_frac = b_frac - a_frac;
+    }
+    f64_norm_round_and_pack(r_sign, r_exp - 1, r_frac, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_f64_sub() {
+        // 0.2 - 0.1 = 0.1
+        assert_eq!(crate::soft_f64::f64_sub(0x3FC999999999999A, 0x3FB999999999999A), 0x3FB999999999999A);
+        // 0.1 - 0.2 = -0.1
+        assert_eq!(crate::soft_f64::f64_sub(0x3FB999999999999A, 0x3FC999999999999A), 0xBFB999999999999A);
+
+        // 80235 - 67890 = 12345
+        assert_eq!(crate::soft_f64::f64_sub(0x40F396B000000000, 0x40F0932000000000), 0x40C81C8000000000);
+
+        // 0.1 - -0.2 = 0.30000000000000004
+        assert_eq!(crate::soft_f64::f64_sub(0x3FB999999999999A, 0xBFC999999999999A), 0x3FD3333333333334);
+
+        // 2.02 - 2 = 0.02
+        assert_eq!(crate::soft_f64::f64_sub(0x400028F5C28F5C29, 0x4000000000000000), 0x3F947AE147AE1480);
+    }
+
+    #[test]
+    fn test_f64_sub_inf_nan() {
+        // Inf - Inf = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_sub(0x7FF0000000000000, 0x7FF0000000000000)), true);
+        // 1 - Inf = -Inf
+        assert_eq!(crate::soft_f64::f64_sub(0x3FF0000000000000, 0x7FF0000000000000), 0xFFF0000000000000);
+        // Inf - 1 = Inf
+        assert_eq!(crate::soft_f64::f64_sub(0x7FF0000000000000, 0x3FF0000000000000), 0x7FF0000000000000);
+        // 1 - NaN = NaN
+        assert_eq!(crate::soft_f64::f64_is_nan(crate::soft_f64::f64_sub(0x3FF0000000000000, 0x7FF8000000000000)), true);
+    }
+
+    #[test]
+    fn test_f64_sub_rounding_mode() {
+        // 1 - 2^-54 is a tie
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FEFFFFFFFFFFFFF);
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut Env::new(RoundingMode::Min)), 0x3FEFFFFFFFFFFFFF);
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut Env::new(RoundingMode::Max)), 0x3FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut Env::new(RoundingMode::NearMaxMag)), 0x3FF0000000000000);
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut Env::new(RoundingMode::Odd)), 0x3FEFFFFFFFFFFFFF);
+
+        // 0.1 - 0.1 is -0 only when rounding toward negative
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FB999999999999A, 0x3FB999999999999A, &mut Env::new(RoundingMode::NearEven)), 0x0000000000000000);
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x3FB999999999999A, 0x3FB999999999999A, &mut Env::new(RoundingMode::Min)), 0x8000000000000000);
+    }
+
+    #[test]
+    fn test_f64_sub_exception_flags() {
+        // 1 - 2^-54, inexact
+        let mut env = Env::default();
+        crate::soft_f64::f64_sub_with_env(0x3FF0000000000000, 0x3C90000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // 0.2 - 0.1, exact
+        let mut env = Env::default();
+        crate::soft_f64::f64_sub_with_env(0x3FC999999999999A, 0x3FB999999999999A, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Inf - Inf, invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x7FF0000000000000, 0x7FF0000000000000, &mut env), 0xFFF8000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f64_sub_subnormal() {
+        // MIN_POSITIVE - MAX_SUBNORMAL = MIN_SUBNORMAL, exact
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f64::f64_sub_with_env(0x0010000000000000, 0x000FFFFFFFFFFFFF, &mut env), 0x0000000000000001);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // (MIN_POSITIVE * 1.5) - MIN_POSITIVE = MIN_POSITIVE / 2
+        assert_eq!(crate::soft_f64::f64_sub(0x0018000000000000, 0x0010000000000000), 0x0008000000000000);
+    }
+}
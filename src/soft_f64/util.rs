@@ -0,0 +1,261 @@
+pub(crate) fn f64_shift_right_jam(a: i64, dist: i32) -> i64 {
+    if dist < 63 {
+        if (a << (-dist & 63)) != 0 {
+            (a >> dist) | 1
+        } else {
+            a >> dist
+        }
+    } else {
+        if a != 0 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+use crate::soft_float::{ RoundingMode, DetectTininess, Env, ExceptionFlags, NanPolicy };
+use crate::soft_f32::util::{
+    f32_count_leading_zero,
+    f32_approx_recip,
+    f32_approx_recip_sqrt,
+};
+
+pub(crate) fn f64_sign(a: u64) -> i32 {
+    ((a >> 63) & 0x01) as i32
+}
+
+pub(crate) fn f64_exp(a: u64) -> i32 {
+    ((a >> 52) & 0x7FF) as i32
+}
+
+pub(crate) fn f64_frac(a: u64) -> i64 {
+    (a & 0x000FFFFFFFFFFFFF) as i64
+}
+
+pub(crate) fn f64_pack_raw(in_sign: i32, in_exp: i32, in_frac: i64) -> u64 {
+    // Use + rather than |, so that a carry out of the fraction increments the exponent
+    ((in_sign as u64) << 63) | (((in_exp as u64) << 52).wrapping_add(in_frac as u64))
+}
+
+// The default NaN of the NaN policy of the environment
+pub(crate) fn f64_default_nan(env: &Env) -> u64 {
+    match env.nan_policy() {
+        NanPolicy::X87 | NanPolicy::X86Sse => 0xFFF8000000000000,
+        NanPolicy::MipsLegacy => 0x7FF7FFFFFFFFFFFF,
+        _ => 0x7FF8000000000000,
+    }
+}
+
+pub fn f64_is_nan(a: u64) -> bool {
+    let is_exp_nan = (a & 0x7FF0000000000000) == 0x7FF0000000000000;
+    let is_frac_nan = (a & 0x000FFFFFFFFFFFFF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// A signaling NaN has the most significant bit of its fraction cleared
+pub fn f64_is_signaling_nan(a: u64) -> bool {
+    let is_exp_nan = (a & 0x7FF8000000000000) == 0x7FF0000000000000;
+    let is_frac_nan = (a & 0x0007FFFFFFFFFFFF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// Whether A is a signaling NaN in the encoding of the NaN policy, where MIPS legacy
+// inverts the quiet bit
+fn f64_is_signaling_nan_in(a: u64, env: &Env) -> bool {
+    match env.nan_policy() {
+        NanPolicy::MipsLegacy => (a & 0x7FF8000000000000) == 0x7FF8000000000000,
+        _ => f64_is_signaling_nan(a),
+    }
+}
+
+// The NaN result of an operation on A and B, at least one of which is a NaN, chosen by the
+// NaN policy of the environment. A signaling NaN is invalid.
+pub(crate) fn f64_propagate_nan(a: u64, b: u64, env: &mut Env) -> u64 {
+    f64_propagate_nans(&[a, b], env)
+}
+
+// The NaN result of the operands, in the order of the instruction
+fn f64_propagate_nans(ops: &[u64], env: &mut Env) -> u64 {
+    if env.nan_policy() == NanPolicy::X87 {
+        // Pairwise, in order
+        return ops[1..].iter().fold(ops[0], |r, &op| f64_propagate_nan_x87(r, op, env));
+    }
+
+    let default_nan = f64_default_nan(env);
+    let first_signaling = ops.iter().copied().find(|&op| f64_is_signaling_nan_in(op, env));
+    let first_nan = ops.iter().copied().find(|&op| f64_is_nan(op)).unwrap_or(default_nan);
+    if first_signaling.is_some() {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+    }
+
+    match env.nan_policy() {
+        NanPolicy::X86Sse => first_nan | 0x0008000000000000,
+        NanPolicy::Armv8 => first_signaling.unwrap_or(first_nan) | 0x0008000000000000,
+        // Quieting a NaN could give infinity
+        NanPolicy::MipsLegacy if first_signaling.is_some() => default_nan,
+        NanPolicy::MipsLegacy => first_nan,
+        _ => default_nan,
+    }
+}
+
+fn f64_propagate_nan_x87(in_a: u64, in_b: u64, env: &mut Env) -> u64 {
+    let a = in_a | 0x0008000000000000;
+    let b = in_b | 0x0008000000000000;
+
+    let is_a_frac_nan = f64_is_signaling_nan(in_a);
+    let is_b_frac_nan = f64_is_signaling_nan(in_b);
+
+    if is_a_frac_nan | is_b_frac_nan {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+        if is_a_frac_nan {
+            if !is_b_frac_nan {
+                return match f64_is_nan(b) {
+                    true => b,
+                    false => a,
+                };
+            }
+        } else {
+            return match f64_is_nan(a) {
+                true => a,
+                false => b,
+            };
+        }
+    }
+
+    // Return the NaN with the larger magnitude
+    let a_mag = a & 0x7FFFFFFFFFFFFFFF;
+    let b_mag = b & 0x7FFFFFFFFFFFFFFF;
+
+    if a_mag < b_mag {
+        b
+    } else if b_mag < a_mag || a < b {
+        a
+    } else {
+        b
+    }
+}
+
+pub fn f64_pack(in_sign: i32, in_exp: i32, in_frac: i64) -> u64 {
+    ((in_sign as u64) << 63) | (((in_exp & 0x7FF) as u64) << 52) | (in_frac as u64 & 0x000FFFFFFFFFFFFF)
+}
+
+pub(crate) fn f64_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i64, env: &mut Env) -> u64 {
+    let rounding_mode = env.rounding_mode();
+    let detect_tininess = env.detect_tininess();
+
+    let round_near_even = rounding_mode == RoundingMode::NearEven;
+    let mut round_increment = 0x200;
+
+    let sign = in_sign;
+    let mut exp = in_exp;
+    let mut frac = in_frac;
+
+    match rounding_mode {
+        RoundingMode::NearEven | RoundingMode::NearMaxMag => { /* Do nothing */ }
+        _ => {
+            // Round away from zero only when rounding toward the infinity of the same sign
+            let round_away = match rounding_mode {
+                RoundingMode::Min => sign == 1,
+                RoundingMode::Max => sign == 0,
+                _ => false,
+            };
+            round_increment = if round_away { 0x3FF } else { 0 };
+        }
+    }
+    let mut round_bits = frac & 0x3FF;
+
+    // A negative exponent is seen as a large unsigned one, so that tiny results are caught here as well
+    if (exp as u32) >= 0x7FD {
+        if exp < 0 {
+            // Tiny result, denormalize it before rounding
+            let is_tiny = detect_tininess == DetectTininess::Before
+                || exp < -1
+                || (frac as u64) + (round_increment as u64) < 0x8000000000000000;
+            frac = f64_shift_right_jam(frac, -exp);
+            exp = 0;
+            round_bits = frac & 0x3FF;
+            if is_tiny && round_bits != 0 {
+                // Underflow
+                env.raise(ExceptionFlags::UNDERFLOW);
+            }
+        } else if exp > 0x7FD || (frac as u64) + (round_increment as u64) >= 0x8000000000000000 {
+            // Overflow and inexact, round to infinity or to the largest finite value
+            env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+            return f64_pack_raw(sign, 0x7FF, 0) - (round_increment == 0) as u64;
+        }
+    }
+
+    let mut r_frac = ((frac as u64) + (round_increment as u64)) >> 10;
+
+    if round_bits != 0 {
+        env.raise(ExceptionFlags::INEXACT);
+    }
+
+    if round_bits != 0 && rounding_mode == RoundingMode::Odd {
+        // Inexact, jam the lost bits into the least significant bit
+        r_frac |= 1;
+    } else if round_near_even && round_bits == 0x200 {
+        // Tie, round to even
+        r_frac &= !1;
+    }
+    if r_frac == 0 { exp = 0; }
+
+    f64_pack_raw(sign, exp, r_frac as i64)
+}
+
+pub(crate) fn f64_count_leading_zero(in_frac: i64) -> i32 {
+    let frac = in_frac as u64;
+    if frac >> 32 == 0 {
+        32 + f32_count_leading_zero(frac as i32)
+    } else {
+        f32_count_leading_zero((frac >> 32) as i32)
+    }
+}
+
+pub(crate) fn f64_norm_round_and_pack(in_sign: i32, in_exp: i32, in_frac: i64, env: &mut Env) -> u64 {
+    let shift_count = f64_count_leading_zero(in_frac) - 1;
+    let sign = in_sign;
+    let mut exp = in_exp - shift_count;
+    let frac = in_frac;
+
+    if (exp as u32) < 0x7FD && shift_count >= 10 {
+        // Exact, no rounding needed
+        if frac == 0 {
+            exp = 0;
+        }
+        f64_pack_raw(sign, exp, frac << (shift_count - 10))
+    } else {
+        f64_round_and_pack(sign, exp, frac << shift_count, env)
+    }
+}
+
+pub(crate) fn f64_norm_subnormal_frac(frac: i64) -> (i32, i64) {
+    let shift_count = f64_count_leading_zero(frac) - 11;
+
+    (1 - shift_count, frac << shift_count)
+}
+
+pub(crate) fn f64_short_shift_right_jam128(a: u128, count: i32) -> i64 {
+    let b = ((a >> count) & 0xFFFFFFFFFFFFFFFF) as i64;
+    if (a & ((0x01 << count) - 1)) != 0 {
+        return b | 1;
+    }
+    b
+}
+
+// Approximate 2^64 / a, where the most significant bit of a is set.
+// Only the high 32 bits of a are used, see f32_approx_recip.
+pub(crate) fn f64_approx_recip(a: u64) -> u32 {
+    f32_approx_recip((a >> 32) as u32)
+}
+
+// Approximate the reciprocal square root of a, where the most significant bit of a is set.
+// Only the high 32 bits of a are used, see f32_approx_recip_sqrt.
+pub(crate) fn f64_approx_recip_sqrt(odd_exp: u32, a: u64) -> u32 {
+    f32_approx_recip_sqrt(odd_exp, (a >> 32) as u32)
+}
@@ -0,0 +1,279 @@
+mod soft_f80_add;
+mod soft_f80_sub;
+mod soft_f80_mul;
+mod soft_f80_div;
+mod soft_f80_comp;
+mod soft_f80_sqrt;
+mod soft_f80_conv;
+mod util;
+
+// The procedural API works on raw u128 bit patterns, whose low 80 bits hold the sign,
+// the 15-bit exponent and the 64-bit significand with its explicit integer bit.
+// Each function uses the environment of the current thread, and has a `_with_env`
+// variant which takes an explicit environment.
+//
+// As on the x87 FPU since the 80387, unnormals, pseudo-infinities and pseudo-NaNs are
+// invalid operands, while pseudo-denormals are accepted. Results of the operations are
+// rounded to the rounding precision of the environment.
+
+// Operations
+pub use soft_f80_add::{ f80_add, f80_add_with_env };
+pub use soft_f80_sub::{ f80_sub, f80_sub_with_env };
+pub use soft_f80_mul::{ f80_mul, f80_mul_with_env };
+pub use soft_f80_div::{ f80_div, f80_div_with_env };
+
+// Comparisons
+pub use soft_f80_comp::{ f80_eq, f80_eq_with_env };
+pub use soft_f80_comp::{ f80_ne, f80_ne_with_env };
+pub use soft_f80_comp::{ f80_lt, f80_lt_with_env };
+pub use soft_f80_comp::{ f80_gt, f80_gt_with_env };
+pub use soft_f80_comp::{ f80_le, f80_le_with_env };
+pub use soft_f80_comp::{ f80_ge, f80_ge_with_env };
+
+// Others
+pub use soft_f80_sqrt::{ f80_sqrt, f80_sqrt_with_env };
+
+// Conversions
+pub use soft_f80_conv::{ f32_to_f80, f32_to_f80_with_env };
+pub use soft_f80_conv::{ f80_to_f32, f80_to_f32_with_env };
+
+// Utilities
+pub use util::{
+    f80_is_nan,
+    f80_is_signaling_nan,
+    f80_is_unsupported,
+    f80_pack,
+};
+
+// F80 struct
+#[derive(Clone, Copy, Debug)]
+pub struct F80 {
+    value: u128,
+}
+
+impl F80 {
+    pub const fn from_bits(bits: u128) -> F80 {
+        F80 {
+            value: bits
+        }
+    }
+
+    pub fn to_bits(self) -> u128 {
+        self.value
+    }
+
+    #[deprecated(note = "use from_bits, as F32::from_bits")]
+    pub const fn from_u128(value: u128) -> F80 {
+        F80::from_bits(value)
+    }
+
+    // The bit pattern, as to_bits
+    pub fn value(self) -> u128 {
+        self.value
+    }
+
+    pub fn sqrt(self) -> Self {
+        F80 {
+            value: f80_sqrt(self.value)
+        }
+    }
+
+    pub fn from_f32(value: F32) -> F80 {
+        F80 {
+            value: f32_to_f80(value.value())
+        }
+    }
+
+    pub fn to_f32(self) -> F32 {
+        F32::from_bits(f80_to_f32(self.value))
+    }
+}
+
+use crate::soft_f32::F32;
+use std::ops;
+
+impl ops::Add<F80> for F80 {
+    type Output = F80;
+
+    fn add(self, other: F80) -> F80 {
+        F80 {
+            value: f80_add(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Sub<F80> for F80 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        F80 {
+            value: f80_sub(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Mul<F80> for F80 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        F80 {
+            value: f80_mul(self.value, other.value)
+        }
+    }
+}
+
+impl ops::Div<F80> for F80 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        F80 {
+            value: f80_div(self.value, other.value)
+        }
+    }
+}
+
+use std::cmp;
+
+impl cmp::PartialEq for F80 {
+    // Implement equal with only symmetric and transitive for F80.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
+    // For example, in floating point numbers NaN != NaN,
+    // so floating point types implement PartialEq but not Eq.
+    fn eq(&self, other: &Self) -> bool {
+        f80_eq(self.value, other.value)
+    }
+
+    #[allow(clippy::partialeq_ne_impl)]
+    fn ne(&self, other: &Self) -> bool {
+        f80_ne(self.value, other.value)
+    }
+}
+
+impl cmp::PartialOrd for F80 {
+    // Implement compare with only symmetric and transitive for F80.
+    //
+    // Ref: https://doc.rust-lang.org/std/cmp/trait.PartialOrd.html
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if f80_eq(self.value, other.value) { return Some(cmp::Ordering::Equal); }
+        if f80_lt(self.value, other.value) { return Some(cmp::Ordering::Less); }
+        if f80_gt(self.value, other.value) { return Some(cmp::Ordering::Greater); }
+
+        None
+    }
+
+    fn gt(&self, other: &Self) -> bool {
+        f80_gt(self.value, other.value)
+    }
+
+    fn lt(&self, other: &Self) -> bool {
+        f80_lt(self.value, other.value)
+    }
+
+    fn ge(&self, other: &Self) -> bool {
+        f80_ge(self.value, other.value)
+    }
+
+    fn le(&self, other: &Self) -> bool {
+        f80_le(self.value, other.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_f80_add_with_struct() {
+        let v0_1 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD);
+        let v0_2 = crate::soft_f80::F80::from_bits(0x3FFCCCCCCCCCCCCCCCCD);
+
+        let v0_3 = v0_1 + v0_2;
+
+        assert_eq!(v0_3.value(), 0x3FFD999999999999999A);
+    }
+
+    #[test]
+    fn test_f80_sub_with_struct() {
+        let v0_1 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD);
+        let v0_2 = crate::soft_f80::F80::from_bits(0x3FFCCCCCCCCCCCCCCCCD);
+
+        let v0_1_result = v0_2 - v0_1;
+
+        assert_eq!(v0_1_result.value(), 0x3FFBCCCCCCCCCCCCCCCD);
+    }
+
+    #[test]
+    fn test_f80_mul_with_struct() {
+        let v0_1 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD);
+        let v0_2 = crate::soft_f80::F80::from_bits(0x3FFCCCCCCCCCCCCCCCCD);
+
+        let v0_02 = v0_2 * v0_1;
+
+        assert_eq!(v0_02.value(), 0x3FF9A3D70A3D70A3D70B);
+    }
+
+    #[test]
+    fn test_f80_div_with_struct() {
+        let v0_1 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD);
+        let v0_2 = crate::soft_f80::F80::from_bits(0x3FFCCCCCCCCCCCCCCCCD);
+
+        let v0_5 = v0_1 / v0_2;
+
+        assert_eq!(v0_5.value(), 0x3FFE8000000000000000);
+    }
+
+    #[test]
+    fn test_f80_compare_with_struct() {
+        let v0_1 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD);
+        let v0_2 = crate::soft_f80::F80::from_bits(0x3FFCCCCCCCCCCCCCCCCD);
+
+        assert_eq!(v0_1 == v0_2, false);
+        assert_eq!(v0_1 != v0_2, true);
+
+        assert_eq!(v0_1 < v0_2, true);
+        assert_eq!(v0_1 <= v0_1, true);
+        assert_eq!(v0_1 > v0_2, false);
+        assert_eq!(v0_1 >= v0_1, true);
+    }
+
+    #[test]
+    fn test_f80_rounding_precision_with_struct() {
+        let v0_1 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD);
+        let v0_2 = crate::soft_f80::F80::from_bits(0x3FFCCCCCCCCCCCCCCCCD);
+
+        // Operators use the rounding precision of the current thread
+        crate::soft_float::set_rounding_precision(crate::soft_float::RoundingPrecision::Double);
+        let v0_3 = v0_1 + v0_2;
+        crate::soft_float::set_rounding_precision(crate::soft_float::RoundingPrecision::Extended);
+
+        assert_eq!(v0_3.value(), 0x3FFD9999999999999800);
+    }
+
+    #[test]
+    fn test_f80_sqrt_with_struct() {
+        // sqrt(4) = 2
+        let v4 = crate::soft_f80::F80::from_bits(0x40018000000000000000);
+
+        let v2 = v4.sqrt();
+
+        assert_eq!(v2.value(), 0x40008000000000000000);
+    }
+
+    #[test]
+    fn test_f80_f32_with_struct() {
+        let v0_1 = crate::soft_f32::F32::from_bits(0x3DCCCCCD);
+
+        let v0_1_f80 = crate::soft_f80::F80::from_f32(v0_1);
+        let v0_1_f32 = crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD).to_f32();
+
+        assert_eq!(v0_1_f80.value(), 0x3FFBCCCCCD0000000000);
+        assert_eq!(v0_1_f32.value(), 0x3DCCCCCD);
+    }
+
+    #[test]
+    fn test_f80_bits_with_struct() {
+        assert_eq!(crate::soft_f80::F80::from_bits(0x3FFBCCCCCCCCCCCCCCCD).to_bits(), 0x3FFBCCCCCCCCCCCCCCCD);
+        #[allow(deprecated)]
+        let v = crate::soft_f80::F80::from_u128(0x3FFBCCCCCCCCCCCCCCCD);
+        assert_eq!(v.to_bits(), 0x3FFBCCCCCCCCCCCCCCCD);
+    }
+}
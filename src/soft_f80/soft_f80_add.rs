@@ -0,0 +1,206 @@
+use super::util::{
+    f80_shift_right_jam_extra,
+    f80_round_and_pack,
+    f80_norm_round_and_pack,
+    f80_pack,
+    f80_propagate_nan,
+    f80_sign, f80_exp, f80_frac,
+    f80_is_nan,
+    f80_is_unsupported,
+    F80_INTEGER_BIT,
+    f80_default_nan,
+};
+
+use super::soft_f80_sub::f80_sub_mags;
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f80_add(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f80_add_with_env(a, b, env))
+}
+
+pub fn f80_add_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    if f80_is_unsupported(a) || f80_is_unsupported(b) {
+        // Invalid operand, return default NaN
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    if f80_sign(a) != f80_sign(b) {
+        // Consider as substraction
+        return f80_sub_mags(a, b, env);
+    }
+
+    f80_add_mags(a, b, env)
+}
+
+// Add the magnitudes of A and B, which have the same sign
+pub(crate) fn f80_add_mags(a: u128, b: u128, env: &mut Env) -> u128 {
+    // Sign
+    let r_sign = f80_sign(a);
+
+    // Exp
+    let mut a_exp = f80_exp(a);
+    let mut b_exp = f80_exp(b);
+
+    // Frac
+    let mut a_frac = f80_frac(a);
+    let mut b_frac = f80_frac(b);
+
+    if a_exp == 0x7FFF || b_exp == 0x7FFF {
+        if f80_is_nan(a) || f80_is_nan(b) {
+            // Propagate NaN
+            return f80_propagate_nan(a, b, env);
+        }
+
+        // Infinity
+        return f80_pack(r_sign, 0x7FFF, F80_INTEGER_BIT);
+    }
+
+    // Denormals have the exponent of the smallest normal numbers
+    a_exp = a_exp.max(1);
+    b_exp = b_exp.max(1);
+
+    if a_exp < b_exp {
+        std::mem::swap(&mut a_exp, &mut b_exp);
+        std::mem::swap(&mut a_frac, &mut b_frac);
+    }
+
+    // Align the fraction of B with the one of A
+    let (b_frac, b_frac_extra) = f80_shift_right_jam_extra(b_frac, 0, a_exp - b_exp);
+
+    let (r_frac, carry) = a_frac.overflowing_add(b_frac);
+    if carry {
+        // Shift the carry back into the integer bit
+        let (r_frac, r_frac_extra) = f80_shift_right_jam_extra(r_frac, b_frac_extra, 1);
+        return f80_round_and_pack(r_sign, a_exp + 1, r_frac | F80_INTEGER_BIT, r_frac_extra, env);
+    }
+
+    if (r_frac | b_frac_extra) == 0 {
+        // Zero
+        return f80_pack(r_sign, 0, 0);
+    }
+
+    // The sum of denormals may have no integer bit
+    f80_norm_round_and_pack(r_sign, a_exp, r_frac, b_frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode, RoundingPrecision };
+
+    #[test]
+    fn test_f80_add() {
+        // 0.1 + 0.2 = 0.3
+        assert_eq!(crate::soft_f80::f80_add(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), 0x3FFD999999999999999A);
+        // -0.1 + -0.2 = -0.3
+        assert_eq!(crate::soft_f80::f80_add(0xBFFBCCCCCCCCCCCCCCCD, 0xBFFCCCCCCCCCCCCCCCCD), 0xBFFD999999999999999A);
+
+        // 1 + 2 = 3
+        assert_eq!(crate::soft_f80::f80_add(0x3FFF8000000000000000, 0x40008000000000000000), 0x4000C000000000000000);
+        // -0.1 + 0.2 = 0.1
+        assert_eq!(crate::soft_f80::f80_add(0xBFFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), 0x3FFBCCCCCCCCCCCCCCCD);
+
+        // Pseudo-denormals have the value of the smallest normal numbers
+        assert_eq!(crate::soft_f80::f80_add(0x00008000000000000000, 0x00008000000000000000), 0x00028000000000000000);
+        // Denormals
+        assert_eq!(crate::soft_f80::f80_add(0x00000000000000000001, 0x00000000000000000001), 0x00000000000000000002);
+    }
+
+    #[test]
+    fn test_f80_add_inf_nan() {
+        // Inf + 1 = Inf
+        assert_eq!(crate::soft_f80::f80_add(0x7FFF8000000000000000, 0x3FFF8000000000000000), 0x7FFF8000000000000000);
+        // -Inf + Inf = NaN
+        assert_eq!(crate::soft_f80::f80_is_nan(crate::soft_f80::f80_add(0xFFFF8000000000000000, 0x7FFF8000000000000000)), true);
+        // NaN + 1 = NaN
+        assert_eq!(crate::soft_f80::f80_add(0xFFFFFFFFFFFFFFFFFFFF, 0x3FFF8000000000000000), 0xFFFFFFFFFFFFFFFFFFFF);
+
+        assert_eq!(crate::soft_f80::f80_add(0x0, 0xBFFBCCCCCCCCCCCCCCCD), 0xBFFBCCCCCCCCCCCCCCCD);
+        assert_eq!(crate::soft_f80::f80_add(0x0, 0x80000000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f80_add_rounding_mode() {
+        // 0.1 + 0.2
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3FFD999999999999999A);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3FFD9999999999999999);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::Max)), 0x3FFD999999999999999A);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::Odd)), 0x3FFD9999999999999999);
+
+        // 1 + 2^-64, a tie
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFF8000000000000000, 0x3FBF8000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FFF8000000000000000);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFF8000000000000000, 0x3FBF8000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FFF8000000000000001);
+
+        // MAX + MAX, overflow
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x7FFEFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFF, &mut Env::new(RoundingMode::NearEven)), 0x7FFF8000000000000000);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x7FFEFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFF, &mut Env::new(RoundingMode::MinMag)), 0x7FFEFFFFFFFFFFFFFFFF);
+    }
+
+    #[test]
+    fn test_f80_add_rounding_precision() {
+        // 0.1 + 0.2, rounded to 24 bits
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Single);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut env), 0x3FFD99999A0000000000);
+
+        // 0.1 + 0.2, rounded to 53 bits
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Double);
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut env), 0x3FFD9999999999999800);
+    }
+
+    #[test]
+    fn test_f80_add_exception_flags() {
+        // 0.1 + 0.2 is inexact
+        let mut env = Env::default();
+        crate::soft_f80::f80_add_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // MAX + MAX overflows
+        let mut env = Env::default();
+        crate::soft_f80::f80_add_with_env(0x7FFEFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFF, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // -Inf + Inf is invalid
+        let mut env = Env::default();
+        crate::soft_f80::f80_add_with_env(0xFFFF8000000000000000, 0x7FFF8000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // An unnormal is an invalid operand
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFF4000000000000000, 0x3FFF8000000000000000, &mut env), 0xFFFFC000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f80_add_nan_policy() {
+        // (qNaN A, qNaN B with a larger payload, sNaN, 1 + sNaN, sNaN + qNaN B, inf - inf) in each policy
+        let cases = [
+            (NanPolicy::X87, 0xFFFFC000000000000002, 0x7FFFC000000000000003, 0xFFFFC000000000000000, 0x7FFFC000000000000002),
+            (NanPolicy::X86Sse, 0x7FFFC000000000000001, 0x7FFFC000000000000003, 0xFFFFC000000000000000, 0x7FFFC000000000000003),
+            (NanPolicy::Armv8, 0x7FFFC000000000000001, 0x7FFFC000000000000003, 0x7FFFC000000000000000, 0x7FFFC000000000000003),
+            (NanPolicy::Armv8DefaultNan, 0x7FFFC000000000000000, 0x7FFFC000000000000000, 0x7FFFC000000000000000, 0x7FFFC000000000000000),
+            (NanPolicy::RiscV, 0x7FFFC000000000000000, 0x7FFFC000000000000000, 0x7FFFC000000000000000, 0x7FFFC000000000000000),
+            (NanPolicy::Wasm, 0x7FFFC000000000000000, 0x7FFFC000000000000000, 0x7FFFC000000000000000, 0x7FFFC000000000000000),
+        ];
+        for &(nan_policy, qnan_qnan, one_snan, inf_inf, snan_qnan) in cases.iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+
+            assert_eq!(crate::soft_f80::f80_add_with_env(0x7FFFC000000000000001, 0xFFFFC000000000000002, &mut env), qnan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+
+            assert_eq!(crate::soft_f80::f80_add_with_env(0x3FFF8000000000000000, 0x7FFF8000000000000003, &mut env), one_snan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f80::f80_add_with_env(0x7FFF8000000000000000, 0xFFFF8000000000000000, &mut env), inf_inf);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+            env.clear_flags();
+            assert_eq!(crate::soft_f80::f80_add_with_env(0x7FFF8000000000000003, 0x7FFFC000000000000002, &mut env), snan_qnan);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use super::util::{
+    f80_pack,
+    f80_sign, f80_exp, f80_frac,
+    f80_is_nan,
+    f80_is_signaling_nan,
+    f80_is_unsupported,
+    F80_INTEGER_BIT,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Equality comparisons are quiet, only a signaling NaN or an unsupported operand is invalid
+fn f80_is_unordered_quiet(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unsupported(a) || f80_is_unsupported(b) {
+        env.raise(ExceptionFlags::INVALID);
+        return true;
+    }
+    if f80_is_nan(a) || f80_is_nan(b) {
+        if f80_is_signaling_nan(a) || f80_is_signaling_nan(b) {
+            env.raise(ExceptionFlags::INVALID);
+        }
+        return true;
+    }
+    false
+}
+
+// Relational comparisons signal, any NaN is invalid
+fn f80_is_unordered_signaling(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unsupported(a) || f80_is_unsupported(b) || f80_is_nan(a) || f80_is_nan(b) {
+        env.raise(ExceptionFlags::INVALID);
+        return true;
+    }
+    false
+}
+
+// Magnitude to compare, a pseudo-denormal is moved to the exponent it stands for
+fn f80_mag(a: u128) -> u128 {
+    let exp = f80_exp(a);
+    let frac = f80_frac(a);
+
+    if exp == 0 && (frac & F80_INTEGER_BIT) != 0 {
+        return f80_pack(0, 1, frac);
+    }
+    f80_pack(0, exp, frac)
+}
+
+pub fn f80_ne(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f80_ne_with_env(a, b, env))
+}
+
+pub fn f80_ne_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unordered_quiet(a, b, env) {
+        // Unable to compare, so they are not equal
+        return true;
+    }
+
+    !f80_eq_ordered(a, b)
+}
+
+pub fn f80_eq(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f80_eq_with_env(a, b, env))
+}
+
+pub fn f80_eq_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unordered_quiet(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f80_eq_ordered(a, b)
+}
+
+fn f80_eq_ordered(a: u128, b: u128) -> bool {
+    let a_mag = f80_mag(a);
+    let b_mag = f80_mag(b);
+
+    // +0 and -0 are equal
+    a_mag == b_mag && (f80_sign(a) == f80_sign(b) || a_mag == 0)
+}
+
+pub fn f80_ge(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f80_ge_with_env(a, b, env))
+}
+
+pub fn f80_ge_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    !f80_lt_ordered(a, b)
+}
+
+pub fn f80_lt(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f80_lt_with_env(a, b, env))
+}
+
+pub fn f80_lt_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f80_lt_ordered(a, b)
+}
+
+fn f80_lt_ordered(a: u128, b: u128) -> bool {
+    // Sign
+    let a_sign = f80_sign(a);
+    let b_sign = f80_sign(b);
+
+    let a_mag = f80_mag(a);
+    let b_mag = f80_mag(b);
+
+    if a_sign != b_sign {
+        // Different sign, but +0 and -0 are equal
+        return a_sign == 1 && (a_mag | b_mag) != 0;
+    }
+
+    // Same sign
+    if a_sign != 0 {
+        a_mag > b_mag
+    } else {
+        a_mag < b_mag
+    }
+}
+
+pub fn f80_gt(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f80_gt_with_env(a, b, env))
+}
+
+pub fn f80_gt_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    !f80_le_ordered(a, b)
+}
+
+pub fn f80_le(a: u128, b: u128) -> bool {
+    with_thread_env(|env| f80_le_with_env(a, b, env))
+}
+
+pub fn f80_le_with_env(a: u128, b: u128, env: &mut Env) -> bool {
+    if f80_is_unordered_signaling(a, b, env) {
+        // Unable to compare
+        return false;
+    }
+
+    f80_le_ordered(a, b)
+}
+
+fn f80_le_ordered(a: u128, b: u128) -> bool {
+    f80_lt_ordered(a, b) || f80_eq_ordered(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags };
+
+    #[test]
+    fn test_f80_compare() {
+        // 0.1 and 0.2
+        assert_eq!(crate::soft_f80::f80_eq(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), false);
+        assert_eq!(crate::soft_f80::f80_ne(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), true);
+        assert_eq!(crate::soft_f80::f80_lt(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), true);
+        assert_eq!(crate::soft_f80::f80_le(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), true);
+        assert_eq!(crate::soft_f80::f80_gt(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), false);
+        assert_eq!(crate::soft_f80::f80_ge(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), false);
+
+        // -0.1 and -0.2
+        assert_eq!(crate::soft_f80::f80_lt(0xBFFBCCCCCCCCCCCCCCCD, 0xBFFCCCCCCCCCCCCCCCCD), false);
+        assert_eq!(crate::soft_f80::f80_gt(0xBFFBCCCCCCCCCCCCCCCD, 0xBFFCCCCCCCCCCCCCCCCD), true);
+
+        // 0 and -0
+        assert_eq!(crate::soft_f80::f80_eq(0x00000000000000000000, 0x80000000000000000000), true);
+        assert_eq!(crate::soft_f80::f80_lt(0x80000000000000000000, 0x00000000000000000000), false);
+        assert_eq!(crate::soft_f80::f80_le(0x80000000000000000000, 0x00000000000000000000), true);
+
+        // A pseudo-denormal equals the smallest normal number
+        assert_eq!(crate::soft_f80::f80_eq(0x00008000000000000000, 0x00018000000000000000), true);
+        assert_eq!(crate::soft_f80::f80_lt(0x00007FFFFFFFFFFFFFFF, 0x00008000000000000000), true);
+
+        // NaN is unordered
+        assert_eq!(crate::soft_f80::f80_eq(0x7FFFC000000000000000, 0x7FFFC000000000000000), false);
+        assert_eq!(crate::soft_f80::f80_ne(0x7FFFC000000000000000, 0x7FFFC000000000000000), true);
+        assert_eq!(crate::soft_f80::f80_lt(0x7FFFC000000000000000, 0x3FFF8000000000000000), false);
+        assert_eq!(crate::soft_f80::f80_ge(0x7FFFC000000000000000, 0x3FFF8000000000000000), false);
+    }
+
+    #[test]
+    fn test_f80_compare_exception_flags() {
+        // Equality with a quiet NaN is quiet
+        let mut env = Env::default();
+        crate::soft_f80::f80_eq_with_env(0x7FFFC000000000000000, 0x3FFF8000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+
+        // Equality with a signaling NaN is invalid
+        let mut env = Env::default();
+        crate::soft_f80::f80_eq_with_env(0x7FFF8000000000000001, 0x3FFF8000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // Ordering with a quiet NaN is invalid
+        let mut env = Env::default();
+        crate::soft_f80::f80_lt_with_env(0x7FFFC000000000000000, 0x3FFF8000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // An unnormal is unordered and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_eq_with_env(0x3FFF4000000000000000, 0x3FFF4000000000000000, &mut env), false);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,177 @@
+use super::util::{
+    f80_shift_right_jam,
+    f80_pack,
+    f80_sign, f80_exp, f80_frac,
+    f80_norm_subnormal_frac,
+    f80_is_unsupported,
+    F80_INTEGER_BIT,
+};
+
+use crate::soft_f32::util::{
+    f32_round_and_pack,
+    f32_pack_raw,
+    f32_sign, f32_exp, f32_frac,
+    f32_norm_subnormal_frac,
+    f32_default_nan,
+    f32_convert_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f32_to_f80(a: u32) -> u128 {
+    with_thread_env(|env| f32_to_f80_with_env(a, env))
+}
+
+// Every Float32 is exactly representable as a Float80
+pub fn f32_to_f80_with_env(a: u32, env: &mut Env) -> u128 {
+    let sign = f32_sign(a);
+    let mut exp = f32_exp(a);
+    let mut frac = f32_frac(a);
+
+    if exp == 0xFF {
+        if frac != 0 {
+            // NaN, as chosen by the NaN policy
+            let nan = f32_convert_nan(sign, (frac & 0x00400000) != 0, (frac & 0x003FFFFF) as u32, env);
+            return f80_pack(f32_sign(nan), 0x7FFF, F80_INTEGER_BIT | ((f32_frac(nan) as u64) << 40));
+        }
+        // Infinity
+        return f80_pack(sign, 0x7FFF, F80_INTEGER_BIT);
+    }
+
+    if exp == 0 {
+        if frac == 0 {
+            // Zero
+            return f80_pack(sign, 0, 0);
+        }
+        let (norm_exp, norm_frac) = f32_norm_subnormal_frac(frac);
+        exp = norm_exp;
+        frac = norm_frac;
+    }
+
+    f80_pack(sign, exp + 0x3F80, ((frac | 0x00800000) as u64) << 40)
+}
+
+pub fn f80_to_f32(a: u128) -> u32 {
+    with_thread_env(|env| f80_to_f32_with_env(a, env))
+}
+
+pub fn f80_to_f32_with_env(a: u128, env: &mut Env) -> u32 {
+    if f80_is_unsupported(a) {
+        // Invalid operand, return default NaN
+        env.raise(ExceptionFlags::INVALID);
+        return f32_default_nan(env);
+    }
+
+    let sign = f80_sign(a);
+    let mut exp = f80_exp(a);
+    let mut frac = f80_frac(a);
+
+    if exp == 0x7FFF {
+        if (frac & 0x7FFFFFFFFFFFFFFF) != 0 {
+            // NaN, as chosen by the NaN policy, with the high bits of its payload
+            let is_quiet_bit = (frac & 0x4000000000000000) != 0;
+            return f32_convert_nan(sign, is_quiet_bit, ((frac >> 40) & 0x003FFFFF) as u32, env);
+        }
+        // Infinity
+        return f32_pack_raw(sign, 0xFF, 0);
+    }
+
+    if frac == 0 {
+        // Zero
+        return f32_pack_raw(sign, 0, 0);
+    }
+
+    if exp == 0 {
+        let (norm_exp, norm_frac) = f80_norm_subnormal_frac(frac);
+        exp = norm_exp;
+        frac = norm_frac;
+    }
+
+    // Keep 31 bits with the integer bit in bit 30, and let Float32 round them
+    let frac32 = f80_shift_right_jam(frac, 33);
+    f32_round_and_pack(sign, exp - 0x3F81, frac32 as i32, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, NanPolicy, RoundingMode };
+
+    #[test]
+    fn test_f32_to_f80() {
+        // 1
+        assert_eq!(crate::soft_f80::f32_to_f80(0x3F800000), 0x3FFF8000000000000000);
+        // -0.1
+        assert_eq!(crate::soft_f80::f32_to_f80(0xBDCCCCCD), 0xBFFBCCCCCD0000000000);
+        // The smallest denormal is normal in Float80
+        assert_eq!(crate::soft_f80::f32_to_f80(0x00000001), 0x3F6A8000000000000000);
+        // -0
+        assert_eq!(crate::soft_f80::f32_to_f80(0x80000000), 0x80000000000000000000);
+        // Inf
+        assert_eq!(crate::soft_f80::f32_to_f80(0x7F800000), 0x7FFF8000000000000000);
+
+        // A signaling NaN is quiet and invalid
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f32_to_f80_with_env(0x7F800001, &mut env), 0x7FFFC000010000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f80_to_f32() {
+        // 1
+        assert_eq!(crate::soft_f80::f80_to_f32(0x3FFF8000000000000000), 0x3F800000);
+        // 0.1
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x3FFBCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3DCCCCCD);
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x3FFBCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3DCCCCCC);
+        // 1.5 * 2^-149 is a tie, rounded to even
+        assert_eq!(crate::soft_f80::f80_to_f32(0x3F6AC000000000000000), 0x00000002);
+        // NaN
+        assert_eq!(crate::soft_f80::f80_to_f32(0xFFFFC000000000000000), 0xFFC00000);
+    }
+
+    #[test]
+    fn test_f80_to_f32_exception_flags() {
+        // 2^256 overflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x40FF8000000000000000, &mut env), 0x7F800000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 2^-150 underflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x3F698000000000000000, &mut env), 0x00000000);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // A pseudo-NaN is an invalid operand
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x7FFF4000000000000000, &mut env), 0xFFC00000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+
+    #[test]
+    fn test_f80_conv_nan_policy() {
+        // The default NaN replaces every NaN, and a signaling NaN is invalid
+        for &nan_policy in [NanPolicy::RiscV, NanPolicy::Armv8DefaultNan].iter() {
+            let mut env = Env::default();
+            env.set_nan_policy(nan_policy);
+            assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x7FFFC000010000000000, &mut env), 0x7FC00000);
+            assert_eq!(crate::soft_f80::f32_to_f80_with_env(0xFFC00001, &mut env), 0x7FFFC000000000000000);
+            assert_eq!(env.flags(), ExceptionFlags::empty());
+            assert_eq!(crate::soft_f80::f80_to_f32_with_env(0xFFFF8000010000000000, &mut env), 0x7FC00000);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+            env.clear_flags();
+            assert_eq!(crate::soft_f80::f32_to_f80_with_env(0x7F800001, &mut env), 0x7FFFC000000000000000);
+            assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        }
+
+        // The quiet bit is inverted in MIPS legacy
+        let mut env = Env::default();
+        env.set_nan_policy(NanPolicy::MipsLegacy);
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x7FFF8000010000000000, &mut env), 0x7F800001);
+        assert_eq!(crate::soft_f80::f32_to_f80_with_env(0xFF800001, &mut env), 0xFFFF8000010000000000);
+        assert_eq!(env.flags(), ExceptionFlags::empty());
+        assert_eq!(crate::soft_f80::f80_to_f32_with_env(0x7FFFC000000000000000, &mut env), 0x7FBFFFFF);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+        env.clear_flags();
+        assert_eq!(crate::soft_f80::f32_to_f80_with_env(0x7FC00000, &mut env), 0x7FFFBFFFFF0000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
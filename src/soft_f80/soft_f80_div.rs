@@ -0,0 +1,170 @@
+use super::util::{
+    f80_round_and_pack,
+    f80_pack,
+    f80_propagate_nan,
+    f80_sign, f80_exp, f80_frac,
+    f80_norm_subnormal_frac,
+    f80_is_nan,
+    f80_is_unsupported,
+    F80_INTEGER_BIT,
+    f80_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f80_div(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f80_div_with_env(a, b, env))
+}
+
+pub fn f80_div_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    if f80_is_unsupported(a) || f80_is_unsupported(b) {
+        // Invalid operand, return default NaN
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    // Sign
+    let a_sign = f80_sign(a);
+    let b_sign = f80_sign(b);
+    let r_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f80_exp(a);
+    let mut b_exp = f80_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f80_frac(a);
+    let mut b_frac = f80_frac(b);
+
+    if a_exp == 0x7FFF {
+        if f80_is_nan(a) || f80_is_nan(b) {
+            return f80_propagate_nan(a, b, env);
+        }
+        if b_exp == 0x7FFF {
+            // Inf / Inf, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f80_default_nan(env);
+        }
+        // INFINITY
+        return f80_pack(r_sign, 0x7FFF, F80_INTEGER_BIT);
+    }
+    if b_exp == 0x7FFF {
+        if f80_is_nan(b) {
+            return f80_propagate_nan(a, b, env);
+        }
+        return f80_pack(r_sign, 0, 0);
+    }
+
+    if b_frac == 0 {
+        if a_frac == 0 {
+            // 0 / 0, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f80_default_nan(env);
+        }
+        // Infinity
+        env.raise(ExceptionFlags::DIVIDE_BY_ZERO);
+        return f80_pack(r_sign, 0x7FFF, F80_INTEGER_BIT);
+    }
+    if a_frac == 0 {
+        // Zero
+        return f80_pack(r_sign, 0, 0);
+    }
+
+    if a_exp == 0 {
+        let (exp, frac) = f80_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    if b_exp == 0 {
+        let (exp, frac) = f80_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+
+    r_exp = a_exp - b_exp + 0x3FFF;
+
+    // Use u128 to divide u64, so that the quotient has its integer bit set
+    let mut a_frac_u128 = a_frac as u128;
+    if a_frac < b_frac {
+        r_exp -= 1;
+        a_frac_u128 <<= 64;
+    } else {
+        a_frac_u128 <<= 63;
+    }
+    let b_frac_u128 = b_frac as u128;
+    let r_frac = a_frac_u128 / b_frac_u128;
+
+    // Divide the remainder again for the extra bits, and keep a sticky bit
+    let rem = (a_frac_u128 % b_frac_u128) << 64;
+    let mut r_frac_extra = rem / b_frac_u128;
+    if rem != r_frac_extra * b_frac_u128 {
+        r_frac_extra |= 0x01;
+    }
+
+    f80_round_and_pack(r_sign, r_exp, r_frac as u64, r_frac_extra as u64, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, RoundingPrecision };
+
+    #[test]
+    fn test_f80_div() {
+        // 1 / 3 = 0.333...
+        assert_eq!(crate::soft_f80::f80_div(0x3FFF8000000000000000, 0x4000C000000000000000), 0x3FFDAAAAAAAAAAAAAAAB);
+        // 6 / 3 = 2
+        assert_eq!(crate::soft_f80::f80_div(0x4001C000000000000000, 0x4000C000000000000000), 0x40008000000000000000);
+        // MIN_NORMAL / 4 = denormal
+        assert_eq!(crate::soft_f80::f80_div(0x00018000000000000000, 0x40018000000000000000), 0x00002000000000000000);
+    }
+
+    #[test]
+    fn test_f80_div_inf_nan() {
+        // 1 / 0 = Inf
+        assert_eq!(crate::soft_f80::f80_div(0x3FFF8000000000000000, 0x0), 0x7FFF8000000000000000);
+        // 0 / 0 = NaN
+        assert_eq!(crate::soft_f80::f80_is_nan(crate::soft_f80::f80_div(0x0, 0x0)), true);
+        // Inf / Inf = NaN
+        assert_eq!(crate::soft_f80::f80_is_nan(crate::soft_f80::f80_div(0x7FFF8000000000000000, 0x7FFF8000000000000000)), true);
+        // 1 / Inf = 0
+        assert_eq!(crate::soft_f80::f80_div(0x3FFF8000000000000000, 0x7FFF8000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f80_div_rounding_mode() {
+        // 1 / 3
+        assert_eq!(crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x4000C000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x3FFDAAAAAAAAAAAAAAAB);
+        assert_eq!(crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x4000C000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFDAAAAAAAAAAAAAAAA);
+        assert_eq!(crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x4000C000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FFDAAAAAAAAAAAAAAAB);
+    }
+
+    #[test]
+    fn test_f80_div_rounding_precision() {
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Single);
+        assert_eq!(crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x4000C000000000000000, &mut env), 0x3FFDAAAAAB0000000000);
+
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Double);
+        assert_eq!(crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x4000C000000000000000, &mut env), 0x3FFDAAAAAAAAAAAAA800);
+    }
+
+    #[test]
+    fn test_f80_div_exception_flags() {
+        // 1 / 0 divides by zero
+        let mut env = Env::default();
+        crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x0, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::DIVIDE_BY_ZERO);
+
+        // 0 / 0 is invalid
+        let mut env = Env::default();
+        crate::soft_f80::f80_div_with_env(0x0, 0x0, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // 1 / 3 is inexact
+        let mut env = Env::default();
+        crate::soft_f80::f80_div_with_env(0x3FFF8000000000000000, 0x4000C000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+}
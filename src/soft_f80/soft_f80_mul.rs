@@ -0,0 +1,148 @@
+use super::util::{
+    f80_round_and_pack,
+    f80_pack,
+    f80_propagate_nan,
+    f80_sign, f80_exp, f80_frac,
+    f80_norm_subnormal_frac,
+    f80_is_nan,
+    f80_is_unsupported,
+    F80_INTEGER_BIT,
+    f80_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+pub fn f80_mul(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f80_mul_with_env(a, b, env))
+}
+
+pub fn f80_mul_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    if f80_is_unsupported(a) || f80_is_unsupported(b) {
+        // Invalid operand, return default NaN
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    // Sign
+    let a_sign = f80_sign(a);
+    let b_sign = f80_sign(b);
+    let r_sign = a_sign ^ b_sign;
+
+    // Exp
+    let mut a_exp = f80_exp(a);
+    let mut b_exp = f80_exp(b);
+    let mut r_exp;
+
+    // Frac
+    let mut a_frac = f80_frac(a);
+    let mut b_frac = f80_frac(b);
+
+    if a_exp == 0x7FFF || b_exp == 0x7FFF {
+        // NaN
+        if f80_is_nan(a) || f80_is_nan(b) {
+            return f80_propagate_nan(a, b, env);
+        }
+
+        // Inf
+        if a_frac == 0 || b_frac == 0 {
+            // Inf x 0, invalid, return default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f80_default_nan(env);
+        }
+
+        return f80_pack(r_sign, 0x7FFF, F80_INTEGER_BIT);
+    }
+
+    if a_frac == 0 || b_frac == 0 {
+        // Zero
+        return f80_pack(r_sign, 0, 0);
+    }
+
+    if a_exp == 0 {
+        let (exp, frac) = f80_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+    if b_exp == 0 {
+        let (exp, frac) = f80_norm_subnormal_frac(b_frac);
+        b_exp = exp;
+        b_frac = frac;
+    }
+
+    r_exp = a_exp + b_exp - 0x3FFE;
+
+    // Use u128 to multiply u64
+    let mut frac_prod: u128 = (a_frac as u128) * (b_frac as u128);
+
+    if frac_prod < (1 << 127) {
+        r_exp -= 1;
+        frac_prod <<= 1;
+    }
+
+    f80_round_and_pack(r_sign, r_exp, (frac_prod >> 64) as u64, frac_prod as u64, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, RoundingPrecision };
+
+    #[test]
+    fn test_f80_mul() {
+        // 0.1 * 0.2 = 0.02
+        assert_eq!(crate::soft_f80::f80_mul(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), 0x3FF9A3D70A3D70A3D70B);
+        // -0.1 * 0.2 = -0.02
+        assert_eq!(crate::soft_f80::f80_mul(0xBFFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), 0xBFF9A3D70A3D70A3D70B);
+        // 2 * 3 = 6
+        assert_eq!(crate::soft_f80::f80_mul(0x40008000000000000000, 0x4000C000000000000000), 0x4001C000000000000000);
+
+        // 1 * pseudo-denormal is normalized
+        assert_eq!(crate::soft_f80::f80_mul(0x3FFF8000000000000000, 0x00008000000000000000), 0x00018000000000000000);
+    }
+
+    #[test]
+    fn test_f80_mul_inf_nan() {
+        // Inf * 0 = NaN
+        assert_eq!(crate::soft_f80::f80_is_nan(crate::soft_f80::f80_mul(0x7FFF8000000000000000, 0x0)), true);
+        // Inf * -1 = -Inf
+        assert_eq!(crate::soft_f80::f80_mul(0x7FFF8000000000000000, 0xBFFF8000000000000000), 0xFFFF8000000000000000);
+        // NaN * 1 = NaN
+        assert_eq!(crate::soft_f80::f80_mul(0x7FFFC000000000000001, 0x3FFF8000000000000000), 0x7FFFC000000000000001);
+    }
+
+    #[test]
+    fn test_f80_mul_rounding_mode() {
+        // 0.1 * 0.2
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::NearEven)), 0x3FF9A3D70A3D70A3D70B);
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::MinMag)), 0x3FF9A3D70A3D70A3D70A);
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut Env::new(RoundingMode::Max)), 0x3FF9A3D70A3D70A3D70B);
+    }
+
+    #[test]
+    fn test_f80_mul_rounding_precision() {
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Single);
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut env), 0x3FF9A3D70A0000000000);
+
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Double);
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD, &mut env), 0x3FF9A3D70A3D70A3D800);
+    }
+
+    #[test]
+    fn test_f80_mul_exception_flags() {
+        // MAX * 2 overflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x7FFEFFFFFFFFFFFFFFFF, 0x40008000000000000000, &mut env), 0x7FFF8000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // 0.75 * the smallest denormal underflows
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x3FFEC000000000000000, 0x00000000000000000001, &mut env), 0x00000000000000000001);
+        assert_eq!(env.flags(), ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+
+        // A pseudo-infinity is an invalid operand
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_mul_with_env(0x7FFF0000000000000000, 0x3FFF8000000000000000, &mut env), 0xFFFFC000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,166 @@
+use super::util::{
+    f80_round_and_pack,
+    f80_propagate_nan,
+    f80_sign, f80_exp, f80_frac,
+    f80_norm_subnormal_frac,
+    f80_is_nan,
+    f80_is_unsupported,
+    f80_default_nan,
+};
+
+use crate::soft_float::{ Env, ExceptionFlags, with_thread_env };
+
+// Integer square root, rounded down
+fn f80_isqrt(a: u128) -> u128 {
+    let mut rem = a;
+    let mut root = 0;
+    let mut bit = 1 << 126;
+
+    while bit > a {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if rem >= root + bit {
+            rem -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    root
+}
+
+pub fn f80_sqrt(a: u128) -> u128 {
+    with_thread_env(|env| f80_sqrt_with_env(a, env))
+}
+
+pub fn f80_sqrt_with_env(a: u128, env: &mut Env) -> u128 {
+    if f80_is_unsupported(a) {
+        // Invalid operand, return default NaN
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    // Sign
+    let a_sign = f80_sign(a);
+
+    // Exp
+    let mut a_exp = f80_exp(a);
+
+    // Frac
+    let mut a_frac = f80_frac(a);
+
+    if a_exp == 0x7FFF {
+        if f80_is_nan(a) {
+            return f80_propagate_nan(a, 0, env);
+        }
+        if a_sign == 0 {
+            return a;
+        }
+
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    if a_frac == 0 {
+        // 0 or -0
+        return a;
+    }
+
+    if a_sign != 0 {
+        // Invalid
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    if a_exp == 0 {
+        let (exp, frac) = f80_norm_subnormal_frac(a_frac);
+        a_exp = exp;
+        a_frac = frac;
+    }
+
+    let r_exp = ((a_exp - 0x3FFF) >> 1) + 0x3FFF;
+
+    // Make the exponent even, so that the root has its integer bit set
+    let a_frac_u128 = if (a_exp - 0x3FFF) & 1 != 0 {
+        (a_frac as u128) << 64
+    } else {
+        (a_frac as u128) << 63
+    };
+    let r_frac = f80_isqrt(a_frac_u128);
+
+    // The root is never exactly halfway, compare the remainder with it to get the extra bits
+    let rem = a_frac_u128 - r_frac * r_frac;
+    let r_frac_extra = if rem == 0 {
+        0
+    } else if rem > r_frac {
+        0xC000000000000000
+    } else {
+        0x4000000000000000
+    };
+
+    f80_round_and_pack(0, r_exp, r_frac as u64, r_frac_extra, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, RoundingPrecision };
+
+    #[test]
+    fn test_f80_sqrt() {
+        // sqrt(2) = 1.4142135623730950488
+        assert_eq!(crate::soft_f80::f80_sqrt(0x40008000000000000000), 0x3FFFB504F333F9DE6484);
+        // sqrt(4) = 2
+        assert_eq!(crate::soft_f80::f80_sqrt(0x40018000000000000000), 0x40008000000000000000);
+        // sqrt of the smallest denormal
+        assert_eq!(crate::soft_f80::f80_sqrt(0x00000000000000000001), 0x1FE0B504F333F9DE6484);
+        // sqrt(-0) = -0
+        assert_eq!(crate::soft_f80::f80_sqrt(0x80000000000000000000), 0x80000000000000000000);
+    }
+
+    #[test]
+    fn test_f80_sqrt_inf_nan() {
+        // sqrt(Inf) = Inf
+        assert_eq!(crate::soft_f80::f80_sqrt(0x7FFF8000000000000000), 0x7FFF8000000000000000);
+        // sqrt(-1) = NaN
+        assert_eq!(crate::soft_f80::f80_is_nan(crate::soft_f80::f80_sqrt(0xBFFF8000000000000000)), true);
+    }
+
+    #[test]
+    fn test_f80_sqrt_rounding_mode() {
+        // sqrt(2)
+        assert_eq!(crate::soft_f80::f80_sqrt_with_env(0x40008000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFFB504F333F9DE6484);
+        assert_eq!(crate::soft_f80::f80_sqrt_with_env(0x40008000000000000000, &mut Env::new(RoundingMode::Max)), 0x3FFFB504F333F9DE6485);
+    }
+
+    #[test]
+    fn test_f80_sqrt_rounding_precision() {
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Single);
+        assert_eq!(crate::soft_f80::f80_sqrt_with_env(0x40008000000000000000, &mut env), 0x3FFFB504F30000000000);
+
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Double);
+        assert_eq!(crate::soft_f80::f80_sqrt_with_env(0x40008000000000000000, &mut env), 0x3FFFB504F333F9DE6800);
+    }
+
+    #[test]
+    fn test_f80_sqrt_exception_flags() {
+        // sqrt(-1) is invalid
+        let mut env = Env::default();
+        crate::soft_f80::f80_sqrt_with_env(0xBFFF8000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+
+        // sqrt(2) is inexact
+        let mut env = Env::default();
+        crate::soft_f80::f80_sqrt_with_env(0x40008000000000000000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        // An unnormal is an invalid operand
+        let mut env = Env::default();
+        assert_eq!(crate::soft_f80::f80_sqrt_with_env(0x40004000000000000000, &mut env), 0xFFFFC000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INVALID);
+    }
+}
@@ -0,0 +1,136 @@
+use super::util::{
+    f80_shift_right_jam_extra,
+    f80_norm_round_and_pack,
+    f80_pack,
+    f80_propagate_nan,
+    f80_sign, f80_exp, f80_frac,
+    f80_is_nan,
+    f80_is_unsupported,
+    F80_INTEGER_BIT,
+    f80_default_nan,
+};
+
+use super::soft_f80_add::f80_add_mags;
+
+use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, with_thread_env };
+
+pub fn f80_sub(a: u128, b: u128) -> u128 {
+    with_thread_env(|env| f80_sub_with_env(a, b, env))
+}
+
+pub fn f80_sub_with_env(a: u128, b: u128, env: &mut Env) -> u128 {
+    if f80_is_unsupported(a) || f80_is_unsupported(b) {
+        // Invalid operand, return default NaN
+        env.raise(ExceptionFlags::INVALID);
+        return f80_default_nan(env);
+    }
+
+    if f80_sign(a) != f80_sign(b) {
+        // Consider as addition
+        return f80_add_mags(a, b, env);
+    }
+
+    f80_sub_mags(a, b, env)
+}
+
+// Subtract the magnitude of B from the magnitude of A, the result has the sign of A
+pub(crate) fn f80_sub_mags(a: u128, b: u128, env: &mut Env) -> u128 {
+    // Sign
+    let a_sign = f80_sign(a);
+    let mut r_sign = a_sign;
+
+    // Exp
+    let mut a_exp = f80_exp(a);
+    let mut b_exp = f80_exp(b);
+
+    // Frac
+    let mut a_frac = f80_frac(a);
+    let mut b_frac = f80_frac(b);
+
+    if a_exp == 0x7FFF || b_exp == 0x7FFF {
+        if f80_is_nan(a) || f80_is_nan(b) {
+            // Propagate NaN
+            return f80_propagate_nan(a, b, env);
+        }
+
+        if a_exp == b_exp {
+            // Inf - Inf, invalid, return the default NaN
+            env.raise(ExceptionFlags::INVALID);
+            return f80_default_nan(env);
+        }
+        if a_exp == 0x7FFF {
+            // Infinity
+            return a;
+        }
+        // Infinity of the opposite sign
+        return f80_pack(r_sign ^ 1, 0x7FFF, F80_INTEGER_BIT);
+    }
+
+    // Denormals have the exponent of the smallest normal numbers
+    a_exp = a_exp.max(1);
+    b_exp = b_exp.max(1);
+
+    if a_exp == b_exp && a_frac == b_frac {
+        // Same, will cause a 0, which is negative only when rounding toward negative
+        let zero_sign = (env.rounding_mode() == RoundingMode::Min) as i32;
+        return f80_pack(zero_sign, 0, 0);
+    }
+
+    if a_exp < b_exp || (a_exp == b_exp && a_frac < b_frac) {
+        // B is greater
+        std::mem::swap(&mut a_exp, &mut b_exp);
+        std::mem::swap(&mut a_frac, &mut b_frac);
+        r_sign ^= 1;
+    }
+
+    // Align the fraction of B with the one of A
+    let (b_frac, b_frac_extra) = f80_shift_right_jam_extra(b_frac, 0, a_exp - b_exp);
+
+    let r = ((a_frac as u128) << 64) - (((b_frac as u128) << 64) | b_frac_extra as u128);
+
+    f80_norm_round_and_pack(r_sign, a_exp, (r >> 64) as u64, r as u64, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode, RoundingPrecision };
+
+    #[test]
+    fn test_f80_sub() {
+        // 0.1 - 0.2 = -0.1
+        assert_eq!(crate::soft_f80::f80_sub(0x3FFBCCCCCCCCCCCCCCCD, 0x3FFCCCCCCCCCCCCCCCCD), 0xBFFBCCCCCCCCCCCCCCCD);
+        // 3 - 2 = 1
+        assert_eq!(crate::soft_f80::f80_sub(0x4000C000000000000000, 0x40008000000000000000), 0x3FFF8000000000000000);
+        // 1 - 1 = 0
+        assert_eq!(crate::soft_f80::f80_sub(0x3FFF8000000000000000, 0x3FFF8000000000000000), 0x0);
+    }
+
+    #[test]
+    fn test_f80_sub_inf_nan() {
+        // Inf - Inf = NaN
+        assert_eq!(crate::soft_f80::f80_is_nan(crate::soft_f80::f80_sub(0x7FFF8000000000000000, 0x7FFF8000000000000000)), true);
+        // Inf - 1 = Inf
+        assert_eq!(crate::soft_f80::f80_sub(0x7FFF8000000000000000, 0x3FFF8000000000000000), 0x7FFF8000000000000000);
+        // 1 - Inf = -Inf
+        assert_eq!(crate::soft_f80::f80_sub(0x3FFF8000000000000000, 0x7FFF8000000000000000), 0xFFFF8000000000000000);
+    }
+
+    #[test]
+    fn test_f80_sub_rounding_mode() {
+        // 1 - 1 is -0 when rounding toward negative infinity
+        assert_eq!(crate::soft_f80::f80_sub_with_env(0x3FFF8000000000000000, 0x3FFF8000000000000000, &mut Env::new(RoundingMode::Min)), 0x80000000000000000000);
+
+        // 2 - 2^-64
+        assert_eq!(crate::soft_f80::f80_sub_with_env(0x40008000000000000000, 0x3FBF8000000000000000, &mut Env::new(RoundingMode::NearEven)), 0x40008000000000000000);
+        assert_eq!(crate::soft_f80::f80_sub_with_env(0x40008000000000000000, 0x3FBF8000000000000000, &mut Env::new(RoundingMode::MinMag)), 0x3FFFFFFFFFFFFFFFFFFF);
+    }
+
+    #[test]
+    fn test_f80_sub_rounding_precision() {
+        // 1 - 2^-64, rounded to 24 bits
+        let mut env = Env::default();
+        env.set_rounding_precision(RoundingPrecision::Single);
+        assert_eq!(crate::soft_f80::f80_sub_with_env(0x3FFF8000000000000000, 0x3FBF8000000000000000, &mut env), 0x3FFF8000000000000000);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+    }
+}
@@ -0,0 +1,369 @@
+pub(crate) fn f80_shift_right_jam(a: u64, dist: i32) -> u64 {
+    if dist < 63 {
+        if (a << (-dist & 63)) != 0 {
+            (a >> dist) | 1
+        } else {
+            a >> dist
+        }
+    } else {
+        if a != 0 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+// Shift the significand and its extra bits right, the lost bits are jammed into the extra bits
+pub(crate) fn f80_shift_right_jam_extra(frac: u64, frac_extra: u64, dist: i32) -> (u64, u64) {
+    if dist == 0 {
+        return (frac, frac_extra);
+    }
+
+    let a = ((frac as u128) << 64) | frac_extra as u128;
+    let r = if dist < 128 {
+        (a >> dist) | ((a << (128 - dist)) != 0) as u128
+    } else {
+        (a != 0) as u128
+    };
+
+    ((r >> 64) as u64, r as u64)
+}
+
+use crate::soft_float::{ RoundingMode, RoundingPrecision, DetectTininess, Env, ExceptionFlags, NanPolicy };
+use crate::soft_f64::util::f64_count_leading_zero;
+
+// The integer bit, which is explicit in Float80
+pub(crate) const F80_INTEGER_BIT: u64 = 0x8000000000000000;
+
+pub(crate) fn f80_sign(a: u128) -> i32 {
+    ((a >> 79) & 0x01) as i32
+}
+
+pub(crate) fn f80_exp(a: u128) -> i32 {
+    ((a >> 64) & 0x7FFF) as i32
+}
+
+// The significand, including the integer bit
+pub(crate) fn f80_frac(a: u128) -> u64 {
+    (a & 0xFFFFFFFFFFFFFFFF) as u64
+}
+
+// The default NaN of the NaN policy of the environment
+pub(crate) fn f80_default_nan(env: &Env) -> u128 {
+    match env.nan_policy() {
+        NanPolicy::X87 | NanPolicy::X86Sse => 0xFFFFC000000000000000,
+        NanPolicy::MipsLegacy => 0x7FFFBFFFFFFFFFFFFFFF,
+        _ => 0x7FFFC000000000000000,
+    }
+}
+
+pub fn f80_is_nan(a: u128) -> bool {
+    let is_exp_nan = f80_exp(a) == 0x7FFF;
+    let is_frac_nan = (f80_frac(a) & 0x7FFFFFFFFFFFFFFF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// A signaling NaN has the most significant bit of its fraction cleared
+pub fn f80_is_signaling_nan(a: u128) -> bool {
+    let is_exp_nan = f80_exp(a) == 0x7FFF && (f80_frac(a) & 0x4000000000000000) == 0;
+    let is_frac_nan = (f80_frac(a) & 0x3FFFFFFFFFFFFFFF) != 0;
+
+    is_exp_nan && is_frac_nan
+}
+
+// Unnormals, pseudo-infinities and pseudo-NaNs have a non-zero exponent but no integer bit.
+// The x87 FPU rejects them as invalid operands since the 80387.
+pub fn f80_is_unsupported(a: u128) -> bool {
+    f80_exp(a) != 0 && (f80_frac(a) & F80_INTEGER_BIT) == 0
+}
+
+// Whether A is a signaling NaN in the encoding of the NaN policy, where MIPS legacy
+// inverts the quiet bit
+fn f80_is_signaling_nan_in(a: u128, env: &Env) -> bool {
+    match env.nan_policy() {
+        NanPolicy::MipsLegacy => (a & 0x7FFFC000000000000000) == 0x7FFFC000000000000000,
+        _ => f80_is_signaling_nan(a),
+    }
+}
+
+// The NaN result of an operation on A and B, at least one of which is a NaN, chosen by the
+// NaN policy of the environment. A signaling NaN is invalid.
+pub(crate) fn f80_propagate_nan(a: u128, b: u128, env: &mut Env) -> u128 {
+    f80_propagate_nans(&[a, b], env)
+}
+
+// The NaN result of the operands, in the order of the instruction
+fn f80_propagate_nans(ops: &[u128], env: &mut Env) -> u128 {
+    if env.nan_policy() == NanPolicy::X87 {
+        // Pairwise, in order
+        return ops[1..].iter().fold(ops[0], |r, &op| f80_propagate_nan_x87(r, op, env));
+    }
+
+    let default_nan = f80_default_nan(env);
+    let first_signaling = ops.iter().copied().find(|&op| f80_is_signaling_nan_in(op, env));
+    let first_nan = ops.iter().copied().find(|&op| f80_is_nan(op)).unwrap_or(default_nan);
+    if first_signaling.is_some() {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+    }
+
+    match env.nan_policy() {
+        NanPolicy::X86Sse => first_nan | 0x4000000000000000,
+        NanPolicy::Armv8 => first_signaling.unwrap_or(first_nan) | 0x4000000000000000,
+        // Quieting a NaN could give infinity
+        NanPolicy::MipsLegacy if first_signaling.is_some() => default_nan,
+        NanPolicy::MipsLegacy => first_nan,
+        _ => default_nan,
+    }
+}
+
+fn f80_propagate_nan_x87(in_a: u128, in_b: u128, env: &mut Env) -> u128 {
+    let a = in_a | 0x4000000000000000;
+    let b = in_b | 0x4000000000000000;
+
+    let is_a_frac_nan = f80_is_signaling_nan(in_a);
+    let is_b_frac_nan = f80_is_signaling_nan(in_b);
+
+    if is_a_frac_nan | is_b_frac_nan {
+        // A signaling NaN is an invalid operand
+        env.raise(ExceptionFlags::INVALID);
+        if is_a_frac_nan {
+            if !is_b_frac_nan {
+                return match f80_is_nan(b) {
+                    true => b,
+                    false => a,
+                };
+            }
+        } else {
+            return match f80_is_nan(a) {
+                true => a,
+                false => b,
+            };
+        }
+    }
+
+    // Return the NaN with the larger magnitude
+    let a_mag = a & 0x7FFFFFFFFFFFFFFFFFFF;
+    let b_mag = b & 0x7FFFFFFFFFFFFFFFFFFF;
+
+    if a_mag < b_mag {
+        b
+    } else if b_mag < a_mag || a < b {
+        a
+    } else {
+        b
+    }
+}
+
+pub fn f80_pack(in_sign: i32, in_exp: i32, in_frac: u64) -> u128 {
+    (((in_sign & 0x01) as u128) << 79) | (((in_exp & 0x7FFF) as u128) << 64) | in_frac as u128
+}
+
+fn f80_overflow(in_sign: i32, round_mask: u64, env: &mut Env) -> u128 {
+    let rounding_mode = env.rounding_mode();
+
+    // Overflow and inexact, round to infinity or to the largest finite value
+    env.raise(ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+    let round_to_inf = match rounding_mode {
+        RoundingMode::NearEven | RoundingMode::NearMaxMag => true,
+        RoundingMode::Min => in_sign == 1,
+        RoundingMode::Max => in_sign == 0,
+        _ => false,
+    };
+    if round_to_inf {
+        f80_pack(in_sign, 0x7FFF, F80_INTEGER_BIT)
+    } else {
+        f80_pack(in_sign, 0x7FFE, !round_mask)
+    }
+}
+
+// Round the significand and its extra bits to the rounding precision of the environment.
+// The integer bit of the significand is set, unless the exponent is not positive.
+pub(crate) fn f80_round_and_pack(in_sign: i32, in_exp: i32, in_frac: u64, in_frac_extra: u64, env: &mut Env) -> u128 {
+    let rounding_mode = env.rounding_mode();
+    let detect_tininess = env.detect_tininess();
+
+    let round_near_even = rounding_mode == RoundingMode::NearEven;
+    let round_near = round_near_even || rounding_mode == RoundingMode::NearMaxMag;
+    // Round away from zero only when rounding toward the infinity of the same sign
+    let round_away = match rounding_mode {
+        RoundingMode::Min => in_sign == 1,
+        RoundingMode::Max => in_sign == 0,
+        _ => false,
+    };
+
+    let sign = in_sign;
+    let mut exp = in_exp;
+    let mut frac = in_frac;
+    let mut frac_extra = in_frac_extra;
+
+    let mut round_mask: u64 = match env.rounding_precision() {
+        RoundingPrecision::Single => 0x000000FFFFFFFFFF,
+        RoundingPrecision::Double => 0x00000000000007FF,
+        RoundingPrecision::Extended => 0,
+    };
+
+    if round_mask != 0 {
+        // Reduced precision, the bits to round are in the significand
+        frac |= (frac_extra != 0) as u64;
+        let mut round_increment = if round_near {
+            (round_mask >> 1) + 1
+        } else if round_away {
+            round_mask
+        } else {
+            0
+        };
+        let round_bits = frac & round_mask;
+
+        // A non positive exponent is seen as a large unsigned one, so that tiny results are caught here as well
+        if (exp - 1) as u32 >= 0x7FFD {
+            if exp <= 0 {
+                // Tiny result, denormalize it before rounding
+                let is_tiny = detect_tininess == DetectTininess::Before
+                    || exp < 0
+                    || frac.checked_add(round_increment).is_some();
+                frac = f80_shift_right_jam(frac, 1 - exp);
+                let round_bits = frac & round_mask;
+                if round_bits != 0 {
+                    if is_tiny {
+                        // Underflow
+                        env.raise(ExceptionFlags::UNDERFLOW);
+                    }
+                    env.raise(ExceptionFlags::INEXACT);
+                    if rounding_mode == RoundingMode::Odd {
+                        // Jam the lost bits into the least significant bit
+                        frac |= round_mask + 1;
+                    }
+                }
+                frac += round_increment;
+                // Rounding may carry into the integer bit, which makes a normal number
+                exp = ((frac & F80_INTEGER_BIT) != 0) as i32;
+                round_increment = round_mask + 1;
+                if round_near_even && (round_bits << 1) == round_increment {
+                    // Tie, round to even
+                    round_mask |= round_increment;
+                }
+                return f80_pack(sign, exp, frac & !round_mask);
+            }
+            if exp > 0x7FFE || (exp == 0x7FFE && frac.checked_add(round_increment).is_none()) {
+                return f80_overflow(sign, round_mask, env);
+            }
+        }
+
+        if round_bits != 0 {
+            env.raise(ExceptionFlags::INEXACT);
+            if rounding_mode == RoundingMode::Odd {
+                // Inexact, jam the lost bits into the least significant bit
+                return f80_pack(sign, exp, (frac & !round_mask) | (round_mask + 1));
+            }
+        }
+        let (r_frac, carry) = frac.overflowing_add(round_increment);
+        frac = r_frac;
+        if carry {
+            exp += 1;
+            frac = F80_INTEGER_BIT;
+        }
+        round_increment = round_mask + 1;
+        if round_near_even && (round_bits << 1) == round_increment {
+            // Tie, round to even
+            round_mask |= round_increment;
+        }
+        return f80_pack(sign, exp, frac & !round_mask);
+    }
+
+    // Full precision, the bits to round are the extra bits
+    let increment = |frac_extra: u64| if round_near {
+        frac_extra >= 0x8000000000000000
+    } else {
+        round_away && frac_extra != 0
+    };
+    let mut do_increment = increment(frac_extra);
+
+    if (exp - 1) as u32 >= 0x7FFD {
+        if exp <= 0 {
+            // Tiny result, denormalize it before rounding
+            let is_tiny = detect_tininess == DetectTininess::Before
+                || exp < 0
+                || !do_increment
+                || frac < 0xFFFFFFFFFFFFFFFF;
+            let (r_frac, r_frac_extra) = f80_shift_right_jam_extra(frac, frac_extra, 1 - exp);
+            frac = r_frac;
+            frac_extra = r_frac_extra;
+            if frac_extra != 0 {
+                if is_tiny {
+                    // Underflow
+                    env.raise(ExceptionFlags::UNDERFLOW);
+                }
+                env.raise(ExceptionFlags::INEXACT);
+                if rounding_mode == RoundingMode::Odd {
+                    // Jam the lost bits into the least significant bit
+                    return f80_pack(sign, 0, frac | 1);
+                }
+            }
+            do_increment = increment(frac_extra);
+            if do_increment {
+                frac += 1;
+                if round_near_even && (frac_extra & 0x7FFFFFFFFFFFFFFF) == 0 {
+                    // Tie, round to even
+                    frac &= !1;
+                }
+            }
+            // Rounding may carry into the integer bit, which makes a normal number
+            exp = ((frac & F80_INTEGER_BIT) != 0) as i32;
+            return f80_pack(sign, exp, frac);
+        }
+        if exp > 0x7FFE || (exp == 0x7FFE && frac == 0xFFFFFFFFFFFFFFFF && do_increment) {
+            return f80_overflow(sign, 0, env);
+        }
+    }
+
+    if frac_extra != 0 {
+        env.raise(ExceptionFlags::INEXACT);
+        if rounding_mode == RoundingMode::Odd {
+            // Inexact, jam the lost bits into the least significant bit
+            return f80_pack(sign, exp, frac | 1);
+        }
+    }
+    if do_increment {
+        frac = frac.wrapping_add(1);
+        if frac == 0 {
+            exp += 1;
+            frac = F80_INTEGER_BIT;
+        } else if round_near_even && (frac_extra & 0x7FFFFFFFFFFFFFFF) == 0 {
+            // Tie, round to even
+            frac &= !1;
+        }
+    }
+
+    f80_pack(sign, exp, frac)
+}
+
+pub(crate) fn f80_norm_round_and_pack(in_sign: i32, in_exp: i32, in_frac: u64, in_frac_extra: u64, env: &mut Env) -> u128 {
+    let mut exp = in_exp;
+    let mut frac = in_frac;
+    let mut frac_extra = in_frac_extra;
+
+    if frac == 0 {
+        exp -= 64;
+        frac = frac_extra;
+        frac_extra = 0;
+    }
+
+    let shift_count = f64_count_leading_zero(frac as i64);
+    exp -= shift_count;
+    if shift_count != 0 {
+        frac = (frac << shift_count) | (frac_extra >> (64 - shift_count));
+        frac_extra <<= shift_count;
+    }
+
+    f80_round_and_pack(in_sign, exp, frac, frac_extra, env)
+}
+
+// Normalize the significand of a denormal, which has the exponent of the smallest normal numbers
+pub(crate) fn f80_norm_subnormal_frac(frac: u64) -> (i32, u64) {
+    let shift_count = f64_count_leading_zero(frac as i64);
+
+    (1 - shift_count, frac << shift_count)
+}
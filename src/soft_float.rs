@@ -1,13 +1,463 @@
+use std::cell::Cell;
+use std::ops;
+
+/// Rounding direction applied when a result cannot be represented exactly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum RoundingMode {
+    /// Round to nearest, ties to even (IEEE 754 default)
+    #[default]
     NearEven,
+    /// Round toward zero
     MinMag,
+    /// Round toward negative infinity
     Min,
+    /// Round toward positive infinity
     Max,
+    /// Round to nearest, ties away from zero
     NearMaxMag,
+    /// Round to odd (jamming), useful to avoid double rounding
     Odd,
 }
 
+/// Whether tininess is detected before or after rounding.
+///
+/// A tiny and inexact result raises underflow. With `Before` a result is tiny when it is
+/// below the smallest normal number before rounding (ARM), with `After` when it would still be
+/// below it after rounding to the precision with an unbounded exponent (x86).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum DetectTininess {
     Before,
+    #[default]
     After,
 }
+
+/// Precision results of Float80 operations are rounded to, as the x87 precision control field.
+///
+/// The exponent keeps its extended range with every precision.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingPrecision {
+    /// 24-bit significand
+    Single,
+    /// 53-bit significand
+    Double,
+    /// 64-bit significand
+    #[default]
+    Extended,
+}
+
+/// Result of a conversion to an integer which is invalid, because the value is a NaN or is
+/// out of the range of the integer type.
+///
+/// The invalid flag is raised with every behavior.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntOverflow {
+    /// Saturate to the smallest or largest integer, a NaN gives 0 (ARM, Rust `as`)
+    #[default]
+    Saturate,
+    /// Saturate to the smallest or largest integer, a NaN gives the largest (RISC-V)
+    SaturateNanMax,
+    /// Always give the integer indefinite value, the smallest signed or the largest unsigned integer (x86)
+    Indefinite,
+}
+
+/// Behavior of `min` and `max`, which differ between ISAs for NaNs and signed zeros.
+///
+/// A signaling NaN operand is invalid with every behavior.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MinMax {
+    /// IEEE 754-2019 minimumNumber and maximumNumber, a NaN operand is ignored and -0 < +0 (C `fmin`)
+    #[default]
+    Number,
+    /// IEEE 754-2019 minimum and maximum, a NaN operand gives NaN and -0 < +0 (ARM `FMIN`, WebAssembly)
+    Propagate,
+    /// IEEE 754-2008 minNum and maxNum, as `Number` but a signaling NaN gives NaN (ARM `FMINNM`)
+    Num2008,
+    /// `a < b ? a : b`, the second operand when unordered or equal, and any NaN is invalid (x86 `MINSS`)
+    Select,
+    /// As `Number`, but two NaN operands give the canonical NaN (RISC-V `fmin.s`)
+    NumberCanonicalNan,
+}
+
+/// How the NaN result of an operation is chosen, which differs between ISAs.
+///
+/// A signaling NaN operand is invalid with every policy. An invalid operation without a NaN
+/// operand gives the default NaN of the policy.
+///
+/// The NaNs below are those of Float32. Float16, BFloat16, Float64, Float80 and Float128 follow
+/// the same rules in their own encoding, as do the conversions between formats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// The quiet NaN operand over a signaling one, then the larger significand, quieted.
+    /// The default NaN is 0xFFC00000 (x87, and Berkeley SoftFloat 8086).
+    #[default]
+    X87,
+    /// The first NaN operand, quieted. The default NaN is 0xFFC00000 (x86 SSE and AVX).
+    X86Sse,
+    /// The first signaling NaN operand, then the first quiet one, quieted. A fused multiply-add
+    /// takes the addend first. The default NaN is 0x7FC00000 (ARMv8 with FPCR.DN clear).
+    Armv8,
+    /// Always the default NaN 0x7FC00000 (ARMv8 with FPCR.DN set)
+    Armv8DefaultNan,
+    /// Always the canonical NaN 0x7FC00000 (RISC-V)
+    RiscV,
+    /// The quiet bit is set in a signaling NaN. A signaling NaN operand gives the default NaN
+    /// 0x7FBFFFFF, otherwise the first NaN operand (MIPS before release 6).
+    MipsLegacy,
+    /// Always the canonical NaN 0x7FC00000, as the deterministic profile of WebAssembly
+    Wasm,
+}
+
+/// Whether a subnormal result is flushed to a zero of the same sign, which is not IEEE 754 compliant.
+///
+/// A result is flushed when it is tiny, which is detected before or after rounding as set by
+/// [`DetectTininess`]. Subnormal operands are flushed separately, with `denormals_are_zero`.
+///
+/// Both apply to every Float32 operation of an environment, the elementary functions and the
+/// conversions to integers included, but not to `next_up`, `next_down`, `next_after` and `ulp`,
+/// which step through the subnormals, nor to parsing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushToZero {
+    /// Subnormal results are kept
+    #[default]
+    Off,
+    /// A tiny result is flushed and raises underflow only, even when exact. Used with tininess
+    /// detected before rounding (ARMv8 FPCR.FZ).
+    Underflow,
+    /// A tiny result is flushed and raises underflow and inexact. Used with tininess detected
+    /// after rounding (x86 MXCSR.FTZ).
+    UnderflowInexact,
+}
+
+/// Sticky IEEE 754 exception flags.
+///
+/// Flags are only ever raised by operations, the caller is responsible for clearing them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExceptionFlags(u8);
+
+impl ExceptionFlags {
+    pub const INEXACT: ExceptionFlags = ExceptionFlags(0x01);
+    pub const UNDERFLOW: ExceptionFlags = ExceptionFlags(0x02);
+    pub const OVERFLOW: ExceptionFlags = ExceptionFlags(0x04);
+    pub const DIVIDE_BY_ZERO: ExceptionFlags = ExceptionFlags(0x08);
+    pub const INVALID: ExceptionFlags = ExceptionFlags(0x10);
+
+    pub fn empty() -> ExceptionFlags {
+        ExceptionFlags(0)
+    }
+
+    pub fn all() -> ExceptionFlags {
+        ExceptionFlags(0x1F)
+    }
+
+    pub fn from_bits(bits: u8) -> ExceptionFlags {
+        ExceptionFlags(bits & 0x1F)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: ExceptionFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: ExceptionFlags) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: ExceptionFlags) {
+        self.0 &= !other.0;
+    }
+}
+
+impl ops::BitOr for ExceptionFlags {
+    type Output = ExceptionFlags;
+
+    fn bitor(self, other: ExceptionFlags) -> ExceptionFlags {
+        ExceptionFlags(self.0 | other.0)
+    }
+}
+
+impl ops::BitOrAssign for ExceptionFlags {
+    fn bitor_assign(&mut self, other: ExceptionFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl ops::BitAnd for ExceptionFlags {
+    type Output = ExceptionFlags;
+
+    fn bitand(self, other: ExceptionFlags) -> ExceptionFlags {
+        ExceptionFlags(self.0 & other.0)
+    }
+}
+
+/// Floating-point environment an operation is executed in.
+///
+/// Every operation has a `_with_env` variant taking an explicit environment,
+/// the other variants use the environment of the current thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Env {
+    rounding_mode: RoundingMode,
+    detect_tininess: DetectTininess,
+    rounding_precision: RoundingPrecision,
+    int_overflow: IntOverflow,
+    min_max: MinMax,
+    nan_policy: NanPolicy,
+    flush_to_zero: FlushToZero,
+    denormals_are_zero: bool,
+    flags: ExceptionFlags,
+}
+
+impl Env {
+    pub fn new(rounding_mode: RoundingMode) -> Env {
+        Env {
+            rounding_mode,
+            ..Env::default()
+        }
+    }
+
+    pub fn rounding_mode(&self) -> RoundingMode {
+        self.rounding_mode
+    }
+
+    pub fn set_rounding_mode(&mut self, rounding_mode: RoundingMode) {
+        self.rounding_mode = rounding_mode;
+    }
+
+    pub fn detect_tininess(&self) -> DetectTininess {
+        self.detect_tininess
+    }
+
+    pub fn set_detect_tininess(&mut self, detect_tininess: DetectTininess) {
+        self.detect_tininess = detect_tininess;
+    }
+
+    pub fn rounding_precision(&self) -> RoundingPrecision {
+        self.rounding_precision
+    }
+
+    pub fn set_rounding_precision(&mut self, rounding_precision: RoundingPrecision) {
+        self.rounding_precision = rounding_precision;
+    }
+
+    pub fn int_overflow(&self) -> IntOverflow {
+        self.int_overflow
+    }
+
+    pub fn set_int_overflow(&mut self, int_overflow: IntOverflow) {
+        self.int_overflow = int_overflow;
+    }
+
+    pub fn min_max(&self) -> MinMax {
+        self.min_max
+    }
+
+    pub fn set_min_max(&mut self, min_max: MinMax) {
+        self.min_max = min_max;
+    }
+
+    pub fn nan_policy(&self) -> NanPolicy {
+        self.nan_policy
+    }
+
+    pub fn set_nan_policy(&mut self, nan_policy: NanPolicy) {
+        self.nan_policy = nan_policy;
+    }
+
+    pub fn flush_to_zero(&self) -> FlushToZero {
+        self.flush_to_zero
+    }
+
+    pub fn set_flush_to_zero(&mut self, flush_to_zero: FlushToZero) {
+        self.flush_to_zero = flush_to_zero;
+    }
+
+    /// Whether subnormal operands are read as a zero of the same sign, without raising any flag
+    /// (ARMv8 FPCR.FZ, x86 MXCSR.DAZ).
+    pub fn denormals_are_zero(&self) -> bool {
+        self.denormals_are_zero
+    }
+
+    pub fn set_denormals_are_zero(&mut self, denormals_are_zero: bool) {
+        self.denormals_are_zero = denormals_are_zero;
+    }
+
+    /// Exception flags raised since they were last cleared.
+    pub fn flags(&self) -> ExceptionFlags {
+        self.flags
+    }
+
+    pub fn set_flags(&mut self, flags: ExceptionFlags) {
+        self.flags = flags;
+    }
+
+    pub fn clear_flags(&mut self) {
+        self.flags = ExceptionFlags::empty();
+    }
+
+    pub fn raise(&mut self, flags: ExceptionFlags) {
+        self.flags |= flags;
+    }
+}
+
+thread_local! {
+    static THREAD_ENV: Cell<Env> = Cell::new(Env::default());
+}
+
+/// Get a copy of the environment of the current thread.
+pub fn get_env() -> Env {
+    THREAD_ENV.with(|env| env.get())
+}
+
+/// Replace the environment of the current thread.
+pub fn set_env(env: Env) {
+    THREAD_ENV.with(|thread_env| thread_env.set(env));
+}
+
+pub fn get_rounding_mode() -> RoundingMode {
+    get_env().rounding_mode()
+}
+
+pub fn set_rounding_mode(rounding_mode: RoundingMode) {
+    with_thread_env(|env| env.set_rounding_mode(rounding_mode));
+}
+
+pub fn get_detect_tininess() -> DetectTininess {
+    get_env().detect_tininess()
+}
+
+pub fn set_detect_tininess(detect_tininess: DetectTininess) {
+    with_thread_env(|env| env.set_detect_tininess(detect_tininess));
+}
+
+pub fn get_rounding_precision() -> RoundingPrecision {
+    get_env().rounding_precision()
+}
+
+pub fn set_rounding_precision(rounding_precision: RoundingPrecision) {
+    with_thread_env(|env| env.set_rounding_precision(rounding_precision));
+}
+
+pub fn get_int_overflow() -> IntOverflow {
+    get_env().int_overflow()
+}
+
+pub fn set_int_overflow(int_overflow: IntOverflow) {
+    with_thread_env(|env| env.set_int_overflow(int_overflow));
+}
+
+pub fn get_min_max() -> MinMax {
+    get_env().min_max()
+}
+
+pub fn set_min_max(min_max: MinMax) {
+    with_thread_env(|env| env.set_min_max(min_max));
+}
+
+pub fn get_nan_policy() -> NanPolicy {
+    get_env().nan_policy()
+}
+
+pub fn set_nan_policy(nan_policy: NanPolicy) {
+    with_thread_env(|env| env.set_nan_policy(nan_policy));
+}
+
+pub fn get_flush_to_zero() -> FlushToZero {
+    get_env().flush_to_zero()
+}
+
+pub fn set_flush_to_zero(flush_to_zero: FlushToZero) {
+    with_thread_env(|env| env.set_flush_to_zero(flush_to_zero));
+}
+
+pub fn get_denormals_are_zero() -> bool {
+    get_env().denormals_are_zero()
+}
+
+pub fn set_denormals_are_zero(denormals_are_zero: bool) {
+    with_thread_env(|env| env.set_denormals_are_zero(denormals_are_zero));
+}
+
+pub fn get_exception_flags() -> ExceptionFlags {
+    get_env().flags()
+}
+
+pub fn clear_exception_flags() {
+    with_thread_env(|env| env.clear_flags());
+}
+
+pub fn raise_exception_flags(flags: ExceptionFlags) {
+    with_thread_env(|env| env.raise(flags));
+}
+
+// Run `f` with the environment of the current thread, and store it back.
+// Operations must call the `_with_env` variants inside `f`.
+pub(crate) fn with_thread_env<T, F: FnOnce(&mut Env) -> T>(f: F) -> T {
+    THREAD_ENV.with(|thread_env| {
+        let mut env = thread_env.get();
+        let result = f(&mut env);
+        thread_env.set(env);
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::soft_float::{ Env, ExceptionFlags, RoundingMode };
+
+    #[test]
+    fn test_thread_rounding_mode() {
+        assert_eq!(crate::soft_float::get_rounding_mode(), RoundingMode::NearEven);
+
+        crate::soft_float::set_rounding_mode(RoundingMode::Min);
+        assert_eq!(crate::soft_float::get_rounding_mode(), RoundingMode::Min);
+
+        // Other threads keep their own environment
+        let other = std::thread::spawn(crate::soft_float::get_rounding_mode).join().unwrap();
+        assert_eq!(other, RoundingMode::NearEven);
+
+        crate::soft_float::set_env(Env::default());
+        assert_eq!(crate::soft_float::get_rounding_mode(), RoundingMode::NearEven);
+    }
+
+    #[test]
+    fn test_exception_flags() {
+        let mut flags = ExceptionFlags::empty();
+        assert_eq!(flags.is_empty(), true);
+
+        flags |= ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT;
+        assert_eq!(flags.contains(ExceptionFlags::OVERFLOW), true);
+        assert_eq!(flags.contains(ExceptionFlags::INEXACT), true);
+        assert_eq!(flags.contains(ExceptionFlags::OVERFLOW | ExceptionFlags::INVALID), false);
+        assert_eq!(flags.bits(), 0x05);
+
+        flags.remove(ExceptionFlags::INEXACT);
+        assert_eq!(flags, ExceptionFlags::OVERFLOW);
+        assert_eq!(ExceptionFlags::from_bits(0xFF), ExceptionFlags::all());
+    }
+
+    #[test]
+    fn test_thread_exception_flags() {
+        assert_eq!(crate::soft_float::get_exception_flags(), ExceptionFlags::empty());
+
+        // 1 / 3 is inexact
+        crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut Env::default());
+        assert_eq!(crate::soft_float::get_exception_flags(), ExceptionFlags::empty());
+        let mut env = Env::default();
+        crate::soft_f32::f32_div_with_env(0x3F800000, 0x40400000, &mut env);
+        assert_eq!(env.flags(), ExceptionFlags::INEXACT);
+
+        crate::soft_float::raise_exception_flags(ExceptionFlags::INVALID);
+        // Flags are sticky
+        crate::soft_float::raise_exception_flags(ExceptionFlags::UNDERFLOW);
+        assert_eq!(crate::soft_float::get_exception_flags(), ExceptionFlags::INVALID | ExceptionFlags::UNDERFLOW);
+
+        crate::soft_float::clear_exception_flags();
+        assert_eq!(crate::soft_float::get_exception_flags(), ExceptionFlags::empty());
+    }
+}